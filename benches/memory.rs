@@ -0,0 +1,148 @@
+//! Compare the memory taken by parsed documents with the representation `JSONValue` had
+//! before short strings were stored inline and arrays boxed without spare capacity: a
+//! `String` per string, a growing `Vec` per array, and objects keeping their keys both
+//! in order and in a hash map. That representation is copied below and filled the way
+//! its parser filled it. Run with `cargo bench --bench memory`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::{HashMap, HashSet};
+use std::mem::size_of;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use jsonparser::{JSONParser, JSONValue, Walk};
+
+/// The system allocator, counting the bytes currently allocated.
+struct Counting;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        LIVE.fetch_add(new_size, Ordering::Relaxed);
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// `JSONValue` as it was before.
+#[allow(dead_code)]
+enum Before {
+    Object(BeforeMap),
+    Array(Vec<Before>),
+    String(String),
+    Number(f64),
+    Boolean(bool),
+    Null
+}
+
+#[allow(dead_code)]
+struct BeforeMap {
+    order: Vec<Arc<str>>,
+    map: HashMap<Arc<str>, Before>
+}
+
+impl Before {
+    /// Copy a value, sharing the keys through the table of interned keys as the parser did.
+    fn from(value: &JSONValue, keys: &mut HashSet<Arc<str>>) -> Before {
+        match value {
+            JSONValue::Object(obj) => {
+                let mut map = BeforeMap { order: Vec::new(), map: HashMap::new() };
+
+                for (key, value) in obj.iter() {
+                    let key = match keys.get(key) {
+                        Some(key) => key.clone(),
+                        None => {
+                            let key: Arc<str> = Arc::from(key);
+
+                            keys.insert(key.clone());
+                            key
+                        }
+                    };
+
+                    map.order.push(key.clone());
+                    map.map.insert(key, Before::from(value, keys));
+                }
+                Before::Object(map)
+            },
+            JSONValue::Array(array) => {
+                let mut items = Vec::new();
+
+                for item in array.iter() {
+                    items.push(Before::from(item, keys));
+                }
+                Before::Array(items)
+            },
+            JSONValue::String(s) => Before::String(s.to_string()),
+            JSONValue::Number(n) => Before::Number(*n),
+            JSONValue::Boolean(b) => Before::Boolean(*b),
+            _ => Before::Null
+        }
+    }
+}
+
+/// Records with short strings, as an API response would hold.
+fn records() -> String {
+    let records = (0..20_000).map(|i| format!(
+        r#"{{"id":{},"name":"user {}","email":"user{}@example.com","score":{},"active":{},"tags":["a","b","c"],"address":{{"city":"Paris","zip":"750{:02}"}}}}"#,
+        i, i, i, i as f64 * 1.25, i % 2 == 0, i % 20
+    )).collect::<Vec<_>>();
+
+    format!(r#"{{"records":[{}]}}"#, records.join(","))
+}
+
+/// Rows of numbers, as a table or a time series would hold.
+fn rows() -> String {
+    let rows = (0..20_000).map(|i| format!("[{},{},{},{},{}]", i, i + 1, i * 2, i % 7, i as f64 / 3.0)).collect::<Vec<_>>();
+
+    format!(r#"{{"rows":[{}]}}"#, rows.join(","))
+}
+
+fn live() -> usize {
+    LIVE.load(Ordering::Relaxed)
+}
+
+fn compare(name: &str, input: &str) {
+    let start = live();
+    let value = JSONParser::from(input).unwrap();
+    let after = live() - start;
+
+    let start = live();
+    let before = Before::from(&value, &mut HashSet::new());
+    let before_bytes = live() - start;
+
+    let mut nodes = 0;
+
+    value.walk(|_, _| {
+        nodes += 1;
+        Walk::Continue
+    });
+    // Values held by their parent are counted through its allocation, and the root inline.
+    let after = after + size_of::<JSONValue>();
+    let before_bytes = before_bytes + size_of::<Before>();
+
+    println!("{}: {} nodes, {:.1} MB of JSON", name, nodes, input.len() as f64 / 1e6);
+    println!("  before: {:>10} bytes, {:>5.1} per node", before_bytes, before_bytes as f64 / nodes as f64);
+    println!("  after:  {:>10} bytes, {:>5.1} per node", after, after as f64 / nodes as f64);
+    println!("  ratio:  {:.2}x", before_bytes as f64 / after as f64);
+    drop(before);
+}
+
+fn main() {
+    println!("size of a value: before {} bytes, after {} bytes", size_of::<Before>(), size_of::<JSONValue>());
+    compare("records", &records());
+    compare("rows", &rows());
+}
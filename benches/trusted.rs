@@ -0,0 +1,42 @@
+//! Compare `JSONParser::from_trusted` with `JSONParser::from` on serialized records.
+//! Run with `cargo bench --bench trusted`.
+
+use std::time::{Duration, Instant};
+
+use jsonparser::{JSONParser, Serialize};
+
+const RECORDS: usize = 20_000;
+const RUNS: usize = 20;
+
+fn document() -> String {
+    let records = (0..RECORDS).map(|i| format!(
+        r#"{{"id":{},"name":"user {}","email":"user{}@example.com","score":{},"active":{},"tags":["a","b\n{}"],"address":{{"city":"Paris","zip":"750{:02}"}}}}"#,
+        i, i, i, i as f64 * 1.25, i % 2 == 0, i, i % 20
+    )).collect::<Vec<_>>();
+    let input = format!(r#"{{"records":[{}]}}"#, records.join(","));
+
+    // Serialize the parsed document, as trusted input is typically our own output.
+    JSONParser::from(&input).unwrap().serialize()
+}
+
+/// The fastest of several runs, which is the least disturbed by the rest of the system.
+fn best(input: &str, parse: fn(&str) -> bool) -> Duration {
+    (0..RUNS).map(|_| {
+        let start = Instant::now();
+
+        assert!(parse(input));
+        start.elapsed()
+    }).min().unwrap()
+}
+
+fn main() {
+    let input = document();
+    let megabytes = input.len() as f64 / 1e6;
+    let checked = best(&input, |input| JSONParser::from(input).is_ok());
+    let trusted = best(&input, |input| JSONParser::from_trusted(input).is_ok());
+
+    println!("document:     {:.1} MB, {} records", megabytes, RECORDS);
+    println!("from:         {:>8.2?} ({:.0} MB/s)", checked, megabytes / checked.as_secs_f64());
+    println!("from_trusted: {:>8.2?} ({:.0} MB/s)", trusted, megabytes / trusted.as_secs_f64());
+    println!("speedup:      {:.2}x", checked.as_secs_f64() / trusted.as_secs_f64());
+}
@@ -1,8 +1,84 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 mod utils;
 
-use utils::{Lexer, Parser};
-pub use utils::{JSONValue, OrderedMap, Serialize};
-pub use utils::{JSONSchema, Validator, StringType, NumberType, BooleanType, ArrayType, ObjectType, NullType};
+#[cfg(feature = "std")]
+use std::io::Read;
+
+use utils::compat::*;
+use utils::{decode, Parser};
+use utils::trusted::TrustedParser;
+pub use utils::{diff, expr, lint};
+#[cfg(feature = "testutils")]
+pub use utils::testutils;
+#[cfg(feature = "wasm")]
+pub use utils::wasm;
+pub use utils::{JSONKind, JSONString, JSONValue, OrderedMap, RawValue, Serialize, SerializeOptions, Stats, Walk};
+pub use utils::{JSONIntoIter, JSONIter, OrderedMapIntoIter, OrderedMapIter};
+pub use utils::AccessError;
+pub use utils::{ArrayBuilder, ObjectBuilder};
+pub use utils::{ArrayMerge, MergeStrategy, NullMerge};
+pub use utils::EqualityOptions;
+pub use utils::Case;
+pub use utils::PruneOptions;
+pub use utils::StreamFilter;
+pub use utils::CsvOptions;
+pub use utils::{Arena, ArenaValue};
+#[cfg(feature = "mmap")]
+pub use utils::MappedFile;
+pub use utils::Document;
+pub use utils::SharedJSONValue;
+pub use utils::{CancellationToken, DEFAULT_MAX_DEPTH, Limit, ParseError, ParserOptions, ParserSession};
+pub use utils::{PushParser, PushStatus};
+#[cfg(feature = "async")]
+pub use utils::{AsyncEventStream, AsyncNdjsonStream, Event};
+#[cfg(feature = "std")]
+pub use utils::JsonSeqStream;
+pub use utils::{ArrayStream, Lexer, LexError, LexErrorKind, Span, Token, TokenKind};
+pub use utils::{highlight, Class};
+pub use utils::{reformat, FormatStyle};
+pub use utils::{CompiledSchema, JSONSchema, Redaction, SchemaBuilder, ValidationReport, Validator, StringType, NumberType, BooleanType, DateType, ArrayType, ObjectType, NullType, AnyType, NotType};
+pub use utils::{EnglishFormatter, MessageFormatter, ValidationContext, ValidationError};
+pub use utils::Migrator;
+pub use utils::{FromJSON, Mapped, TypedValidator};
+pub use utils::{RuleDoc, Rng};
+
+/// Serialize any value implementing `Serialize` to a JSON string.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// let mut scores = HashMap::new();
+///
+/// scores.insert("bob".to_string(), vec![Some(3), None]);
+/// scores.insert("alice".to_string(), vec![Some(1)]);
+///
+/// assert_eq!(jsonparser::to_string(&scores), r#"{"alice":[1],"bob":[3,null]}"#);
+/// assert_eq!(jsonparser::to_string(&("id", 42, true)), r#"["id",42,true]"#);
+/// ```
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> String {
+    value.serialize()
+}
+
+/// Convert any value implementing `Serialize` to a JSONValue.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::JSONValue;
+///
+/// let value = jsonparser::to_value(&vec!["a", "b"]);
+///
+/// assert_eq!(value[1].as_str(), Some("b"));
+/// assert_eq!(jsonparser::to_value(&None::<u8>), JSONValue::Null);
+/// ```
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> JSONValue {
+    value.to_value()
+}
 
 /// A JSON parser that can parse a JSON input string to a JSONValue.
 ///
@@ -48,6 +124,112 @@ impl<'a> JSONParser<'a> {
         Self { parser }
     }
 
+    /// Create a new JSONParser instance with the given input string and options.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, Limit, ParseError, ParserOptions};
+    ///
+    /// let options = ParserOptions::new().max_array_length(2);
+    /// let result = JSONParser::with_options(r#"{ "ids": [1, 2, 3] }"#, options).parse();
+    ///
+    /// assert_eq!(result.unwrap_err(), ParseError::LimitExceeded { limit: Limit::ArrayLength, max: 2 });
+    /// ```
+    pub fn with_options(input: &'a str, options: ParserOptions) -> Self {
+        let lexer = Lexer::new(input);
+        let parser = Parser::with_options(lexer, options);
+
+        Self { parser }
+    }
+
+    /// Accept unescaped control characters (U+0000 to U+001F) inside strings.
+    /// Strict JSON forbids them, so they are rejected by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let input = "{ \"note\": \"line\tbreak\" }";
+    ///
+    /// assert!(JSONParser::new(input).parse().is_err());
+    /// assert!(JSONParser::new(input).allow_control_characters().parse().is_ok());
+    /// ```
+    pub fn allow_control_characters(mut self) -> Self {
+        self.parser = self.parser.allow_control_characters();
+        self
+    }
+
+    /// Call the callback with the number of bytes consumed and the size of the input
+    /// every 64 KiB and once the input is consumed, to drive a progress bar.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let input = format!(r#"{{ "ids": [{}0] }}"#, "1, ".repeat(100_000));
+    /// let mut reports = Vec::new();
+    ///
+    /// JSONParser::new(&input).on_progress(|consumed, total| reports.push((consumed, total))).parse().unwrap();
+    ///
+    /// assert!(reports.len() > 1);
+    /// assert_eq!(reports.last(), Some(&(input.len(), Some(input.len()))));
+    /// ```
+    pub fn on_progress<F: FnMut(usize, Option<usize>) + 'a>(mut self, callback: F) -> Self {
+        self.parser = self.parser.on_progress(callback);
+        self
+    }
+
+    /// Stop parsing with `ParseError::Cancelled` once the token is cancelled, such as from
+    /// another thread. The token is checked every 64 KiB of input.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{CancellationToken, JSONParser, ParseError};
+    ///
+    /// let input = format!(r#"{{ "ids": [{}0] }}"#, "1, ".repeat(100_000));
+    /// let token = CancellationToken::new();
+    /// let cancel = token.clone();
+    /// let result = JSONParser::new(&input)
+    ///     .cancellation(token)
+    ///     .on_progress(move |consumed, _| if consumed > 100_000 { cancel.cancel() })
+    ///     .parse();
+    ///
+    /// assert_eq!(result, Err(ParseError::Cancelled));
+    /// ```
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.parser = self.parser.cancellation(token);
+        self
+    }
+
+    /// Keep the value at the given JSON Pointer as `JSONValue::Raw` instead of building it,
+    /// so that a large subdocument can be passed along or parsed later with `parse_raw`.
+    /// The value is still checked. Can be called several times to keep several values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, Serialize};
+    ///
+    /// let input = r#"{ "id": 1, "payload": { "items": [1, 2, 3] }, "tags": ["a", [true, null]] }"#;
+    /// let json = JSONParser::new(input).raw_at("/payload").raw_at("/tags/1").parse().unwrap();
+    ///
+    /// assert_eq!(json["payload"].as_raw(), Some(r#"{ "items": [1, 2, 3] }"#));
+    /// assert_eq!(json["tags"][1].as_raw(), Some("[true, null]"));
+    /// assert_eq!(json["payload"].parse_raw()["items"][2].as_f64(), Some(3.0));
+    /// assert_eq!(json.serialize(), r#"{"id":1,"payload":{ "items": [1, 2, 3] },"tags":["a",[true, null]]}"#);
+    ///
+    /// assert!(JSONParser::new(r#"{ "payload": { "items": } }"#).raw_at("/payload").parse().is_err());
+    /// assert!(JSONParser::new(input).raw_at("payload").parse().is_err());
+    /// ```
+    pub fn raw_at(mut self, pointer: &str) -> Self {
+        self.parser = self.parser.raw_at(pointer);
+        self
+    }
+
     /// Parse the JSON input to a JSONValue.
     ///
     /// # Example
@@ -73,10 +255,54 @@ impl<'a> JSONParser<'a> {
     ///
     /// println!("{:#?}", json["name"].as_str());
     /// ```
-    pub fn parse(&mut self) -> Result<JSONValue, String> {
+    pub fn parse(&mut self) -> Result<JSONValue, ParseError> {
         self.parser.parse()
     }
 
+    /// Parse the JSON input into the given arena instead of building an owned JSONValue.
+    /// The returned view borrows the arena, which can be reset and reused afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{Arena, JSONParser};
+    ///
+    /// let mut arena = Arena::new();
+    /// let json = JSONParser::new(r#"{ "cars": [{ "model": "Ford" }] }"#).parse_in(&mut arena).unwrap();
+    ///
+    /// let model = json.get("cars").and_then(|cars| cars.get_index(0)).and_then(|car| car.get("model"));
+    ///
+    /// assert_eq!(model.and_then(|model| model.as_str()), Some("Ford"));
+    /// ```
+    pub fn parse_in<'b>(&mut self, arena: &'b mut Arena) -> Result<ArenaValue<'b>, ParseError> {
+        self.parser.parse_in(arena)
+    }
+
+    /// Parse the JSON input into the given arena like `parse_in`, without copying the
+    /// strings and keys written without escapes: the returned view reads them from the
+    /// input, which must outlive it. Only the strings with escapes are decoded into the arena.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{Arena, JSONParser};
+    ///
+    /// let input = r#"{ "name": "John Doe", "quote": "\"Hi\"" }"#.to_string();
+    /// let mut arena = Arena::new();
+    /// let json = JSONParser::new(&input).parse_borrowed_in(&mut arena).unwrap();
+    ///
+    /// let name = json.get("name").and_then(|name| name.as_str()).unwrap();
+    ///
+    /// assert!(input.as_bytes().as_ptr_range().contains(&name.as_ptr()));
+    /// assert_eq!(json.get("quote").and_then(|quote| quote.as_str()), Some("\"Hi\""));
+    /// ```
+    pub fn parse_borrowed_in<'b>(&mut self, arena: &'b mut Arena) -> Result<ArenaValue<'b>, ParseError>
+    where
+        'a: 'b
+    {
+        self.parser.parse_borrowed_in(arena)
+    }
+
     /// Parse the JSON input to a JSONValue.
     ///
     /// # Example
@@ -101,9 +327,110 @@ impl<'a> JSONParser<'a> {
     ///
     /// println!("{:#?}", json["name"].as_str());
     /// ```
-    pub fn from(input: &'a str) -> Result<JSONValue, String>{
+    pub fn from(input: &'a str) -> Result<JSONValue, ParseError>{
+        let mut parser = JSONParser::new(input);
+
+        parser.parse()
+    }
+
+    /// Parse input that is already known to be valid JSON, such as the output of
+    /// `Serialize`. The bytes are read directly instead of going through tokens:
+    /// strings without escapes are copied at once, escapes are decoded without being
+    /// checked, control characters are kept, and numbers are read without checking their
+    /// grammar. On the serialized records of `cargo bench --bench trusted`, this parses
+    /// about 1.5 times as fast as `JSONParser::from`.
+    ///
+    /// Invalid input never causes undefined behavior, but may produce garbage values:
+    /// bad escapes become U+FFFD and malformed numbers become NaN. Structural errors, such
+    /// as a missing comma, are still reported. No limit applies but the nesting depth,
+    /// bounded by `DEFAULT_MAX_DEPTH`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, Serialize};
+    ///
+    /// let json = JSONParser::from(r#"{ "name": "John \"Doe\"", "tags": ["a", "\u00e9"], "age": -1.5e2 }"#).unwrap();
+    /// let reparsed = JSONParser::from_trusted(&json.serialize()).unwrap();
+    ///
+    /// assert_eq!(reparsed, json);
+    ///
+    /// let garbage = JSONParser::from_trusted(r#"{ "name": "\x", "age": 1-2 }"#).unwrap();
+    ///
+    /// assert_eq!(garbage["name"].as_str(), Some("\u{FFFD}x"));
+    /// assert!(garbage["age"].as_f64().unwrap().is_nan());
+    /// assert!(JSONParser::from_trusted(r#"{ "a": 1 "b": 2 }"#).is_err());
+    /// ```
+    pub fn from_trusted(input: &'a str) -> Result<JSONValue, ParseError> {
+        TrustedParser::new(input).parse()
+    }
+
+    /// Parse only the value at the given JSON Pointer, skipping the rest of the document
+    /// at the token level. Returns None if the pointer does not resolve.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let input = r#"{ "data": { "items": [10, 20, 30, { "id": 4 }] } }"#;
+    ///
+    /// let item = JSONParser::parse_pointer(input, "/data/items/3/id").unwrap();
+    ///
+    /// assert_eq!(item.and_then(|v| v.as_f64()), Some(4.0));
+    /// assert!(JSONParser::parse_pointer(input, "/data/missing").unwrap().is_none());
+    /// ```
+    pub fn parse_pointer(input: &'a str, pointer: &str) -> Result<Option<JSONValue>, ParseError> {
         let mut parser = JSONParser::new(input);
 
+        parser.parser.parse_pointer(pointer)
+    }
+
+    /// Stream the items of a top-level JSON array read from the given reader,
+    /// parsing each item only when the iterator reaches it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let input = r#"[{ "id": 1 }, { "id": 2 }, { "id": 3 }]"#;
+    /// let mut total = 0.0;
+    ///
+    /// for item in JSONParser::stream_array(input.as_bytes()) {
+    ///     total += item.unwrap()["id"].as_f64().unwrap();
+    /// }
+    ///
+    /// assert_eq!(total, 6.0);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn stream_array<R: Read + 'a>(reader: R) -> ArrayStream<'a> {
+        ArrayStream::new(Parser::new(Lexer::from_reader(reader)))
+    }
+
+    /// Parse raw JSON bytes to a JSONValue.
+    /// A leading BOM is stripped, and UTF-16 or UTF-32 input is detected
+    /// as described in RFC 4627 and transcoded before parsing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let input = b"\xEF\xBB\xBF{ \"name\": \"John Doe\" }";
+    /// let json = JSONParser::from_bytes(input).unwrap();
+    ///
+    /// assert_eq!(json["name"].as_str(), Some("John Doe"));
+    ///
+    /// let utf16: Vec<u8> = "{ \"age\": 30 }".encode_utf16().flat_map(|u| u.to_le_bytes()).collect();
+    /// let json = JSONParser::from_bytes(&utf16).unwrap();
+    ///
+    /// assert_eq!(json["age"].as_f64(), Some(30.0));
+    /// ```
+    pub fn from_bytes(input: &[u8]) -> Result<JSONValue, ParseError> {
+        let input = decode(input)?;
+        let mut parser = JSONParser::new(&input);
+
         parser.parse()
     }
 }
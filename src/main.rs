@@ -1,6 +1,111 @@
-use jsonparser::{ArrayType, BooleanType, JSONParser, JSONSchema, NullType, NumberType, ObjectType, StringType};
+use std::io::Read;
+
+use jsonparser::{expr, reformat, ArrayType, BooleanType, Document, FormatStyle, JSONParser, JSONSchema, JSONValue, NullType, NumberType, ObjectType, Serialize, StringType};
+
+/// Read the file named by the first argument that is not a flag, or standard input.
+fn read_input(args: &[String]) -> Result<Vec<u8>, String> {
+    let mut input = Vec::new();
+
+    match args.iter().find(|arg| !arg.starts_with('-')) {
+        Some(path) => input = std::fs::read(path).map_err(|e| format!("{}: {}", path, e))?,
+        None => {
+            std::io::stdin().read_to_end(&mut input).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(input)
+}
+
+/// `jsonparser gron [--ungron] [FILE]`: flatten a JSON document into greppable
+/// assignments, or rebuild it from them. Reads standard input when no file is given.
+fn gron(args: &[String]) -> Result<String, String> {
+    let ungron = args.iter().any(|arg| arg == "--ungron" || arg == "-u");
+    let input = read_input(args)?;
+
+    if ungron {
+        let input = String::from_utf8(input).map_err(|e| e.to_string())?;
+
+        JSONValue::from_gron(&input).map(|json| format!("{}\n", json.serialize()))
+    } else {
+        JSONParser::from_bytes(&input).map(|json| json.to_gron()).map_err(|e| e.to_string())
+    }
+}
+
+/// `jsonparser stats [FILE]`: summarize the structure of a JSON document.
+/// Reads standard input when no file is given.
+fn stats(args: &[String]) -> Result<String, String> {
+    let input = read_input(args)?;
+
+    JSONParser::from_bytes(&input).map(|json| format!("{}\n", json.stats())).map_err(|e| e.to_string())
+}
+
+/// `jsonparser fmt [--indent=N] [--tabs] [--write] [FILE]`: pretty-print a JSON document,
+/// keeping its `//` and `/* */` comments. `--write` rewrites the file in place instead of
+/// printing it. Reads standard input when no file is given.
+fn fmt(args: &[String]) -> Result<String, String> {
+    let mut style = FormatStyle::new();
+
+    for arg in args {
+        if arg == "--tabs" {
+            style = style.tabs();
+        } else if let Some(width) = arg.strip_prefix("--indent=") {
+            style = style.indent(width.parse().map_err(|_| format!("Invalid indent '{}'", width))?);
+        }
+    }
+    let input = String::from_utf8(read_input(args)?).map_err(|e| e.to_string())?;
+    let output = reformat(&input, style).map_err(|e| e.to_string())?;
+
+    match args.iter().find(|arg| !arg.starts_with('-')) {
+        Some(path) if args.iter().any(|arg| arg == "--write") => {
+            std::fs::write(path, output).map_err(|e| format!("{}: {}", path, e))?;
+            Ok(String::new())
+        },
+        _ => Ok(output)
+    }
+}
+
+/// `jsonparser expr [--raw] EXPR [FILE]`: evaluate a jq-like expression against a JSON
+/// document and print each output on its own line, strings unquoted with `--raw`.
+/// Reads standard input when no file is given.
+fn expr(args: &[String]) -> Result<String, String> {
+    let raw = args.iter().any(|arg| arg == "--raw" || arg == "-r");
+    let position = args.iter().position(|arg| !arg.starts_with('-')).ok_or("Missing expression")?;
+    let expr = expr::Expr::parse(&args[position])?;
+    let input = String::from_utf8(read_input(&args[position + 1..])?).map_err(|e| e.to_string())?;
+    let document = Document::parse(&input).map_err(|e| e.to_string())?;
+    let mut output = String::new();
+
+    for value in expr.eval(document.value())? {
+        match value {
+            JSONValue::String(s) if raw => output.push_str(&s),
+            value => output.push_str(&value.serialize())
+        }
+        output.push('\n');
+    }
+    Ok(output)
+}
 
 fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    let result = match args.first().map(|arg| arg.as_str()) {
+        Some("gron") => Some(gron(&args[1..])),
+        Some("stats") => Some(stats(&args[1..])),
+        Some("fmt") => Some(fmt(&args[1..])),
+        Some("expr") => Some(expr(&args[1..])),
+        _ => None
+    };
+
+    if let Some(result) = result {
+        match result {
+            Ok(output) => print!("{}", output),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     let input = r#"
         {
             "name": "     John Doe     ",
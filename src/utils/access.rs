@@ -0,0 +1,132 @@
+use crate::utils::compat::*;
+use crate::utils::path::{parse_path, Segment};
+use crate::utils::{AccessError, JSONKind, JSONValue, OrderedMap};
+
+/// Append a segment to a dot/bracket path, escaping the characters `parse_path` reads specially.
+pub(crate) fn push_segment(path: &mut String, segment: &Segment) {
+    match segment {
+        Segment::Key(key) => {
+            if !path.is_empty() {
+                path.push('.');
+            }
+            for c in key.chars() {
+                if matches!(c, '.' | '[' | ']' | '\\') {
+                    path.push('\\');
+                }
+                path.push(c);
+            }
+        },
+        Segment::Index(index) => path.push_str(&format!("[{}]", index))
+    }
+}
+
+fn wrong_type(path: &str, expected: JSONKind, value: &JSONValue) -> AccessError {
+    AccessError::WrongType { path: path.to_string(), expected, found: value.kind() }
+}
+
+impl JSONValue {
+    /// Returns the value at the given dot/bracket path, such as `name` or `cars[1].model`,
+    /// or an error telling which key is missing, which index is out of bounds or which
+    /// value is not a container, along with its path.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "cars": [{ "model": "Ford" }], "name": "John" }"#).unwrap();
+    ///
+    /// assert_eq!(json.try_get("cars[0].model").unwrap().as_str(), Some("Ford"));
+    /// assert_eq!(json.try_get("cars[0].year").unwrap_err().to_string(), "Missing key 'year' in 'cars[0]'");
+    /// assert_eq!(json.try_get("cars[1]").unwrap_err().to_string(), "Index 1 out of bounds in 'cars' (length: 1)");
+    /// assert_eq!(json.try_get("name.first").unwrap_err().to_string(), "Expected object at 'name', found string");
+    /// ```
+    pub fn try_get(&self, path: &str) -> Result<&JSONValue, AccessError> {
+        let segments = parse_path(path).map_err(AccessError::InvalidPath)?;
+        let mut value = self;
+        let mut current = String::new();
+
+        for segment in segments.iter() {
+            value = match (segment, value) {
+                (Segment::Key(key), JSONValue::Object(obj)) => obj.get(key).ok_or_else(|| {
+                    AccessError::MissingKey { path: current.clone(), key: key.clone() }
+                })?,
+                (Segment::Index(index), JSONValue::Array(array)) => array.get(*index).ok_or_else(|| {
+                    AccessError::OutOfBounds { path: current.clone(), index: *index, len: array.len() }
+                })?,
+                (Segment::Key(_), value) => return Err(wrong_type(&current, JSONKind::Object, value)),
+                (Segment::Index(_), value) => return Err(wrong_type(&current, JSONKind::Array, value))
+            };
+            push_segment(&mut current, segment);
+        }
+        Ok(value)
+    }
+
+    /// Returns the item at the given index of an array, or an error telling whether the
+    /// value is not an array or the index is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONValue};
+    ///
+    /// let json = JSONParser::from(r#"{ "tags": ["a", "b"] }"#).unwrap();
+    ///
+    /// assert_eq!(json["tags"].try_at(1).unwrap().as_str(), Some("b"));
+    /// assert_eq!(json["tags"].try_at(2).unwrap_err().to_string(), "Index 2 out of bounds (length: 2)");
+    /// assert_eq!(json.try_at(0).unwrap_err().to_string(), "Expected array, found object");
+    /// ```
+    pub fn try_at(&self, index: usize) -> Result<&JSONValue, AccessError> {
+        match self {
+            JSONValue::Array(array) => array.get(index).ok_or(AccessError::OutOfBounds { path: String::new(), index, len: array.len() }),
+            value => Err(wrong_type("", JSONKind::Array, value))
+        }
+    }
+
+    /// Returns the string at the given path. See `try_get`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "address": { "city": "Springfield", "zip": 62701 } }"#).unwrap();
+    ///
+    /// assert_eq!(json.try_get_str("address.city"), Ok("Springfield"));
+    /// assert_eq!(json.try_get_str("address.zip").unwrap_err().to_string(), "Expected string at 'address.zip', found number");
+    /// assert_eq!(json.try_get_str("address.state").unwrap_err().to_string(), "Missing key 'state' in 'address'");
+    /// ```
+    pub fn try_get_str(&self, path: &str) -> Result<&str, AccessError> {
+        let value = self.try_get(path)?;
+
+        value.as_str().ok_or_else(|| wrong_type(path, JSONKind::String, value))
+    }
+
+    /// Returns the number at the given path. See `try_get`.
+    pub fn try_get_f64(&self, path: &str) -> Result<f64, AccessError> {
+        let value = self.try_get(path)?;
+
+        value.as_f64().ok_or_else(|| wrong_type(path, JSONKind::Number, value))
+    }
+
+    /// Returns the boolean at the given path. See `try_get`.
+    pub fn try_get_bool(&self, path: &str) -> Result<bool, AccessError> {
+        let value = self.try_get(path)?;
+
+        value.as_bool().ok_or_else(|| wrong_type(path, JSONKind::Boolean, value))
+    }
+
+    /// Returns the array at the given path. See `try_get`.
+    pub fn try_get_array(&self, path: &str) -> Result<&[JSONValue], AccessError> {
+        let value = self.try_get(path)?;
+
+        value.as_array().ok_or_else(|| wrong_type(path, JSONKind::Array, value))
+    }
+
+    /// Returns the object at the given path. See `try_get`.
+    pub fn try_get_object(&self, path: &str) -> Result<&OrderedMap<JSONValue>, AccessError> {
+        let value = self.try_get(path)?;
+
+        value.as_object().ok_or_else(|| wrong_type(path, JSONKind::Object, value))
+    }
+}
@@ -0,0 +1,106 @@
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::utils::compat::*;
+use crate::utils::{JSONValue, OrderedMap};
+
+/// How deep arrays and objects are nested by `JSONValue::arbitrary`.
+const MAX_DEPTH: usize = 8;
+
+/// How many values, nested ones included, `JSONValue::arbitrary` builds at most.
+const MAX_VALUES: usize = 256;
+
+impl JSONValue {
+    /// Build a value from fuzzer input, nesting arrays and objects at most `max_depth`
+    /// levels deep and building at most `max_values` values, nested ones included.
+    ///
+    /// Numbers are always finite and raw values are never built, so that serializing
+    /// and parsing the value gives it back. `Arbitrary::arbitrary` uses a depth of 8
+    /// and 256 values.
+    ///
+    /// Available with the `arbitrary` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use arbitrary::{Arbitrary, Unstructured};
+    /// use jsonparser::{Document, JSONValue, Serialize};
+    ///
+    /// let data = (0..=255u8).cycle().take(4096).collect::<Vec<_>>();
+    /// let mut u = Unstructured::new(&data);
+    ///
+    /// while !u.is_empty() {
+    ///     let value = JSONValue::arbitrary(&mut u).unwrap();
+    ///
+    ///     assert_eq!(Document::parse(&value.serialize()).unwrap().value(), &value);
+    /// }
+    ///
+    /// let value = JSONValue::arbitrary_with(&mut Unstructured::new(&data), 0, 16).unwrap();
+    /// assert!(!matches!(value, JSONValue::Array(_) | JSONValue::Object(_)));
+    /// ```
+    pub fn arbitrary_with(u: &mut Unstructured, max_depth: usize, max_values: usize) -> Result<Self> {
+        let mut budget = max_values.max(1);
+
+        arbitrary_value(u, max_depth, &mut budget)
+    }
+}
+
+fn arbitrary_value(u: &mut Unstructured, depth: usize, budget: &mut usize) -> Result<JSONValue> {
+    *budget -= 1;
+
+    let kinds = if depth == 0 || *budget == 0 { 4 } else { 6 };
+
+    Ok(match u.choose_index(kinds)? {
+        0 => JSONValue::Null,
+        1 => JSONValue::Boolean(bool::arbitrary(u)?),
+        2 => JSONValue::Number(arbitrary_number(u)?),
+        3 => JSONValue::String(String::arbitrary(u)?.into()),
+        4 => {
+            let len = u.arbitrary_len::<JSONValue>()?.min(*budget);
+            let mut array = Vec::with_capacity(len);
+
+            for _ in 0..len {
+                if *budget == 0 {
+                    break;
+                }
+                array.push(arbitrary_value(u, depth - 1, budget)?);
+            }
+            JSONValue::Array(array.into())
+        },
+        _ => {
+            let len = u.arbitrary_len::<(String, JSONValue)>()?.min(*budget);
+            let mut obj = OrderedMap::with_capacity(len);
+
+            for _ in 0..len {
+                if *budget == 0 {
+                    break;
+                }
+                let key = String::arbitrary(u)?;
+                let value = arbitrary_value(u, depth - 1, budget)?;
+
+                obj.insert(&key, value);
+            }
+            JSONValue::Object(obj)
+        }
+    })
+}
+
+/// A finite number, an integer more often than not since they are the most common in JSON.
+fn arbitrary_number(u: &mut Unstructured) -> Result<f64> {
+    if u.ratio(2u8, 3u8)? {
+        return Ok(i32::arbitrary(u)? as f64);
+    }
+
+    let n = f64::arbitrary(u)?;
+
+    Ok(if n.is_finite() { n } else { 0.0 })
+}
+
+impl<'a> Arbitrary<'a> for JSONValue {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        JSONValue::arbitrary_with(u, MAX_DEPTH, MAX_VALUES)
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (1, None)
+    }
+}
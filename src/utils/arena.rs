@@ -0,0 +1,305 @@
+use crate::utils::compat::*;
+use crate::{JSONKind, JSONValue, OrderedMap};
+
+#[derive(Debug, Clone, Copy)]
+enum Node {
+    Null,
+    Boolean(bool),
+    Number(f64),
+    String(Text),
+    Key(Text),
+    Array { len: usize, end: usize },
+    Object { len: usize, end: usize }
+}
+
+/// Where the text of a string or a key is stored.
+#[derive(Debug, Clone, Copy)]
+enum Text {
+    /// A range of the text buffer of the arena.
+    Arena(usize, usize),
+    /// A range of the parsed input, for text read without escapes by `Parser::parse_borrowed_in`.
+    Input(usize, usize)
+}
+
+/// Storage for documents parsed with `JSONParser::parse_in`.
+///
+/// Every node is appended to one flat buffer and every string to one text
+/// buffer, so a parse performs a handful of allocations and dropping the
+/// document is immediate. Call `reset` to reuse the buffers for the next parse.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{Arena, JSONParser};
+///
+/// let mut arena = Arena::new();
+///
+/// for input in [r#"{ "id": 1 }"#, r#"{ "id": 2 }"#] {
+///     arena.reset();
+///     let json = JSONParser::new(input).parse_in(&mut arena).unwrap();
+///
+///     assert!(json.get("id").and_then(|id| id.as_f64()).is_some());
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct Arena {
+    nodes: Vec<Node>,
+    text: String
+}
+
+impl Arena {
+    /// Create a new empty Arena instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Clear the arena while keeping its allocated capacity.
+    pub fn reset(&mut self) {
+        self.nodes.clear();
+        self.text.clear();
+    }
+
+    /// The value at the given index, whose borrowed strings are ranges of `input`.
+    pub(crate) fn root<'a>(&'a self, index: usize, input: &'a str) -> ArenaValue<'a> {
+        ArenaValue { arena: self, input, index }
+    }
+
+    pub(crate) fn push_scalar(&mut self, value: JSONValue) {
+        let node = match value {
+            JSONValue::Null => Node::Null,
+            JSONValue::Boolean(b) => Node::Boolean(b),
+            JSONValue::Number(n) => Node::Number(n),
+            JSONValue::String(s) => {
+                let (start, end) = self.push_text(&s);
+
+                Node::String(Text::Arena(start, end))
+            },
+            _ => unreachable!("Containers are opened and closed by the parser")
+        };
+
+        self.nodes.push(node);
+    }
+
+    pub(crate) fn push_key(&mut self, key: &str) {
+        let (start, end) = self.push_text(key);
+
+        self.nodes.push(Node::Key(Text::Arena(start, end)));
+    }
+
+    /// Push a string stored in the parsed input between the given byte offsets.
+    pub(crate) fn push_borrowed_string(&mut self, start: usize, end: usize) {
+        self.nodes.push(Node::String(Text::Input(start, end)));
+    }
+
+    /// Push a key stored in the parsed input between the given byte offsets.
+    pub(crate) fn push_borrowed_key(&mut self, start: usize, end: usize) {
+        self.nodes.push(Node::Key(Text::Input(start, end)));
+    }
+
+    /// Start a container and return its index, to be passed to `close`.
+    pub(crate) fn open(&mut self, kind: JSONKind) -> usize {
+        self.nodes.push(match kind {
+            JSONKind::Object => Node::Object { len: 0, end: 0 },
+            _ => Node::Array { len: 0, end: 0 }
+        });
+        self.nodes.len() - 1
+    }
+
+    pub(crate) fn close(&mut self, index: usize, count: usize) {
+        let next = self.nodes.len();
+
+        match &mut self.nodes[index] {
+            Node::Object { len, end } | Node::Array { len, end } => {
+                *len = count;
+                *end = next;
+            },
+            _ => {}
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn push_text(&mut self, text: &str) -> (usize, usize) {
+        let start = self.text.len();
+
+        self.text.push_str(text);
+        (start, self.text.len())
+    }
+
+    /// Index of the node following the whole subtree at `index`.
+    fn next(&self, index: usize) -> usize {
+        match self.nodes[index] {
+            Node::Array { end, .. } | Node::Object { end, .. } => end,
+            _ => index + 1
+        }
+    }
+}
+
+/// A value stored in an `Arena`, borrowed for the lifetime of the arena.
+#[derive(Clone, Copy)]
+pub struct ArenaValue<'a> {
+    arena: &'a Arena,
+    /// The input the value was parsed from, holding the strings that were not copied.
+    input: &'a str,
+    index: usize
+}
+
+impl<'a> ArenaValue<'a> {
+    fn node(&self) -> Node {
+        self.arena.nodes[self.index]
+    }
+
+    fn text(&self, text: Text) -> &'a str {
+        match text {
+            Text::Arena(start, end) => &self.arena.text[start..end],
+            Text::Input(start, end) => &self.input[start..end]
+        }
+    }
+
+    /// Returns the type of the value.
+    pub fn kind(&self) -> JSONKind {
+        match self.node() {
+            Node::Null => JSONKind::Null,
+            Node::Boolean(_) => JSONKind::Boolean,
+            Node::Number(_) => JSONKind::Number,
+            Node::String(..) | Node::Key(..) => JSONKind::String,
+            Node::Array { .. } => JSONKind::Array,
+            Node::Object { .. } => JSONKind::Object
+        }
+    }
+
+    /// Returns the value as a string if it is a string.
+    pub fn as_str(&self) -> Option<&'a str> {
+        match self.node() {
+            Node::String(text) => Some(self.text(text)),
+            _ => None
+        }
+    }
+
+    /// Returns the value as a number if it is a number.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self.node() {
+            Node::Number(n) => Some(n),
+            _ => None
+        }
+    }
+
+    /// Returns the value as a boolean if it is a boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self.node() {
+            Node::Boolean(b) => Some(b),
+            _ => None
+        }
+    }
+
+    /// Returns true if the value is a null value.
+    pub fn is_null(&self) -> bool {
+        matches!(self.node(), Node::Null)
+    }
+
+    /// Returns the number of keys of an object or items of an array, 0 otherwise.
+    pub fn len(&self) -> usize {
+        match self.node() {
+            Node::Array { len, .. } | Node::Object { len, .. } => len,
+            _ => 0
+        }
+    }
+
+    /// Returns true if `len()` is 0.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over the items of an array.
+    /// Returns an empty iterator for any other value.
+    pub fn members(&self) -> impl Iterator<Item = ArenaValue<'a>> {
+        let value = *self;
+        let (mut index, end) = match self.node() {
+            Node::Array { end, .. } => (self.index + 1, end),
+            _ => (0, 0)
+        };
+
+        core::iter::from_fn(move || {
+            if index >= end {
+                return None;
+            }
+            let item = ArenaValue { index, ..value };
+
+            index = value.arena.next(index);
+            Some(item)
+        })
+    }
+
+    /// Returns an iterator over the key-value pairs of an object, in document order.
+    /// Returns an empty iterator for any other value.
+    pub fn entries(&self) -> impl Iterator<Item = (&'a str, ArenaValue<'a>)> {
+        let value = *self;
+        let (mut index, end) = match self.node() {
+            Node::Object { end, .. } => (self.index + 1, end),
+            _ => (0, 0)
+        };
+
+        core::iter::from_fn(move || {
+            if index >= end {
+                return None;
+            }
+            let key = match value.arena.nodes[index] {
+                Node::Key(text) => value.text(text),
+                _ => unreachable!("Object entries start with a key")
+            };
+            let item = ArenaValue { index: index + 1, ..value };
+
+            index = value.arena.next(index + 1);
+            Some((key, item))
+        })
+    }
+
+    /// Returns the value of the given key if the value is an object.
+    /// When a key is repeated, the last value wins, as with `JSONValue`.
+    pub fn get(&self, key: &str) -> Option<ArenaValue<'a>> {
+        self.entries().filter(|(k, _)| *k == key).map(|(_, value)| value).last()
+    }
+
+    /// Returns the item at the given position if the value is an array.
+    pub fn get_index(&self, index: usize) -> Option<ArenaValue<'a>> {
+        self.members().nth(index)
+    }
+
+    /// Copy the value out of the arena into an owned JSONValue.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{Arena, JSONParser, Serialize};
+    ///
+    /// let mut arena = Arena::new();
+    /// let json = JSONParser::new(r#"{ "ids": [1, 2], "name": "John Doe" }"#).parse_in(&mut arena).unwrap();
+    ///
+    /// assert_eq!(json.to_value().serialize(), r#"{"ids":[1,2],"name":"John Doe"}"#);
+    /// ```
+    pub fn to_value(&self) -> JSONValue {
+        match self.node() {
+            Node::Null => JSONValue::Null,
+            Node::Boolean(b) => JSONValue::Boolean(b),
+            Node::Number(n) => JSONValue::Number(n),
+            Node::String(text) | Node::Key(text) => JSONValue::String(self.text(text).into()),
+            Node::Array { .. } => JSONValue::Array(self.members().map(|item| item.to_value()).collect()),
+            Node::Object { .. } => {
+                let mut object = OrderedMap::new();
+
+                for (key, value) in self.entries() {
+                    object.insert(key, value.to_value());
+                }
+                JSONValue::Object(object)
+            }
+        }
+    }
+}
+
+impl<'a> core::fmt::Debug for ArenaValue<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self.to_value())
+    }
+}
@@ -0,0 +1,241 @@
+// `core::future::poll_fn` needs Rust 1.64, which the `async` feature requires.
+#![allow(clippy::incompatible_msrv)]
+
+use core::future::poll_fn;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use std::io::ErrorKind;
+
+use futures_core::Stream;
+use futures_io::AsyncRead;
+
+use crate::utils::events::{EventReader, Step};
+use crate::utils::{Event, Lexer, Parser};
+use crate::{JSONParser, JSONValue, ParseError};
+
+const CHUNK_SIZE: usize = 8192;
+
+/// Read the next chunk of the reader into the buffer, retrying interrupted reads.
+/// Returns the number of bytes read, 0 at the end of the input.
+fn poll_chunk<R: AsyncRead + Unpin>(reader: &mut R, cx: &mut Context<'_>, chunk: &mut [u8]) -> Poll<Result<usize, ParseError>> {
+    loop {
+        return match Pin::new(&mut *reader).poll_read(cx, chunk) {
+            Poll::Ready(Err(e)) if e.kind() == ErrorKind::Interrupted => continue,
+            Poll::Ready(Err(e)) => Poll::Ready(Err(ParseError::Io(format!("Failed to read input: {}", e)))),
+            Poll::Ready(Ok(read)) => Poll::Ready(Ok(read)),
+            Poll::Pending => Poll::Pending
+        };
+    }
+}
+
+impl<'a> JSONParser<'a> {
+    /// Read a whole document from an async reader without blocking the executor
+    /// while waiting for input, then parse it like `JSONParser::from_bytes`.
+    ///
+    /// Available with the `async` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures::executor::block_on;
+    /// use jsonparser::JSONParser;
+    ///
+    /// let body = r#"{ "name": "John Doe" }"#.as_bytes();
+    /// let json = block_on(JSONParser::from_async_reader(body)).unwrap();
+    ///
+    /// assert_eq!(json["name"].as_str(), Some("John Doe"));
+    /// ```
+    pub async fn from_async_reader<R: AsyncRead + Unpin>(mut reader: R) -> Result<JSONValue, ParseError> {
+        let mut input = Vec::new();
+        let mut chunk = vec![0; CHUNK_SIZE];
+
+        loop {
+            match poll_fn(|cx| poll_chunk(&mut reader, cx, &mut chunk)).await? {
+                0 => return JSONParser::from_bytes(&input),
+                read => input.extend_from_slice(&chunk[..read])
+            }
+        }
+    }
+
+    /// Read a document from an async reader as a stream of events, produced as soon as
+    /// the input holding them arrives, so that large bodies can be processed without
+    /// being held in memory. Unlike `JSONParser::from`, any value is accepted as the root.
+    /// The stream ends after the first error.
+    ///
+    /// Available with the `async` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures::executor::block_on_stream;
+    /// use jsonparser::{Event, JSONParser, JSONValue};
+    ///
+    /// let body = r#"{ "tags": ["a", 1] }"#.as_bytes();
+    /// let events = block_on_stream(JSONParser::stream_events_async(body)).collect::<Result<Vec<_>, _>>().unwrap();
+    ///
+    /// assert_eq!(events, vec![
+    ///     Event::StartObject,
+    ///     Event::Key("tags".to_string()),
+    ///     Event::StartArray,
+    ///     Event::Value(JSONValue::String("a".into())),
+    ///     Event::Value(JSONValue::Number(1.0)),
+    ///     Event::EndArray,
+    ///     Event::EndObject
+    /// ]);
+    ///
+    /// let mut events = block_on_stream(JSONParser::stream_events_async(r#"{ "tags": ["a" }"#.as_bytes()));
+    ///
+    /// assert_eq!(events.nth(4).unwrap().unwrap_err().to_string(), "Unexpected '}' (offset: 15)");
+    /// assert!(events.next().is_none());
+    /// ```
+    pub fn stream_events_async<R: AsyncRead + Unpin>(reader: R) -> AsyncEventStream<R> {
+        AsyncEventStream {
+            reader,
+            events: EventReader::new(),
+            chunk: vec![0; CHUNK_SIZE],
+            done: false
+        }
+    }
+
+    /// Read newline-delimited JSON from an async reader as a stream of values, one per
+    /// non-blank line. Any value is accepted on a line, and errors are prefixed with the
+    /// line number. The stream ends after the first error.
+    ///
+    /// Available with the `async` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures::executor::block_on_stream;
+    /// use jsonparser::JSONParser;
+    ///
+    /// let body = "{ \"amount\": 2 }\n\n[1, 2]\n{ \"amount\": }\n".as_bytes();
+    /// let mut lines = block_on_stream(JSONParser::stream_ndjson_async(body));
+    ///
+    /// assert_eq!(lines.next().unwrap().unwrap()["amount"].as_f64(), Some(2.0));
+    /// assert_eq!(lines.next().unwrap().unwrap().as_array().map(|items| items.len()), Some(2));
+    /// assert_eq!(lines.next().unwrap().unwrap_err().to_string(), "Line 4: Unexpected token: CloseBrace");
+    /// assert!(lines.next().is_none());
+    /// ```
+    pub fn stream_ndjson_async<R: AsyncRead + Unpin>(reader: R) -> AsyncNdjsonStream<R> {
+        AsyncNdjsonStream {
+            reader,
+            buffer: Vec::new(),
+            chunk: vec![0; CHUNK_SIZE],
+            line: 0,
+            eof: false,
+            done: false
+        }
+    }
+}
+
+/// A stream of the events of a document read from an async reader.
+/// Created by `JSONParser::stream_events_async`.
+pub struct AsyncEventStream<R> {
+    reader: R,
+    events: EventReader,
+    chunk: Vec<u8>,
+    done: bool
+}
+
+impl<R: AsyncRead + Unpin> Stream for AsyncEventStream<R> {
+    type Item = Result<Event, ParseError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        while !this.done {
+            let error = match this.events.next_step() {
+                Ok(Step::Event(event)) => return Poll::Ready(Some(Ok(event))),
+                Ok(Step::Done) => break,
+                Ok(Step::NeedMoreData) => match poll_chunk(&mut this.reader, cx, &mut this.chunk) {
+                    Poll::Ready(Ok(0)) => {
+                        this.events.finish();
+                        continue;
+                    },
+                    Poll::Ready(Ok(read)) => {
+                        this.events.feed(&this.chunk[..read]);
+                        continue;
+                    },
+                    Poll::Ready(Err(e)) => e,
+                    Poll::Pending => return Poll::Pending
+                },
+                Err(e) => e
+            };
+
+            this.done = true;
+            return Poll::Ready(Some(Err(error)));
+        }
+        this.done = true;
+        Poll::Ready(None)
+    }
+}
+
+/// A stream of the values of newline-delimited JSON read from an async reader.
+/// Created by `JSONParser::stream_ndjson_async`.
+pub struct AsyncNdjsonStream<R> {
+    reader: R,
+    /// Input read past the last complete line.
+    buffer: Vec<u8>,
+    chunk: Vec<u8>,
+    /// Number of the last line taken from the buffer.
+    line: usize,
+    eof: bool,
+    done: bool
+}
+
+impl<R> AsyncNdjsonStream<R> {
+    /// Take the next complete line from the buffer, or the rest of it at the end of the input.
+    fn take_line(&mut self) -> Option<Vec<u8>> {
+        let end = match self.buffer.iter().position(|b| *b == b'\n') {
+            Some(i) => i + 1,
+            None if self.eof && !self.buffer.is_empty() => self.buffer.len(),
+            None => return None
+        };
+
+        self.line += 1;
+        Some(self.buffer.drain(..end).collect())
+    }
+}
+
+impl<R: AsyncRead + Unpin> Stream for AsyncNdjsonStream<R> {
+    type Item = Result<JSONValue, ParseError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        while !this.done {
+            if let Some(line) = this.take_line() {
+                let result = core::str::from_utf8(&line)
+                    .map_err(|_| ParseError::Syntax("Invalid UTF-8 input".to_string()))
+                    .and_then(|line| match line.trim() {
+                        "" => Ok(None),
+                        line => Parser::new(Lexer::new(line)).parse_document().map(Some)
+                    });
+
+                match result {
+                    Ok(None) => continue,
+                    Ok(Some(value)) => return Poll::Ready(Some(Ok(value))),
+                    Err(e) => {
+                        this.done = true;
+                        return Poll::Ready(Some(Err(ParseError::Syntax(format!("Line {}: {}", this.line, e)))));
+                    }
+                }
+            }
+            if this.eof {
+                break;
+            }
+            match poll_chunk(&mut this.reader, cx, &mut this.chunk) {
+                Poll::Ready(Ok(0)) => this.eof = true,
+                Poll::Ready(Ok(read)) => this.buffer.extend_from_slice(&this.chunk[..read]),
+                Poll::Ready(Err(e)) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(e)));
+                },
+                Poll::Pending => return Poll::Pending
+            }
+        }
+        this.done = true;
+        Poll::Ready(None)
+    }
+}
@@ -0,0 +1,144 @@
+use crate::utils::compat::*;
+use crate::{JSONValue, OrderedMap};
+
+/// A fluent builder for JSON objects.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{ObjectBuilder, Serialize};
+///
+/// let json = ObjectBuilder::new()
+///     .string("name", "John Doe")
+///     .number("age", 30)
+///     .boolean("is_student", false)
+///     .array("cars", |a| a.object(|o| o.string("model", "Ford").number("year", 2018)))
+///     .object("address", |o| o.string("city", "Springfield"))
+///     .null("spouse")
+///     .build();
+///
+/// assert_eq!(
+///     json.serialize(),
+///     r#"{"name":"John Doe","age":30,"is_student":false,"cars":[{"model":"Ford","year":2018}],"address":{"city":"Springfield"},"spouse":null}"#
+/// );
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct ObjectBuilder {
+    object: OrderedMap<JSONValue>
+}
+
+impl ObjectBuilder {
+    /// Create a new ObjectBuilder instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a property to the given value.
+    pub fn value(mut self, key: &str, value: JSONValue) -> Self {
+        self.object.insert(key, value);
+        self
+    }
+
+    /// Set a property to a string.
+    pub fn string(self, key: &str, value: &str) -> Self {
+        self.value(key, JSONValue::String(value.into()))
+    }
+
+    /// Set a property to a number.
+    pub fn number<N: Into<f64>>(self, key: &str, value: N) -> Self {
+        self.value(key, JSONValue::Number(value.into()))
+    }
+
+    /// Set a property to a boolean.
+    pub fn boolean(self, key: &str, value: bool) -> Self {
+        self.value(key, JSONValue::Boolean(value))
+    }
+
+    /// Set a property to null.
+    pub fn null(self, key: &str) -> Self {
+        self.value(key, JSONValue::Null)
+    }
+
+    /// Set a property to an array built by the given function.
+    pub fn array<F: FnOnce(ArrayBuilder) -> ArrayBuilder>(self, key: &str, build: F) -> Self {
+        self.value(key, build(ArrayBuilder::new()).build())
+    }
+
+    /// Set a property to an object built by the given function.
+    pub fn object<F: FnOnce(ObjectBuilder) -> ObjectBuilder>(self, key: &str, build: F) -> Self {
+        self.value(key, build(ObjectBuilder::new()).build())
+    }
+
+    /// Build the JSONValue.
+    pub fn build(self) -> JSONValue {
+        JSONValue::Object(self.object)
+    }
+}
+
+/// A fluent builder for JSON arrays.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{ArrayBuilder, Serialize};
+///
+/// let json = ArrayBuilder::new()
+///     .number(1)
+///     .string("two")
+///     .array(|a| a.boolean(true).null())
+///     .build();
+///
+/// assert_eq!(json.serialize(), r#"[1,"two",[true,null]]"#);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct ArrayBuilder {
+    array: Vec<JSONValue>
+}
+
+impl ArrayBuilder {
+    /// Create a new ArrayBuilder instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append the given value.
+    pub fn push(mut self, value: JSONValue) -> Self {
+        self.array.push(value);
+        self
+    }
+
+    /// Append a string.
+    pub fn string(self, value: &str) -> Self {
+        self.push(JSONValue::String(value.into()))
+    }
+
+    /// Append a number.
+    pub fn number<N: Into<f64>>(self, value: N) -> Self {
+        self.push(JSONValue::Number(value.into()))
+    }
+
+    /// Append a boolean.
+    pub fn boolean(self, value: bool) -> Self {
+        self.push(JSONValue::Boolean(value))
+    }
+
+    /// Append null.
+    pub fn null(self) -> Self {
+        self.push(JSONValue::Null)
+    }
+
+    /// Append an array built by the given function.
+    pub fn array<F: FnOnce(ArrayBuilder) -> ArrayBuilder>(self, build: F) -> Self {
+        self.push(build(ArrayBuilder::new()).build())
+    }
+
+    /// Append an object built by the given function.
+    pub fn object<F: FnOnce(ObjectBuilder) -> ObjectBuilder>(self, build: F) -> Self {
+        self.push(build(ObjectBuilder::new()).build())
+    }
+
+    /// Build the JSONValue.
+    pub fn build(self) -> JSONValue {
+        JSONValue::Array(self.array.into())
+    }
+}
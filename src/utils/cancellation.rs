@@ -0,0 +1,41 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// A flag shared between a long parse or validation and the code that may abort it,
+/// such as a UI or a watchdog thread. Clones share the same flag.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{CancellationToken, JSONParser, ParseError};
+///
+/// let token = CancellationToken::new();
+/// let input = r#"{ "name": "John Doe" }"#;
+///
+/// assert!(JSONParser::new(input).cancellation(token.clone()).parse().is_ok());
+///
+/// token.cancel();
+///
+/// assert_eq!(JSONParser::new(input).cancellation(token).parse(), Err(ParseError::Cancelled));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>
+}
+
+impl CancellationToken {
+    /// Create a new CancellationToken instance, not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask every parse or validation holding a clone of the token to stop.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether `cancel` was called on the token or one of its clones.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
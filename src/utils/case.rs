@@ -0,0 +1,131 @@
+use crate::utils::compat::*;
+use crate::utils::JSONValue;
+
+/// A naming convention for object keys, used by `JSONValue::rename_keys_deep`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Case {
+    /// `userId`
+    CamelCase,
+    /// `UserId`
+    PascalCase,
+    /// `user_id`
+    SnakeCase,
+    /// `user-id`
+    KebabCase
+}
+
+impl Case {
+    /// Write the key in this case. Words are split at underscores, hyphens, spaces and
+    /// changes of case, so that `HTTPServer` holds the words `HTTP` and `Server`. Leading
+    /// underscores and hyphens are kept, so that `_id` stays `_id`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::Case;
+    ///
+    /// assert_eq!(Case::SnakeCase.convert("userId"), "user_id");
+    /// assert_eq!(Case::CamelCase.convert("created_at"), "createdAt");
+    /// assert_eq!(Case::KebabCase.convert("HTTPServer2Port"), "http-server2-port");
+    /// assert_eq!(Case::PascalCase.convert("__typename-field"), "__TypenameField");
+    /// ```
+    pub fn convert(&self, key: &str) -> String {
+        let rest = key.trim_start_matches(['_', '-']);
+        let mut output = key[..key.len() - rest.len()].to_string();
+        let separator = match self {
+            Case::SnakeCase => "_",
+            Case::KebabCase => "-",
+            Case::CamelCase | Case::PascalCase => ""
+        };
+
+        for (i, word) in words(rest).iter().enumerate() {
+            if i > 0 {
+                output.push_str(separator);
+            }
+            match self {
+                Case::CamelCase if i == 0 => output.push_str(&word.to_lowercase()),
+                Case::CamelCase | Case::PascalCase => {
+                    let mut chars = word.chars();
+
+                    output.extend(chars.next().into_iter().flat_map(char::to_uppercase));
+                    output.push_str(&chars.as_str().to_lowercase());
+                },
+                Case::SnakeCase | Case::KebabCase => output.push_str(&word.to_lowercase())
+            }
+        }
+        output
+    }
+}
+
+/// Split a key into words, at separators and where a lowercase letter or digit is followed
+/// by an uppercase letter, or an uppercase letter is followed by an uppercase and a lowercase one.
+fn words(key: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+
+    for part in key.split(['_', '-', ' ']).filter(|part| !part.is_empty()) {
+        let chars = part.char_indices().collect::<Vec<_>>();
+        let mut start = 0;
+
+        for window in 1..chars.len() {
+            let (index, c) = chars[window];
+            let previous = chars[window - 1].1;
+            let next = chars.get(window + 1).map(|(_, c)| *c);
+            let boundary = c.is_uppercase()
+                && (previous.is_lowercase() || previous.is_numeric() || (previous.is_uppercase() && next.map_or(false, char::is_lowercase)));
+
+            if boundary {
+                words.push(&part[start..index]);
+                start = index;
+            }
+        }
+        words.push(&part[start..]);
+    }
+    words
+}
+
+impl JSONValue {
+    /// Rename the keys of every object in the tree to the given case. Keys listed in `skip`
+    /// keep their name and their value is left untouched, such as for maps whose keys are data.
+    /// When two keys of an object get the same name, the last value is kept at the position
+    /// of the first key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{Case, JSONParser, Serialize};
+    ///
+    /// let mut json = JSONParser::from(r#"{
+    ///   "userId": 1,
+    ///   "_id": "a1",
+    ///   "billingAddress": { "zipCode": "75001" },
+    ///   "recentOrders": [{ "orderId": 7 }],
+    ///   "extraData": { "someKey": true }
+    /// }"#).unwrap();
+    ///
+    /// json.rename_keys_deep(Case::SnakeCase, &["extraData"]);
+    ///
+    /// assert_eq!(
+    ///     json.serialize(),
+    ///     r#"{"user_id":1,"_id":"a1","billing_address":{"zip_code":"75001"},"recent_orders":[{"order_id":7}],"extraData":{"someKey":true}}"#
+    /// );
+    /// ```
+    pub fn rename_keys_deep(&mut self, case: Case, skip: &[&str]) {
+        match self {
+            JSONValue::Object(obj) => {
+                *obj = core::mem::take(obj).into_iter().map(|(key, mut value)| {
+                    if skip.contains(&key.as_str()) {
+                        return (key, value);
+                    }
+                    value.rename_keys_deep(case, skip);
+                    (case.convert(&key), value)
+                }).collect();
+            },
+            JSONValue::Array(array) => {
+                for value in array.iter_mut() {
+                    value.rename_keys_deep(case, skip);
+                }
+            },
+            _ => {}
+        }
+    }
+}
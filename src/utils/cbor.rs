@@ -0,0 +1,368 @@
+use crate::utils::compat::*;
+use crate::utils::{JSONValue, OrderedMap, Serialize, DEFAULT_MAX_DEPTH};
+
+impl JSONValue {
+    /// Encode the value as CBOR (RFC 8949). Numbers holding an integer are written
+    /// as the shortest integer that fits them, others as single precision floats
+    /// when no precision is lost and as double precision floats otherwise.
+    /// Strings, arrays and maps always have a definite length.
+    ///
+    /// Available with the `cbor` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "id": 1, "tags": ["a"], "ratio": 0.5 }"#).unwrap();
+    ///
+    /// assert_eq!(json.to_cbor(), b"\xa3\x62id\x01\x64tags\x81\x61a\x65ratio\xfa\x3f\x00\x00\x00");
+    /// ```
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut output = Vec::new();
+
+        self.write_cbor(&mut output);
+        output
+    }
+
+    fn write_cbor(&self, output: &mut Vec<u8>) {
+        match self {
+            JSONValue::Null => output.push(0xf6),
+            JSONValue::Boolean(false) => output.push(0xf4),
+            JSONValue::Boolean(true) => output.push(0xf5),
+            JSONValue::Number(n) => write_number(*n, output),
+            JSONValue::Raw(raw) => raw.to_value().write_cbor(output),
+            JSONValue::String(s) => {
+                write_head(3, s.len() as u64, output);
+                output.extend_from_slice(s.as_bytes());
+            },
+            JSONValue::Array(array) => {
+                write_head(4, array.len() as u64, output);
+                for value in array.iter() {
+                    value.write_cbor(output);
+                }
+            },
+            JSONValue::Object(obj) => {
+                write_head(5, obj.len() as u64, output);
+                for (key, value) in obj.iter() {
+                    write_head(3, key.len() as u64, output);
+                    output.extend_from_slice(key.as_bytes());
+                    value.write_cbor(output);
+                }
+            }
+        }
+    }
+
+    /// Decode a CBOR value, converting it to JSON as recommended by RFC 8949, section 6.1:
+    ///
+    /// - integers, bignums (tags 2 and 3) and floats become numbers,
+    ///   except NaN and infinities which become null;
+    /// - byte strings become base64url strings without padding, or base64 and base16
+    ///   strings when tagged with the expected conversion (tags 22 and 23);
+    /// - undefined and unassigned simple values become null;
+    /// - map keys that are not strings become their JSON text;
+    /// - other tags are dropped, keeping their content.
+    ///
+    /// Indefinite length strings, arrays and maps are accepted. Bytes left after
+    /// the value, and arrays, maps and tags nested deeper than `DEFAULT_MAX_DEPTH`, are
+    /// rejected.
+    ///
+    /// Available with the `cbor` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONValue};
+    ///
+    /// let json = JSONParser::from(r#"{ "id": -300, "ratio": 0.1, "tags": ["a", null, true] }"#).unwrap();
+    ///
+    /// assert_eq!(JSONValue::from_cbor(&json.to_cbor()), Ok(json));
+    ///
+    /// // { 1: h'0102', "at": 1(1700000000) } with an indefinite length map
+    /// let sensor = JSONValue::from_cbor(b"\xbf\x01\x42\x01\x02\x62at\xc1\x1a\x65\x53\xf1\x00\xff").unwrap();
+    ///
+    /// assert_eq!(sensor["1"].as_str(), Some("AQI"));
+    /// assert_eq!(sensor["at"].as_f64(), Some(1700000000.0));
+    ///
+    /// let nested = vec![0x81; 1_000_000];
+    ///
+    /// assert_eq!(JSONValue::from_cbor(&nested).unwrap_err(), "Maximum nesting depth exceeded (max: 128, offset: 128)");
+    /// ```
+    pub fn from_cbor(input: &[u8]) -> Result<JSONValue, String> {
+        let mut decoder = Decoder { input, offset: 0, depth: 0 };
+        let value = decoder.value(None)?;
+
+        if decoder.offset < input.len() {
+            return Err(format!("Unexpected data after the value (offset: {})", decoder.offset));
+        }
+        Ok(value)
+    }
+}
+
+fn write_head(major: u8, n: u64, output: &mut Vec<u8>) {
+    let major = major << 5;
+
+    match n {
+        n if n < 24 => output.push(major | n as u8),
+        n if n <= u8::MAX as u64 => output.extend_from_slice(&[major | 24, n as u8]),
+        n if n <= u16::MAX as u64 => {
+            output.push(major | 25);
+            output.extend_from_slice(&(n as u16).to_be_bytes());
+        },
+        n if n <= u32::MAX as u64 => {
+            output.push(major | 26);
+            output.extend_from_slice(&(n as u32).to_be_bytes());
+        },
+        n => {
+            output.push(major | 27);
+            output.extend_from_slice(&n.to_be_bytes());
+        }
+    }
+}
+
+fn write_number(n: f64, output: &mut Vec<u8>) {
+    let integral = n.fract() == 0.0 && !(n == 0.0 && n.is_sign_negative());
+
+    if integral && (0.0..18446744073709551616.0).contains(&n) {
+        write_head(0, n as u64, output);
+    } else if integral && (-18446744073709551616.0..0.0).contains(&n) {
+        write_head(1, (-1.0 - n) as u64, output);
+    } else if (n as f32) as f64 == n || n.is_nan() {
+        output.push(0xfa);
+        output.extend_from_slice(&(n as f32).to_be_bytes());
+    } else {
+        output.push(0xfb);
+        output.extend_from_slice(&n.to_be_bytes());
+    }
+}
+
+/// The conversion to text applied to byte strings, set by tags 21 to 23.
+#[derive(Clone, Copy)]
+enum Conversion {
+    Base64Url,
+    Base64,
+    Base16
+}
+
+fn encode_bytes(bytes: &[u8], conversion: Conversion) -> String {
+    const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    const BASE64URL: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+    let alphabet = match conversion {
+        Conversion::Base16 => return bytes.iter().map(|b| format!("{:02x}", b)).collect(),
+        Conversion::Base64 => BASE64,
+        Conversion::Base64Url => BASE64URL
+    };
+    let mut encoded = String::with_capacity((bytes.len() + 2) / 3 * 4);
+
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+
+        for i in 0..=chunk.len() {
+            encoded.push(alphabet[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+        if let Conversion::Base64 = conversion {
+            for _ in chunk.len()..3 {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+fn half_to_f64(bits: u16) -> f64 {
+    let exponent = (bits >> 10) & 0x1f;
+    let mantissa = (bits & 0x3ff) as f64;
+    let value = match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (mantissa + 1024.0) * 2f64.powi(exponent as i32 - 25)
+    };
+
+    if bits & 0x8000 != 0 { -value } else { value }
+}
+
+struct Decoder<'a> {
+    input: &'a [u8],
+    offset: usize,
+    depth: usize
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let bytes = self.input.get(self.offset..self.offset.saturating_add(len))
+            .ok_or_else(|| format!("Unexpected end of input (offset: {})", self.input.len()))?;
+
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn uint(&mut self, size: usize) -> Result<u64, String> {
+        Ok(self.take(size)?.iter().fold(0, |n, &b| n << 8 | b as u64))
+    }
+
+    /// Read the argument following the initial byte. None stands for an indefinite length.
+    fn argument(&mut self, info: u8, offset: usize) -> Result<Option<u64>, String> {
+        match info {
+            0..=23 => Ok(Some(info as u64)),
+            24..=27 => Ok(Some(self.uint(1 << (info - 24))?)),
+            31 => Ok(None),
+            _ => Err(format!("Invalid additional information {} (offset: {})", info, offset))
+        }
+    }
+
+    fn at_break(&mut self) -> Result<bool, String> {
+        let end = self.input.get(self.offset) == Some(&0xff);
+
+        if end {
+            self.offset += 1;
+        } else if self.offset >= self.input.len() {
+            return Err(format!("Unexpected end of input (offset: {})", self.offset));
+        }
+        Ok(end)
+    }
+
+    fn value(&mut self, conversion: Option<Conversion>) -> Result<JSONValue, String> {
+        let offset = self.offset;
+        let initial = self.take(1)?[0];
+        let (major, info) = (initial >> 5, initial & 0x1f);
+
+        if major == 7 {
+            return self.simple(info, offset);
+        }
+        let argument = self.argument(info, offset)?;
+        let nested = matches!(major, 4..=6);
+
+        if nested {
+            self.enter(offset)?;
+        }
+        let value = match (major, argument) {
+            (0, Some(n)) => JSONValue::Number(n as f64),
+            (1, Some(n)) => JSONValue::Number(-1.0 - n as f64),
+            (2, _) => JSONValue::String(encode_bytes(&self.chunks(2, argument, offset)?, conversion.unwrap_or(Conversion::Base64Url)).into()),
+            (3, _) => {
+                let bytes = self.chunks(3, argument, offset)?;
+
+                JSONValue::String(String::from_utf8(bytes).map_err(|_| format!("Invalid UTF-8 in string (offset: {})", offset))?.into())
+            },
+            (4, len) => {
+                // Every item takes at least one byte, so a bogus length cannot over-allocate.
+                let mut array = Vec::with_capacity(len.unwrap_or(0).min((self.input.len() - self.offset) as u64) as usize);
+
+                match len {
+                    Some(len) => for _ in 0..len {
+                        array.push(self.value(conversion)?);
+                    },
+                    None => while !self.at_break()? {
+                        array.push(self.value(conversion)?);
+                    }
+                }
+                JSONValue::Array(array.into())
+            },
+            (5, len) => {
+                // Every entry takes at least two bytes.
+                let mut obj = OrderedMap::with_capacity(len.unwrap_or(0).min((self.input.len() - self.offset) as u64 / 2) as usize);
+                let mut remaining = len;
+
+                loop {
+                    match remaining {
+                        Some(0) => break,
+                        Some(n) => remaining = Some(n - 1),
+                        None if self.at_break()? => break,
+                        None => {}
+                    }
+                    let key = match self.value(conversion)? {
+                        JSONValue::String(key) => key,
+                        key => key.serialize().into()
+                    };
+
+                    obj.insert(&key, self.value(conversion)?);
+                }
+                JSONValue::Object(obj)
+            },
+            (6, Some(tag)) => self.tagged(tag, conversion)?,
+            _ => return Err(format!("Indefinite length is not allowed for major type {} (offset: {})", major, offset))
+        };
+
+        if nested {
+            self.depth -= 1;
+        }
+        Ok(value)
+    }
+
+    /// Count a nested array, map or tag, failing past `DEFAULT_MAX_DEPTH` levels.
+    fn enter(&mut self, offset: usize) -> Result<(), String> {
+        if self.depth >= DEFAULT_MAX_DEPTH {
+            return Err(format!("Maximum nesting depth exceeded (max: {}, offset: {})", DEFAULT_MAX_DEPTH, offset));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn simple(&mut self, info: u8, offset: usize) -> Result<JSONValue, String> {
+        let number = match info {
+            20 => return Ok(JSONValue::Boolean(false)),
+            21 => return Ok(JSONValue::Boolean(true)),
+            22 | 23 => return Ok(JSONValue::Null),
+            24 => {
+                self.take(1)?;
+                return Ok(JSONValue::Null);
+            },
+            25 => half_to_f64(self.uint(2)? as u16),
+            26 => f32::from_bits(self.uint(4)? as u32) as f64,
+            27 => f64::from_bits(self.uint(8)?),
+            31 => return Err(format!("Unexpected break (offset: {})", offset)),
+            0..=19 => return Ok(JSONValue::Null),
+            _ => return Err(format!("Invalid additional information {} (offset: {})", info, offset))
+        };
+
+        if !number.is_finite() {
+            return Ok(JSONValue::Null);
+        }
+        Ok(JSONValue::Number(number))
+    }
+
+    fn tagged(&mut self, tag: u64, conversion: Option<Conversion>) -> Result<JSONValue, String> {
+        let offset = self.offset;
+
+        match tag {
+            2 | 3 => {
+                let initial = self.take(1)?[0];
+                let argument = self.argument(initial & 0x1f, offset)?;
+
+                if initial >> 5 != 2 {
+                    return Err(format!("Bignum must hold a byte string (offset: {})", offset));
+                }
+                let magnitude = self.chunks(2, argument, offset)?.iter().fold(0.0, |n, &b| n * 256.0 + b as f64);
+
+                Ok(JSONValue::Number(if tag == 2 { magnitude } else { -1.0 - magnitude }))
+            },
+            21 => self.value(Some(Conversion::Base64Url)),
+            22 => self.value(Some(Conversion::Base64)),
+            23 => self.value(Some(Conversion::Base16)),
+            _ => self.value(conversion)
+        }
+    }
+
+    /// Read the content of a byte or text string, joining the chunks of an indefinite length one.
+    fn chunks(&mut self, major: u8, len: Option<u64>, offset: usize) -> Result<Vec<u8>, String> {
+        if let Some(len) = len {
+            return Ok(self.take(len as usize)?.to_vec());
+        }
+        let mut bytes = Vec::new();
+
+        while !self.at_break()? {
+            let initial = self.take(1)?[0];
+
+            if initial >> 5 != major {
+                return Err(format!("Invalid chunk in indefinite length string (offset: {})", offset));
+            }
+            match self.argument(initial & 0x1f, offset)? {
+                Some(len) => bytes.extend_from_slice(self.take(len as usize)?),
+                None => return Err(format!("Nested indefinite length string (offset: {})", offset))
+            }
+        }
+        Ok(bytes)
+    }
+}
@@ -0,0 +1,79 @@
+//! The parts of `std` used by the crate that `core` and `alloc` lack, so that it
+//! builds without the `std` feature.
+
+pub(crate) use alloc::boxed::Box;
+pub(crate) use alloc::string::{String, ToString};
+pub(crate) use alloc::vec::Vec;
+pub(crate) use alloc::{format, vec};
+
+/// Maps and sets keep their keys in order without `std`, as hashing needs a source of randomness.
+#[cfg(feature = "std")]
+pub(crate) use std::collections::{HashMap as Map, HashSet as Set};
+#[cfg(not(feature = "std"))]
+pub(crate) use alloc::collections::{BTreeMap as Map, BTreeSet as Set};
+
+/// The rounding methods of f64 that are only available with `std`.
+#[cfg(not(feature = "std"))]
+pub(crate) trait Float {
+    fn trunc(self) -> f64;
+    fn fract(self) -> f64;
+    fn floor(self) -> f64;
+    fn ceil(self) -> f64;
+    fn round(self) -> f64;
+    /// `core` has its own `f64::abs` from Rust 1.85 on, which takes precedence.
+    #[allow(dead_code)]
+    fn abs(self) -> f64;
+    #[cfg_attr(not(feature = "cbor"), allow(dead_code))]
+    fn powi(self, n: i32) -> f64;
+}
+
+#[cfg(not(feature = "std"))]
+impl Float for f64 {
+    fn trunc(self) -> f64 {
+        // From 2^52 on, every f64 is an integer.
+        if self.is_nan() || !(-4503599627370496.0..=4503599627370496.0).contains(&self) {
+            return self;
+        }
+        self as i64 as f64
+    }
+
+    fn fract(self) -> f64 {
+        self - Float::trunc(self)
+    }
+
+    fn floor(self) -> f64 {
+        let trunc = Float::trunc(self);
+
+        if self < trunc { trunc - 1.0 } else { trunc }
+    }
+
+    fn ceil(self) -> f64 {
+        let trunc = Float::trunc(self);
+
+        if self > trunc { trunc + 1.0 } else { trunc }
+    }
+
+    /// Round half away from zero, like `f64::round`.
+    fn round(self) -> f64 {
+        let trunc = Float::trunc(self);
+
+        match self - trunc {
+            fract if fract >= 0.5 => trunc + 1.0,
+            fract if fract <= -0.5 => trunc - 1.0,
+            _ => trunc
+        }
+    }
+
+    fn abs(self) -> f64 {
+        f64::from_bits(self.to_bits() & !(1 << 63))
+    }
+
+    fn powi(self, n: i32) -> f64 {
+        let mut result = 1.0;
+
+        for _ in 0..n.unsigned_abs() {
+            result *= self;
+        }
+        if n < 0 { 1.0 / result } else { result }
+    }
+}
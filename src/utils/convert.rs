@@ -0,0 +1,143 @@
+use alloc::collections::BTreeMap;
+use core::convert::TryFrom;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::hash::BuildHasher;
+
+use crate::utils::compat::*;
+use crate::utils::{AccessError, JSONKind, JSONValue, OrderedMap};
+
+/// The entries of an object, or an error naming the type of any other value. A raw
+/// value holding an object is parsed.
+fn into_object(value: JSONValue) -> Result<OrderedMap<JSONValue>, AccessError> {
+    match value {
+        JSONValue::Object(obj) => Ok(obj),
+        JSONValue::Raw(raw) if raw.kind() == JSONKind::Object => into_object(raw.to_value()),
+        value => Err(AccessError::WrongType { path: String::new(), expected: JSONKind::Object, found: value.kind() })
+    }
+}
+
+/// Build an object from the entries in order. A repeated key keeps its first position
+/// and its last value, as with `OrderedMap::insert`.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{JSONValue, Serialize};
+///
+/// let value = JSONValue::from(vec![
+///     ("b".to_string(), JSONValue::Number(1.0)),
+///     ("a".to_string(), JSONValue::Null)
+/// ]);
+///
+/// assert_eq!(value.serialize(), r#"{"b":1,"a":null}"#);
+/// ```
+impl From<Vec<(String, JSONValue)>> for JSONValue {
+    fn from(entries: Vec<(String, JSONValue)>) -> Self {
+        JSONValue::Object(entries.into_iter().collect())
+    }
+}
+
+/// Build an object with the keys in sorted order.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use jsonparser::{JSONValue, Serialize};
+///
+/// let mut map = BTreeMap::new();
+///
+/// map.insert("b".to_string(), JSONValue::Boolean(true));
+/// map.insert("a".to_string(), JSONValue::String("x".into()));
+///
+/// assert_eq!(JSONValue::from(map).serialize(), r#"{"a":"x","b":true}"#);
+/// ```
+impl From<BTreeMap<String, JSONValue>> for JSONValue {
+    fn from(map: BTreeMap<String, JSONValue>) -> Self {
+        JSONValue::Object(map.into_iter().collect())
+    }
+}
+
+/// Build an object with the keys in the iteration order of the map, which is unspecified.
+/// Collect the map into a BTreeMap first for a stable order.
+#[cfg(feature = "std")]
+impl<S: BuildHasher> From<HashMap<String, JSONValue, S>> for JSONValue {
+    fn from(map: HashMap<String, JSONValue, S>) -> Self {
+        JSONValue::Object(map.into_iter().collect())
+    }
+}
+
+/// The entries of an object, in order.
+///
+/// # Example
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use jsonparser::{AccessError, JSONKind, JSONParser, JSONValue};
+///
+/// let json = JSONParser::from(r#"{ "b": 1, "a": 2 }"#).unwrap();
+/// let entries = Vec::<(String, JSONValue)>::try_from(json).unwrap();
+///
+/// assert_eq!(entries[0], ("b".to_string(), JSONValue::Number(1.0)));
+///
+/// let error = Vec::<(String, JSONValue)>::try_from(JSONValue::Array(Box::new([]))).unwrap_err();
+///
+/// assert_eq!(error, AccessError::WrongType { path: String::new(), expected: JSONKind::Object, found: JSONKind::Array });
+/// assert_eq!(error.to_string(), "Expected object, found array");
+/// ```
+impl TryFrom<JSONValue> for Vec<(String, JSONValue)> {
+    type Error = AccessError;
+
+    fn try_from(value: JSONValue) -> Result<Self, Self::Error> {
+        Ok(into_object(value)?.into_iter().collect())
+    }
+}
+
+/// The entries of an object, sorted by key.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use std::convert::TryFrom;
+/// use jsonparser::{JSONParser, JSONValue};
+///
+/// let json = JSONParser::from(r#"{ "b": 1, "a": 2 }"#).unwrap();
+/// let map = BTreeMap::try_from(json).unwrap();
+///
+/// assert_eq!(map.keys().collect::<Vec<_>>(), ["a", "b"]);
+/// assert!(BTreeMap::try_from(JSONValue::Null).is_err());
+/// ```
+impl TryFrom<JSONValue> for BTreeMap<String, JSONValue> {
+    type Error = AccessError;
+
+    fn try_from(value: JSONValue) -> Result<Self, Self::Error> {
+        Ok(into_object(value)?.into_iter().collect())
+    }
+}
+
+/// The entries of an object, in a hash map.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use std::convert::TryFrom;
+/// use jsonparser::{JSONParser, JSONValue};
+///
+/// let json = JSONParser::from(r#"{ "name": "John" }"#).unwrap();
+/// let map: HashMap<String, JSONValue> = HashMap::try_from(json.clone()).unwrap();
+///
+/// assert_eq!(map["name"].as_str(), Some("John"));
+/// assert_eq!(JSONValue::from(map), json);
+/// ```
+#[cfg(feature = "std")]
+impl<S: BuildHasher + Default> TryFrom<JSONValue> for HashMap<String, JSONValue, S> {
+    type Error = AccessError;
+
+    fn try_from(value: JSONValue) -> Result<Self, Self::Error> {
+        Ok(into_object(value)?.into_iter().collect())
+    }
+}
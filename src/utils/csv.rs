@@ -0,0 +1,333 @@
+use crate::utils::compat::*;
+use crate::utils::parser::edit_items;
+use crate::utils::{JSONValue, Lexer, OrderedMap, Parser, Serialize};
+
+/// Options used by `JSONValue::to_csv_with` and `JSONValue::from_csv_with`.
+/// By default fields are separated by commas and nested values are written
+/// as JSON text in a single cell.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::CsvOptions;
+///
+/// let options = CsvOptions::new()
+///     .delimiter(';')
+///     .flatten();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CsvOptions {
+    delimiter: char,
+    flatten: bool
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self { delimiter: ',', flatten: false }
+    }
+}
+
+impl CsvOptions {
+    /// Create a new CsvOptions instance with the default behavior.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the character separating fields.
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Spread nested values over one column per leaf, named by their dotted path
+    /// (`address.city`, `tags.0`), and rebuild them from such columns when reading.
+    pub fn flatten(mut self) -> Self {
+        self.flatten = true;
+        self
+    }
+}
+
+impl JSONValue {
+    /// Convert an array of objects to CSV with the default options.
+    /// See `to_csv_with` for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "users": [{ "name": "Doe, John", "age": 30 }, { "name": "Jane", "tags": ["a"] }] }"#).unwrap();
+    ///
+    /// assert_eq!(json["users"].to_csv(), Ok(concat!(
+    ///     "name,age,tags\n",
+    ///     "\"Doe, John\",30,\n",
+    ///     "Jane,,\"[\"\"a\"\"]\"\n"
+    /// ).to_string()));
+    /// ```
+    pub fn to_csv(&self) -> Result<String, String> {
+        self.to_csv_with(CsvOptions::new())
+    }
+
+    /// Convert an array of objects to CSV. The header is the union of the keys of
+    /// every object, in order of first appearance, and missing keys leave their cell empty.
+    /// Strings are written as is, other values as JSON text.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{CsvOptions, JSONParser};
+    ///
+    /// let json = JSONParser::from(r#"{ "users": [{ "name": "John", "address": { "city": "Springfield" }, "tags": [1, 2] }] }"#).unwrap();
+    ///
+    /// assert_eq!(json["users"].to_csv_with(CsvOptions::new().flatten()), Ok(concat!(
+    ///     "name,address.city,tags.0,tags.1\n",
+    ///     "John,Springfield,1,2\n"
+    /// ).to_string()));
+    /// assert!(json.to_csv().is_err());
+    /// ```
+    pub fn to_csv_with(&self, options: CsvOptions) -> Result<String, String> {
+        let items = self.as_array().ok_or_else(|| format!("Expected an array of objects, found {}", self.type_name()))?;
+        let mut header: OrderedMap<()> = OrderedMap::new();
+        let mut rows = Vec::with_capacity(items.len());
+
+        for (index, item) in items.iter().enumerate() {
+            let obj = item.as_object().ok_or_else(|| format!("Item {} is {}, expected an object", index, item.type_name()))?;
+            let mut row = OrderedMap::new();
+
+            for (key, value) in obj.iter() {
+                if options.flatten {
+                    flatten_into(&mut key.to_string(), value, &mut row);
+                } else {
+                    row.insert(key, cell(value));
+                }
+            }
+            for (key, _) in row.iter() {
+                header.insert(key, ());
+            }
+            rows.push(row);
+        }
+
+        let mut output = String::new();
+
+        write_record(header.iter().map(|(key, _)| key), options.delimiter, &mut output);
+        for row in rows.iter() {
+            write_record(header.iter().map(|(key, _)| row.get(key).map_or("", |cell| cell.as_str())), options.delimiter, &mut output);
+        }
+        Ok(output)
+    }
+
+    /// Read CSV with the default options into an array of objects.
+    /// See `from_csv_with` for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONValue};
+    ///
+    /// let json = JSONParser::from(r#"{ "users": [{ "name": "Doe, John", "age": 30 }, { "name": "Jane", "tags": ["a"] }] }"#).unwrap();
+    ///
+    /// assert_eq!(JSONValue::from_csv(&json["users"].to_csv().unwrap()), Ok(json["users"].clone()));
+    /// ```
+    pub fn from_csv(input: &str) -> Result<JSONValue, String> {
+        JSONValue::from_csv_with(input, CsvOptions::new())
+    }
+
+    /// Read CSV into an array of objects keyed by the header. Blank lines are skipped and
+    /// empty cells are left out. Cells holding a JSON literal (number, boolean, null, array
+    /// or object) are parsed, any other cell is kept as a string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{CsvOptions, JSONParser, JSONValue};
+    ///
+    /// let csv = "name;address.city;tags.0\nJohn;Springfield;true\n";
+    ///
+    /// let json = JSONParser::from(r#"{ "users": [{ "name": "John", "address": { "city": "Springfield" }, "tags": [true] }] }"#).unwrap();
+    ///
+    /// assert_eq!(JSONValue::from_csv_with(csv, CsvOptions::new().delimiter(';').flatten()), Ok(json["users"].clone()));
+    /// assert!(JSONValue::from_csv("a,b\n1,2,3\n").is_err());
+    ///
+    /// let error = JSONValue::from_csv_with("a.4000000000000\n1\n", CsvOptions::new().flatten()).unwrap_err();
+    ///
+    /// assert_eq!(error, "Row 2: Index 4000000000000 skips items of an array of length 0");
+    /// ```
+    pub fn from_csv_with(input: &str, options: CsvOptions) -> Result<JSONValue, String> {
+        let mut records = parse_records(input, options.delimiter)?.into_iter();
+        let header = match records.next() {
+            Some(header) => header,
+            None => return Ok(JSONValue::Array(Box::new([])))
+        };
+        let mut items = Vec::new();
+
+        for (number, record) in records.enumerate().filter(|(_, record)| record.len() > 1 || !record[0].is_empty()) {
+            if record.len() > header.len() {
+                return Err(format!("Row {}: found {} fields, the header has {}", number + 2, record.len(), header.len()));
+            }
+            let mut item = JSONValue::Object(OrderedMap::new());
+
+            for (key, field) in header.iter().zip(record).filter(|(_, field)| !field.is_empty()) {
+                let value = parse_cell(&field);
+
+                if options.flatten {
+                    let segments = key.split('.').collect::<Vec<_>>();
+
+                    insert_at(&mut item, &segments, value).map_err(|e| format!("Row {}: {}", number + 2, e))?;
+                } else if let JSONValue::Object(obj) = &mut item {
+                    obj.insert(key, value);
+                }
+            }
+            items.push(item);
+        }
+        Ok(JSONValue::Array(items.into()))
+    }
+}
+
+fn cell(value: &JSONValue) -> String {
+    match value {
+        JSONValue::String(s) => s.to_string(),
+        value => value.serialize()
+    }
+}
+
+/// Add one cell per leaf of the value, named by its dotted path. Empty containers are leaves.
+fn flatten_into(path: &mut String, value: &JSONValue, row: &mut OrderedMap<String>) {
+    let len = path.len();
+
+    match value {
+        JSONValue::Object(obj) if !obj.is_empty() => {
+            for (key, value) in obj.iter() {
+                path.push('.');
+                path.push_str(key);
+                flatten_into(path, value, row);
+                path.truncate(len);
+            }
+        },
+        JSONValue::Array(array) if !array.is_empty() => {
+            for (index, value) in array.iter().enumerate() {
+                path.push_str(&format!(".{}", index));
+                flatten_into(path, value, row);
+                path.truncate(len);
+            }
+        },
+        value => row.insert(path, cell(value))
+    }
+}
+
+/// Set the value found at a dotted path, creating the containers along it.
+/// A numeric segment indexes an array, and may only add the item after its last one.
+fn insert_at(target: &mut JSONValue, segments: &[&str], value: JSONValue) -> Result<(), String> {
+    let (segment, rest) = match segments.split_first() {
+        Some(split) => split,
+        None => {
+            *target = value;
+            return Ok(());
+        }
+    };
+    let index = segment.parse::<usize>().ok();
+
+    if target.is_null() {
+        *target = match index {
+            Some(_) => JSONValue::Array(Box::new([])),
+            None => JSONValue::Object(OrderedMap::new())
+        };
+    }
+    match (target, index) {
+        (JSONValue::Array(array), Some(index)) => {
+            if index > array.len() {
+                return Err(format!("Index {} skips items of an array of length {}", index, array.len()));
+            }
+            if index == array.len() {
+                edit_items(array, |items| items.push(JSONValue::Null));
+            }
+            insert_at(&mut array[index], rest, value)
+        },
+        (JSONValue::Object(obj), _) => {
+            if !obj.contains_key(segment) {
+                obj.insert(segment, JSONValue::Null);
+            }
+            insert_at(obj.get_mut(segment).expect("Key was just inserted"), rest, value)
+        },
+        (target, _) => Err(format!("Cannot set '{}' in {}", segment, target.type_name()))
+    }
+}
+
+fn parse_cell(field: &str) -> JSONValue {
+    let literal = match field.chars().next() {
+        Some(c) => c == '{' || c == '[' || c == '-' || c.is_ascii_digit() || matches!(field, "true" | "false" | "null"),
+        None => false
+    };
+
+    if literal {
+        if let Ok(value) = Parser::new(Lexer::new(field)).parse_document() {
+            return value;
+        }
+    }
+    JSONValue::String(field.into())
+}
+
+fn write_record<'a, I: Iterator<Item = &'a str>>(fields: I, delimiter: char, output: &mut String) {
+    for (index, field) in fields.enumerate() {
+        if index > 0 {
+            output.push(delimiter);
+        }
+        if field.contains([delimiter, '"', '\n', '\r']) {
+            output.push('"');
+            output.push_str(&field.replace('"', "\"\""));
+            output.push('"');
+        } else {
+            output.push_str(field);
+        }
+    }
+    output.push('\n');
+}
+
+/// Split CSV text into records, following RFC 4180 quoting and accepting both line endings.
+fn parse_records(input: &str, delimiter: char) -> Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut chars = input.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if field.is_empty() => {
+                let start = line;
+
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        },
+                        Some('"') => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            field.push(c);
+                        },
+                        None => return Err(format!("Line {}: unterminated quoted field", start))
+                    }
+                }
+                if !matches!(chars.peek(), None | Some('\r') | Some('\n')) && chars.peek() != Some(&delimiter) {
+                    return Err(format!("Line {}: unexpected character after quoted field", line));
+                }
+            },
+            c if c == delimiter => record.push(core::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {},
+            '\n' => {
+                record.push(core::mem::take(&mut field));
+                records.push(core::mem::take(&mut record));
+                line += 1;
+            },
+            c => field.push(c)
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
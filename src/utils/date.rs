@@ -0,0 +1,173 @@
+use crate::utils::compat::*;
+
+/// An instant, as seconds and nanoseconds since 1970-01-01T00:00:00Z.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub(crate) struct Timestamp {
+    seconds: i64,
+    nanos: u32
+}
+
+impl Timestamp {
+    /// Parse an ISO 8601 date such as `2024-02-29`, or a date and time such as
+    /// `2024-02-29T13:45:30.5+02:00`. Seconds, fractions and the offset are optional,
+    /// and a date or a time without an offset is read as UTC.
+    pub(crate) fn parse(text: &str) -> Result<Timestamp, String> {
+        let mut reader = Reader { bytes: text.as_bytes(), position: 0 };
+        let invalid = || format!("Invalid date '{}'", text);
+        let year = reader.number(4).ok_or_else(invalid)?;
+        let month = reader.expect(b'-').and_then(|_| reader.number(2)).ok_or_else(invalid)?;
+        let day = reader.expect(b'-').and_then(|_| reader.number(2)).ok_or_else(invalid)?;
+
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+            return Err(invalid());
+        }
+        let mut seconds = days_from_civil(year, month, day) * 86400;
+        let mut nanos = 0;
+
+        if reader.peek().map_or(false, |b| matches!(b, b'T' | b't' | b' ')) {
+            reader.position += 1;
+            let hour = reader.number(2).filter(|hour| *hour < 24).ok_or_else(invalid)?;
+            let minute = reader.expect(b':').and_then(|_| reader.number(2)).filter(|minute| *minute < 60).ok_or_else(invalid)?;
+            let second = match reader.peek() {
+                Some(b':') => {
+                    reader.position += 1;
+                    reader.number(2).filter(|second| *second < 60).ok_or_else(invalid)?
+                },
+                _ => 0
+            };
+
+            if reader.peek().map_or(false, |b| b == b'.' || b == b',') {
+                reader.position += 1;
+                let digits = reader.digits();
+
+                if digits.is_empty() {
+                    return Err(invalid());
+                }
+                // Digits beyond nanoseconds are dropped.
+                nanos = digits.iter().chain(core::iter::repeat(&b'0')).take(9).fold(0, |n, d| n * 10 + (d - b'0') as u32);
+            }
+            seconds += hour * 3600 + minute * 60 + second;
+            seconds -= match reader.peek() {
+                Some(b'Z') | Some(b'z') => {
+                    reader.position += 1;
+                    0
+                },
+                Some(sign @ b'+') | Some(sign @ b'-') => {
+                    reader.position += 1;
+                    let hours = reader.number(2).filter(|hours| *hours < 24).ok_or_else(invalid)?;
+                    let minutes = match reader.peek() {
+                        Some(b':') => reader.expect(b':').and_then(|_| reader.number(2)),
+                        Some(_) => reader.number(2),
+                        None => Some(0)
+                    };
+                    let offset = hours * 3600 + minutes.filter(|minutes| *minutes < 60).ok_or_else(invalid)? * 60;
+
+                    if sign == b'-' { -offset } else { offset }
+                },
+                _ => 0
+            };
+        }
+
+        match reader.peek() {
+            Some(_) => Err(invalid()),
+            None => Ok(Timestamp { seconds, nanos })
+        }
+    }
+
+    /// The instant the given number of seconds after 1970-01-01T00:00:00Z.
+    pub(crate) fn from_seconds(seconds: i64) -> Timestamp {
+        Timestamp { seconds, nanos: 0 }
+    }
+
+    /// The number of whole seconds since 1970-01-01T00:00:00Z.
+    pub(crate) fn seconds(self) -> i64 {
+        self.seconds
+    }
+
+    /// Format the instant as an RFC 3339 date and time in UTC, such as `2024-02-29T11:45:30.5Z`.
+    pub(crate) fn to_rfc3339(self) -> String {
+        let days = self.seconds.div_euclid(86400);
+        let time = self.seconds.rem_euclid(86400);
+        let (year, month, day) = civil_from_days(days);
+        let mut output = format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}", year, month, day, time / 3600, time % 3600 / 60, time % 60);
+
+        if self.nanos > 0 {
+            output.push_str(format!(".{:09}", self.nanos).trim_end_matches('0'));
+        }
+        output.push('Z');
+        output
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize
+}
+
+impl<'a> Reader<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        if self.peek() != Some(byte) {
+            return None;
+        }
+        self.position += 1;
+        Some(())
+    }
+
+    /// Read a number of exactly the given number of digits.
+    fn number(&mut self, len: usize) -> Option<i64> {
+        let digits = self.bytes.get(self.position..self.position + len)?;
+
+        if !digits.iter().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        self.position += len;
+        Some(digits.iter().fold(0, |n, d| n * 10 + (d - b'0') as i64))
+    }
+
+    fn digits(&mut self) -> &'a [u8] {
+        let start = self.position;
+
+        while self.peek().map_or(false, |b| b.is_ascii_digit()) {
+            self.position += 1;
+        }
+        &self.bytes[start..self.position]
+    }
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31
+    }
+}
+
+/// The number of days from 1970-01-01 to the given date of the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146097 + day_of_era - 719468
+}
+
+/// The date of the proleptic Gregorian calendar the given number of days after 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+
+    (year_of_era + era * 400 + if month <= 2 { 1 } else { 0 }, month, day)
+}
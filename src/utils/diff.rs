@@ -0,0 +1,143 @@
+//! Structural comparison of two JSON values.
+
+use core::fmt;
+
+use crate::utils::compat::*;
+use crate::utils::walk::push_token;
+use crate::utils::{JSONValue, Serialize};
+
+/// The kind of change found at a path.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DifferenceKind {
+    /// The path only exists in the right value.
+    Added,
+    /// The path only exists in the left value.
+    Removed,
+    /// Both values have the same type but a different content.
+    Changed,
+    /// The values have a different type.
+    TypeChanged
+}
+
+/// A single difference between two values, located by a JSON Pointer path.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Difference {
+    pub path: String,
+    pub kind: DifferenceKind,
+    /// The value on the left side, None when it was added.
+    pub left: Option<JSONValue>,
+    /// The value on the right side, None when it was removed.
+    pub right: Option<JSONValue>
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() { "/" } else { &self.path };
+        let left = self.left.as_ref().map(|value| value.serialize()).unwrap_or_default();
+        let right = self.right.as_ref().map(|value| value.serialize()).unwrap_or_default();
+
+        match self.kind {
+            DifferenceKind::Added => write!(f, "+ {}: {}", path, right),
+            DifferenceKind::Removed => write!(f, "- {}: {}", path, left),
+            DifferenceKind::Changed => write!(f, "~ {}: {} -> {}", path, left, right),
+            DifferenceKind::TypeChanged => {
+                let left_type = self.left.as_ref().map(|value| value.type_name()).unwrap_or_default();
+                let right_type = self.right.as_ref().map(|value| value.type_name()).unwrap_or_default();
+
+                write!(f, "! {}: {} {} -> {} {}", path, left_type, left, right_type, right)
+            }
+        }
+    }
+}
+
+/// Compare two values and list their differences, depth-first.
+/// Object entries are matched by key and array items by index.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::JSONParser;
+/// use jsonparser::diff::{compare, DifferenceKind};
+///
+/// let a = JSONParser::from(r#"{ "port": 80, "tags": ["a"], "debug": true, "id": 1 }"#).unwrap();
+/// let b = JSONParser::from(r#"{ "port": 8080, "tags": ["a", "b"], "id": "1" }"#).unwrap();
+///
+/// let differences = compare(&a, &b);
+/// let kinds = differences.iter().map(|d| d.kind).collect::<Vec<_>>();
+///
+/// assert_eq!(kinds, vec![DifferenceKind::Changed, DifferenceKind::Added, DifferenceKind::Removed, DifferenceKind::TypeChanged]);
+/// assert_eq!(differences[1].path, "/tags/1");
+/// ```
+pub fn compare(left: &JSONValue, right: &JSONValue) -> Vec<Difference> {
+    let mut differences = Vec::new();
+
+    compare_at(&mut String::new(), left, right, &mut differences);
+    differences
+}
+
+/// Render differences as text, one per line: `+` for added, `-` for removed,
+/// `~` for changed and `!` for type changes.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::JSONParser;
+/// use jsonparser::diff::{compare, render};
+///
+/// let a = JSONParser::from(r#"{ "port": 80, "debug": true }"#).unwrap();
+/// let b = JSONParser::from(r#"{ "port": "80", "host": "localhost" }"#).unwrap();
+///
+/// assert_eq!(render(&compare(&a, &b)), "! /port: number 80 -> string \"80\"\n- /debug: true\n+ /host: \"localhost\"\n");
+/// ```
+pub fn render(differences: &[Difference]) -> String {
+    differences.iter().map(|difference| format!("{}\n", difference)).collect()
+}
+
+fn compare_at(path: &mut String, left: &JSONValue, right: &JSONValue, differences: &mut Vec<Difference>) {
+    let len = path.len();
+
+    match (left, right) {
+        (JSONValue::Object(a), JSONValue::Object(b)) => {
+            for (key, value) in a.iter() {
+                push_token(path, key);
+                match b.get(key) {
+                    Some(other) => compare_at(path, value, other, differences),
+                    None => differences.push(removed(path, value))
+                }
+                path.truncate(len);
+            }
+            for (key, value) in b.iter().filter(|(key, _)| !a.contains_key(key)) {
+                push_token(path, key);
+                differences.push(added(path, value));
+                path.truncate(len);
+            }
+        },
+        (JSONValue::Array(a), JSONValue::Array(b)) => {
+            for index in 0..a.len().max(b.len()) {
+                push_token(path, &index.to_string());
+                match (a.get(index), b.get(index)) {
+                    (Some(value), Some(other)) => compare_at(path, value, other, differences),
+                    (Some(value), None) => differences.push(removed(path, value)),
+                    (None, Some(other)) => differences.push(added(path, other)),
+                    (None, None) => {}
+                }
+                path.truncate(len);
+            }
+        },
+        _ if left == right => {},
+        _ => differences.push(Difference {
+            path: path.clone(),
+            kind: if left.kind() == right.kind() { DifferenceKind::Changed } else { DifferenceKind::TypeChanged },
+            left: Some(left.clone()),
+            right: Some(right.clone())
+        })
+    }
+}
+
+fn added(path: &str, value: &JSONValue) -> Difference {
+    Difference { path: path.to_string(), kind: DifferenceKind::Added, left: None, right: Some(value.clone()) }
+}
+
+fn removed(path: &str, value: &JSONValue) -> Difference {
+    Difference { path: path.to_string(), kind: DifferenceKind::Removed, left: Some(value.clone()), right: None }
+}
@@ -0,0 +1,190 @@
+use core::hash::{Hash, Hasher};
+
+use crate::utils::compat::*;
+use crate::utils::parser::escape;
+use crate::utils::{JSONValue, Serialize};
+
+/// Hashes are consistent with `PartialEq`: object keys are hashed in order, since
+/// objects with the same entries in a different order are not equal, and `0.0`
+/// and `-0.0` hash the same. Use `content_hash` for a key-order independent fingerprint.
+impl Hash for JSONValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        core::mem::discriminant(self).hash(state);
+        match self {
+            JSONValue::Object(obj) => {
+                obj.len().hash(state);
+                for (key, value) in obj.iter() {
+                    key.hash(state);
+                    value.hash(state);
+                }
+            },
+            JSONValue::Array(array) => array.hash(state),
+            JSONValue::String(s) => s.hash(state),
+            JSONValue::Number(n) => canonical_number(*n).to_bits().hash(state),
+            JSONValue::Boolean(b) => b.hash(state),
+            JSONValue::Null => {},
+            JSONValue::Raw(raw) => raw.hash(state)
+        }
+    }
+}
+
+fn canonical_number(n: f64) -> f64 {
+    if n == 0.0 { 0.0 } else { n }
+}
+
+impl JSONValue {
+    /// Serialize the value with object keys sorted at every level, so that documents
+    /// holding the same data always produce the same text.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "b": [2, { "d": 1, "c": -0 }], "a": null }"#).unwrap();
+    ///
+    /// assert_eq!(json.canonical(), r#"{"a":null,"b":[2,{"c":0,"d":1}]}"#);
+    /// ```
+    pub fn canonical(&self) -> String {
+        let mut output = String::new();
+
+        self.write_canonical(&mut output);
+        output
+    }
+
+    fn write_canonical(&self, output: &mut String) {
+        match self {
+            JSONValue::Object(obj) => {
+                let mut entries = obj.iter().collect::<Vec<_>>();
+
+                entries.sort_by_key(|(key, _)| *key);
+                output.push('{');
+                for (i, (key, value)) in entries.into_iter().enumerate() {
+                    if i > 0 {
+                        output.push(',');
+                    }
+                    output.push_str(&escape(key));
+                    output.push(':');
+                    value.write_canonical(output);
+                }
+                output.push('}');
+            },
+            JSONValue::Array(array) => {
+                output.push('[');
+                for (i, value) in array.iter().enumerate() {
+                    if i > 0 {
+                        output.push(',');
+                    }
+                    value.write_canonical(output);
+                }
+                output.push(']');
+            },
+            JSONValue::Number(n) => output.push_str(&canonical_number(*n).to_string()),
+            JSONValue::Raw(raw) => raw.to_value().write_canonical(output),
+            value => output.push_str(&value.serialize())
+        }
+    }
+
+    /// A 64-bit FNV-1a hash of the canonical serialization.
+    /// Unlike `Hash`, it ignores key order and is stable across runs and platforms.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let a = JSONParser::from(r#"{ "name": "John Doe", "age": 30 }"#).unwrap();
+    /// let b = JSONParser::from(r#"{ "age": 30, "name": "John Doe" }"#).unwrap();
+    ///
+    /// assert_eq!(a.content_hash(), b.content_hash());
+    /// ```
+    pub fn content_hash(&self) -> u64 {
+        self.canonical().bytes().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+    }
+
+    /// The SHA-256 digest of the canonical serialization.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONValue;
+    ///
+    /// let digest = JSONValue::Array(Box::new([])).digest_sha256();
+    /// let hex = digest.iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+    ///
+    /// assert_eq!(hex, "4f53cda18c2baa0c0354bb5f9a3ecbe5ed12ab4d8e11ba873c2f11161202b945");
+    /// ```
+    pub fn digest_sha256(&self) -> [u8; 32] {
+        sha256(self.canonical().as_bytes())
+    }
+}
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2
+];
+
+/// SHA-256 as specified in FIPS 180-4.
+fn sha256(input: &[u8]) -> [u8; 32] {
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19
+    ];
+    let mut message = input.to_vec();
+
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((input.len() as u64) * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut w = [0u32; 64];
+
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(K[i]).wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *word = word.wrapping_add(value);
+        }
+    }
+
+    let mut digest = [0u8; 32];
+
+    for (chunk, word) in digest.chunks_mut(4).zip(state.iter()) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
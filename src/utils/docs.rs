@@ -0,0 +1,228 @@
+use crate::utils::compat::*;
+use crate::utils::parser::escape;
+use crate::utils::Case;
+use crate::{JSONValue, Serialize};
+
+/// What the documentation generated from a schema shows of a rule, as returned by
+/// `Validator::document`.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct RuleDoc {
+    /// The type of the values, named as in `JSONSchema::from_json`, such as `string`.
+    pub kind: String,
+    pub description: Option<String>,
+    pub examples: Vec<JSONValue>,
+    pub deprecated: bool,
+    /// The constraints and transformations, named after the builder methods, such as `min_length: 3`.
+    pub constraints: Vec<String>,
+    /// The rules of the properties of an object, or of the items of an array under `[]`.
+    pub children: Vec<(String, RuleDoc)>
+}
+
+impl RuleDoc {
+    /// Create a RuleDoc for the given type of values.
+    pub fn new(kind: &str) -> Self {
+        Self { kind: kind.to_string(), ..Self::default() }
+    }
+
+    /// The type of the values followed by the constraints, such as `string (min_length: 3)`.
+    pub fn summary(&self) -> String {
+        if self.constraints.is_empty() {
+            self.kind.clone()
+        } else {
+            format!("{} ({})", self.kind, self.constraints.join(", "))
+        }
+    }
+}
+
+/// A row of the reference: a field, with its path from the root, and its rule.
+struct Row<'a> {
+    field: String,
+    doc: &'a RuleDoc
+}
+
+/// List the fields of the rules and of their children, each after its parent.
+fn rows<'a>(rules: &'a [(String, RuleDoc)], parent: &str, rows: &mut Vec<Row<'a>>) {
+    for (key, doc) in rules {
+        let field = match (parent, key.as_str()) {
+            ("", key) => key.to_string(),
+            (parent, "[]") => format!("{}[]", parent),
+            (parent, key) => format!("{}.{}", parent, key)
+        };
+
+        rows.push(Row { field: field.clone(), doc });
+        self::rows(&doc.children, &field, rows);
+    }
+}
+
+fn examples(doc: &RuleDoc) -> Vec<String> {
+    doc.examples.iter().map(|example| example.serialize()).collect()
+}
+
+/// Render the rules as a Markdown table.
+pub(crate) fn to_markdown(rules: &[(String, RuleDoc)]) -> String {
+    let mut all = Vec::new();
+    let mut output = String::from("| Field | Type | Description | Constraints | Examples |\n| --- | --- | --- | --- | --- |\n");
+    let cell = |text: &str| text.replace('|', "\\|").replace('\n', " ");
+
+    rows(rules, "", &mut all);
+    for Row { field, doc } in all {
+        let mut description = String::new();
+
+        if doc.deprecated {
+            description.push_str("**Deprecated.** ");
+        }
+        description.push_str(doc.description.as_deref().unwrap_or_default());
+
+        let examples = examples(doc).iter().map(|example| format!("`{}`", cell(example))).collect::<Vec<_>>();
+
+        output.push_str(&format!(
+            "| `{}` | {} | {} | {} | {} |\n",
+            cell(&field), cell(&doc.kind), cell(description.trim_end()), cell(&doc.constraints.join(", ")), examples.join(", ")
+        ));
+    }
+    output
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Render the rules as an HTML table.
+pub(crate) fn to_html(rules: &[(String, RuleDoc)]) -> String {
+    let mut all = Vec::new();
+    let mut output = String::from("<table>\n<thead>\n<tr><th>Field</th><th>Type</th><th>Description</th><th>Constraints</th><th>Examples</th></tr>\n</thead>\n<tbody>\n");
+
+    rows(rules, "", &mut all);
+    for Row { field, doc } in all {
+        let mut description = String::new();
+
+        if doc.deprecated {
+            description.push_str("<strong>Deprecated.</strong> ");
+        }
+        description.push_str(&escape_html(doc.description.as_deref().unwrap_or_default()));
+
+        let examples = examples(doc).iter().map(|example| format!("<code>{}</code>", escape_html(example))).collect::<Vec<_>>();
+
+        output.push_str(&format!(
+            "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            escape_html(&field), escape_html(&doc.kind), description.trim_end(), escape_html(&doc.constraints.join(", ")), examples.join(", ")
+        ));
+    }
+    output.push_str("</tbody>\n</table>\n");
+    output
+}
+
+/// A field of a TypeScript interface: its key, its rule, and whether it may be missing.
+type Field<'a> = (&'a str, &'a RuleDoc, bool);
+
+/// The interfaces to declare, in order, each with its name and its fields.
+struct Interfaces<'a> {
+    declared: Vec<(String, Vec<Field<'a>>)>
+}
+
+impl<'a> Interfaces<'a> {
+    /// The TypeScript type of the values of the rule, declaring an interface for each
+    /// object with properties. `key` names the values, and `parent` their interface.
+    fn type_of(&mut self, doc: &'a RuleDoc, key: &str, parent: &str) -> String {
+        match doc.kind.as_str() {
+            "string" | "date" => "string".to_string(),
+            "number" => "number".to_string(),
+            "boolean" => "boolean".to_string(),
+            "null" => "null".to_string(),
+            "array" => match doc.children.iter().find(|(key, _)| key == "[]") {
+                Some((_, item)) => format!("{}[]", self.type_of(item, &singular(key), parent)),
+                None => "unknown[]".to_string()
+            },
+            "object" if doc.children.is_empty() => "Record<string, unknown>".to_string(),
+            "object" => {
+                let name = self.name(key, parent);
+                let fields = doc.children.iter().map(|(key, doc)| (key.as_str(), doc, false)).collect();
+
+                self.declared.push((name.clone(), fields));
+                name
+            },
+            _ => "unknown".to_string()
+        }
+    }
+
+    /// A name for the interface of the values of the key, prefixed with the name of its
+    /// parent, then numbered, when already taken.
+    fn name(&self, key: &str, parent: &str) -> String {
+        let base = Case::PascalCase.convert(key).chars().filter(|c| c.is_ascii_alphanumeric() || *c == '_').collect::<String>();
+        let base = match base.chars().next() {
+            Some(c) if !c.is_ascii_digit() => base,
+            _ => format!("{}Item", parent)
+        };
+        let taken = |name: &str| self.declared.iter().any(|(declared, _)| declared == name);
+
+        if !taken(&base) {
+            return base;
+        }
+        let prefixed = format!("{}{}", parent, base);
+
+        if !taken(&prefixed) {
+            return prefixed;
+        }
+        (2..).map(|i| format!("{}{}", prefixed, i)).find(|name| !taken(name)).unwrap()
+    }
+}
+
+/// The singular of an English plural, such as `Car` for the items of `cars`, or the word
+/// itself when it does not look like a plural.
+fn singular(word: &str) -> String {
+    if let Some(stem) = word.strip_suffix("ies") {
+        format!("{}y", stem)
+    } else if ["sses", "xes", "ches", "shes"].iter().any(|suffix| word.ends_with(suffix)) {
+        word[..word.len() - 2].to_string()
+    } else if word.ends_with('s') && !["ss", "us", "is"].iter().any(|suffix| word.ends_with(suffix)) {
+        word[..word.len() - 1].to_string()
+    } else {
+        word.to_string()
+    }
+}
+
+/// A TSDoc comment for a property, with the description of its rule and whether it is
+/// deprecated, if any.
+fn comment(doc: &RuleDoc) -> String {
+    let mut lines = doc.description.iter().flat_map(|description| description.lines()).map(|line| line.replace("*/", "*\\/")).collect::<Vec<_>>();
+
+    if doc.deprecated {
+        lines.push("@deprecated".to_string());
+    }
+    match lines.len() {
+        0 => String::new(),
+        1 => format!("  /** {} */\n", lines[0]),
+        _ => format!("  /**\n{}   */\n", lines.iter().map(|line| format!("   * {}\n", line)).collect::<String>())
+    }
+}
+
+/// Render the rules as TypeScript interface declarations, the root one under the given
+/// name, followed by one for each object with properties.
+pub(crate) fn to_typescript(name: &str, fields: Vec<Field>) -> String {
+    let mut interfaces = Interfaces { declared: vec![(name.to_string(), fields)] };
+    let mut output = String::new();
+    let mut index = 0;
+
+    while index < interfaces.declared.len() {
+        let (name, fields) = interfaces.declared[index].clone();
+
+        if index > 0 {
+            output.push('\n');
+        }
+        output.push_str(&format!("export interface {} {{\n", name));
+        for (key, doc, optional) in fields {
+            let identifier = key.chars().next().map_or(false, |c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+            let key_type = interfaces.type_of(doc, key, &name);
+
+            output.push_str(&comment(doc));
+            output.push_str(&format!(
+                "  {}{}: {};\n",
+                if identifier { key.to_string() } else { escape(key) }, if optional { "?" } else { "" }, key_type
+            ));
+        }
+        output.push_str("}\n");
+        index += 1;
+    }
+    output
+}
@@ -0,0 +1,173 @@
+use core::fmt;
+
+use crate::utils::compat::*;
+use crate::utils::pointer::parse_pointer;
+use crate::utils::{JSONValue, Lexer, ParseError, Parser, Serialize, Span, Token, TokenKind};
+
+/// A value of a document along with the span of the input it was read from.
+struct Node {
+    span: Span,
+    children: Children
+}
+
+enum Children {
+    None,
+    Object(Vec<(String, Node)>),
+    Array(Vec<Node>)
+}
+
+/// A JSON document that keeps its original text, so that it is written back exactly as
+/// read: numbers such as `1.10` or `1e2`, escapes such as `\u00e9`, whitespace and key
+/// order are preserved. Editing a value with `set` only rewrites the text of that value,
+/// so tools can update one field of a hand-maintained file without touching the rest.
+/// Any value is accepted as the root, unlike `JSONParser::from`.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{Document, JSONValue};
+///
+/// let input = "{\n  \"version\": 1.10,\n  \"name\": \"caf\\u00e9\",\n  \"size\": 1e2\n}\n";
+/// let mut document = Document::parse(input).unwrap();
+///
+/// assert_eq!(document.to_string(), input);
+/// assert_eq!(document.lexeme("/version"), Some("1.10"));
+/// assert_eq!(document.value()["name"].as_str(), Some("café"));
+///
+/// document.set("/size", &JSONValue::Number(200.0)).unwrap();
+///
+/// assert_eq!(document.to_string(), input.replace("1e2", "200"));
+/// assert_eq!(document.value()["size"].as_f64(), Some(200.0));
+/// ```
+pub struct Document {
+    text: String,
+    value: JSONValue,
+    root: Node
+}
+
+impl Document {
+    /// Parse a document, keeping its text.
+    pub fn parse(input: &str) -> Result<Document, ParseError> {
+        let value = Parser::new(Lexer::new(input)).parse_document()?;
+        let mut lexer = Lexer::new(input);
+        let mut tokens = core::iter::from_fn(|| lexer.next_spanned().ok().flatten());
+        let root = tokens.next()
+            .and_then(|first| read_node(first, &mut tokens))
+            .ok_or_else(|| ParseError::Syntax("Unexpected end of input".to_string()))?;
+
+        Ok(Document { text: input.to_string(), value, root })
+    }
+
+    /// The value of the document.
+    pub fn value(&self) -> &JSONValue {
+        &self.value
+    }
+
+    /// The text of the document, with any edit made by `set`.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// The text the value at the given JSON Pointer was read from.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::Document;
+    ///
+    /// let document = Document::parse(r#"{ "sizes": [1.50, 2e3] }"#).unwrap();
+    ///
+    /// assert_eq!(document.lexeme("/sizes/1"), Some("2e3"));
+    /// assert_eq!(document.lexeme("/sizes"), Some("[1.50, 2e3]"));
+    /// assert_eq!(document.lexeme("/missing"), None);
+    /// ```
+    pub fn lexeme(&self, pointer: &str) -> Option<&str> {
+        let span = self.find(pointer).ok()?;
+
+        Some(&self.text[span.start..span.end])
+    }
+
+    /// Replace the value at the given JSON Pointer with the serialization of the new value,
+    /// leaving the rest of the text untouched. The value must already exist. Use
+    /// `JSONValue::raw` to write a number or a string with a specific lexeme.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{Document, JSONValue};
+    ///
+    /// let mut document = Document::parse(r#"{ "price": 1.10, "tags": ["a"] }"#).unwrap();
+    ///
+    /// document.set("/price", &JSONValue::raw("1.20").unwrap()).unwrap();
+    /// document.set("/tags/0", &JSONValue::String("b".into())).unwrap();
+    ///
+    /// assert_eq!(document.as_str(), r#"{ "price": 1.20, "tags": ["b"] }"#);
+    /// assert_eq!(document.set("/size", &JSONValue::Null).unwrap_err(), "No value at '/size'");
+    /// ```
+    pub fn set(&mut self, pointer: &str, value: &JSONValue) -> Result<(), String> {
+        let span = self.find(pointer)?;
+        let mut text = String::with_capacity(self.text.len());
+
+        text.push_str(&self.text[..span.start]);
+        text.push_str(&value.serialize());
+        text.push_str(&self.text[span.end..]);
+        *self = Document::parse(&text).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// The span of the value at the given JSON Pointer.
+    fn find(&self, pointer: &str) -> Result<Span, String> {
+        let mut node = &self.root;
+
+        for token in parse_pointer(pointer)? {
+            let child = match &node.children {
+                Children::Object(entries) => entries.iter().rev().find(|(key, _)| *key == token).map(|(_, node)| node),
+                Children::Array(items) => token.parse::<usize>().ok().and_then(|index| items.get(index)),
+                Children::None => None
+            };
+
+            node = child.ok_or_else(|| format!("No value at '{}'", pointer))?;
+        }
+        Ok(node.span)
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+/// Read the spans of the value starting at the given token. The tokens must form a valid value.
+fn read_node<I: Iterator<Item = (Token, Span)>>((token, span): (Token, Span), tokens: &mut I) -> Option<Node> {
+    let close = match token.kind {
+        TokenKind::OpenBrace => TokenKind::CloseBrace,
+        TokenKind::OpenBracket => TokenKind::CloseBracket,
+        _ => return Some(Node { span, children: Children::None })
+    };
+    let mut entries = Vec::new();
+    let mut items = Vec::new();
+
+    loop {
+        let (token, end) = tokens.next()?;
+
+        match token.kind {
+            kind if kind == close => {
+                let children = match close {
+                    TokenKind::CloseBrace => Children::Object(entries),
+                    _ => Children::Array(items)
+                };
+
+                return Some(Node { span: Span { start: span.start, end: end.end }, children });
+            },
+            TokenKind::Comma => {},
+            TokenKind::QuotedString if close == TokenKind::CloseBrace => {
+                tokens.next()?;
+                let first = tokens.next()?;
+
+                entries.push((token.text?, read_node(first, tokens)?));
+            },
+            _ => items.push(read_node((token, end), tokens)?)
+        }
+    }
+}
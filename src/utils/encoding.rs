@@ -0,0 +1,69 @@
+use crate::utils::compat::*;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Encoding {
+    Utf8,
+    Utf16Be,
+    Utf16Le,
+    Utf32Be,
+    Utf32Le
+}
+
+/// Detect the encoding of a JSON document, returning it with the length of its BOM.
+///
+/// A BOM wins when present. Otherwise the pattern of null bytes in the first four
+/// octets is used, as described in RFC 4627 section 3.
+fn detect(bytes: &[u8]) -> (Encoding, usize) {
+    match bytes {
+        [0x00, 0x00, 0xFE, 0xFF, ..] => (Encoding::Utf32Be, 4),
+        [0xFF, 0xFE, 0x00, 0x00, ..] => (Encoding::Utf32Le, 4),
+        [0xEF, 0xBB, 0xBF, ..] => (Encoding::Utf8, 3),
+        [0xFE, 0xFF, ..] => (Encoding::Utf16Be, 2),
+        [0xFF, 0xFE, ..] => (Encoding::Utf16Le, 2),
+        [0x00, 0x00, 0x00, _, ..] => (Encoding::Utf32Be, 0),
+        [_, 0x00, 0x00, 0x00, ..] => (Encoding::Utf32Le, 0),
+        [0x00, _, ..] => (Encoding::Utf16Be, 0),
+        [_, 0x00, ..] => (Encoding::Utf16Le, 0),
+        _ => (Encoding::Utf8, 0)
+    }
+}
+
+/// Decode raw JSON bytes to a string, stripping any BOM and transcoding
+/// UTF-16 and UTF-32 input to UTF-8.
+pub fn decode(bytes: &[u8]) -> Result<String, String> {
+    let (encoding, bom) = detect(bytes);
+    let bytes = &bytes[bom..];
+
+    match encoding {
+        Encoding::Utf8 => core::str::from_utf8(bytes)
+            .map(|s| s.to_string())
+            .map_err(|e| format!("Invalid UTF-8 input: {}", e)),
+        Encoding::Utf16Be | Encoding::Utf16Le => {
+            if bytes.len() % 2 != 0 {
+                return Err("Invalid UTF-16 input: odd number of bytes".to_string());
+            }
+            let units = bytes.chunks(2).map(|pair| match encoding {
+                Encoding::Utf16Be => u16::from_be_bytes([pair[0], pair[1]]),
+                _ => u16::from_le_bytes([pair[0], pair[1]])
+            });
+
+            core::char::decode_utf16(units)
+                .collect::<Result<String, _>>()
+                .map_err(|e| format!("Invalid UTF-16 input: lone surrogate {:#06X}", e.unpaired_surrogate()))
+        },
+        Encoding::Utf32Be | Encoding::Utf32Le => {
+            if bytes.len() % 4 != 0 {
+                return Err("Invalid UTF-32 input: byte count is not a multiple of 4".to_string());
+            }
+
+            bytes.chunks(4).map(|quad| {
+                let code = match encoding {
+                    Encoding::Utf32Be => u32::from_be_bytes([quad[0], quad[1], quad[2], quad[3]]),
+                    _ => u32::from_le_bytes([quad[0], quad[1], quad[2], quad[3]])
+                };
+
+                char::from_u32(code).ok_or_else(|| format!("Invalid UTF-32 input: code point {:#X}", code))
+            }).collect()
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::utils::compat::*;
+use crate::utils::JSONValue;
+
+/// Options relaxing the comparison made by `JSONValue::equals_with`.
+/// By default the comparison is exact, except for object key order.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::EqualityOptions;
+///
+/// let options = EqualityOptions::new()
+///     .ignore_array_order()
+///     .epsilon(1e-9)
+///     .case_insensitive_keys();
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EqualityOptions {
+    ignore_array_order: bool,
+    epsilon: f64,
+    case_insensitive_keys: bool
+}
+
+impl EqualityOptions {
+    /// Create a new EqualityOptions instance comparing values exactly.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Consider arrays equal when they hold the same items in any order.
+    pub fn ignore_array_order(mut self) -> Self {
+        self.ignore_array_order = true;
+        self
+    }
+
+    /// Consider numbers equal when they differ by at most `epsilon`.
+    pub fn epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    /// Match object keys regardless of their case.
+    pub fn case_insensitive_keys(mut self) -> Self {
+        self.case_insensitive_keys = true;
+        self
+    }
+}
+
+impl JSONValue {
+    /// Compare two values using relaxed rules. Object keys are always matched
+    /// regardless of their order, the other rules are set by the options.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{EqualityOptions, JSONParser};
+    ///
+    /// let response = JSONParser::from(r#"{ "Total": 0.30000000000000004, "tags": ["b", "a"] }"#).unwrap();
+    /// let fixture = JSONParser::from(r#"{ "tags": ["a", "b"], "total": 0.3 }"#).unwrap();
+    ///
+    /// assert!(!response.equals_with(&fixture, EqualityOptions::new()));
+    ///
+    /// let options = EqualityOptions::new().ignore_array_order().epsilon(1e-9).case_insensitive_keys();
+    ///
+    /// assert!(response.equals_with(&fixture, options));
+    /// ```
+    pub fn equals_with(&self, other: &JSONValue, options: EqualityOptions) -> bool {
+        match (self, other) {
+            (JSONValue::Number(a), JSONValue::Number(b)) => a == b || (a - b).abs() <= options.epsilon,
+            (JSONValue::Array(a), JSONValue::Array(b)) if options.ignore_array_order => {
+                let mut used = vec![false; b.len()];
+
+                a.len() == b.len() && a.iter().all(|item| {
+                    let found = b.iter().enumerate().position(|(i, other)| !used[i] && item.equals_with(other, options));
+
+                    found.map(|i| used[i] = true).is_some()
+                })
+            },
+            (JSONValue::Array(a), JSONValue::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| a.equals_with(b, options))
+            },
+            (JSONValue::Object(a), JSONValue::Object(b)) => {
+                a.len() == b.len() && a.iter().all(|(key, value)| {
+                    let other = if options.case_insensitive_keys {
+                        b.iter().find(|(other, _)| other.to_lowercase() == key.to_lowercase()).map(|(_, value)| value)
+                    } else {
+                        b.get(key)
+                    };
+
+                    other.map_or(false, |other| value.equals_with(other, options))
+                })
+            },
+            _ => self == other
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use core::fmt;
+
+use crate::utils::compat::*;
+use crate::utils::JSONKind;
+
+/// A parser limit that can be configured through `ParserOptions`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Limit {
+    DocumentSize,
+    StringLength,
+    ArrayLength,
+    ObjectKeys,
+    Nodes,
+    Depth
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Limit::DocumentSize => write!(f, "document size"),
+            Limit::StringLength => write!(f, "string length"),
+            Limit::ArrayLength => write!(f, "array length"),
+            Limit::ObjectKeys => write!(f, "object keys"),
+            Limit::Nodes => write!(f, "nodes"),
+            Limit::Depth => write!(f, "nesting depth")
+        }
+    }
+}
+
+/// The reason a `LexError` was raised.
+#[derive(Debug, PartialEq, Clone)]
+pub enum LexErrorKind {
+    InvalidInput(String),
+    UnterminatedString,
+    ControlCharacter(char),
+    InvalidEscape(char),
+    InvalidUnicodeEscape,
+    LoneSurrogate(u32),
+    InvalidLowSurrogate { high: u32, low: u32 },
+    UnterminatedComment
+}
+
+/// An error returned when the input cannot be split into tokens.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LexError {
+    pub kind: LexErrorKind,
+    /// Byte offset in the input where the error was detected.
+    pub offset: usize
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            LexErrorKind::InvalidInput(message) => write!(f, "{}", message)?,
+            LexErrorKind::UnterminatedString => write!(f, "Unterminated string")?,
+            LexErrorKind::ControlCharacter(c) => write!(f, "Unescaped control character U+{:04X} in string", *c as u32)?,
+            LexErrorKind::InvalidEscape(c) => write!(f, "Invalid escape sequence '\\{}' in string", c)?,
+            LexErrorKind::InvalidUnicodeEscape => write!(f, "Invalid \\u escape: expected 4 hexadecimal digits")?,
+            LexErrorKind::LoneSurrogate(code) => write!(f, "Lone surrogate \\u{:04X} in string", code)?,
+            LexErrorKind::InvalidLowSurrogate { high, low } => write!(f, "Invalid low surrogate \\u{:04X} after \\u{:04X}", low, high)?,
+            LexErrorKind::UnterminatedComment => write!(f, "Unterminated comment")?
+        }
+        write!(f, " (offset: {})", self.offset)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LexError {}
+
+/// An error returned when parsing a JSON document fails.
+///
+/// # Example
+///
+/// ```
+/// use std::error::Error;
+/// use jsonparser::JSONParser;
+///
+/// fn name(input: &str) -> Result<String, Box<dyn Error>> {
+///     let json = JSONParser::from(input)?;
+///
+///     Ok(json["name"].as_str().unwrap_or_default().to_string())
+/// }
+///
+/// let error = JSONParser::from(r#"{ "name": "unterminated }"#).unwrap_err();
+///
+/// assert!(error.source().is_some());
+/// assert!(name(r#"{ "name": "#).is_err());
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParseError {
+    /// The input could not be split into tokens.
+    Lex(LexError),
+    /// The input is not valid JSON.
+    Syntax(String),
+    /// The input exceeds one of the configured limits.
+    LimitExceeded { limit: Limit, max: usize },
+    /// The parse was stopped through a `CancellationToken`.
+    Cancelled,
+    /// The input could not be read, with the message of the I/O error.
+    Io(String)
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Lex(error) => write!(f, "{}", error),
+            ParseError::Syntax(message) => write!(f, "{}", message),
+            ParseError::LimitExceeded { limit, max } => write!(f, "Maximum {} exceeded (max: {})", limit, max),
+            ParseError::Cancelled => write!(f, "Cancelled"),
+            ParseError::Io(message) => write!(f, "{}", message)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {
+    /// The lexer error behind a `ParseError::Lex`, so error reporters can walk the chain.
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::Lex(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+impl From<String> for ParseError {
+    fn from(message: String) -> Self {
+        ParseError::Syntax(message)
+    }
+}
+
+impl From<LexError> for ParseError {
+    fn from(error: LexError) -> Self {
+        ParseError::Lex(error)
+    }
+}
+
+/// An error returned by `JSONValue::try_get` and the other checked accessors. Paths use
+/// the dot/bracket syntax of `JSONValue::get_path`, and are empty for the root value.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{AccessError, JSONKind, JSONParser};
+///
+/// let json = JSONParser::from(r#"{ "server": { "port": "8080" } }"#).unwrap();
+/// let error = json.try_get_f64("server.port").unwrap_err();
+///
+/// assert_eq!(error, AccessError::WrongType { path: "server.port".to_string(), expected: JSONKind::Number, found: JSONKind::String });
+/// assert_eq!(error.to_string(), "Expected number at 'server.port', found string");
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub enum AccessError {
+    /// The object at `path` has no such key.
+    MissingKey { path: String, key: String },
+    /// The array at `path` is too short for the index.
+    OutOfBounds { path: String, index: usize, len: usize },
+    /// The value at `path` does not have the expected type.
+    WrongType { path: String, expected: JSONKind, found: JSONKind },
+    /// The path cannot be parsed.
+    InvalidPath(String)
+}
+
+impl fmt::Display for AccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccessError::MissingKey { path, key } if path.is_empty() => write!(f, "Missing key '{}'", key),
+            AccessError::MissingKey { path, key } => write!(f, "Missing key '{}' in '{}'", key, path),
+            AccessError::OutOfBounds { path, index, len } if path.is_empty() => write!(f, "Index {} out of bounds (length: {})", index, len),
+            AccessError::OutOfBounds { path, index, len } => write!(f, "Index {} out of bounds in '{}' (length: {})", index, path, len),
+            AccessError::WrongType { path, expected, found } if path.is_empty() => write!(f, "Expected {}, found {}", expected, found),
+            AccessError::WrongType { path, expected, found } => write!(f, "Expected {} at '{}', found {}", expected, path, found),
+            AccessError::InvalidPath(message) => write!(f, "{}", message)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AccessError {}
@@ -0,0 +1,236 @@
+use crate::utils::compat::*;
+use crate::utils::{JSONValue, Lexer, ParseError, Parser};
+
+/// A step of a document read as a stream of events. Keys and values of
+/// objects come in pairs, scalars are read as a whole.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Event {
+    StartObject,
+    EndObject,
+    StartArray,
+    EndArray,
+    Key(String),
+    /// A string, number, boolean or null.
+    Value(JSONValue)
+}
+
+/// The result of asking an `EventReader` for its next event.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) enum Step {
+    Event(Event),
+    /// The input fed so far ends in the middle of the document, or of a token.
+    NeedMoreData,
+    /// The input ended after a complete root value and whitespace.
+    Done
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Container {
+    Object,
+    Array
+}
+
+/// What the reader expects after skipping whitespace.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Expect {
+    Value,
+    /// A key or the end of an empty object.
+    FirstKey,
+    Key,
+    Colon,
+    /// An item or the end of an empty array.
+    FirstItem,
+    /// A comma or the end of the current container.
+    Separator,
+    End
+}
+
+/// An incremental reader turning chunks of input into events. Tokens are only read
+/// once they are complete, so chunks may be split anywhere, even inside a character.
+pub(crate) struct EventReader {
+    buffer: Vec<u8>,
+    /// Index in the buffer of the next byte to read.
+    position: usize,
+    /// Number of bytes consumed and dropped from the buffer.
+    offset: usize,
+    finished: bool,
+    stack: Vec<Container>,
+    expect: Expect
+}
+
+impl EventReader {
+    pub(crate) fn new() -> Self {
+        Self {
+            buffer: Vec::new(),
+            position: 0,
+            offset: 0,
+            finished: false,
+            stack: Vec::new(),
+            expect: Expect::Value
+        }
+    }
+
+    /// Append a chunk of input.
+    pub(crate) fn feed(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Mark the end of the input, so that a truncated document becomes an error.
+    pub(crate) fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    pub(crate) fn next_step(&mut self) -> Result<Step, ParseError> {
+        while self.buffer.get(self.position).map_or(false, |b| b.is_ascii_whitespace()) {
+            self.position += 1;
+        }
+        let b = match self.buffer.get(self.position) {
+            Some(b) => *b,
+            None if self.expect == Expect::End && self.finished => return Ok(Step::Done),
+            None => return self.need_more()
+        };
+
+        match (self.expect, b) {
+            (Expect::End, _) => Err(self.unexpected(b)),
+            (Expect::Colon, b':') => {
+                self.position += 1;
+                self.expect = Expect::Value;
+                self.next_step()
+            },
+            (Expect::Separator, b',') => {
+                self.position += 1;
+                self.expect = match self.stack.last() {
+                    Some(Container::Object) => Expect::Key,
+                    _ => Expect::Value
+                };
+                self.next_step()
+            },
+            (Expect::FirstKey, b'}') | (Expect::Separator, b'}') if self.stack.last() == Some(&Container::Object) => {
+                self.close(Event::EndObject)
+            },
+            (Expect::FirstItem, b']') | (Expect::Separator, b']') if self.stack.last() == Some(&Container::Array) => {
+                self.close(Event::EndArray)
+            },
+            (Expect::FirstKey, b'"') | (Expect::Key, b'"') => {
+                let end = match self.string_end()? {
+                    Some(end) => end,
+                    None => return self.need_more()
+                };
+                let key = match self.scalar(end)? {
+                    JSONValue::String(key) => key,
+                    _ => unreachable!("A quoted token is a string")
+                };
+
+                self.expect = Expect::Colon;
+                Ok(Step::Event(Event::Key(key.into_string())))
+            },
+            (Expect::Value, _) | (Expect::FirstItem, _) => self.value(b),
+            _ => Err(self.unexpected(b))
+        }
+    }
+
+    fn value(&mut self, b: u8) -> Result<Step, ParseError> {
+        let (container, event) = match b {
+            b'{' => (Container::Object, Event::StartObject),
+            b'[' => (Container::Array, Event::StartArray),
+            _ => {
+                let end = match b {
+                    b'"' => self.string_end()?,
+                    b'-' | b'0'..=b'9' => self.run_end(|b| b.is_ascii_digit() || matches!(b, b'.' | b'-' | b'+' | b'e' | b'E')),
+                    b'a'..=b'z' => self.run_end(|b| b.is_ascii_alphabetic()),
+                    _ => return Err(self.unexpected(b))
+                };
+                let end = match end {
+                    Some(end) => end,
+                    None => return self.need_more()
+                };
+                let value = self.scalar(end)?;
+
+                self.after_value();
+                return Ok(Step::Event(Event::Value(value)));
+            }
+        };
+
+        self.position += 1;
+        self.stack.push(container);
+        self.expect = match container {
+            Container::Object => Expect::FirstKey,
+            Container::Array => Expect::FirstItem
+        };
+        Ok(Step::Event(event))
+    }
+
+    fn close(&mut self, event: Event) -> Result<Step, ParseError> {
+        self.position += 1;
+        self.stack.pop();
+        self.after_value();
+        Ok(Step::Event(event))
+    }
+
+    fn after_value(&mut self) {
+        self.expect = if self.stack.is_empty() { Expect::End } else { Expect::Separator };
+    }
+
+    /// The index after the closing quote of the string starting at the current position,
+    /// or None when it has not been fed yet.
+    fn string_end(&self) -> Result<Option<usize>, ParseError> {
+        let mut escaped = false;
+
+        for (i, b) in self.buffer.iter().enumerate().skip(self.position + 1) {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => return Ok(Some(i + 1)),
+                _ => {}
+            }
+        }
+        if self.finished {
+            return Err(ParseError::Syntax(format!("Unterminated string (offset: {})", self.offset + self.position)));
+        }
+        Ok(None)
+    }
+
+    /// The index after the run of bytes starting at the current position, or None
+    /// when the run may go on in the next chunk.
+    fn run_end<F: Fn(u8) -> bool>(&self, accept: F) -> Option<usize> {
+        match self.buffer[self.position..].iter().position(|b| !accept(*b)) {
+            Some(len) => Some(self.position + len),
+            None if self.finished => Some(self.buffer.len()),
+            None => None
+        }
+    }
+
+    /// Parse the complete scalar token ending at `end` with the regular lexer and parser.
+    fn scalar(&mut self, end: usize) -> Result<JSONValue, ParseError> {
+        let start = self.offset + self.position;
+        let text = core::str::from_utf8(&self.buffer[self.position..end])
+            .map_err(|_| ParseError::Syntax(format!("Invalid UTF-8 input (offset: {})", start)))?;
+        let value = Parser::new(Lexer::new(text)).parse_document().map_err(|e| match e {
+            ParseError::Lex(mut error) => {
+                error.offset += start;
+                ParseError::Lex(error)
+            },
+            ParseError::Syntax(message) => ParseError::Syntax(format!("{} (offset: {})", message, start)),
+            e => e
+        })?;
+
+        self.position = end;
+        Ok(value)
+    }
+
+    fn need_more(&mut self) -> Result<Step, ParseError> {
+        if self.finished {
+            return Err(ParseError::Syntax(format!("Unexpected end of input (offset: {})", self.offset + self.position)));
+        }
+        self.buffer.drain(..self.position);
+        self.offset += self.position;
+        self.position = 0;
+        Ok(Step::NeedMoreData)
+    }
+
+    fn unexpected(&self, b: u8) -> ParseError {
+        let found = if b.is_ascii() { format!("'{}'", b as char) } else { "non-ASCII input".to_string() };
+
+        ParseError::Syntax(format!("Unexpected {} (offset: {})", found, self.offset + self.position))
+    }
+}
@@ -0,0 +1,918 @@
+//! A small jq-like language to select and reshape values, such as
+//! `.cars[] | select(.year > 2018) | .model`.
+//!
+//! An expression reads an input value and produces a stream of values, zero or more:
+//!
+//! - `.` is the input, `..` the input and every value nested in it;
+//! - `.name`, `."a key"`, `.[expr]` and `.[from:to]` read a key, an index (negative
+//!   from the end) or a slice, `.[]` every item or value, and a trailing `?` ignores errors;
+//! - `a | b` runs `b` on each output of `a`, and `a, b` outputs those of `a`, then those of `b`;
+//! - `[a]` collects the outputs of `a` into an array, and `{key: a, name}` builds objects;
+//! - literals are JSON numbers, strings, `true`, `false` and `null`;
+//! - `+ - * / %`, `== != < <= > >=`, `and`, `or` and `a // b` (the outputs of `a` that are
+//!   not null or false, or else those of `b`) work as in jq;
+//! - functions: `length`, `keys`, `has(key)`, `type`, `not`, `select(f)`, `map(f)`,
+//!   `first`, `last`, `sort`, `sort_by(f)`, `unique`, `reverse`, `min`, `max`, `add`,
+//!   `join(separator)`, `tostring`, `tonumber` and `empty`. Arguments are separated by `;`.
+//!
+//! # Example
+//!
+//! ```
+//! use jsonparser::{expr, JSONParser, JSONValue};
+//!
+//! let json = JSONParser::from(r#"{ "cars": [{ "model": "Ford", "year": 2018 }, { "model": "BMW", "year": 2020 }] }"#).unwrap();
+//!
+//! assert_eq!(expr::eval(".cars[] | select(.year > 2018) | .model", &json), Ok(vec![JSONValue::String("BMW".into())]));
+//! assert_eq!(expr::eval("[.cars[].year] | add", &json), Ok(vec![JSONValue::Number(4038.0)]));
+//! assert_eq!(expr::eval(".cars | length", &json), Ok(vec![JSONValue::Number(2.0)]));
+//! ```
+
+use core::cmp::Ordering;
+use core::fmt;
+
+use crate::utils::compat::*;
+use crate::utils::{EqualityOptions, JSONValue, Lexer, OrderedMap, Parser, Serialize};
+
+/// The functions of the language, with their number of arguments.
+const FUNCTIONS: [(&str, usize); 20] = [
+    ("add", 0), ("empty", 0), ("first", 0), ("has", 1), ("join", 1), ("keys", 0), ("last", 0), ("length", 0),
+    ("map", 1), ("max", 0), ("min", 0), ("not", 0), ("reverse", 0), ("select", 1), ("sort", 0), ("sort_by", 1),
+    ("tonumber", 0), ("tostring", 0), ("type", 0), ("unique", 0)
+];
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Dot,
+    DotDot,
+    Field(String),
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Punct(&'static str)
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Dot => write!(f, "'.'"),
+            Token::DotDot => write!(f, "'..'"),
+            Token::Field(name) => write!(f, "'.{}'", name),
+            Token::Ident(name) => write!(f, "'{}'", name),
+            Token::Str(s) => write!(f, "{}", s.serialize()),
+            Token::Num(n) => write!(f, "{}", n),
+            Token::Punct(p) => write!(f, "'{}'", p)
+        }
+    }
+}
+
+const PUNCTS: [&str; 24] = [
+    "==", "!=", "<=", ">=", "//", "|", ",", ":", ";", "?", "[", "]", "{", "}", "(", ")", "+", "-", "*", "/", "%", "<", ">", "$"
+];
+
+/// Read a string literal starting at the given offset, and return it with its length.
+fn read_string(input: &str, start: usize) -> Result<(String, usize), String> {
+    let mut escaped = false;
+
+    for (i, c) in input[start + 1..].char_indices() {
+        match c {
+            '\\' if !escaped => escaped = true,
+            '"' if !escaped => {
+                let literal = &input[start..start + i + 2];
+                let value = Parser::new(Lexer::new(literal)).parse_document().map_err(|e| format!("Invalid string at offset {}: {}", start, e))?;
+
+                return Ok((value.into_string().unwrap_or_default(), i + 2));
+            },
+            _ => escaped = false
+        }
+    }
+    Err(format!("Unterminated string at offset {}", start))
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn read_ident(input: &str) -> &str {
+    let end = input.find(|c: char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(input.len());
+
+    &input[..end]
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, String> {
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+
+    while let Some(c) = input[offset..].chars().next() {
+        let rest = &input[offset..];
+        let start = offset;
+
+        if c.is_whitespace() {
+            offset += c.len_utf8();
+            continue;
+        }
+
+        let token = if rest.starts_with("..") {
+            offset += 2;
+            Token::DotDot
+        } else if c == '.' {
+            let after = &rest[1..];
+
+            match after.chars().next() {
+                Some(next) if is_ident_start(next) => {
+                    let name = read_ident(after);
+
+                    offset += 1 + name.len();
+                    Token::Field(name.to_string())
+                },
+                Some('"') => {
+                    let (name, len) = read_string(input, offset + 1)?;
+
+                    offset += 1 + len;
+                    Token::Field(name)
+                },
+                _ => {
+                    offset += 1;
+                    Token::Dot
+                }
+            }
+        } else if c == '"' {
+            let (value, len) = read_string(input, offset)?;
+
+            offset += len;
+            Token::Str(value)
+        } else if c.is_ascii_digit() {
+            let bytes = rest.as_bytes();
+            let mut end = 0;
+
+            while end < bytes.len() {
+                let b = bytes[end];
+                let exponent_sign = (b == b'+' || b == b'-') && end > 0 && (bytes[end - 1] == b'e' || bytes[end - 1] == b'E');
+
+                if !(b.is_ascii_digit() || b == b'.' || b == b'e' || b == b'E' || exponent_sign) {
+                    break;
+                }
+                end += 1;
+            }
+            offset += end;
+            Token::Num(rest[..end].parse().map_err(|_| format!("Invalid number '{}' at offset {}", &rest[..end], start))?)
+        } else if is_ident_start(c) {
+            let name = read_ident(rest);
+
+            offset += name.len();
+            Token::Ident(name.to_string())
+        } else {
+            let punct = PUNCTS.iter().find(|p| rest.starts_with(*p)).ok_or_else(|| format!("Unexpected character '{}' at offset {}", c, start))?;
+
+            offset += punct.len();
+            Token::Punct(punct)
+        };
+
+        tokens.push((token, start));
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Identity,
+    Recurse,
+    Literal(JSONValue),
+    Field(Box<Node>, String),
+    Index(Box<Node>, Box<Node>),
+    Slice(Box<Node>, Option<Box<Node>>, Option<Box<Node>>),
+    Iterate(Box<Node>),
+    Optional(Box<Node>),
+    Pipe(Box<Node>, Box<Node>),
+    Comma(Box<Node>, Box<Node>),
+    Alternative(Box<Node>, Box<Node>),
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    Binary(Op, Box<Node>, Box<Node>),
+    Neg(Box<Node>),
+    Array(Option<Box<Node>>),
+    Object(Vec<(Node, Node)>),
+    Call(String, Vec<Node>)
+}
+
+struct ExprParser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    len: usize
+}
+
+impl ExprParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).map(|(token, _)| token.clone());
+
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Punct(p)) if *p == punct) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(name)) if name == keyword) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn unexpected(&self) -> String {
+        match self.tokens.get(self.position) {
+            Some((token, offset)) => format!("Unexpected {} at offset {}", token, offset),
+            None => format!("Unexpected end of expression at offset {}", self.len)
+        }
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<(), String> {
+        if self.eat(punct) {
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn pipe(&mut self) -> Result<Node, String> {
+        let left = self.comma()?;
+
+        if self.eat("|") {
+            Ok(Node::Pipe(Box::new(left), Box::new(self.pipe()?)))
+        } else {
+            Ok(left)
+        }
+    }
+
+    fn comma(&mut self) -> Result<Node, String> {
+        let mut left = self.alternative()?;
+
+        while self.eat(",") {
+            left = Node::Comma(Box::new(left), Box::new(self.alternative()?));
+        }
+        Ok(left)
+    }
+
+    fn alternative(&mut self) -> Result<Node, String> {
+        let left = self.or()?;
+
+        if self.eat("//") {
+            Ok(Node::Alternative(Box::new(left), Box::new(self.alternative()?)))
+        } else {
+            Ok(left)
+        }
+    }
+
+    fn or(&mut self) -> Result<Node, String> {
+        let mut left = self.and()?;
+
+        while self.eat_keyword("or") {
+            left = Node::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Node, String> {
+        let mut left = self.comparison()?;
+
+        while self.eat_keyword("and") {
+            left = Node::And(Box::new(left), Box::new(self.comparison()?));
+        }
+        Ok(left)
+    }
+
+    fn comparison(&mut self) -> Result<Node, String> {
+        let left = self.additive()?;
+        let op = match self.peek() {
+            Some(Token::Punct("==")) => Op::Eq,
+            Some(Token::Punct("!=")) => Op::Ne,
+            Some(Token::Punct("<")) => Op::Lt,
+            Some(Token::Punct("<=")) => Op::Le,
+            Some(Token::Punct(">")) => Op::Gt,
+            Some(Token::Punct(">=")) => Op::Ge,
+            _ => return Ok(left)
+        };
+
+        self.position += 1;
+        Ok(Node::Binary(op, Box::new(left), Box::new(self.additive()?)))
+    }
+
+    fn additive(&mut self) -> Result<Node, String> {
+        let mut left = self.multiplicative()?;
+
+        loop {
+            let op = match self.peek() {
+                Some(Token::Punct("+")) => Op::Add,
+                Some(Token::Punct("-")) => Op::Sub,
+                _ => return Ok(left)
+            };
+
+            self.position += 1;
+            left = Node::Binary(op, Box::new(left), Box::new(self.multiplicative()?));
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Node, String> {
+        let mut left = self.unary()?;
+
+        loop {
+            let op = match self.peek() {
+                Some(Token::Punct("*")) => Op::Mul,
+                Some(Token::Punct("/")) => Op::Div,
+                Some(Token::Punct("%")) => Op::Rem,
+                _ => return Ok(left)
+            };
+
+            self.position += 1;
+            left = Node::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Node, String> {
+        if self.eat("-") {
+            Ok(Node::Neg(Box::new(self.unary()?)))
+        } else {
+            self.postfix()
+        }
+    }
+
+    fn postfix(&mut self) -> Result<Node, String> {
+        let mut node = self.primary()?;
+
+        loop {
+            node = match self.peek() {
+                Some(Token::Field(name)) => {
+                    let name = name.clone();
+
+                    self.position += 1;
+                    Node::Field(Box::new(node), name)
+                },
+                Some(Token::Dot) if matches!(self.tokens.get(self.position + 1), Some((Token::Punct("["), _))) => {
+                    self.position += 1;
+                    continue;
+                },
+                Some(Token::Punct("[")) => {
+                    self.position += 1;
+                    self.brackets(node)?
+                },
+                Some(Token::Punct("?")) => {
+                    self.position += 1;
+                    Node::Optional(Box::new(node))
+                },
+                _ => return Ok(node)
+            };
+        }
+    }
+
+    /// The suffix of the given node after an opening bracket: an iteration, an index or a slice.
+    fn brackets(&mut self, node: Node) -> Result<Node, String> {
+        if self.eat("]") {
+            return Ok(Node::Iterate(Box::new(node)));
+        }
+
+        let from = if matches!(self.peek(), Some(Token::Punct(":"))) { None } else { Some(Box::new(self.pipe()?)) };
+
+        if self.eat(":") {
+            let to = if matches!(self.peek(), Some(Token::Punct("]"))) { None } else { Some(Box::new(self.pipe()?)) };
+
+            self.expect("]")?;
+            return Ok(Node::Slice(Box::new(node), from, to));
+        }
+        self.expect("]")?;
+        match from {
+            Some(index) => Ok(Node::Index(Box::new(node), index)),
+            None => Err(self.unexpected())
+        }
+    }
+
+    fn primary(&mut self) -> Result<Node, String> {
+        let start = self.position;
+
+        match self.next() {
+            Some(Token::Dot) => Ok(Node::Identity),
+            Some(Token::DotDot) => Ok(Node::Recurse),
+            Some(Token::Field(name)) => Ok(Node::Field(Box::new(Node::Identity), name)),
+            Some(Token::Str(s)) => Ok(Node::Literal(JSONValue::String(s.into()))),
+            Some(Token::Num(n)) => Ok(Node::Literal(JSONValue::Number(n))),
+            Some(Token::Ident(name)) => match name.as_str() {
+                "true" => Ok(Node::Literal(JSONValue::Boolean(true))),
+                "false" => Ok(Node::Literal(JSONValue::Boolean(false))),
+                "null" => Ok(Node::Literal(JSONValue::Null)),
+                _ => self.call(name, start)
+            },
+            Some(Token::Punct("(")) => {
+                let node = self.pipe()?;
+
+                self.expect(")")?;
+                Ok(node)
+            },
+            Some(Token::Punct("[")) => {
+                if self.eat("]") {
+                    return Ok(Node::Array(None));
+                }
+
+                let node = self.pipe()?;
+
+                self.expect("]")?;
+                Ok(Node::Array(Some(Box::new(node))))
+            },
+            Some(Token::Punct("{")) => self.object(),
+            _ => {
+                self.position = start;
+                Err(self.unexpected())
+            }
+        }
+    }
+
+    fn call(&mut self, name: String, start: usize) -> Result<Node, String> {
+        let mut args = Vec::new();
+
+        if self.eat("(") {
+            loop {
+                args.push(self.pipe()?);
+                if !self.eat(";") {
+                    break;
+                }
+            }
+            self.expect(")")?;
+        }
+        if !FUNCTIONS.contains(&(name.as_str(), args.len())) {
+            return Err(format!("Unknown function '{}/{}' at offset {}", name, args.len(), self.tokens[start].1));
+        }
+        Ok(Node::Call(name, args))
+    }
+
+    fn object(&mut self) -> Result<Node, String> {
+        let mut entries = Vec::new();
+
+        if self.eat("}") {
+            return Ok(Node::Object(entries));
+        }
+        loop {
+            let (key, shorthand) = match self.next() {
+                Some(Token::Ident(name)) | Some(Token::Str(name)) => (Node::Literal(JSONValue::String(name.clone().into())), Some(name)),
+                Some(Token::Punct("(")) => {
+                    let key = self.pipe()?;
+
+                    self.expect(")")?;
+                    (key, None)
+                },
+                _ => {
+                    self.position -= 1;
+                    return Err(self.unexpected());
+                }
+            };
+            let value = match shorthand {
+                _ if self.eat(":") => self.alternative()?,
+                Some(name) => Node::Field(Box::new(Node::Identity), name),
+                None => return Err(self.unexpected())
+            };
+
+            entries.push((key, value));
+            if !self.eat(",") {
+                break;
+            }
+        }
+        self.expect("}")?;
+        Ok(Node::Object(entries))
+    }
+}
+
+/// A parsed expression, to be evaluated against many values.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::expr::Expr;
+/// use jsonparser::{JSONParser, Serialize};
+///
+/// let expr = Expr::parse("{ name, cars: [.cars[] | .model | select(. != null)] }").unwrap();
+/// let json = JSONParser::from(r#"{ "name": "John", "age": 30, "cars": [{ "model": "Ford" }, {}] }"#).unwrap();
+///
+/// assert_eq!(expr.eval(&json).unwrap()[0].serialize(), r#"{"name":"John","cars":["Ford"]}"#);
+///
+/// assert_eq!(Expr::parse(".cars[").err(), Some("Unexpected end of expression at offset 6".to_string()));
+/// assert_eq!(Expr::parse("count(.)").err(), Some("Unknown function 'count/1' at offset 0".to_string()));
+/// assert_eq!(Expr::parse(".name | .first").unwrap().eval(&json).err(), Some(r#"Cannot index string with "first""#.to_string()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Expr {
+    root: Node
+}
+
+impl Expr {
+    /// Parse an expression.
+    pub fn parse(source: &str) -> Result<Expr, String> {
+        let mut parser = ExprParser { tokens: tokenize(source)?, position: 0, len: source.len() };
+        let root = parser.pipe()?;
+
+        match parser.peek() {
+            Some(_) => Err(parser.unexpected()),
+            None => Ok(Expr { root })
+        }
+    }
+
+    /// Evaluate the expression against the value, and return its outputs.
+    pub fn eval(&self, value: &JSONValue) -> Result<Vec<JSONValue>, String> {
+        eval_node(&self.root, value)
+    }
+}
+
+/// Parse the expression and evaluate it against the value.
+pub fn eval(source: &str, value: &JSONValue) -> Result<Vec<JSONValue>, String> {
+    Expr::parse(source)?.eval(value)
+}
+
+fn truthy(value: &JSONValue) -> bool {
+    !matches!(value, JSONValue::Null | JSONValue::Boolean(false))
+}
+
+fn resolve_raw(value: &JSONValue) -> Option<JSONValue> {
+    match value {
+        JSONValue::Raw(raw) => Some(raw.to_value()),
+        _ => None
+    }
+}
+
+fn index_key(value: &JSONValue, key: &str) -> Result<JSONValue, String> {
+    match value {
+        JSONValue::Object(obj) => Ok(obj.get(key).cloned().unwrap_or(JSONValue::Null)),
+        JSONValue::Null => Ok(JSONValue::Null),
+        JSONValue::Raw(raw) => index_key(&raw.to_value(), key),
+        value => Err(format!("Cannot index {} with {}", value.type_name(), key.serialize()))
+    }
+}
+
+fn index(value: &JSONValue, index: &JSONValue) -> Result<JSONValue, String> {
+    match (value, index) {
+        (_, JSONValue::String(key)) => index_key(value, key),
+        (JSONValue::Array(array), JSONValue::Number(n)) => {
+            let i = n.floor() as isize;
+            let i = if i < 0 { array.len() as isize + i } else { i };
+
+            Ok(if i < 0 { None } else { array.get(i as usize) }.cloned().unwrap_or(JSONValue::Null))
+        },
+        (JSONValue::Null, JSONValue::Number(_)) => Ok(JSONValue::Null),
+        (JSONValue::Raw(raw), _) => self::index(&raw.to_value(), index),
+        (value, index) => Err(format!("Cannot index {} with {}", value.type_name(), index.type_name()))
+    }
+}
+
+/// The position of a bound of a slice in a sequence of the given length.
+fn slice_bound(bound: &JSONValue, len: usize, default: usize) -> Result<usize, String> {
+    match bound {
+        JSONValue::Null => Ok(default),
+        JSONValue::Number(n) => {
+            let i = n.floor() as isize;
+            let i = if i < 0 { len as isize + i } else { i };
+
+            Ok(i.max(0).min(len as isize) as usize)
+        },
+        bound => Err(format!("Cannot slice with {}", bound.type_name()))
+    }
+}
+
+fn slice(value: &JSONValue, from: &JSONValue, to: &JSONValue) -> Result<JSONValue, String> {
+    match value {
+        JSONValue::Array(array) => {
+            let start = slice_bound(from, array.len(), 0)?;
+            let end = slice_bound(to, array.len(), array.len())?.max(start);
+
+            Ok(JSONValue::Array(array[start..end].to_vec().into()))
+        },
+        JSONValue::String(s) => {
+            let chars = s.chars().collect::<Vec<_>>();
+            let start = slice_bound(from, chars.len(), 0)?;
+            let end = slice_bound(to, chars.len(), chars.len())?.max(start);
+
+            Ok(JSONValue::String(chars[start..end].iter().collect::<String>().into()))
+        },
+        JSONValue::Null => Ok(JSONValue::Null),
+        value => Err(format!("Cannot slice {}", value.type_name()))
+    }
+}
+
+fn iterate(value: &JSONValue) -> Result<Vec<JSONValue>, String> {
+    match value {
+        JSONValue::Array(array) => Ok(array.to_vec()),
+        JSONValue::Object(obj) => Ok(obj.iter().map(|(_, value)| value.clone()).collect()),
+        JSONValue::Raw(raw) => iterate(&raw.to_value()),
+        value => Err(format!("Cannot iterate over {}", value.type_name()))
+    }
+}
+
+fn recurse(value: &JSONValue, output: &mut Vec<JSONValue>) {
+    output.push(value.clone());
+    match value {
+        JSONValue::Array(array) => array.iter().for_each(|item| recurse(item, output)),
+        JSONValue::Object(obj) => obj.iter().for_each(|(_, value)| recurse(value, output)),
+        _ => {}
+    }
+}
+
+fn binary(op: Op, left: &JSONValue, right: &JSONValue) -> Result<JSONValue, String> {
+    let (left, right) = (resolve_raw(left).unwrap_or_else(|| left.clone()), resolve_raw(right).unwrap_or_else(|| right.clone()));
+    let ordering = || left.cmp_values(&right);
+    let mismatch = |verb: &str| format!("Cannot {} {} and {}", verb, left.type_name(), right.type_name());
+
+    Ok(match op {
+        Op::Eq => JSONValue::Boolean(left.equals_with(&right, EqualityOptions::new())),
+        Op::Ne => JSONValue::Boolean(!left.equals_with(&right, EqualityOptions::new())),
+        Op::Lt => JSONValue::Boolean(ordering() == Ordering::Less),
+        Op::Le => JSONValue::Boolean(ordering() != Ordering::Greater),
+        Op::Gt => JSONValue::Boolean(ordering() == Ordering::Greater),
+        Op::Ge => JSONValue::Boolean(ordering() != Ordering::Less),
+        Op::Add => match (&left, &right) {
+            (JSONValue::Null, value) | (value, JSONValue::Null) => (*value).clone(),
+            (JSONValue::Number(a), JSONValue::Number(b)) => JSONValue::Number(a + b),
+            (JSONValue::String(a), JSONValue::String(b)) => JSONValue::String(format!("{}{}", a, b).into()),
+            (JSONValue::Array(a), JSONValue::Array(b)) => JSONValue::Array(a.iter().chain(b.iter()).cloned().collect()),
+            (JSONValue::Object(a), JSONValue::Object(b)) => {
+                let mut merged = a.clone();
+
+                merged.extend(b.iter().map(|(key, value)| (key.to_string(), value.clone())));
+                JSONValue::Object(merged)
+            },
+            _ => return Err(mismatch("add"))
+        },
+        Op::Sub => match (&left, &right) {
+            (JSONValue::Number(a), JSONValue::Number(b)) => JSONValue::Number(a - b),
+            (JSONValue::Array(a), JSONValue::Array(b)) => JSONValue::Array(a.iter().filter(|item| !b.contains(item)).cloned().collect()),
+            _ => return Err(mismatch("subtract"))
+        },
+        Op::Mul => match (&left, &right) {
+            (JSONValue::Number(a), JSONValue::Number(b)) => JSONValue::Number(a * b),
+            _ => return Err(mismatch("multiply"))
+        },
+        Op::Div => match (&left, &right) {
+            (JSONValue::Number(_), JSONValue::Number(b)) if *b == 0.0 => return Err("Cannot divide by zero".to_string()),
+            (JSONValue::Number(a), JSONValue::Number(b)) => JSONValue::Number(a / b),
+            (JSONValue::String(a), JSONValue::String(b)) => JSONValue::Array(a.split(b.as_str()).map(|part| JSONValue::String(part.into())).collect()),
+            _ => return Err(mismatch("divide"))
+        },
+        Op::Rem => match (&left, &right) {
+            (JSONValue::Number(_), JSONValue::Number(b)) if b.trunc() == 0.0 => return Err("Cannot divide by zero".to_string()),
+            (JSONValue::Number(a), JSONValue::Number(b)) => JSONValue::Number(a.trunc() % b.trunc()),
+            _ => return Err(mismatch("divide"))
+        }
+    })
+}
+
+/// Evaluate both nodes against the input, and combine each output of `right` with each of `left`.
+fn combine<F: FnMut(&JSONValue, &JSONValue) -> Result<JSONValue, String>>(left: &Node, right: &Node, input: &JSONValue, mut f: F) -> Result<Vec<JSONValue>, String> {
+    let rights = eval_node(right, input)?;
+    let mut output = Vec::new();
+
+    for l in eval_node(left, input)? {
+        for r in &rights {
+            output.push(f(&l, r)?);
+        }
+    }
+    Ok(output)
+}
+
+fn eval_node(node: &Node, input: &JSONValue) -> Result<Vec<JSONValue>, String> {
+    match node {
+        Node::Identity => Ok(vec![input.clone()]),
+        Node::Recurse => {
+            let mut output = Vec::new();
+
+            recurse(input, &mut output);
+            Ok(output)
+        },
+        Node::Literal(value) => Ok(vec![value.clone()]),
+        Node::Field(target, name) => eval_node(target, input)?.iter().map(|value| index_key(value, name)).collect(),
+        Node::Index(target, key) => combine(target, key, input, index),
+        Node::Slice(target, from, to) => {
+            let from = match from {
+                Some(from) => eval_node(from, input)?,
+                None => vec![JSONValue::Null]
+            };
+            let to = match to {
+                Some(to) => eval_node(to, input)?,
+                None => vec![JSONValue::Null]
+            };
+            let mut output = Vec::new();
+
+            for value in eval_node(target, input)? {
+                for from in &from {
+                    for to in &to {
+                        output.push(slice(&value, from, to)?);
+                    }
+                }
+            }
+            Ok(output)
+        },
+        Node::Iterate(target) => {
+            let mut output = Vec::new();
+
+            for value in eval_node(target, input)? {
+                output.extend(iterate(&value)?);
+            }
+            Ok(output)
+        },
+        Node::Optional(inner) => Ok(eval_node(inner, input).unwrap_or_default()),
+        Node::Pipe(left, right) => {
+            let mut output = Vec::new();
+
+            for value in eval_node(left, input)? {
+                output.extend(eval_node(right, &value)?);
+            }
+            Ok(output)
+        },
+        Node::Comma(left, right) => {
+            let mut output = eval_node(left, input)?;
+
+            output.extend(eval_node(right, input)?);
+            Ok(output)
+        },
+        Node::Alternative(left, right) => {
+            let output = eval_node(left, input).unwrap_or_default().into_iter().filter(truthy).collect::<Vec<_>>();
+
+            if output.is_empty() {
+                eval_node(right, input)
+            } else {
+                Ok(output)
+            }
+        },
+        Node::And(left, right) | Node::Or(left, right) => {
+            let and = matches!(node, Node::And(..));
+            let mut output = Vec::new();
+
+            for l in eval_node(left, input)? {
+                if truthy(&l) != and {
+                    output.push(JSONValue::Boolean(!and));
+                    continue;
+                }
+                for r in eval_node(right, input)? {
+                    output.push(JSONValue::Boolean(truthy(&r)));
+                }
+            }
+            Ok(output)
+        },
+        Node::Binary(op, left, right) => combine(left, right, input, |l, r| binary(*op, l, r)),
+        Node::Neg(inner) => eval_node(inner, input)?.into_iter().map(|value| match value {
+            JSONValue::Number(n) => Ok(JSONValue::Number(-n)),
+            value => Err(format!("Cannot negate {}", value.type_name()))
+        }).collect(),
+        Node::Array(None) => Ok(vec![JSONValue::Array(Box::new([]))]),
+        Node::Array(Some(inner)) => Ok(vec![JSONValue::Array(eval_node(inner, input)?.into())]),
+        Node::Object(entries) => {
+            let mut objects = vec![OrderedMap::new()];
+
+            for (key, value) in entries {
+                let keys = eval_node(key, input)?;
+                let values = eval_node(value, input)?;
+                let mut next = Vec::new();
+
+                for obj in &objects {
+                    for key in &keys {
+                        let key = key.as_str().ok_or_else(|| format!("Object keys must be strings, found {}", key.type_name()))?;
+
+                        for value in &values {
+                            let mut obj: OrderedMap<JSONValue> = obj.clone();
+
+                            obj.insert(key, value.clone());
+                            next.push(obj);
+                        }
+                    }
+                }
+                objects = next;
+            }
+            Ok(objects.into_iter().map(JSONValue::Object).collect())
+        },
+        Node::Call(name, args) => call(name, args, input)
+    }
+}
+
+/// The items of an array, or an error naming the function.
+fn items<'v>(name: &str, value: &'v JSONValue) -> Result<&'v [JSONValue], String> {
+    value.as_array().ok_or_else(|| format!("Cannot apply {} to {}", name, value.type_name()))
+}
+
+fn call(name: &str, args: &[Node], input: &JSONValue) -> Result<Vec<JSONValue>, String> {
+    let resolved = resolve_raw(input);
+    let input = resolved.as_ref().unwrap_or(input);
+    let one = |value: JSONValue| Ok(vec![value]);
+
+    match name {
+        "empty" => Ok(Vec::new()),
+        "not" => one(JSONValue::Boolean(!truthy(input))),
+        "type" => one(JSONValue::String(input.type_name().into())),
+        "length" => one(JSONValue::Number(match input {
+            JSONValue::Number(n) => n.abs(),
+            JSONValue::Boolean(_) => return Err("Cannot apply length to boolean".to_string()),
+            value => value.len() as f64
+        })),
+        "keys" => match input {
+            JSONValue::Object(obj) => {
+                let mut keys = obj.iter().map(|(key, _)| key.to_string()).collect::<Vec<_>>();
+
+                keys.sort();
+                one(JSONValue::Array(keys.into_iter().map(|key| JSONValue::String(key.into())).collect()))
+            },
+            JSONValue::Array(array) => one(JSONValue::Array((0..array.len()).map(|i| JSONValue::Number(i as f64)).collect())),
+            value => Err(format!("Cannot apply keys to {}", value.type_name()))
+        },
+        "has" => eval_node(&args[0], input)?.iter().map(|key| match (input, key) {
+            (JSONValue::Object(obj), JSONValue::String(key)) => Ok(JSONValue::Boolean(obj.contains_key(key))),
+            (JSONValue::Array(array), JSONValue::Number(n)) => Ok(JSONValue::Boolean(*n >= 0.0 && (*n as usize) < array.len())),
+            (value, key) => Err(format!("Cannot check whether {} has a {} key", value.type_name(), key.type_name()))
+        }).collect(),
+        "select" => Ok(eval_node(&args[0], input)?.iter().filter(|value| truthy(value)).map(|_| input.clone()).collect()),
+        "map" => {
+            let mut output = Vec::new();
+
+            for item in iterate(input)? {
+                output.extend(eval_node(&args[0], &item)?);
+            }
+            one(JSONValue::Array(output.into()))
+        },
+        "first" => one(items(name, input)?.first().cloned().unwrap_or(JSONValue::Null)),
+        "last" => one(items(name, input)?.last().cloned().unwrap_or(JSONValue::Null)),
+        "sort" => {
+            let mut sorted = items(name, input)?.to_vec();
+
+            sorted.sort_by(JSONValue::cmp_values);
+            one(JSONValue::Array(sorted.into()))
+        },
+        "sort_by" => {
+            let mut keyed = Vec::new();
+
+            for item in items(name, input)? {
+                keyed.push((JSONValue::Array(eval_node(&args[0], item)?.into()), item.clone()));
+            }
+            keyed.sort_by(|(a, _), (b, _)| a.cmp_values(b));
+            one(JSONValue::Array(keyed.into_iter().map(|(_, item)| item).collect()))
+        },
+        "unique" => {
+            let mut sorted = items(name, input)?.to_vec();
+
+            sorted.sort_by(JSONValue::cmp_values);
+            sorted.dedup_by(|a, b| a.cmp_values(b) == Ordering::Equal);
+            one(JSONValue::Array(sorted.into()))
+        },
+        "reverse" => match input {
+            JSONValue::String(s) => one(JSONValue::String(s.chars().rev().collect())),
+            JSONValue::Null => one(JSONValue::Array(Box::new([]))),
+            value => one(JSONValue::Array(items(name, value)?.iter().rev().cloned().collect()))
+        },
+        "min" => one(items(name, input)?.iter().min_by(|a, b| a.cmp_values(b)).cloned().unwrap_or(JSONValue::Null)),
+        "max" => one(items(name, input)?.iter().max_by(|a, b| a.cmp_values(b)).cloned().unwrap_or(JSONValue::Null)),
+        "add" => {
+            let mut sum = JSONValue::Null;
+
+            for item in iterate(input)? {
+                sum = binary(Op::Add, &sum, &item)?;
+            }
+            one(sum)
+        },
+        "join" => eval_node(&args[0], input)?.iter().map(|separator| {
+            let separator = separator.as_str().ok_or_else(|| format!("Cannot join with {}", separator.type_name()))?;
+            let parts = items(name, input)?.iter().map(|item| match item {
+                JSONValue::Null => Ok(String::new()),
+                JSONValue::String(s) => Ok(s.to_string()),
+                JSONValue::Number(_) | JSONValue::Boolean(_) => Ok(item.serialize()),
+                item => Err(format!("Cannot join {}", item.type_name()))
+            }).collect::<Result<Vec<_>, String>>()?;
+
+            Ok(JSONValue::String(parts.join(separator).into()))
+        }).collect(),
+        "tostring" => one(match input {
+            JSONValue::String(_) => input.clone(),
+            value => JSONValue::String(value.serialize().into())
+        }),
+        "tonumber" => match input {
+            JSONValue::Number(_) => one(input.clone()),
+            JSONValue::String(s) => s.trim().parse().map(|n| vec![JSONValue::Number(n)]).map_err(|_| format!("Cannot parse '{}' as a number", s)),
+            value => Err(format!("Cannot convert {} to a number", value.type_name()))
+        },
+        _ => Err(format!("Unknown function '{}'", name))
+    }
+}
@@ -0,0 +1,143 @@
+#[cfg(feature = "mmap")]
+use std::fs::File;
+use std::path::Path;
+
+#[cfg(feature = "mmap")]
+use crate::{Arena, ArenaValue};
+use crate::{JSONParser, JSONValue, ParseError};
+
+/// Describe an I/O error along with the path it happened on.
+fn io_error(path: &Path, error: std::io::Error) -> ParseError {
+    ParseError::Io(format!("{}: {}", path.display(), error))
+}
+
+impl<'a> JSONParser<'a> {
+    /// Read and parse a file, like `JSONParser::from_bytes`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, ParseError};
+    ///
+    /// let path = std::env::temp_dir().join("jsonparser-from-file.json");
+    /// std::fs::write(&path, r#"{ "name": "John Doe" }"#).unwrap();
+    ///
+    /// let json = JSONParser::from_file(&path).unwrap();
+    ///
+    /// assert_eq!(json["name"].as_str(), Some("John Doe"));
+    /// assert!(matches!(JSONParser::from_file("missing.json"), Err(ParseError::Io(_))));
+    /// ```
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<JSONValue, ParseError> {
+        let path = path.as_ref();
+        let input = std::fs::read(path).map_err(|e| io_error(path, e))?;
+
+        JSONParser::from_bytes(&input)
+    }
+
+    /// Parse a file through a memory map, so that large files are not copied into
+    /// memory before parsing: UTF-8 input is parsed in place, and only the values
+    /// are allocated. Other encodings are transcoded like `JSONParser::from_bytes`.
+    ///
+    /// The returned JSONValue owns its strings and keys, which are copied out of the
+    /// map, and the map is released before returning. To read the strings from the map
+    /// instead, keep it open with `JSONParser::map_file`.
+    ///
+    /// The file must not be modified while it is being parsed.
+    ///
+    /// Available with the `mmap` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let path = std::env::temp_dir().join("jsonparser-from-mmap.json");
+    /// std::fs::write(&path, "\u{FEFF}{ \"tags\": [\"a\", \"b\"] }").unwrap();
+    ///
+    /// let json = JSONParser::from_mmap(&path).unwrap();
+    ///
+    /// assert_eq!(json["tags"][1].as_str(), Some("b"));
+    /// ```
+    #[cfg(feature = "mmap")]
+    pub fn from_mmap<P: AsRef<Path>>(path: P) -> Result<JSONValue, ParseError> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| io_error(path, e))?;
+        // SAFETY: the map is only read, and the file must not be modified while it is alive.
+        let map = unsafe { memmap2::Mmap::map(&file) }.map_err(|e| io_error(path, e))?;
+        let bytes = map.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(&map);
+
+        match core::str::from_utf8(bytes) {
+            Ok(input) => JSONParser::new(input).parse(),
+            Err(_) => JSONParser::from_bytes(&map)
+        }
+    }
+
+    /// Map a UTF-8 file into memory, to be parsed with `MappedFile::parse_in` without
+    /// copying its strings. A leading BOM is skipped. Other encodings cannot be read in
+    /// place and are rejected, `JSONParser::from_mmap` transcodes them instead.
+    ///
+    /// The file must not be modified while it is mapped.
+    ///
+    /// Available with the `mmap` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{Arena, JSONParser};
+    ///
+    /// let path = std::env::temp_dir().join("jsonparser-map-file.json");
+    /// std::fs::write(&path, r#"{ "name": "John Doe", "tags": ["a", "b"] }"#).unwrap();
+    ///
+    /// let file = JSONParser::map_file(&path).unwrap();
+    /// let mut arena = Arena::new();
+    /// let json = file.parse_in(&mut arena).unwrap();
+    ///
+    /// let name = json.get("name").and_then(|name| name.as_str()).unwrap();
+    ///
+    /// assert_eq!(name, "John Doe");
+    /// assert!(file.as_str().as_bytes().as_ptr_range().contains(&name.as_ptr()));
+    ///
+    /// std::fs::write(&path, b"\xFF\xFE{\x00}\x00").unwrap();
+    /// assert!(JSONParser::map_file(&path).is_err());
+    /// ```
+    #[cfg(feature = "mmap")]
+    pub fn map_file<P: AsRef<Path>>(path: P) -> Result<MappedFile, ParseError> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| io_error(path, e))?;
+        // SAFETY: the map is only read, and the file must not be modified while it is alive.
+        let map = unsafe { memmap2::Mmap::map(&file) }.map_err(|e| io_error(path, e))?;
+        let start = if map.starts_with(b"\xEF\xBB\xBF") { 3 } else { 0 };
+
+        core::str::from_utf8(&map[start..])
+            .map_err(|e| ParseError::Syntax(format!("{}: Invalid UTF-8 input: {}", path.display(), e)))?;
+        Ok(MappedFile { map, start })
+    }
+}
+
+/// A UTF-8 file mapped into memory by `JSONParser::map_file`, whose strings are read in
+/// place by the values parsed from it.
+///
+/// Available with the `mmap` feature.
+#[cfg(feature = "mmap")]
+pub struct MappedFile {
+    map: memmap2::Mmap,
+    /// Length of the BOM skipped at the start of the file.
+    start: usize
+}
+
+#[cfg(feature = "mmap")]
+impl MappedFile {
+    /// Returns the text of the file, without its BOM.
+    pub fn as_str(&self) -> &str {
+        // SAFETY: the text was checked to be UTF-8 by `map_file`, and the file is not
+        // modified while it is mapped.
+        unsafe { core::str::from_utf8_unchecked(&self.map[self.start..]) }
+    }
+
+    /// Parse the file into the given arena like `JSONParser::parse_borrowed_in`: the
+    /// strings and keys written without escapes are read from the map rather than copied,
+    /// so the returned view borrows both the arena and the file.
+    pub fn parse_in<'m>(&'m self, arena: &'m mut Arena) -> Result<ArenaValue<'m>, ParseError> {
+        JSONParser::new(self.as_str()).parse_borrowed_in(arena)
+    }
+}
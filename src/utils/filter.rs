@@ -0,0 +1,239 @@
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+use crate::utils::compat::*;
+use crate::utils::parser::escape;
+use crate::utils::pointer::parse_pointer;
+use crate::utils::project::rest;
+use crate::utils::{JSONKind, Lexer, ParseError, Parser};
+
+/// Rewrite a document as it is read, keeping or dropping subtrees selected by JSON
+/// Pointers, without building the document in memory. A `*` token matches every key of
+/// an object or item of an array.
+///
+/// With include pointers, only the values they point to are written, within their
+/// parents, as `JSONValue::project` does. Exclude pointers then drop values, as
+/// `JSONValue::exclude` does. The output is compact JSON, and numbers keep their text.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{Lexer, StreamFilter};
+///
+/// let input = r#"{
+///   "id": 1.50,
+///   "attachments": [{ "name": "a.png", "data": "iVBORw0KGgo..." }, { "name": "b.png", "data": "R0lGODlh..." }],
+///   "meta": { "size": 42 }
+/// }"#;
+/// let mut output = String::new();
+///
+/// StreamFilter::new().exclude("/attachments/*/data").filter(Lexer::new(input), &mut output).unwrap();
+/// assert_eq!(output, r#"{"id":1.50,"attachments":[{"name":"a.png"},{"name":"b.png"}],"meta":{"size":42}}"#);
+///
+/// output.clear();
+/// StreamFilter::new().include("/attachments/*/name").include("/missing").filter(Lexer::new(input), &mut output).unwrap();
+/// assert_eq!(output, r#"{"attachments":[{"name":"a.png"},{"name":"b.png"}]}"#);
+///
+/// assert!(StreamFilter::new().filter(Lexer::new(r#"{ "id": }"#), &mut String::new()).is_err());
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct StreamFilter {
+    includes: Vec<String>,
+    excludes: Vec<String>
+}
+
+/// The output, where the openings of containers written only if one of their values is
+/// are held back until then.
+struct Output<'w, W: fmt::Write> {
+    out: &'w mut W,
+    pending: String,
+    written: usize
+}
+
+impl<'w, W: fmt::Write> Output<'w, W> {
+    fn write(&mut self, text: &str) -> Result<(), ParseError> {
+        let write_error = |_| ParseError::Syntax("Cannot write the output".to_string());
+
+        if !self.pending.is_empty() {
+            self.out.write_str(&self.pending).map_err(write_error)?;
+            self.pending.clear();
+        }
+        self.out.write_str(text).map_err(write_error)?;
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Close the container opened at the given length of the pending text. A container
+    /// written only for some of its values is dropped when none of them was written.
+    fn close(&mut self, mark: usize, whole: bool, close: &str) -> Result<(), ParseError> {
+        if self.pending.len() > mark && !whole {
+            self.pending.truncate(mark);
+            Ok(())
+        } else {
+            self.write(close)
+        }
+    }
+}
+
+impl StreamFilter {
+    /// Create a new StreamFilter instance, keeping the whole document.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep the value at the given JSON Pointer. Once a pointer is included, only the
+    /// included values are kept.
+    pub fn include(mut self, pointer: &str) -> Self {
+        self.includes.push(pointer.to_string());
+        self
+    }
+
+    /// Drop the value at the given JSON Pointer.
+    pub fn exclude(mut self, pointer: &str) -> Self {
+        self.excludes.push(pointer.to_string());
+        self
+    }
+
+    /// Read the document from the lexer and write the filtered document to the output.
+    /// When nothing is kept, an empty container of the kind of the root is written, or
+    /// null when the root is a scalar or excluded.
+    pub fn filter<W: fmt::Write>(&self, lexer: Lexer, out: &mut W) -> Result<(), ParseError> {
+        let parse = |pointers: &[String]| pointers.iter().map(|pointer| parse_pointer(pointer)).collect::<Result<Vec<_>, _>>().map_err(ParseError::Syntax);
+        let includes = parse(&self.includes)?;
+        let excludes = parse(&self.excludes)?;
+        let includes = includes.iter().map(|path| path.as_slice()).collect::<Vec<_>>();
+        let excludes = excludes.iter().map(|path| path.as_slice()).collect::<Vec<_>>();
+        let mut parser = Parser::new(lexer);
+        let mut output = Output { out, pending: String::new(), written: 0 };
+
+        parser.next_token()?;
+
+        let kind = parser.peek_kind();
+
+        filter_value(&mut parser, &mut output, if includes.is_empty() { None } else { Some(&includes) }, &excludes)?;
+        if !parser.at_end() {
+            return Err(parser.unexpected());
+        }
+        if output.written == 0 {
+            output.write(match kind {
+                _ if excludes.iter().any(|path| path.is_empty()) => "null",
+                Some(JSONKind::Object) => "{}",
+                Some(JSONKind::Array) => "[]",
+                _ => "null"
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Read the document from the reader and write the filtered document to the writer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::StreamFilter;
+    ///
+    /// let mut output = Vec::new();
+    ///
+    /// StreamFilter::new().include("/name").filter_io(r#"{ "name": "John", "photo": "/9j/4AAQ..." }"#.as_bytes(), &mut output).unwrap();
+    /// assert_eq!(output, br#"{"name":"John"}"#);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn filter_io<R: io::Read, W: io::Write>(&self, reader: R, writer: W) -> Result<(), ParseError> {
+        struct Adapter<W: io::Write> {
+            writer: W,
+            error: Option<io::Error>
+        }
+
+        impl<W: io::Write> fmt::Write for Adapter<W> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.writer.write_all(s.as_bytes()).map_err(|e| {
+                    self.error = Some(e);
+                    fmt::Error
+                })
+            }
+        }
+
+        let mut adapter = Adapter { writer: io::BufWriter::new(writer), error: None };
+        let result = self.filter(Lexer::from_reader(reader), &mut adapter);
+
+        match adapter.error.take() {
+            Some(e) => Err(ParseError::Syntax(format!("Cannot write the output: {}", e))),
+            None => result.and_then(|_| io::Write::flush(&mut adapter.writer).map_err(|e| ParseError::Syntax(format!("Cannot write the output: {}", e))))
+        }
+    }
+}
+
+/// Filter the value starting at the current token. `includes` is None once the value is
+/// included whole.
+fn filter_value<W: fmt::Write>(parser: &mut Parser, output: &mut Output<W>, includes: Option<&[&[String]]>, excludes: &[&[String]]) -> Result<(), ParseError> {
+    if excludes.iter().any(|path| path.is_empty()) {
+        return parser.skip_value();
+    }
+
+    let includes = includes.filter(|paths| !paths.iter().any(|path| path.is_empty()));
+
+    if includes.map_or(false, |paths| paths.is_empty()) {
+        return parser.skip_value();
+    }
+
+    match parser.peek_kind() {
+        Some(JSONKind::Object) => {
+            let mark = output.pending.len();
+            let mut first = true;
+
+            parser.count_node()?;
+            output.pending.push('{');
+            parser.parse_entries(|parser: &mut Parser, key| {
+                let start = output.pending.len();
+                let written = output.written;
+
+                if !first {
+                    output.pending.push(',');
+                }
+                output.pending.push_str(&escape(key));
+                output.pending.push(':');
+                filter_value(parser, output, includes.map(|paths| rest(paths, key)).as_deref(), &rest(excludes, key))?;
+                if output.written == written {
+                    output.pending.truncate(start);
+                } else {
+                    first = false;
+                }
+                Ok::<(), ParseError>(())
+            })?;
+            output.close(mark, includes.is_none(), "}")
+        },
+        Some(JSONKind::Array) => {
+            let mark = output.pending.len();
+            let mut index = 0;
+            let mut first = true;
+
+            parser.count_node()?;
+            output.pending.push('[');
+            parser.parse_items(|parser: &mut Parser| {
+                let start = output.pending.len();
+                let written = output.written;
+                let token = index.to_string();
+
+                if !first {
+                    output.pending.push(',');
+                }
+                filter_value(parser, output, includes.map(|paths| rest(paths, &token)).as_deref(), &rest(excludes, &token))?;
+                if output.written == written {
+                    output.pending.truncate(start);
+                } else {
+                    first = false;
+                }
+                index += 1;
+                Ok::<(), ParseError>(())
+            })?;
+            output.close(mark, includes.is_none(), "]")
+        },
+        Some(_) if includes.is_none() => {
+            let text = parser.scalar_text()?;
+
+            output.write(&text)
+        },
+        _ => parser.skip_value()
+    }
+}
@@ -0,0 +1,196 @@
+use crate::utils::compat::*;
+use crate::utils::parser::{edit_items, escape};
+use crate::utils::{JSONValue, Lexer, OrderedMap, Parser, Serialize, DEFAULT_MAX_DEPTH};
+
+enum Step {
+    Key(String),
+    Index(usize)
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+impl JSONValue {
+    /// Serialize the value as gron: one JavaScript-like assignment per line,
+    /// giving the full path of every value so the output can be searched with grep.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "cars": [{ "model": "Ford" }], "my key": null }"#).unwrap();
+    ///
+    /// assert_eq!(json.to_gron(), concat!(
+    ///     "json = {};\n",
+    ///     "json.cars = [];\n",
+    ///     "json.cars[0] = {};\n",
+    ///     "json.cars[0].model = \"Ford\";\n",
+    ///     "json[\"my key\"] = null;\n"
+    /// ));
+    /// ```
+    pub fn to_gron(&self) -> String {
+        let mut output = String::new();
+
+        self.write_gron(&mut "json".to_string(), &mut output);
+        output
+    }
+
+    fn write_gron(&self, path: &mut String, output: &mut String) {
+        let value = match self {
+            JSONValue::Object(_) => "{}".to_string(),
+            JSONValue::Array(_) => "[]".to_string(),
+            value => value.serialize()
+        };
+        let len = path.len();
+
+        output.push_str(&format!("{} = {};\n", path, value));
+        match self {
+            JSONValue::Object(obj) => {
+                for (key, value) in obj.iter() {
+                    if is_identifier(key) {
+                        path.push('.');
+                        path.push_str(key);
+                    } else {
+                        path.push_str(&format!("[{}]", escape(key)));
+                    }
+                    value.write_gron(path, output);
+                    path.truncate(len);
+                }
+            },
+            JSONValue::Array(array) => {
+                for (index, value) in array.iter().enumerate() {
+                    path.push_str(&format!("[{}]", index));
+                    value.write_gron(path, output);
+                    path.truncate(len);
+                }
+            },
+            _ => {}
+        }
+    }
+
+    /// Rebuild a value from gron assignments, as produced by `to_gron`.
+    /// Empty lines are ignored, statements apply in order, and missing
+    /// containers along a path are created. A path may have at most
+    /// `DEFAULT_MAX_DEPTH` steps.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONValue};
+    ///
+    /// let json = JSONParser::from(r#"{ "cars": [{ "model": "Ford" }], "my key": null }"#).unwrap();
+    ///
+    /// assert_eq!(JSONValue::from_gron(&json.to_gron()), Ok(json));
+    /// assert_eq!(JSONValue::from_gron("json.cars[0].model = \"Ford\";"), JSONParser::from(r#"{ "cars": [{ "model": "Ford" }] }"#).map_err(|e| e.to_string()));
+    /// assert!(JSONValue::from_gron("json = 1;\njson.cars = [];").is_err());
+    ///
+    /// let nested = format!("json{} = 1;", ".a".repeat(200_000));
+    ///
+    /// assert_eq!(JSONValue::from_gron(&nested), Err("Line 1: Maximum nesting depth exceeded (max: 128)".to_string()));
+    /// ```
+    pub fn from_gron(input: &str) -> Result<JSONValue, String> {
+        let mut root = JSONValue::Null;
+
+        for (number, line) in input.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            let (steps, value) = parse_statement(line.trim())
+                .map_err(|e| format!("Line {}: {}", number + 1, e))?;
+            let target = steps.iter().try_fold(&mut root, step_into)
+                .map_err(|e| format!("Line {}: {}", number + 1, e))?;
+
+            *target = value;
+        }
+        Ok(root)
+    }
+}
+
+/// Move to the child reached by a step, appending an item when indexing one past the end of an array.
+/// A null target becomes the container the step expects, so filtered output such as
+/// `grep`'s, missing the container assignments, can still be rebuilt.
+fn step_into<'a>(target: &'a mut JSONValue, step: &Step) -> Result<&'a mut JSONValue, String> {
+    if target.is_null() {
+        *target = match step {
+            Step::Key(_) => JSONValue::Object(OrderedMap::new()),
+            Step::Index(_) => JSONValue::Array(Box::new([]))
+        };
+    }
+    match (target, step) {
+        (JSONValue::Object(obj), Step::Key(key)) => {
+            if !obj.contains_key(key) {
+                obj.insert(key, JSONValue::Null);
+            }
+            Ok(obj.get_mut(key).expect("Key was just inserted"))
+        },
+        (JSONValue::Array(array), Step::Index(index)) => {
+            if *index == array.len() {
+                edit_items(array, |items| items.push(JSONValue::Null));
+            }
+            array.get_mut(*index).ok_or_else(|| format!("Index {} is out of bounds", index))
+        },
+        (target, _) => Err(format!("Cannot assign into {}", target.type_name()))
+    }
+}
+
+/// Split a statement into the steps of its path and its value.
+fn parse_statement(line: &str) -> Result<(Vec<Step>, JSONValue), String> {
+    let mut rest = line.strip_prefix("json").ok_or("Statement must start with 'json'")?;
+    let mut steps = Vec::new();
+
+    loop {
+        if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$')).unwrap_or(after.len());
+
+            steps.push(Step::Key(after[..end].to_string()));
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix("[\"") {
+            let end = closing_quote(after).ok_or("Unterminated key")?;
+            let key = parse_value(&rest[1..end + 3])?;
+
+            steps.push(Step::Key(key.into_string().expect("Quoted key is a string")));
+            rest = after[end + 1..].strip_prefix(']').ok_or("Expected ']'")?;
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or("Expected ']'")?;
+            let index = after[..end].parse::<usize>().map_err(|_| format!("Invalid index: {}", &after[..end]))?;
+
+            steps.push(Step::Index(index));
+            rest = &after[end + 1..];
+        } else {
+            break;
+        }
+    }
+    if steps.len() > DEFAULT_MAX_DEPTH {
+        return Err(format!("Maximum nesting depth exceeded (max: {})", DEFAULT_MAX_DEPTH));
+    }
+    let value = rest.trim_start()
+        .strip_prefix('=')
+        .and_then(|value| value.trim_end().strip_suffix(';'))
+        .ok_or("Expected '= value;'")?;
+
+    Ok((steps, parse_value(value)?))
+}
+
+/// Byte offset of the quote ending a string whose opening quote was already consumed.
+fn closing_quote(input: &str) -> Option<usize> {
+    let mut escaped = false;
+
+    input.char_indices().find(|&(_, c)| {
+        let end = c == '"' && !escaped;
+
+        escaped = c == '\\' && !escaped;
+        end
+    }).map(|(i, _)| i)
+}
+
+fn parse_value(input: &str) -> Result<JSONValue, String> {
+    let value = match input.trim() {
+        "{}" => JSONValue::Object(OrderedMap::new()),
+        "[]" => JSONValue::Array(Box::new([])),
+        input => Parser::new(Lexer::new(input)).parse_document().map_err(|e| e.to_string())?
+    };
+
+    Ok(value)
+}
@@ -0,0 +1,92 @@
+use crate::utils::compat::*;
+use crate::utils::{Lexer, Span, TokenKind};
+
+/// The highlighting class of a span of input.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Class {
+    /// A string followed by a colon.
+    Key,
+    /// Any other string.
+    String,
+    Number,
+    /// Braces, brackets, colons and commas.
+    Punctuation,
+    /// `true`, `false` or `null`.
+    Keyword,
+    /// Input that cannot be part of a JSON document. After a string or an escape
+    /// sequence that cannot be read, the rest of the input is a single error span.
+    Error
+}
+
+/// Split the input into classified spans for syntax highlighting. Whitespace is not
+/// covered by any span. The input does not need to be valid or complete JSON, so
+/// that it can be highlighted while being edited.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{highlight, Class};
+///
+/// let input = r#"{ "id": 42, "tags": ["a", nul] }"#;
+/// let classes = highlight(input)
+///     .into_iter()
+///     .map(|(span, class)| (&input[span.start..span.end], class))
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(classes, vec![
+///     ("{", Class::Punctuation),
+///     (r#""id""#, Class::Key),
+///     (":", Class::Punctuation),
+///     ("42", Class::Number),
+///     (",", Class::Punctuation),
+///     (r#""tags""#, Class::Key),
+///     (":", Class::Punctuation),
+///     ("[", Class::Punctuation),
+///     (r#""a""#, Class::String),
+///     (",", Class::Punctuation),
+///     ("nul", Class::Error),
+///     ("]", Class::Punctuation),
+///     ("}", Class::Punctuation)
+/// ]);
+///
+/// let spans = highlight(r#"{ "name": "unterminated }"#);
+///
+/// assert_eq!(spans.last(), Some(&(jsonparser::Span { start: 10, end: 25 }, Class::Error)));
+/// ```
+pub fn highlight(input: &str) -> Vec<(Span, Class)> {
+    let mut lexer = Lexer::new(input);
+    let mut spans: Vec<(Span, Class)> = Vec::new();
+    let mut previous_string: Option<usize> = None;
+
+    loop {
+        let (token, span) = match lexer.next_spanned() {
+            Ok(Some(spanned)) => spanned,
+            Ok(None) => break,
+            Err(_) => {
+                let end = spans.last().map_or(0, |(span, _)| span.end);
+                let start = input.len() - input[end..].trim_start().len();
+
+                spans.push((Span { start, end: input.len() }, Class::Error));
+                break;
+            }
+        };
+        let class = match token.kind {
+            TokenKind::QuotedString => Class::String,
+            TokenKind::Number if token.text.as_deref().map_or(false, |text| text.parse::<f64>().is_ok()) => Class::Number,
+            TokenKind::Keyword if matches!(token.text.as_deref(), Some("true") | Some("false") | Some("null")) => Class::Keyword,
+            TokenKind::OpenBrace | TokenKind::CloseBrace | TokenKind::OpenBracket | TokenKind::CloseBracket
+                | TokenKind::Colon | TokenKind::Comma => Class::Punctuation,
+            _ => Class::Error
+        };
+
+        if let (Some(index), TokenKind::Colon) = (previous_string, token.kind) {
+            spans[index].1 = Class::Key;
+        }
+        previous_string = match class {
+            Class::String => Some(spans.len()),
+            _ => None
+        };
+        spans.push((span, class));
+    }
+    spans
+}
@@ -0,0 +1,101 @@
+use crate::utils::compat::*;
+use crate::utils::{JSONValue, Walk};
+
+/// Expand the placeholders of a string, or describe why it cannot be.
+/// Missing names are collected so that every one of them can be reported.
+fn expand<F: FnMut(&str) -> Option<String>>(input: &str, resolver: &mut F, missing: &mut Vec<String>) -> Result<String, String> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find(['$', '{', '\\']) {
+        let (before, from) = rest.split_at(start);
+
+        output.push_str(before);
+        if from.starts_with("\\${") || from.starts_with("\\{{") {
+            output.push_str(&from[1..3]);
+            rest = &from[3..];
+            continue;
+        }
+        let (open, close) = if from.starts_with("${") {
+            ("${", "}")
+        } else if from.starts_with("{{") {
+            ("{{", "}}")
+        } else {
+            output.push_str(&from[..1]);
+            rest = &from[1..];
+            continue;
+        };
+        let end = from.find(close).ok_or_else(|| format!("Unterminated placeholder '{}'", open))?;
+        let name = from[open.len()..end].trim();
+
+        match resolver(name) {
+            Some(value) => output.push_str(&value),
+            None => missing.push(name.to_string())
+        }
+        rest = &from[end + close.len()..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+impl JSONValue {
+    /// Expand the placeholders found in string values, `${NAME}` or `{{ name }}`, with
+    /// the value the resolver returns for the trimmed name. A backslash before a placeholder
+    /// keeps it as is, without the backslash. Object keys are left untouched.
+    ///
+    /// Every placeholder the resolver cannot expand is reported in the error, along with
+    /// the JSON Pointer path of its string. The strings holding one are left unchanged,
+    /// the others are still expanded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let mut config = JSONParser::from(r#"{
+    ///   "database": { "url": "${DATABASE_URL}", "password": "{{ secrets.db }}" },
+    ///   "greeting": "Costs \\${PRICE}"
+    /// }"#).unwrap();
+    ///
+    /// let result = config.interpolate(|name| match name {
+    ///     "DATABASE_URL" => Some("postgres://localhost/app".to_string()),
+    ///     "secrets.db" => Some("hunter2".to_string()),
+    ///     _ => None
+    /// });
+    ///
+    /// assert!(result.is_ok());
+    /// assert_eq!(config["database"]["url"].as_str(), Some("postgres://localhost/app"));
+    /// assert_eq!(config["database"]["password"].as_str(), Some("hunter2"));
+    /// assert_eq!(config["greeting"].as_str(), Some("Costs ${PRICE}"));
+    ///
+    /// let mut config = JSONParser::from(r#"{ "hosts": ["${PRIMARY}", "${REPLICA}"] }"#).unwrap();
+    ///
+    /// assert_eq!(
+    ///     config.interpolate(|_| None),
+    ///     Err("Unresolved placeholder 'PRIMARY' at /hosts/0; Unresolved placeholder 'REPLICA' at /hosts/1".to_string())
+    /// );
+    /// ```
+    pub fn interpolate<F: FnMut(&str) -> Option<String>>(&mut self, mut resolver: F) -> Result<(), String> {
+        let mut errors = Vec::new();
+
+        self.walk_mut(|path, value| {
+            let s = match value {
+                JSONValue::String(s) => s,
+                _ => return Walk::Continue
+            };
+            let mut missing = Vec::new();
+
+            match expand(s, &mut resolver, &mut missing) {
+                Ok(expanded) if missing.is_empty() => *s = expanded.into(),
+                Ok(_) => errors.extend(missing.iter().map(|name| format!("Unresolved placeholder '{}' at {}", name, path))),
+                Err(e) => errors.push(format!("{} at {}", e, path))
+            }
+            Walk::Continue
+        });
+
+        if !errors.is_empty() {
+            return Err(errors.join("; "));
+        }
+        Ok(())
+    }
+}
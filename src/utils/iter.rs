@@ -0,0 +1,130 @@
+use crate::utils::compat::*;
+use crate::utils::{JSONValue, OrderedMapIntoIter, OrderedMapIter};
+
+/// An iterator over the children of a `&JSONValue`: the entries of an object, with
+/// their keys, or the items of an array, without keys. Other values have no children.
+pub struct JSONIter<'a> {
+    children: Children<'a>
+}
+
+enum Children<'a> {
+    Object(OrderedMapIter<'a, JSONValue>),
+    Array(core::slice::Iter<'a, JSONValue>),
+    None
+}
+
+impl<'a> Iterator for JSONIter<'a> {
+    type Item = (Option<&'a str>, &'a JSONValue);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.children {
+            Children::Object(entries) => entries.next().map(|(key, value)| (Some(key), value)),
+            Children::Array(items) => items.next().map(|item| (None, item)),
+            Children::None => None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.children {
+            Children::Object(entries) => entries.size_hint(),
+            Children::Array(items) => items.size_hint(),
+            Children::None => (0, Some(0))
+        }
+    }
+}
+
+impl ExactSizeIterator for JSONIter<'_> {}
+
+/// An iterator moving the children out of a `JSONValue`, like `JSONIter`.
+pub struct JSONIntoIter {
+    children: IntoChildren
+}
+
+enum IntoChildren {
+    Object(OrderedMapIntoIter<JSONValue>),
+    Array(alloc::vec::IntoIter<JSONValue>),
+    None
+}
+
+impl Iterator for JSONIntoIter {
+    type Item = (Option<String>, JSONValue);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.children {
+            IntoChildren::Object(entries) => entries.next().map(|(key, value)| (Some(key), value)),
+            IntoChildren::Array(items) => items.next().map(|item| (None, item)),
+            IntoChildren::None => None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.children {
+            IntoChildren::Object(entries) => entries.size_hint(),
+            IntoChildren::Array(items) => items.size_hint(),
+            IntoChildren::None => (0, Some(0))
+        }
+    }
+}
+
+impl ExactSizeIterator for JSONIntoIter {}
+
+/// Iterate over the children of a value, with the key of each entry of an object.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::JSONParser;
+///
+/// let json = JSONParser::from(r#"{ "name": "John Doe", "tags": ["a", "b"] }"#).unwrap();
+/// let mut keys = Vec::new();
+///
+/// for (key, _) in &json {
+///     keys.extend(key);
+/// }
+///
+/// assert_eq!(keys, vec!["name", "tags"]);
+/// assert_eq!((&json["tags"]).into_iter().filter_map(|(_, tag)| tag.as_str()).collect::<Vec<_>>(), vec!["a", "b"]);
+/// assert_eq!((&json["name"]).into_iter().count(), 0);
+/// ```
+impl<'a> IntoIterator for &'a JSONValue {
+    type Item = (Option<&'a str>, &'a JSONValue);
+    type IntoIter = JSONIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let children = match self {
+            JSONValue::Object(obj) => Children::Object(obj.iter()),
+            JSONValue::Array(array) => Children::Array(array.iter()),
+            _ => Children::None
+        };
+
+        JSONIter { children }
+    }
+}
+
+/// Consume a value, moving out its children with the key of each entry of an object.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{JSONParser, JSONValue};
+///
+/// let json = JSONParser::from(r#"{ "name": "John Doe", "age": 30 }"#).unwrap();
+/// let entries: Vec<(Option<String>, JSONValue)> = json.into_iter().collect();
+///
+/// assert_eq!(entries[0], (Some("name".to_string()), JSONValue::String("John Doe".into())));
+/// assert_eq!(entries[1].1.as_f64(), Some(30.0));
+/// ```
+impl IntoIterator for JSONValue {
+    type Item = (Option<String>, JSONValue);
+    type IntoIter = JSONIntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let children = match self {
+            JSONValue::Object(obj) => IntoChildren::Object(obj.into_iter()),
+            JSONValue::Array(array) => IntoChildren::Array(array.into_vec().into_iter()),
+            _ => IntoChildren::None
+        };
+
+        JSONIntoIter { children }
+    }
+}
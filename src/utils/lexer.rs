@@ -1,11 +1,21 @@
+#[cfg(feature = "std")]
+use std::io::Read;
+
+use crate::utils::compat::*;
+use crate::utils::error::{LexError, LexErrorKind};
+use crate::utils::scan;
+#[cfg(feature = "std")]
+use crate::utils::source::ReadChars;
+use crate::utils::source::Source;
+
 #[derive(Clone)]
 pub struct Token {
     pub kind: TokenKind,
     pub text: Option<String>
 }
 
-impl std::fmt::Debug for Token {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for Token {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match &self.text {
             Some(text) => write!(f, "{}", text),
             None => write!(f, "{:?}", self.kind)
@@ -13,6 +23,13 @@ impl std::fmt::Debug for Token {
     }
 }
 
+/// A byte range of the input, from `start` included to `end` excluded.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize
+}
+
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum TokenKind {
     QuotedString,
@@ -25,105 +42,455 @@ pub enum TokenKind {
     CloseParen,
     OpenBrace,
     CloseBrace,
-    Keyword
+    Keyword,
+    /// A `//` or `/* */` comment, only read by the lexer used by `reformat`.
+    Comment
+}
+
+/// How the lexer reads `//` and `/* */` comments, which strict JSON forbids.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum Comments {
+    Reject,
+    Skip,
+    Keep
 }
 
+/// A lexer splitting a JSON input string into tokens.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{Lexer, TokenKind};
+///
+/// let kinds = Lexer::new(r#"{ "ids": [1, 2] }"#)
+///     .map(|token| token.map(|token| token.kind))
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+///
+/// assert_eq!(kinds.first(), Some(&TokenKind::OpenBrace));
+/// assert_eq!(kinds.len(), 9);
+///
+/// assert!(Lexer::new(r#"{ "name": "unterminated }"#).lex().is_err());
+/// ```
 pub struct Lexer<'a> {
-    chars: std::iter::Peekable<std::str::Chars<'a>>
+    source: Source<'a>,
+    /// The whole input, when it is held in memory.
+    input: Option<&'a str>,
+    peeked: Option<Option<char>>,
+    size: Option<usize>,
+    offset: usize,
+    token_start: usize,
+    failed: bool,
+    allow_control_characters: bool,
+    comments: Comments,
+    /// Buffer in which strings with escapes are decoded.
+    scratch: String,
+    /// Maximum length in bytes of the next string, set by `limit_next_string`.
+    string_limit: Option<usize>,
+    /// Whether the last string was cut short by `string_limit`, its start being kept in `scratch`.
+    truncated: bool
 }
 
 impl<'a> Lexer<'a> {
     pub fn new(input: &'a str) -> Self {
         Self {
-            chars: input.chars().peekable()
+            source: Source::Str(input.chars()),
+            input: Some(input),
+            peeked: None,
+            size: Some(input.len()),
+            offset: 0,
+            token_start: 0,
+            failed: false,
+            allow_control_characters: false,
+            comments: Comments::Reject,
+            scratch: String::new(),
+            string_limit: None,
+            truncated: false
+        }
+    }
+
+    /// Create a lexer reading UTF-8 input from the given reader as tokens are requested.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::Lexer;
+    ///
+    /// let tokens = Lexer::from_reader(r#"{ "name": "John Doe" }"#.as_bytes()).lex().unwrap();
+    ///
+    /// assert_eq!(tokens.len(), 5);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_reader<R: Read + 'a>(reader: R) -> Self {
+        Self {
+            source: Source::Reader(ReadChars::new(reader)),
+            input: None,
+            peeked: None,
+            size: None,
+            offset: 0,
+            token_start: 0,
+            failed: false,
+            allow_control_characters: false,
+            comments: Comments::Reject,
+            scratch: String::new(),
+            string_limit: None,
+            truncated: false
+        }
+    }
+
+    /// Size of the input, in bytes, when known upfront.
+    pub fn size(&self) -> Option<usize> {
+        self.size
+    }
+
+    /// Byte offset of the next character to be read.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Byte offset of the start of the last token read.
+    pub(crate) fn token_start(&self) -> usize {
+        self.token_start
+    }
+
+    /// Whether the whole input is held in memory, rather than read from a reader.
+    pub(crate) fn in_memory(&self) -> bool {
+        self.input.is_some()
+    }
+
+    /// Whether the input is read as strict JSON, without comments or raw control characters.
+    pub(crate) fn strict(&self) -> bool {
+        !self.allow_control_characters && self.comments == Comments::Reject
+    }
+
+    /// The input between the given byte offsets, when it is held in memory.
+    pub(crate) fn slice(&self, start: usize, end: usize) -> Option<&'a str> {
+        self.input.map(|input| &input[start..end])
+    }
+
+    /// Accept unescaped control characters (U+0000 to U+001F) inside strings.
+    pub fn allow_control_characters(mut self) -> Self {
+        self.allow_control_characters = true;
+        self
+    }
+
+    /// Read `//` and `/* */` comments as whitespace (`Comments::Skip`) or as tokens (`Comments::Keep`).
+    pub(crate) fn comments(mut self, comments: Comments) -> Self {
+        self.comments = comments;
+        self
+    }
+
+    /// Decode strings in the given buffer, typically taken back with `into_scratch` after
+    /// an earlier parse, so that its capacity is reused.
+    pub(crate) fn with_scratch(mut self, scratch: String) -> Self {
+        self.scratch = scratch;
+        self
+    }
+
+    pub(crate) fn into_scratch(self) -> String {
+        self.scratch
+    }
+
+    /// Fail as soon as the strings read are longer than `max` bytes once decoded, until
+    /// the limit is lifted with None. The start of a string cut short is kept for
+    /// `truncated_string`.
+    #[cfg(feature = "std")]
+    pub(crate) fn limit_next_string(&mut self, max: Option<usize>) {
+        self.string_limit = max;
+    }
+
+    /// The start of the string cut short by `limit_next_string`, if any.
+    #[cfg(feature = "std")]
+    pub(crate) fn truncated_string(&self) -> Option<&str> {
+        if self.truncated { Some(&self.scratch) } else { None }
+    }
+
+    pub fn next_token(&mut self) -> Result<Option<Token>, LexError> {
+        if self.failed {
+            return Ok(None);
         }
+        let mut token = self.read_token();
+
+        if let Some(message) = self.source.error() {
+            token = Err(LexError { kind: LexErrorKind::InvalidInput(message.to_string()), offset: self.offset });
+        }
+        self.failed = token.is_err();
+        token
     }
 
-    pub fn next_token(&mut self) -> Option<Token> {
-        while let Some(&c) = self.chars.peek() {
-            return if c == '"' {
-                self.chars.next();
-                let text = self.consume_while(|c| c != '"');
+    /// Read the next token along with the span of the input it was read from.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{Lexer, Span};
+    ///
+    /// let mut lexer = Lexer::new(r#"{ "id": 42 }"#);
+    /// let spans = std::iter::from_fn(|| lexer.next_spanned().unwrap())
+    ///     .map(|(_, span)| (span.start, span.end))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(spans, vec![(0, 1), (2, 6), (6, 7), (8, 10), (11, 12)]);
+    /// ```
+    pub fn next_spanned(&mut self) -> Result<Option<(Token, Span)>, LexError> {
+        let token = self.next_token()?;
 
-                self.chars.next();
+        Ok(token.map(|token| (token, Span { start: self.token_start, end: self.offset })))
+    }
+
+    fn read_token(&mut self) -> Result<Option<Token>, LexError> {
+        self.skip_run(scan::whitespace_run);
+
+        while let Some(c) = self.peek() {
+            self.token_start = self.offset;
+            return Ok(if c == '"' {
+                self.bump();
                 Some(Token {
                     kind: TokenKind::QuotedString,
-                    text: Some(text)
+                    text: Some(self.consume_string()?)
                 })
             } else if c == '-' || c.is_numeric() {
                 Some(Token {
                     kind: TokenKind::Number,
-                    text: Some(self.consume_while(|c| c.is_numeric() || c == '.' || c == '-'))
+                    text: Some(self.consume_while(|c| c.is_numeric() || matches!(c, '.' | '-' | '+' | 'e' | 'E')))
                 })
             } else if c == '(' {
-                self.chars.next();
+                self.bump();
                 Some(Token {
                     kind: TokenKind::OpenParen,
                     text: None
                 })
             } else if c == ')' {
-                self.chars.next();
+                self.bump();
                 Some(Token {
                     kind: TokenKind::CloseParen,
                     text: None
                 })
             } else if c == '[' {
-                self.chars.next();
+                self.bump();
                 Some(Token {
                     kind: TokenKind::OpenBracket,
                     text: None
                 })
             } else if c == ']' {
-                self.chars.next();
+                self.bump();
                 Some(Token {
                     kind: TokenKind::CloseBracket,
                     text: None
                 })
             } else if c == '{' {
-                self.chars.next();
+                self.bump();
                 Some(Token {
                     kind: TokenKind::OpenBrace,
                     text: None
                 })
             } else if c == '}' {
-                self.chars.next();
+                self.bump();
                 Some(Token {
                     kind: TokenKind::CloseBrace,
                     text: None
                 })
             } else if c == ':' {
-                self.chars.next();
+                self.bump();
                 Some(Token {
                     kind: TokenKind::Colon,
                     text: None
                 })
             } else if c == ',' {
-                self.chars.next();
+                self.bump();
                 Some(Token {
                     kind: TokenKind::Comma,
                     text: None
                 })
+            } else if c == '/' && self.comments != Comments::Reject {
+                match self.consume_comment()? {
+                    Some(text) if self.comments == Comments::Keep => Some(Token {
+                        kind: TokenKind::Comment,
+                        text: Some(text)
+                    }),
+                    Some(_) => continue,
+                    None => Some(Token {
+                        kind: TokenKind::Keyword,
+                        text: Some("/".to_string())
+                    })
+                }
             } else if c.is_whitespace() {
-                self.chars.next();
+                self.bump();
                 continue;
             } else {
-                return Some(Token {
+                let mut text = self.consume_while(|c| c.is_alphabetic());
+
+                if text.is_empty() {
+                    text.extend(self.bump());
+                }
+                Some(Token {
                     kind: TokenKind::Keyword,
-                    text: Some(self.consume_while(|c| c.is_alphabetic()))
+                    text: Some(text)
                 })
-            };
+            });
         }
 
-        None
+        Ok(None)
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        let source = &mut self.source;
+
+        *self.peeked.get_or_insert_with(|| source.next())
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = match self.peeked.take() {
+            Some(peeked) => peeked,
+            None => self.source.next()
+        }?;
+
+        self.offset += c.len_utf8();
+        Some(c)
+    }
+
+    /// Skip a run of bytes measured by `scan` on in-memory input, returning it.
+    fn skip_run(&mut self, scan: fn(&[u8]) -> usize) -> &'a str {
+        let rest = match (self.peeked, self.source.as_str()) {
+            (None, Some(rest)) => rest,
+            _ => return ""
+        };
+        let len = scan(rest.as_bytes());
+
+        self.source.advance(len);
+        self.offset += len;
+        &rest[..len]
+    }
+
+    fn error(&self, kind: LexErrorKind) -> LexError {
+        LexError { kind, offset: self.offset }
+    }
+
+    fn consume_string(&mut self) -> Result<String, LexError> {
+        let run = self.skip_run(scan::string_run);
+
+        // Most strings have no escape and are copied at once.
+        if self.peek() == Some('"') && !self.exceeds_limit(run) {
+            self.bump();
+            return Ok(run.to_string());
+        }
+        // Others are built in the scratch buffer, which keeps its capacity from string to
+        // string, and copied out once complete.
+        let mut result = core::mem::take(&mut self.scratch);
+
+        result.clear();
+        result.push_str(run);
+        let read = self.read_string(&mut result).map(|_| result.as_str().to_string());
+
+        self.scratch = result;
+        read
+    }
+
+    fn read_string(&mut self, result: &mut String) -> Result<(), LexError> {
+        loop {
+            result.push_str(self.skip_run(scan::string_run));
+            if let Some(max) = self.string_limit.filter(|&max| result.len() > max) {
+                self.truncated = true;
+                return Err(self.error(LexErrorKind::InvalidInput(format!("String longer than {} bytes", max))));
+            }
+
+            match self.bump() {
+                Some('"') => return Ok(()),
+                Some('\\') => result.push(self.consume_escape()?),
+                Some(c) if c < '\u{20}' && !self.allow_control_characters => {
+                    return Err(self.error(LexErrorKind::ControlCharacter(c)));
+                },
+                Some(c) => result.push(c),
+                None => return Err(self.error(LexErrorKind::UnterminatedString))
+            }
+        }
+    }
+
+    fn exceeds_limit(&self, string: &str) -> bool {
+        self.string_limit.map_or(false, |max| string.len() > max)
+    }
+
+    fn consume_escape(&mut self) -> Result<char, LexError> {
+        match self.bump() {
+            Some('"') => Ok('"'),
+            Some('\\') => Ok('\\'),
+            Some('/') => Ok('/'),
+            Some('b') => Ok('\u{08}'),
+            Some('f') => Ok('\u{0C}'),
+            Some('n') => Ok('\n'),
+            Some('r') => Ok('\r'),
+            Some('t') => Ok('\t'),
+            Some('u') => {
+                let high = self.consume_hex()?;
+
+                match high {
+                    0xD800..=0xDBFF => {
+                        if self.bump() != Some('\\') || self.bump() != Some('u') {
+                            return Err(self.error(LexErrorKind::LoneSurrogate(high)));
+                        }
+                        let low = self.consume_hex()?;
+
+                        if !(0xDC00..=0xDFFF).contains(&low) {
+                            return Err(self.error(LexErrorKind::InvalidLowSurrogate { high, low }));
+                        }
+                        let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+
+                        Ok(char::from_u32(code).expect("Surrogate pair is a valid code point"))
+                    },
+                    0xDC00..=0xDFFF => Err(self.error(LexErrorKind::LoneSurrogate(high))),
+                    _ => Ok(char::from_u32(high).expect("Non-surrogate is a valid code point"))
+                }
+            },
+            Some(c) => Err(self.error(LexErrorKind::InvalidEscape(c))),
+            None => Err(self.error(LexErrorKind::UnterminatedString))
+        }
+    }
+
+    fn consume_hex(&mut self) -> Result<u32, LexError> {
+        let mut code = 0;
+
+        for _ in 0..4 {
+            match self.bump().and_then(|c| c.to_digit(16)) {
+                Some(digit) => code = code * 16 + digit,
+                None => return Err(self.error(LexErrorKind::InvalidUnicodeEscape))
+            }
+        }
+
+        Ok(code)
+    }
+
+    /// Read a comment starting at the current `/`, or only the `/` if no comment follows.
+    fn consume_comment(&mut self) -> Result<Option<String>, LexError> {
+        self.bump();
+        match self.peek() {
+            Some('/') => Ok(Some(format!("/{}", self.consume_while(|c| c != '\n')))),
+            Some('*') => {
+                let mut text = String::from("/");
+
+                text.extend(self.bump());
+                loop {
+                    match self.bump() {
+                        Some('*') if self.peek() == Some('/') => {
+                            self.bump();
+                            text.push_str("*/");
+                            return Ok(Some(text));
+                        },
+                        Some(c) => text.push(c),
+                        None => return Err(self.error(LexErrorKind::UnterminatedComment))
+                    }
+                }
+            },
+            _ => Ok(None)
+        }
     }
 
     fn consume_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
         let mut result = String::new();
 
-        while let Some(&c) = self.chars.peek() {
+        while let Some(c) = self.peek() {
             if predicate(c) {
                 result.push(c);
-                self.chars.next();
+                self.bump();
             } else {
                 break;
             }
@@ -132,13 +499,17 @@ impl<'a> Lexer<'a> {
         result
     }
 
-    pub fn lex(&mut self) -> Result<Vec<Token>, String> {
-        let mut tokens = Vec::new();
+    pub fn lex(&mut self) -> Result<Vec<Token>, LexError> {
+        self.collect()
+    }
+}
 
-        while let Some(token) = self.next_token() {
-            tokens.push(token);
-        }
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Result<Token, LexError>;
 
-        Ok(tokens)
+    /// Yield the next token, or the error that stopped the lexer.
+    /// The iterator ends after the first error.
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_token().transpose()
     }
 }
\ No newline at end of file
@@ -0,0 +1,290 @@
+//! Non-fatal issues found in valid JSON documents.
+
+use core::fmt;
+
+use crate::utils::compat::*;
+use crate::utils::{JSONKind, Lexer, ParseError, Parser, Span, TokenKind};
+
+/// The kind of issue found at a position.
+#[derive(Debug, PartialEq, Clone)]
+pub enum WarningKind {
+    /// A key appearing more than once in the same object, the last value being kept.
+    DuplicateKey(String),
+    /// A number whose text cannot be represented exactly as an f64.
+    PrecisionLoss(String),
+    /// A container nested deeper than the configured maximum depth.
+    DeepNesting(usize),
+    /// A line longer than the configured maximum length, in characters.
+    LongLine(usize),
+    /// An array holding values of two different types, nulls aside.
+    MixedArray(JSONKind, JSONKind),
+    /// A key holding combining marks that likely have a precomposed (NFC) form.
+    NonNormalizedKey(String)
+}
+
+impl fmt::Display for WarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WarningKind::DuplicateKey(key) => write!(f, "Duplicate key '{}'", key),
+            WarningKind::PrecisionLoss(number) => write!(f, "Number {} cannot be represented exactly", number),
+            WarningKind::DeepNesting(depth) => write!(f, "Nesting depth {} exceeds the maximum", depth),
+            WarningKind::LongLine(length) => write!(f, "Line is {} characters long", length),
+            WarningKind::MixedArray(first, other) => write!(f, "Array mixes {} and {} values", first, other),
+            WarningKind::NonNormalizedKey(key) => write!(f, "Key '{}' is not in Unicode NFC form", key)
+        }
+    }
+}
+
+/// An issue found in a document, located by its byte offset and its 1-based line and column.
+/// Columns are counted in characters.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {}, column {}: {}", self.line, self.column, self.kind)
+    }
+}
+
+/// The thresholds used by `check_with`. By default containers may be nested
+/// 32 levels deep and lines may hold 120 characters.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::lint::LintOptions;
+///
+/// let options = LintOptions::new().max_depth(8).max_line_length(80);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct LintOptions {
+    max_depth: usize,
+    max_line_length: usize
+}
+
+impl Default for LintOptions {
+    fn default() -> Self {
+        LintOptions {
+            max_depth: 32,
+            max_line_length: 120
+        }
+    }
+}
+
+impl LintOptions {
+    /// Create a new LintOptions instance with the default thresholds.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Warn about containers nested more than `depth` levels deep.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Warn about lines holding more than `length` characters.
+    pub fn max_line_length(mut self, length: usize) -> Self {
+        self.max_line_length = length;
+        self
+    }
+}
+
+/// The container a token belongs to.
+enum Frame {
+    Object { keys: Set<String>, expecting_key: bool },
+    Array { start: usize, first: Option<JSONKind>, reported: bool }
+}
+
+/// Turns byte offsets into 1-based lines and columns.
+struct Positions<'a> {
+    input: &'a str,
+    line_starts: Vec<usize>
+}
+
+impl<'a> Positions<'a> {
+    fn new(input: &'a str) -> Self {
+        let line_starts = core::iter::once(0).chain(input.match_indices('\n').map(|(i, _)| i + 1)).collect();
+
+        Positions { input, line_starts }
+    }
+
+    fn warning(&self, kind: WarningKind, offset: usize) -> Warning {
+        let line = self.line_starts.partition_point(|start| *start <= offset);
+        let column = self.input[self.line_starts[line - 1]..offset].chars().count() + 1;
+
+        Warning { kind, offset, line, column }
+    }
+}
+
+/// Check a document with the default options.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::lint::{self, WarningKind};
+///
+/// let warnings = lint::check(r#"{
+///   "id": 9007199254740993,
+///   "id": 1,
+///   "values": [1, "two", 3]
+/// }"#).unwrap();
+///
+/// assert_eq!(warnings.len(), 3);
+/// assert_eq!(warnings[0].kind, WarningKind::PrecisionLoss("9007199254740993".to_string()));
+/// assert_eq!(warnings[1].to_string(), "Line 3, column 3: Duplicate key 'id'");
+/// assert_eq!((warnings[2].line, warnings[2].column), (4, 13));
+///
+/// assert!(lint::check(r#"{ "id": }"#).is_err());
+/// ```
+pub fn check(input: &str) -> Result<Vec<Warning>, ParseError> {
+    check_with(input, LintOptions::new())
+}
+
+/// Check a document for issues that do not prevent parsing it but are likely
+/// mistakes or portability hazards, sorted by position. The document is parsed
+/// first, and its parse error is returned if it is not valid.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::lint::{self, LintOptions, WarningKind};
+///
+/// let input = "{ \"matrix\": [[[1]]], \"cafe\u{301}\": true, \"enabled\": false }";
+/// let warnings = lint::check_with(input, LintOptions::new().max_depth(2).max_line_length(50)).unwrap();
+/// let kinds = warnings.into_iter().map(|warning| warning.kind).collect::<Vec<_>>();
+///
+/// assert_eq!(kinds, vec![
+///     WarningKind::DeepNesting(3),
+///     WarningKind::NonNormalizedKey("cafe\u{301}".to_string()),
+///     WarningKind::LongLine(54)
+/// ]);
+/// ```
+pub fn check_with(input: &str, options: LintOptions) -> Result<Vec<Warning>, ParseError> {
+    Parser::new(Lexer::new(input)).parse()?;
+
+    let positions = Positions::new(input);
+    let mut warnings = Vec::new();
+    let mut frames: Vec<Frame> = Vec::new();
+    let mut lexer = Lexer::new(input);
+
+    for (start, line) in positions.line_starts.iter().zip(input.split('\n')) {
+        let length = line.trim_end_matches('\r').chars().count();
+
+        if length > options.max_line_length {
+            let offset = start + line.char_indices().nth(options.max_line_length).map_or(0, |(i, _)| i);
+
+            warnings.push(positions.warning(WarningKind::LongLine(length), offset));
+        }
+    }
+
+    while let Some((token, Span { start, .. })) = lexer.next_spanned()? {
+        let kind = match token.kind {
+            TokenKind::OpenBrace => Some(JSONKind::Object),
+            TokenKind::OpenBracket => Some(JSONKind::Array),
+            TokenKind::Number => Some(JSONKind::Number),
+            TokenKind::Keyword if token.text.as_deref() == Some("null") => Some(JSONKind::Null),
+            TokenKind::Keyword => Some(JSONKind::Boolean),
+            TokenKind::QuotedString => match frames.last_mut() {
+                Some(Frame::Object { keys, expecting_key: expecting_key @ true }) => {
+                    let key = token.text.clone().unwrap_or_default();
+
+                    *expecting_key = false;
+                    if has_decomposed_marks(&key) {
+                        warnings.push(positions.warning(WarningKind::NonNormalizedKey(key.clone()), start));
+                    }
+                    if !keys.insert(key.clone()) {
+                        warnings.push(positions.warning(WarningKind::DuplicateKey(key), start));
+                    }
+                    None
+                },
+                _ => Some(JSONKind::String)
+            },
+            TokenKind::Comma => {
+                if let Some(Frame::Object { expecting_key, .. }) = frames.last_mut() {
+                    *expecting_key = true;
+                }
+                None
+            },
+            TokenKind::CloseBrace | TokenKind::CloseBracket => {
+                frames.pop();
+                None
+            },
+            _ => None
+        };
+        let kind = match kind {
+            Some(kind) => kind,
+            None => continue
+        };
+
+        if let Some(Frame::Array { start: array_start, first, reported }) = frames.last_mut() {
+            match first {
+                _ if kind == JSONKind::Null || *reported => {},
+                None => *first = Some(kind),
+                Some(first) if *first != kind => {
+                    *reported = true;
+                    warnings.push(positions.warning(WarningKind::MixedArray(*first, kind), *array_start));
+                },
+                Some(_) => {}
+            }
+        }
+        match kind {
+            JSONKind::Number => {
+                let text = token.text.unwrap_or_default();
+
+                if loses_precision(&text) {
+                    warnings.push(positions.warning(WarningKind::PrecisionLoss(text), start));
+                }
+            },
+            JSONKind::Object | JSONKind::Array => {
+                frames.push(match kind {
+                    JSONKind::Object => Frame::Object { keys: Set::new(), expecting_key: true },
+                    _ => Frame::Array { start, first: None, reported: false }
+                });
+                if frames.len() == options.max_depth + 1 {
+                    warnings.push(positions.warning(WarningKind::DeepNesting(frames.len()), start));
+                }
+            },
+            _ => {}
+        }
+    }
+
+    warnings.sort_by_key(|warning| warning.offset);
+    Ok(warnings)
+}
+
+/// Whether parsing the number gives a different value than its text, comparing the
+/// significant digits of the text with those of the shortest exact form of the f64.
+fn loses_precision(text: &str) -> bool {
+    let value = match text.parse::<f64>() {
+        Ok(value) if value.is_finite() => value,
+        _ => return true
+    };
+    let significant = |digits: &str| {
+        let digits = digits.chars().filter(|c| c.is_ascii_digit()).collect::<String>();
+        let digits = digits.trim_start_matches('0').trim_end_matches('0');
+
+        if digits.is_empty() { "0".to_string() } else { digits.to_string() }
+    };
+    let shortest = format!("{:e}", value.abs());
+    let mantissa = shortest.split('e').next().unwrap_or_default();
+
+    significant(text) != significant(mantissa)
+}
+
+/// Whether a key holds a combining diacritic right after a Latin letter, a sequence
+/// that almost always has a precomposed form in NFC.
+fn has_decomposed_marks(key: &str) -> bool {
+    let chars = key.chars().collect::<Vec<_>>();
+
+    chars.windows(2).any(|pair| {
+        let latin = pair[0].is_ascii_alphabetic() || ('\u{C0}'..='\u{24F}').contains(&pair[0]);
+
+        latin && ('\u{300}'..='\u{36F}').contains(&pair[1])
+    })
+}
@@ -0,0 +1,152 @@
+use crate::utils::compat::*;
+use crate::utils::parser::edit_items;
+use crate::utils::{JSONValue, OrderedMap};
+
+/// How arrays are combined by `JSONValue::merge`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ArrayMerge {
+    /// The incoming array replaces the existing one.
+    Replace,
+    /// The incoming items are appended to the existing ones.
+    Concat,
+    /// Incoming objects are merged into the existing object holding the same value
+    /// at the given key, and appended otherwise. Other items are appended unless
+    /// an equal item is already present.
+    UnionByKey(String)
+}
+
+/// How null values found in the incoming value are handled by `JSONValue::merge`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum NullMerge {
+    /// Null overwrites the existing value.
+    Overwrite,
+    /// Null removes the key from the existing object.
+    Remove,
+    /// Null leaves the existing value untouched.
+    Ignore
+}
+
+/// The strategy used by `JSONValue::merge`.
+/// By default arrays are replaced and nulls overwrite existing values.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{ArrayMerge, MergeStrategy, NullMerge};
+///
+/// let strategy = MergeStrategy::new()
+///     .arrays(ArrayMerge::UnionByKey("id".to_string()))
+///     .nulls(NullMerge::Remove);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MergeStrategy {
+    arrays: ArrayMerge,
+    nulls: NullMerge
+}
+
+impl Default for MergeStrategy {
+    fn default() -> Self {
+        Self { arrays: ArrayMerge::Replace, nulls: NullMerge::Overwrite }
+    }
+}
+
+impl MergeStrategy {
+    /// Create a new MergeStrategy instance with the default behavior.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how arrays are combined.
+    pub fn arrays(mut self, arrays: ArrayMerge) -> Self {
+        self.arrays = arrays;
+        self
+    }
+
+    /// Set how incoming nulls are handled.
+    pub fn nulls(mut self, nulls: NullMerge) -> Self {
+        self.nulls = nulls;
+        self
+    }
+}
+
+impl JSONValue {
+    /// Deep merge another value into this one. Objects are merged key by key recursively,
+    /// arrays and nulls follow the strategy, and any other incoming value replaces the existing one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{ArrayMerge, JSONParser, MergeStrategy, NullMerge, Serialize};
+    ///
+    /// let mut config = JSONParser::from(r#"{ "server": { "port": 80, "host": "localhost" }, "tags": ["a"], "debug": true }"#).unwrap();
+    /// let overrides = JSONParser::from(r#"{ "server": { "port": 8080 }, "tags": ["b"], "debug": null }"#).unwrap();
+    ///
+    /// let strategy = MergeStrategy::new().arrays(ArrayMerge::Concat).nulls(NullMerge::Remove);
+    /// config.merge(&overrides, &strategy);
+    ///
+    /// assert_eq!(config.serialize(), r#"{"server":{"port":8080,"host":"localhost"},"tags":["a","b"]}"#);
+    ///
+    /// let mut users = JSONParser::from(r#"{ "users": [{ "id": 1, "name": "Ann" }] }"#).unwrap();
+    /// let update = JSONParser::from(r#"{ "users": [{ "id": 1, "admin": true }, { "id": 2 }] }"#).unwrap();
+    ///
+    /// users.merge(&update, &MergeStrategy::new().arrays(ArrayMerge::UnionByKey("id".to_string())));
+    ///
+    /// assert_eq!(users.serialize(), r#"{"users":[{"id":1,"name":"Ann","admin":true},{"id":2}]}"#);
+    /// ```
+    pub fn merge(&mut self, other: &JSONValue, strategy: &MergeStrategy) {
+        match (self, other) {
+            (JSONValue::Object(target), JSONValue::Object(source)) => {
+                for (key, value) in source.iter() {
+                    match (value, strategy.nulls) {
+                        (JSONValue::Null, NullMerge::Remove) => {
+                            target.remove(key);
+                        },
+                        (JSONValue::Null, NullMerge::Ignore) => {},
+                        _ => match target.get_mut(key) {
+                            Some(existing) => existing.merge(value, strategy),
+                            None => {
+                                let mut merged = empty_like(value);
+
+                                merged.merge(value, strategy);
+                                target.insert(key, merged);
+                            }
+                        }
+                    }
+                }
+            },
+            (JSONValue::Array(target), JSONValue::Array(source)) => match &strategy.arrays {
+                ArrayMerge::Replace => *target = source.clone(),
+                ArrayMerge::Concat => edit_items(target, |target| target.extend(source.iter().cloned())),
+                ArrayMerge::UnionByKey(key) => edit_items(target, |target| {
+                    for item in source.iter() {
+                        let position = match item.get(key.as_str()) {
+                            Some(id) => target.iter().position(|existing| existing.get(key.as_str()) == Some(id)),
+                            None if target.contains(item) => continue,
+                            None => None
+                        };
+
+                        match position {
+                            Some(index) => target[index].merge(item, strategy),
+                            None => target.push(item.clone())
+                        }
+                    }
+                })
+            },
+            (_, JSONValue::Null) if strategy.nulls != NullMerge::Overwrite => {},
+            (target, JSONValue::Object(_)) => {
+                *target = JSONValue::Object(OrderedMap::new());
+                target.merge(other, strategy);
+            },
+            (target, other) => *target = other.clone()
+        }
+    }
+}
+
+/// An empty container of the same kind as the value, so that merging the value
+/// into it applies the strategy to nested nulls.
+fn empty_like(value: &JSONValue) -> JSONValue {
+    match value {
+        JSONValue::Object(_) => JSONValue::Object(OrderedMap::new()),
+        _ => JSONValue::Null
+    }
+}
@@ -0,0 +1,176 @@
+use alloc::borrow::Cow;
+use core::any::Any;
+
+use crate::utils::compat::*;
+use crate::utils::walk::push_token;
+
+/// A failed constraint of a rule, before it is turned into a message.
+///
+/// The `code` names the constraint, as in the custom messages of the rules: `type` for a
+/// type mismatch, `required` for a missing property, or the name of the builder method
+/// such as `min_length` or `gt`. The `params` hold the arguments of the constraint, such
+/// as `min` for `min_length` or `expected` for `type`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ValidationError {
+    pub code: &'static str,
+    /// The name of the validated value, usually the key of its property.
+    pub name: String,
+    /// The JSON Pointer of the validated value, such as `/cars/0/model`.
+    pub path: String,
+    pub params: Vec<(&'static str, String)>
+}
+
+impl ValidationError {
+    /// The value of the given parameter.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.iter().find(|(n, _)| *n == name).map(|(_, value)| value.as_str())
+    }
+}
+
+/// Turn validation errors into messages, such as to translate them. Messages set on a
+/// rule with `message` take precedence over the formatter.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{JSONParser, JSONSchema, MessageFormatter, NumberType, ValidationError};
+///
+/// struct French;
+///
+/// impl MessageFormatter for French {
+///     fn format(&self, error: &ValidationError) -> String {
+///         match error.code {
+///             "gt" => format!("{} doit être supérieur à {}", error.name, error.param("gt").unwrap()),
+///             "required" => format!("La clé '{}' est manquante", error.param("key").unwrap()),
+///             _ => format!("{} est invalide", error.path)
+///         }
+///     }
+/// }
+///
+/// let schema = JSONSchema::new([
+///   ("age", NumberType::new().gt(18.0).boxed())
+/// ]).formatter(French);
+///
+/// let json = JSONParser::from(r#"{ "age": 16 }"#).unwrap();
+/// assert_eq!(schema.validate(&json).unwrap_err(), "age doit être supérieur à 18");
+///
+/// let json = JSONParser::from(r#"{ "age": "16" }"#).unwrap();
+/// assert_eq!(schema.validate(&json).unwrap_err(), "/age est invalide");
+///
+/// let json = JSONParser::from(r#"{}"#).unwrap();
+/// assert_eq!(schema.validate(&json).unwrap_err(), "La clé 'age' est manquante");
+/// ```
+pub trait MessageFormatter: Send + Sync {
+    fn format(&self, error: &ValidationError) -> String;
+}
+
+/// The English messages used by default.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EnglishFormatter;
+
+impl MessageFormatter for EnglishFormatter {
+    fn format(&self, error: &ValidationError) -> String {
+        let name = &error.name;
+        let param = |n: &str| error.param(n).unwrap_or_default();
+
+        match error.code {
+            "type" => format!("Type of {} mismatch, expected {}", name, param("expected")),
+            "required" if name.is_empty() => format!("Key '{}' not found", param("key")),
+            "required" => format!("In {}, key '{}' not found", name, param("key")),
+            "min_length" => format!("{} is too short (min: {})", name, param("min")),
+            "max_length" => format!("{} is too long (max: {})", name, param("max")),
+            "length" => format!("{} is not the correct length (length: {})", name, param("length")),
+            "non_empty" | "empty" => format!("{} is empty", name),
+            "starts_with" => format!("{} does not start with '{}'", name, param("prefix")),
+            "ends_with" => format!("{} does not end with '{}'", name, param("suffix")),
+            "includes" => format!("{} does not include '{}'", name, param("substring")),
+            "pattern" => format!("{} does not match the pattern '{}'", name, param("pattern")),
+            "finite" => format!("{} is not finite", name),
+            "gt" => format!("{} must be greater than {}", name, param("gt")),
+            "gte" => format!("{} is too small (min: {})", name, param("min")),
+            "lt" => format!("{} must be less than {}", name, param("lt")),
+            "lte" => format!("{} is too large (max: {})", name, param("max")),
+            "eq" => format!("{} must be equal to {}", name, param("expected")),
+            "positive" => format!("{} is not positive", name),
+            "negative" => format!("{} is not negative", name),
+            "multiple_of" => format!("{} is not a multiple of {}", name, param("step")),
+            "integer" => format!("{} is not an integer", name),
+            "unique" => match error.param("key") {
+                Some(key) => format!("In {}, item {} has a duplicate '{}'", name, param("index"), key),
+                None => format!("In {}, item {} is a duplicate", name, param("index"))
+            },
+            "contains" => match error.param("max") {
+                Some(max) => format!("{} has {} matching items (max: {})", name, param("count"), max),
+                None => format!("{} has {} matching items (min: {})", name, param("count"), param("min"))
+            },
+            "some" => format!("No items in the {} match the rule", name),
+            "at" => format!("In {}, index {} not found", name, param("index")),
+            "truthy" | "falsy" => format!("For {}, expected {}", name, param("expected")),
+            "min_properties" => format!("{} has too few properties (min: {})", name, param("min")),
+            "max_properties" => format!("{} has too many properties (max: {})", name, param("max")),
+            "keys" => format!("In {}, {}", name, param("error")),
+            "date" => format!("{} is not a valid date", name),
+            "before" => format!("{} must be before {}", name, param("before")),
+            "after" => format!("{} must be after {}", name, param("after")),
+            "between" => format!("{} must be between {} and {}", name, param("start"), param("end")),
+            "not" => format!("{} matches an excluded rule", name),
+            code => format!("{} is invalid ({})", name, code)
+        }
+    }
+}
+
+/// Where a value is validated, how its errors are formatted, and the data supplied by
+/// the caller of `JSONSchema::validate_with_ctx`. Passed to `Validator::validate_in`,
+/// so that rules holding other rules can pass it on.
+pub struct ValidationContext<'c> {
+    name: Cow<'c, str>,
+    path: Cow<'c, str>,
+    formatter: &'c dyn MessageFormatter,
+    data: Option<&'c dyn Any>
+}
+
+impl<'c> ValidationContext<'c> {
+    /// Create a context for the root value, with the given name.
+    pub fn new(name: &str, formatter: &'c dyn MessageFormatter) -> Self {
+        Self { name: Cow::Owned(name.to_string()), path: Cow::Borrowed(""), formatter, data: None }
+    }
+
+    /// Attach data for the rules to read, such as the locale of the current user.
+    pub fn with_data(mut self, data: &'c dyn Any) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// The data supplied by the caller, if it is of the given type.
+    pub fn data<T: Any>(&self) -> Option<&T> {
+        self.data.and_then(|data| data.downcast_ref())
+    }
+
+    /// The name of the validated value.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The JSON Pointer of the validated value.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The context of a nested value, with the given name and the given token of its path.
+    pub fn child(&self, name: &str, token: &str) -> ValidationContext<'c> {
+        let mut path = self.path.to_string();
+
+        push_token(&mut path, token);
+        Self { name: Cow::Owned(name.to_string()), path: Cow::Owned(path), formatter: self.formatter, data: self.data }
+    }
+
+    /// The context of a nested value whose name and path are already known, without copying them.
+    pub(crate) fn at(&self, name: &'c str, path: &'c str) -> ValidationContext<'c> {
+        Self { name: Cow::Borrowed(name), path: Cow::Borrowed(path), formatter: self.formatter, data: self.data }
+    }
+
+    /// Format the error of the given constraint.
+    pub fn error(&self, code: &'static str, params: Vec<(&'static str, String)>) -> String {
+        self.formatter.format(&ValidationError { code, name: self.name.to_string(), path: self.path.to_string(), params })
+    }
+}
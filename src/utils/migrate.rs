@@ -0,0 +1,175 @@
+use crate::utils::compat::*;
+use crate::utils::{JSONSchema, JSONValue};
+
+type Migration<'a> = Box<dyn Fn(JSONValue) -> Result<JSONValue, String> + Send + Sync + 'a>;
+type VersionDetector<'a> = Box<dyn Fn(&JSONValue) -> Option<u32> + Send + Sync + 'a>;
+
+/// Bring stored documents up to the latest version of their schema. Each version has a
+/// schema, and each migration turns a document of a version into one of the next version.
+///
+/// `migrate` detects the version of a document, validates it against the schema of that
+/// version, applies the migrations in turn, and validates the result against the latest
+/// schema. Each validation applies the transformations of the schema, as `JSONSchema::validate` does.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{JSONParser, JSONSchema, JSONValue, Migrator, NumberType, StringType};
+///
+/// let migrator = Migrator::new()
+///   .version_field("version")
+///   .schema(1, JSONSchema::new([
+///     ("name", StringType::new().boxed())
+///   ]))
+///   .schema(2, JSONSchema::new([
+///     ("first_name", StringType::new().trim().boxed()),
+///     ("last_name", StringType::new().trim().boxed())
+///   ]))
+///   .schema(3, JSONSchema::new([
+///     ("first_name", StringType::new().boxed()),
+///     ("last_name", StringType::new().boxed()),
+///     ("age", NumberType::new().boxed())
+///   ]))
+///   .migration(1, |mut value| {
+///     let name = value["name"].as_str().unwrap_or_default().to_string();
+///     let (first, last) = name.split_once(' ').unwrap_or((&name, ""));
+///     let obj = value.as_object_mut().unwrap();
+///
+///     obj.insert("first_name", JSONValue::String(first.into()));
+///     obj.insert("last_name", JSONValue::String(last.into()));
+///     obj.remove("name");
+///     Ok(value)
+///   })
+///   .migration(2, |mut value| {
+///     value.as_object_mut().unwrap().insert("age", JSONValue::Number(0.0));
+///     Ok(value)
+///   });
+///
+/// let json = JSONParser::from(r#"{ "version": 1, "name": "John Doe" }"#).unwrap();
+/// let migrated = migrator.migrate(&json).unwrap();
+///
+/// assert_eq!(migrated.to_string(), r#"{"version":3,"first_name":"John","last_name":"Doe","age":0}"#);
+///
+/// let json = JSONParser::from(r#"{ "version": 2, "first_name": 42 }"#).unwrap();
+/// assert_eq!(migrator.migrate(&json).unwrap_err(), "In version 2, Type of first_name mismatch, expected String");
+///
+/// let json = JSONParser::from(r#"{ "version": 4 }"#).unwrap();
+/// assert_eq!(migrator.migrate(&json).unwrap_err(), "Version 4 is newer than the latest version 3");
+/// ```
+#[derive(Default)]
+pub struct Migrator<'a> {
+    schemas: Vec<(u32, JSONSchema<'a>)>,
+    migrations: Vec<(u32, Migration<'a>)>,
+    field: Option<String>,
+    detector: Option<VersionDetector<'a>>
+}
+
+impl<'a> Migrator<'a> {
+    /// Create a new Migrator instance, without any version.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the schema of the given version. The highest version is the latest one.
+    pub fn schema(mut self, version: u32, schema: JSONSchema<'a>) -> Self {
+        self.schemas.retain(|(v, _)| *v != version);
+        self.schemas.push((version, schema));
+        self
+    }
+
+    /// Set how a document of the given version becomes one of the next version.
+    pub fn migration<F: Fn(JSONValue) -> Result<JSONValue, String> + Send + Sync + 'a>(mut self, from: u32, migration: F) -> Self {
+        self.migrations.retain(|(v, _)| *v != from);
+        self.migrations.push((from, Box::new(migration)));
+        self
+    }
+
+    /// Read the version of documents from the given property, which `migrate` then sets
+    /// to the latest version.
+    pub fn version_field(mut self, field: &str) -> Self {
+        self.field = Some(field.to_string());
+        self
+    }
+
+    /// Detect the version of documents whose version property is missing, such as from the
+    /// properties they hold.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONSchema, JSONValue, Migrator, StringType};
+    ///
+    /// let migrator = Migrator::new()
+    ///   .schema(1, JSONSchema::new([("zip_code", StringType::new().boxed())]))
+    ///   .schema(2, JSONSchema::new([("zip", StringType::new().boxed())]))
+    ///   .detect(|value| if value.get("zip_code").is_some() { Some(1) } else { Some(2) })
+    ///   .migration(1, |mut value| {
+    ///     let obj = value.as_object_mut().unwrap();
+    ///     let zip = obj.remove("zip_code").unwrap();
+    ///
+    ///     obj.insert("zip", zip);
+    ///     Ok(value)
+    ///   });
+    ///
+    /// let json = JSONParser::from(r#"{ "zip_code": "75001" }"#).unwrap();
+    ///
+    /// assert_eq!(migrator.migrate(&json).unwrap()["zip"].as_str(), Some("75001"));
+    /// ```
+    pub fn detect<F: Fn(&JSONValue) -> Option<u32> + Send + Sync + 'a>(mut self, detector: F) -> Self {
+        self.detector = Some(Box::new(detector));
+        self
+    }
+
+    /// The latest version, the highest one with a schema.
+    pub fn latest(&self) -> Option<u32> {
+        self.schemas.iter().map(|(version, _)| *version).max()
+    }
+
+    /// The version of the document, read from the version property or else detected.
+    pub fn version_of(&self, value: &JSONValue) -> Option<u32> {
+        let field = self.field.as_ref()
+            .and_then(|field| value.get(field.as_str()))
+            .and_then(|version| version.as_f64())
+            .filter(|version| version.fract() == 0.0 && *version >= 0.0 && *version <= u32::MAX as f64)
+            .map(|version| version as u32);
+
+        field.or_else(|| self.detector.as_ref().and_then(|detect| detect(value)))
+    }
+
+    /// Migrate a copy of the document to the latest version and validate it.
+    pub fn migrate(&self, value: &JSONValue) -> Result<JSONValue, String> {
+        let latest = self.latest().ok_or("No schema to migrate to")?;
+        let mut version = self.version_of(value).ok_or("Cannot detect the version of the document")?;
+
+        if version > latest {
+            return Err(format!("Version {} is newer than the latest version {}", version, latest));
+        }
+
+        // A document already at the latest version is only validated once, at the end.
+        let mut value = match self.schema_of(version) {
+            Some(schema) if version < latest => schema.validate(value).map_err(|e| format!("In version {}, {}", version, e))?,
+            _ => value.clone()
+        };
+
+        while version < latest {
+            let (_, migration) = self.migrations.iter()
+                .find(|(from, _)| *from == version)
+                .ok_or_else(|| format!("No migration from version {}", version))?;
+
+            value = migration(value).map_err(|e| format!("From version {}, {}", version, e))?;
+            version += 1;
+        }
+
+        if let (Some(field), JSONValue::Object(obj)) = (&self.field, &mut value) {
+            obj.insert(field, JSONValue::Number(latest as f64));
+        }
+        match self.schema_of(latest) {
+            Some(schema) => schema.validate(&value),
+            None => Ok(value)
+        }
+    }
+
+    fn schema_of(&self, version: u32) -> Option<&JSONSchema<'a>> {
+        self.schemas.iter().find(|(v, _)| *v == version).map(|(_, schema)| schema)
+    }
+}
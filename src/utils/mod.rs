@@ -1,11 +1,123 @@
+mod access;
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+mod arena;
+#[cfg(feature = "async")]
+mod async_reader;
+mod builder;
+mod cancellation;
+mod case;
+#[cfg(feature = "cbor")]
+mod cbor;
+pub(crate) mod compat;
+mod convert;
+mod csv;
+mod date;
+pub mod diff;
+mod digest;
+mod docs;
+mod document;
+mod encoding;
+mod equality;
+mod error;
+mod events;
+pub mod expr;
+#[cfg(feature = "std")]
+mod file;
+mod filter;
+mod gron;
+mod highlight;
+mod interpolate;
+mod iter;
 mod lexer;
+pub mod lint;
+mod merge;
+mod messages;
+mod migrate;
+#[cfg(feature = "msgpack")]
+mod msgpack;
+mod mutate;
+mod options;
+mod ordering;
+#[cfg(feature = "rayon")]
+mod parallel;
 mod parser;
+mod path;
+mod pointer;
+mod preview;
+mod project;
+mod prune;
+mod push;
+mod query;
+mod random;
+mod raw;
+mod redact;
+mod regex;
+mod reformat;
+mod scan;
+mod search;
+mod seq;
+#[cfg(feature = "serde_json")]
+mod serde;
+mod serialize;
+mod session;
+mod shared;
+mod source;
+mod stats;
+mod stream;
+mod string;
+mod toml;
+#[cfg(feature = "testutils")]
+pub mod testutils;
+pub(crate) mod trusted;
+mod typed;
 mod validator;
+mod walk;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+mod yaml;
 
-pub use lexer::Lexer;
-pub use lexer::Token;
-pub use lexer::TokenKind;
+pub use arena::{Arena, ArenaValue};
+#[cfg(feature = "async")]
+pub use async_reader::{AsyncEventStream, AsyncNdjsonStream};
+pub use builder::{ArrayBuilder, ObjectBuilder};
+pub use cancellation::CancellationToken;
+pub use case::Case;
+pub use csv::CsvOptions;
+pub use docs::RuleDoc;
+pub use document::Document;
+pub use encoding::decode;
+pub use equality::EqualityOptions;
+pub use error::{AccessError, LexError, LexErrorKind, Limit, ParseError};
+#[cfg(feature = "async")]
+pub use events::Event;
+#[cfg(feature = "mmap")]
+pub use file::MappedFile;
+pub use filter::StreamFilter;
+pub use highlight::{highlight, Class};
+pub use iter::{JSONIntoIter, JSONIter};
+pub use lexer::{Lexer, Span, Token, TokenKind};
+pub use merge::{ArrayMerge, MergeStrategy, NullMerge};
+pub use messages::{EnglishFormatter, MessageFormatter, ValidationContext, ValidationError};
+pub use migrate::Migrator;
+pub use options::{DEFAULT_MAX_DEPTH, ParserOptions};
+pub use prune::PruneOptions;
+pub use push::{PushParser, PushStatus};
+pub use random::Rng;
+pub use raw::RawValue;
+pub use reformat::{reformat, FormatStyle};
+#[cfg(feature = "std")]
+pub use seq::JsonSeqStream;
+pub use serialize::SerializeOptions;
+pub use session::ParserSession;
+pub use shared::SharedJSONValue;
+pub use stats::Stats;
+pub use stream::ArrayStream;
+pub use string::JSONString;
+pub use typed::{FromJSON, Mapped, TypedValidator};
 
-pub use parser::{Parser, JSONValue, OrderedMap, Serialize};
+pub use parser::{Parser, JSONKind, JSONValue, OrderedMap, OrderedMapIntoIter, OrderedMapIter, Serialize};
 
-pub use validator::{JSONSchema, Validator, StringType, NumberType, BooleanType, ArrayType, ObjectType, NullType};
+pub use validator::{CompiledSchema, JSONSchema, Redaction, SchemaBuilder, ValidationReport, Validator, StringType, NumberType, BooleanType, DateType, ArrayType, ObjectType, NullType, AnyType, NotType};
+
+pub use walk::Walk;
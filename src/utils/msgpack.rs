@@ -0,0 +1,260 @@
+use crate::utils::compat::*;
+use crate::utils::{JSONValue, OrderedMap, DEFAULT_MAX_DEPTH};
+
+impl JSONValue {
+    /// Encode the value as MessagePack. Object keys keep their order, and numbers
+    /// holding an integer are written in the smallest integer format that fits it,
+    /// others as 64-bit floats.
+    ///
+    /// Available with the `msgpack` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "id": 1, "tags": ["a"] }"#).unwrap();
+    ///
+    /// assert_eq!(json.to_msgpack(), b"\x82\xa2id\x01\xa4tags\x91\xa1a");
+    /// ```
+    pub fn to_msgpack(&self) -> Vec<u8> {
+        let mut output = Vec::new();
+
+        self.write_msgpack(&mut output);
+        output
+    }
+
+    fn write_msgpack(&self, output: &mut Vec<u8>) {
+        match self {
+            JSONValue::Null => output.push(0xc0),
+            JSONValue::Boolean(false) => output.push(0xc2),
+            JSONValue::Boolean(true) => output.push(0xc3),
+            JSONValue::Number(n) => write_number(*n, output),
+            JSONValue::Raw(raw) => raw.to_value().write_msgpack(output),
+            JSONValue::String(s) => {
+                write_length(s.len(), [0xa0, 0xd9, 0xda, 0xdb], 32, output);
+                output.extend_from_slice(s.as_bytes());
+            },
+            JSONValue::Array(array) => {
+                write_length(array.len(), [0x90, 0, 0xdc, 0xdd], 16, output);
+                for value in array.iter() {
+                    value.write_msgpack(output);
+                }
+            },
+            JSONValue::Object(obj) => {
+                write_length(obj.len(), [0x80, 0, 0xde, 0xdf], 16, output);
+                for (key, value) in obj.iter() {
+                    write_length(key.len(), [0xa0, 0xd9, 0xda, 0xdb], 32, output);
+                    output.extend_from_slice(key.as_bytes());
+                    value.write_msgpack(output);
+                }
+            }
+        }
+    }
+
+    /// Decode a MessagePack value. Integers and floats become numbers, and map keys
+    /// must be strings. Binary and extension types have no JSON equivalent and are
+    /// rejected, as are bytes left after the value and arrays and maps nested deeper
+    /// than `DEFAULT_MAX_DEPTH`.
+    ///
+    /// Available with the `msgpack` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONValue};
+    ///
+    /// let json = JSONParser::from(r#"{ "id": -300, "ratio": 0.5, "tags": ["a", null, true] }"#).unwrap();
+    ///
+    /// assert_eq!(JSONValue::from_msgpack(&json.to_msgpack()), Ok(json));
+    /// assert!(JSONValue::from_msgpack(b"\xc4\x01\x00").is_err());
+    ///
+    /// let nested = vec![0x91; 1_000_000];
+    ///
+    /// assert_eq!(JSONValue::from_msgpack(&nested).unwrap_err(), "Maximum nesting depth exceeded (max: 128, offset: 128)");
+    /// ```
+    pub fn from_msgpack(input: &[u8]) -> Result<JSONValue, String> {
+        let mut decoder = Decoder { input, offset: 0, depth: 0 };
+        let value = decoder.value()?;
+
+        if decoder.offset < input.len() {
+            return Err(format!("Unexpected data after the value (offset: {})", decoder.offset));
+        }
+        Ok(value)
+    }
+}
+
+fn write_number(n: f64, output: &mut Vec<u8>) {
+    let integral = n.fract() == 0.0 && !(n == 0.0 && n.is_sign_negative());
+
+    if integral && (0.0..18446744073709551616.0).contains(&n) {
+        match n as u64 {
+            n if n < 0x80 => output.push(n as u8),
+            n if n <= u8::MAX as u64 => output.extend_from_slice(&[0xcc, n as u8]),
+            n if n <= u16::MAX as u64 => {
+                output.push(0xcd);
+                output.extend_from_slice(&(n as u16).to_be_bytes());
+            },
+            n if n <= u32::MAX as u64 => {
+                output.push(0xce);
+                output.extend_from_slice(&(n as u32).to_be_bytes());
+            },
+            n => {
+                output.push(0xcf);
+                output.extend_from_slice(&n.to_be_bytes());
+            }
+        }
+    } else if integral && (-9223372036854775808.0..0.0).contains(&n) {
+        match n as i64 {
+            n if n >= -32 => output.push(n as u8),
+            n if n >= i8::MIN as i64 => output.extend_from_slice(&[0xd0, n as u8]),
+            n if n >= i16::MIN as i64 => {
+                output.push(0xd1);
+                output.extend_from_slice(&(n as i16).to_be_bytes());
+            },
+            n if n >= i32::MIN as i64 => {
+                output.push(0xd2);
+                output.extend_from_slice(&(n as i32).to_be_bytes());
+            },
+            n => {
+                output.push(0xd3);
+                output.extend_from_slice(&n.to_be_bytes());
+            }
+        }
+    } else {
+        output.push(0xcb);
+        output.extend_from_slice(&n.to_be_bytes());
+    }
+}
+
+/// Write the header of a string, array or map. `markers` holds the fix marker and
+/// the 8, 16 and 32-bit length markers, 0 when the format has none.
+fn write_length(len: usize, markers: [u8; 4], fix_limit: usize, output: &mut Vec<u8>) {
+    if len < fix_limit {
+        output.push(markers[0] | len as u8);
+    } else if len <= u8::MAX as usize && markers[1] != 0 {
+        output.extend_from_slice(&[markers[1], len as u8]);
+    } else if len <= u16::MAX as usize {
+        output.push(markers[2]);
+        output.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        output.push(markers[3]);
+        output.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+}
+
+struct Decoder<'a> {
+    input: &'a [u8],
+    offset: usize,
+    depth: usize
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let bytes = self.input.get(self.offset..self.offset.saturating_add(len))
+            .ok_or_else(|| format!("Unexpected end of input (offset: {})", self.input.len()))?;
+
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn uint(&mut self, size: usize) -> Result<u64, String> {
+        Ok(self.take(size)?.iter().fold(0, |n, &b| n << 8 | b as u64))
+    }
+
+    fn int(&mut self, size: usize) -> Result<i64, String> {
+        let shift = 64 - 8 * size as u32;
+
+        Ok(((self.uint(size)? << shift) as i64) >> shift)
+    }
+
+    fn value(&mut self) -> Result<JSONValue, String> {
+        let offset = self.offset;
+        let marker = self.take(1)?[0];
+        let nested = matches!(marker, 0x80..=0x9f | 0xdc..=0xdf);
+
+        if nested {
+            self.enter(offset)?;
+        }
+        let value = match marker {
+            0x00..=0x7f => JSONValue::Number(marker as f64),
+            0x80..=0x8f => self.map((marker & 0x0f) as usize)?,
+            0x90..=0x9f => self.array((marker & 0x0f) as usize)?,
+            0xa0..=0xbf => self.string((marker & 0x1f) as usize)?,
+            0xc0 => JSONValue::Null,
+            0xc2 => JSONValue::Boolean(false),
+            0xc3 => JSONValue::Boolean(true),
+            0xca => JSONValue::Number(f32::from_bits(self.uint(4)? as u32) as f64),
+            0xcb => JSONValue::Number(f64::from_bits(self.uint(8)?)),
+            0xcc..=0xcf => JSONValue::Number(self.uint(1 << (marker - 0xcc))? as f64),
+            0xd0..=0xd3 => JSONValue::Number(self.int(1 << (marker - 0xd0))? as f64),
+            0xd9..=0xdb => {
+                let len = self.uint(1 << (marker - 0xd9))? as usize;
+
+                self.string(len)?
+            },
+            0xdc | 0xdd => {
+                let len = self.uint(2 << (marker - 0xdc))? as usize;
+
+                self.array(len)?
+            },
+            0xde | 0xdf => {
+                let len = self.uint(2 << (marker - 0xde))? as usize;
+
+                self.map(len)?
+            },
+            0xe0..=0xff => JSONValue::Number((marker as i8) as f64),
+            0xc4..=0xc6 => return Err(format!("Binary data has no JSON equivalent (offset: {})", offset)),
+            0xc7..=0xc9 | 0xd4..=0xd8 => return Err(format!("Extension types have no JSON equivalent (offset: {})", offset)),
+            _ => return Err(format!("Invalid marker 0x{:02x} (offset: {})", marker, offset))
+        };
+
+        if nested {
+            self.depth -= 1;
+        }
+        Ok(value)
+    }
+
+    /// Count a nested array or map, failing past `DEFAULT_MAX_DEPTH` levels.
+    fn enter(&mut self, offset: usize) -> Result<(), String> {
+        if self.depth >= DEFAULT_MAX_DEPTH {
+            return Err(format!("Maximum nesting depth exceeded (max: {}, offset: {})", DEFAULT_MAX_DEPTH, offset));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn string(&mut self, len: usize) -> Result<JSONValue, String> {
+        let offset = self.offset;
+        let bytes = self.take(len)?;
+
+        core::str::from_utf8(bytes)
+            .map(|s| JSONValue::String(s.into()))
+            .map_err(|_| format!("Invalid UTF-8 in string (offset: {})", offset))
+    }
+
+    fn array(&mut self, len: usize) -> Result<JSONValue, String> {
+        // Every item takes at least one byte, so a bogus length cannot over-allocate.
+        let mut array = Vec::with_capacity(len.min(self.input.len() - self.offset));
+
+        for _ in 0..len {
+            array.push(self.value()?);
+        }
+        Ok(JSONValue::Array(array.into()))
+    }
+
+    fn map(&mut self, len: usize) -> Result<JSONValue, String> {
+        let mut obj = OrderedMap::with_capacity(len.min((self.input.len() - self.offset) / 2));
+
+        for _ in 0..len {
+            let offset = self.offset;
+            let key = match self.value()? {
+                JSONValue::String(key) => key,
+                key => return Err(format!("Map key must be a string, found {} (offset: {})", key.type_name(), offset))
+            };
+
+            obj.insert(&key, self.value()?);
+        }
+        Ok(JSONValue::Object(obj))
+    }
+}
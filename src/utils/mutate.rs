@@ -0,0 +1,298 @@
+use crate::utils::access::push_segment;
+use crate::utils::compat::*;
+use crate::utils::parser::edit_items;
+use crate::utils::path::Segment;
+use crate::utils::{AccessError, JSONKind, JSONValue, OrderedMap};
+
+/// The error for a value of the wrong kind, at the given key or at the value itself.
+fn wrong_type(key: Option<&str>, expected: JSONKind, found: JSONKind) -> AccessError {
+    let mut path = String::new();
+
+    if let Some(key) = key {
+        push_segment(&mut path, &Segment::Key(key.to_string()));
+    }
+    AccessError::WrongType { path, expected, found }
+}
+
+impl JSONValue {
+    /// Returns the entries of the object, turning null into an empty object first.
+    fn object_or_insert(&mut self, key: Option<&str>) -> Result<&mut OrderedMap<JSONValue>, AccessError> {
+        if self.is_null() {
+            *self = JSONValue::Object(OrderedMap::new());
+        }
+        match self {
+            JSONValue::Object(obj) => Ok(obj),
+            value => Err(wrong_type(key, JSONKind::Object, value.kind()))
+        }
+    }
+
+    /// Returns the items of the array, turning null into an empty array first.
+    fn array_or_insert(&mut self, key: Option<&str>) -> Result<&mut Box<[JSONValue]>, AccessError> {
+        if self.is_null() {
+            *self = JSONValue::Array(Box::new([]));
+        }
+        match self {
+            JSONValue::Array(array) => Ok(array),
+            value => Err(wrong_type(key, JSONKind::Array, value.kind()))
+        }
+    }
+
+    /// Returns the items of the array.
+    fn array_items(&mut self) -> Result<&mut Box<[JSONValue]>, AccessError> {
+        match self {
+            JSONValue::Array(array) => Ok(array),
+            value => Err(wrong_type(None, JSONKind::Array, value.kind()))
+        }
+    }
+
+    /// Returns the value of the given key, adding it as null when missing.
+    fn entry(&mut self, key: &str) -> Result<&mut JSONValue, AccessError> {
+        let obj = self.object_or_insert(None)?;
+
+        if !obj.contains_key(key) {
+            obj.insert(key, JSONValue::Null);
+        }
+        Ok(obj.get_mut(key).unwrap())
+    }
+
+    /// Set the value of the given key and return the previous one. A null value is turned
+    /// into an object first, so that a document can be built up from `JSONValue::Null`.
+    /// Returns an error for any other value that is not an object.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONValue;
+    ///
+    /// let mut json = JSONValue::Null;
+    ///
+    /// json.insert("name", JSONValue::String("John".into())).unwrap();
+    ///
+    /// let previous = json.insert("name", JSONValue::String("Jane".into())).unwrap();
+    ///
+    /// assert_eq!(previous.and_then(|name| name.into_string()), Some("John".to_string()));
+    /// assert_eq!(json["name"].as_str(), Some("Jane"));
+    /// assert_eq!(json.pointer_mut("/name").unwrap().insert("first", JSONValue::Null).unwrap_err().to_string(), "Expected object, found string");
+    /// ```
+    pub fn insert(&mut self, key: &str, value: JSONValue) -> Result<Option<JSONValue>, AccessError> {
+        let obj = self.object_or_insert(None)?;
+
+        match obj.get_mut(key) {
+            Some(previous) => Ok(Some(core::mem::replace(previous, value))),
+            None => {
+                obj.insert(key, value);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Add an item at the end of the array. A null value is turned into an array first.
+    /// Returns an error for any other value that is not an array.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONValue};
+    ///
+    /// let mut json = JSONParser::from(r#"{ "tags": ["admin"], "owner": null }"#).unwrap();
+    ///
+    /// json.pointer_mut("/tags").unwrap().push(JSONValue::String("staff".into())).unwrap();
+    /// json.pointer_mut("/owner").unwrap().push(JSONValue::Number(1.0)).unwrap();
+    ///
+    /// assert_eq!(json.to_string(), r#"{"tags":["admin","staff"],"owner":[1]}"#);
+    /// assert_eq!(json.push(JSONValue::Null).unwrap_err().to_string(), "Expected array, found object");
+    /// ```
+    pub fn push(&mut self, value: JSONValue) -> Result<(), AccessError> {
+        edit_items(self.array_or_insert(None)?, |items| items.push(value));
+        Ok(())
+    }
+
+    /// Returns the object at the given key for changing it, adding an empty object when the
+    /// key is missing or null. A null value is turned into an object first. Returns an error
+    /// when the value or the value at the key is something else.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONValue};
+    ///
+    /// let mut json = JSONParser::from(r#"{ "name": "John" }"#).unwrap();
+    ///
+    /// json.get_or_insert_object("address").unwrap().insert("city", JSONValue::String("Paris".into()));
+    /// json.get_or_insert_object("address").unwrap().insert("zip", JSONValue::String("75001".into()));
+    ///
+    /// assert_eq!(json.to_string(), r#"{"name":"John","address":{"city":"Paris","zip":"75001"}}"#);
+    /// assert_eq!(json.get_or_insert_object("name").unwrap_err().to_string(), "Expected object at 'name', found string");
+    /// ```
+    pub fn get_or_insert_object(&mut self, key: &str) -> Result<&mut OrderedMap<JSONValue>, AccessError> {
+        self.entry(key)?.object_or_insert(Some(key))
+    }
+
+    /// Returns the array at the given key for changing it with `push` and the other array
+    /// methods, adding an empty array when the key is missing or null. A null value is
+    /// turned into an object first. Returns an error when the value or the value at the key
+    /// is something else.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONValue;
+    ///
+    /// let mut json = JSONValue::Null;
+    ///
+    /// json.get_or_insert_array("tags").unwrap().push(JSONValue::String("admin".into())).unwrap();
+    /// json.get_or_insert_object("address").unwrap();
+    ///
+    /// assert_eq!(json.to_string(), r#"{"tags":["admin"],"address":{}}"#);
+    /// assert_eq!(json.get_or_insert_array("address").unwrap_err().to_string(), "Expected array at 'address', found object");
+    /// ```
+    pub fn get_or_insert_array(&mut self, key: &str) -> Result<&mut JSONValue, AccessError> {
+        let value = self.entry(key)?;
+
+        value.array_or_insert(Some(key))?;
+        Ok(value)
+    }
+
+    /// Remove the last item of the array and return it, or None when it is empty.
+    /// Returns an error when the value is not an array.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let mut json = JSONParser::from(r#"{ "ids": [1, 2] }"#).unwrap();
+    /// let ids = json.pointer_mut("/ids").unwrap();
+    ///
+    /// assert_eq!(ids.pop().unwrap().and_then(|id| id.as_f64()), Some(2.0));
+    /// assert!(ids.pop().unwrap().is_some());
+    /// assert_eq!(ids.pop(), Ok(None));
+    /// assert!(json.pop().is_err());
+    /// ```
+    pub fn pop(&mut self) -> Result<Option<JSONValue>, AccessError> {
+        Ok(edit_items(self.array_items()?, |items| items.pop()))
+    }
+
+    /// Insert an item into the array before the given index, shifting the following
+    /// items. A null value is turned into an array first. Returns an error when the value
+    /// is something else or the index is past the end of the array.
+    ///
+    /// Named apart from `insert`, which sets the key of an object.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONValue};
+    ///
+    /// let mut json = JSONParser::from(r#"{ "ids": [1, 3] }"#).unwrap();
+    /// let ids = json.pointer_mut("/ids").unwrap();
+    ///
+    /// ids.insert_at(1, JSONValue::Number(2.0)).unwrap();
+    /// ids.insert_at(3, JSONValue::Number(4.0)).unwrap();
+    ///
+    /// assert_eq!(ids.to_string(), "[1,2,3,4]");
+    /// assert_eq!(ids.insert_at(5, JSONValue::Null).unwrap_err().to_string(), "Index 5 out of bounds (length: 4)");
+    /// ```
+    pub fn insert_at(&mut self, index: usize, value: JSONValue) -> Result<(), AccessError> {
+        let array = self.array_or_insert(None)?;
+
+        if index > array.len() {
+            return Err(AccessError::OutOfBounds { path: String::new(), index, len: array.len() });
+        }
+        edit_items(array, |items| items.insert(index, value));
+        Ok(())
+    }
+
+    /// Remove the item at the given index of the array and return it, shifting the
+    /// following items. Returns an error when the value is not an array or the index is out
+    /// of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let mut json = JSONParser::from(r#"{ "tags": ["a", "b", "c"] }"#).unwrap();
+    /// let tags = json.pointer_mut("/tags").unwrap();
+    ///
+    /// assert_eq!(tags.remove_at(1).unwrap().as_str(), Some("b"));
+    /// assert_eq!(tags.to_string(), r#"["a","c"]"#);
+    /// assert_eq!(tags.remove_at(2).unwrap_err().to_string(), "Index 2 out of bounds (length: 2)");
+    /// ```
+    pub fn remove_at(&mut self, index: usize) -> Result<JSONValue, AccessError> {
+        let array = self.array_items()?;
+
+        if index >= array.len() {
+            return Err(AccessError::OutOfBounds { path: String::new(), index, len: array.len() });
+        }
+        Ok(edit_items(array, |items| items.remove(index)))
+    }
+
+    /// Keep only the items of the array for which the predicate returns true, in order.
+    /// Returns an error when the value is not an array.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let mut json = JSONParser::from(r#"{ "ids": [1, null, 2, null] }"#).unwrap();
+    ///
+    /// json.pointer_mut("/ids").unwrap().retain(|id| !id.is_null()).unwrap();
+    ///
+    /// assert_eq!(json.to_string(), r#"{"ids":[1,2]}"#);
+    /// assert_eq!(json.retain(|_| true).unwrap_err().to_string(), "Expected array, found object");
+    /// ```
+    pub fn retain<F: FnMut(&JSONValue) -> bool>(&mut self, predicate: F) -> Result<(), AccessError> {
+        edit_items(self.array_items()?, |items| items.retain(predicate));
+        Ok(())
+    }
+
+    /// Replace each item of the array with the result of the function, which takes the
+    /// item by value. Returns an error when the value is not an array.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONValue};
+    ///
+    /// let mut json = JSONParser::from(r#"{ "prices": [1, 2, 3] }"#).unwrap();
+    ///
+    /// json.pointer_mut("/prices").unwrap().map_in_place(|price| JSONValue::Number(price.as_f64().unwrap_or(0.0) * 10.0)).unwrap();
+    ///
+    /// assert_eq!(json.to_string(), r#"{"prices":[10,20,30]}"#);
+    /// ```
+    pub fn map_in_place<F: FnMut(JSONValue) -> JSONValue>(&mut self, mut f: F) -> Result<(), AccessError> {
+        for item in self.array_items()?.iter_mut() {
+            *item = f(item.take());
+        }
+        Ok(())
+    }
+
+    /// Add the items of another array at the end of the array. A null value is turned
+    /// into an array first. Returns an error when either value is something else, leaving
+    /// the value unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONValue;
+    ///
+    /// let mut json = JSONValue::Null;
+    ///
+    /// json.concat(JSONValue::Array(vec![JSONValue::Number(1.0), JSONValue::Number(2.0)].into())).unwrap();
+    /// json.concat(JSONValue::Array(vec![JSONValue::Number(3.0)].into())).unwrap();
+    ///
+    /// assert_eq!(json.to_string(), "[1,2,3]");
+    /// assert_eq!(json.concat(JSONValue::Number(4.0)).unwrap_err().to_string(), "Expected array, found number");
+    /// ```
+    pub fn concat(&mut self, other: JSONValue) -> Result<(), AccessError> {
+        let items = match other {
+            JSONValue::Array(items) => items,
+            other => return Err(wrong_type(None, JSONKind::Array, other.kind()))
+        };
+
+        edit_items(self.array_or_insert(None)?, |array| array.extend(items.into_vec()));
+        Ok(())
+    }
+}
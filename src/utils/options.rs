@@ -0,0 +1,140 @@
+/// Options controlling how a document is parsed.
+/// Every limit is disabled by default, except the nesting depth, limited to
+/// `DEFAULT_MAX_DEPTH` so that deeply nested input fails with an error instead of
+/// overflowing the stack.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{JSONParser, ParserOptions};
+///
+/// let options = ParserOptions::new()
+///     .max_document_size(1024 * 1024)
+///     .max_string_length(4096)
+///     .max_nodes(10_000);
+///
+/// let json = JSONParser::with_options(r#"{ "name": "John Doe" }"#, options).parse();
+///
+/// assert!(json.is_ok());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ParserOptions {
+    pub(crate) max_document_size: Option<usize>,
+    pub(crate) max_string_length: Option<usize>,
+    pub(crate) max_array_length: Option<usize>,
+    pub(crate) max_object_keys: Option<usize>,
+    pub(crate) max_nodes: Option<usize>,
+    pub(crate) max_depth: usize,
+    pub(crate) allow_control_characters: bool,
+    pub(crate) preserve_duplicate_keys: bool
+}
+
+/// The default maximum nesting depth of arrays and objects.
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        Self {
+            max_document_size: None,
+            max_string_length: None,
+            max_array_length: None,
+            max_object_keys: None,
+            max_nodes: None,
+            max_depth: DEFAULT_MAX_DEPTH,
+            allow_control_characters: false,
+            preserve_duplicate_keys: false
+        }
+    }
+}
+
+impl ParserOptions {
+    /// Create a new ParserOptions instance without any limit but the nesting depth.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum size of the document, in bytes.
+    pub fn max_document_size(mut self, max: usize) -> Self {
+        self.max_document_size = Some(max);
+        self
+    }
+
+    /// Set the maximum length of strings and object keys, in bytes.
+    pub fn max_string_length(mut self, max: usize) -> Self {
+        self.max_string_length = Some(max);
+        self
+    }
+
+    /// Set the maximum number of items in an array.
+    pub fn max_array_length(mut self, max: usize) -> Self {
+        self.max_array_length = Some(max);
+        self
+    }
+
+    /// Set the maximum number of keys in an object.
+    pub fn max_object_keys(mut self, max: usize) -> Self {
+        self.max_object_keys = Some(max);
+        self
+    }
+
+    /// Set the maximum number of values in the whole document.
+    pub fn max_nodes(mut self, max: usize) -> Self {
+        self.max_nodes = Some(max);
+        self
+    }
+
+    /// Set the maximum nesting depth of arrays and objects, the root being at depth 1.
+    /// Defaults to `DEFAULT_MAX_DEPTH`. Nested values are parsed recursively, so a much
+    /// larger limit lets hostile input overflow the stack of the thread.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, Limit, ParseError, ParserOptions};
+    ///
+    /// let input = format!(r#"{{ "a": {}{} }}"#, "[".repeat(200_000), "]".repeat(200_000));
+    /// let error = JSONParser::from(&input).unwrap_err();
+    ///
+    /// assert_eq!(error, ParseError::LimitExceeded { limit: Limit::Depth, max: 128 });
+    /// assert_eq!(error.to_string(), "Maximum nesting depth exceeded (max: 128)");
+    ///
+    /// let options = ParserOptions::new().max_depth(2);
+    ///
+    /// assert!(JSONParser::with_options(r#"{ "a": [1] }"#, options).parse().is_ok());
+    /// assert!(JSONParser::with_options(r#"{ "a": [[1]] }"#, options).parse().is_err());
+    /// ```
+    pub fn max_depth(mut self, max: usize) -> Self {
+        self.max_depth = max;
+        self
+    }
+
+    /// Accept unescaped control characters (U+0000 to U+001F) inside strings.
+    pub fn allow_control_characters(mut self) -> Self {
+        self.allow_control_characters = true;
+        self
+    }
+
+    /// Keep every entry of an object whose key is repeated, in order, instead of keeping
+    /// only the last value. `OrderedMap::get_all` returns them, `get` the last one, and
+    /// serializing writes them all back.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, ParserOptions, Serialize};
+    ///
+    /// let input = r#"{ "Set-Cookie": "a=1", "Vary": "Accept", "Set-Cookie": "b=2" }"#;
+    /// let json = JSONParser::with_options(input, ParserOptions::new().preserve_duplicate_keys()).parse().unwrap();
+    /// let headers = json.as_object().unwrap();
+    ///
+    /// assert_eq!(headers.get_all("Set-Cookie").filter_map(|value| value.as_str()).collect::<Vec<_>>(), vec!["a=1", "b=2"]);
+    /// assert_eq!(json["Set-Cookie"].as_str(), Some("b=2"));
+    /// assert_eq!(json.serialize(), r#"{"Set-Cookie":"a=1","Vary":"Accept","Set-Cookie":"b=2"}"#);
+    ///
+    /// assert_eq!(JSONParser::from(input).unwrap().serialize(), r#"{"Set-Cookie":"b=2","Vary":"Accept"}"#);
+    /// ```
+    pub fn preserve_duplicate_keys(mut self) -> Self {
+        self.preserve_duplicate_keys = true;
+        self
+    }
+}
@@ -0,0 +1,112 @@
+use core::cmp::Ordering;
+
+use crate::utils::JSONValue;
+
+impl JSONValue {
+    /// Compare two values with a total ordering.
+    /// Values of different kinds are ordered as null < boolean < number < string < array < object < raw.
+    /// Numbers compare numerically with NaN above every other number, strings compare by code point,
+    /// arrays compare item by item, and objects compare entry by entry (key, then value)
+    /// in their current order, then by length. Raw values compare by their text.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use jsonparser::{JSONParser, JSONValue, Serialize};
+    ///
+    /// let json = JSONParser::from(r#"{ "values": [true, null, "b", 2, [1], "a", 1] }"#).unwrap();
+    /// let mut values = json["values"].as_array().unwrap().to_vec();
+    ///
+    /// values.sort_by(JSONValue::cmp_values);
+    ///
+    /// assert_eq!(JSONValue::Array(values.into()).serialize(), r#"[null,true,1,2,"a","b",[1]]"#);
+    /// assert_eq!(JSONValue::Null.cmp_values(&JSONValue::Boolean(false)), Ordering::Less);
+    /// ```
+    pub fn cmp_values(&self, other: &JSONValue) -> Ordering {
+        match (self, other) {
+            (JSONValue::Boolean(a), JSONValue::Boolean(b)) => a.cmp(b),
+            (JSONValue::Number(a), JSONValue::Number(b)) => match (a.is_nan(), b.is_nan()) {
+                (false, false) => a.partial_cmp(b).expect("Numbers are not NaN"),
+                (a, b) => a.cmp(&b)
+            },
+            (JSONValue::String(a), JSONValue::String(b)) => a.cmp(b),
+            (JSONValue::Raw(a), JSONValue::Raw(b)) => a.cmp(b),
+            (JSONValue::Array(a), JSONValue::Array(b)) => {
+                a.iter().zip(b.iter())
+                    .map(|(a, b)| a.cmp_values(b))
+                    .find(|ordering| *ordering != Ordering::Equal)
+                    .unwrap_or_else(|| a.len().cmp(&b.len()))
+            },
+            (JSONValue::Object(a), JSONValue::Object(b)) => {
+                a.iter().zip(b.iter())
+                    .map(|((k1, v1), (k2, v2))| k1.cmp(k2).then_with(|| v1.cmp_values(v2)))
+                    .find(|ordering| *ordering != Ordering::Equal)
+                    .unwrap_or_else(|| a.len().cmp(&b.len()))
+            },
+            _ => rank(self).cmp(&rank(other))
+        }
+    }
+
+    /// Sort the items of an array by the value each item holds at the given key,
+    /// using `cmp_values`. Items without the key come first, and the sort is stable.
+    /// Does nothing if the value is not an array.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let mut json = JSONParser::from(r#"{ "users": [{ "name": "Zoe" }, { "name": "Adam" }, { "id": 3 }] }"#).unwrap();
+    ///
+    /// json.pointer_mut("/users").unwrap().sort_array_by_key("name");
+    ///
+    /// assert_eq!(json["users"][1]["name"].as_str(), Some("Adam"));
+    /// assert_eq!(json["users"][2]["name"].as_str(), Some("Zoe"));
+    /// ```
+    pub fn sort_array_by_key(&mut self, key: &str) {
+        if let JSONValue::Array(array) = self {
+            array.sort_by(|a, b| match (a.get(key), b.get(key)) {
+                (Some(a), Some(b)) => a.cmp_values(b),
+                (a, b) => a.is_some().cmp(&b.is_some())
+            });
+        }
+    }
+}
+
+fn rank(value: &JSONValue) -> u8 {
+    match value {
+        JSONValue::Null => 0,
+        JSONValue::Boolean(_) => 1,
+        JSONValue::Number(_) => 2,
+        JSONValue::String(_) => 3,
+        JSONValue::Array(_) => 4,
+        JSONValue::Object(_) => 5,
+        JSONValue::Raw(_) => 6
+    }
+}
+
+/// Follows `cmp_values`, except that comparing NaN with any number has no ordering,
+/// as NaN is not equal to itself.
+impl PartialOrd for JSONValue {
+    fn partial_cmp(&self, other: &JSONValue) -> Option<Ordering> {
+        match (self, other) {
+            (JSONValue::Number(a), JSONValue::Number(b)) => a.partial_cmp(b),
+            (JSONValue::Array(a), JSONValue::Array(b)) => a.partial_cmp(b),
+            (JSONValue::Object(a), JSONValue::Object(b)) => {
+                for ((k1, v1), (k2, v2)) in a.iter().zip(b.iter()) {
+                    match k1.cmp(k2) {
+                        Ordering::Equal => {},
+                        ordering => return Some(ordering)
+                    }
+                    match v1.partial_cmp(v2)? {
+                        Ordering::Equal => {},
+                        ordering => return Some(ordering)
+                    }
+                }
+                Some(a.len().cmp(&b.len()))
+            },
+            _ => Some(self.cmp_values(other))
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use rayon::prelude::*;
+
+use crate::utils::scan;
+use crate::utils::{Lexer, Parser};
+use crate::{JSONParser, JSONValue, ParseError};
+
+/// Find the byte ranges of the items of a top-level array, outside of strings.
+/// Returns None when the input is not a well-delimited array.
+fn split_items(input: &str) -> Option<Vec<(usize, usize)>> {
+    let bytes = input.as_bytes();
+    let open = bytes.iter().position(|b| !b.is_ascii_whitespace())?;
+
+    if bytes[open] != b'[' {
+        return None;
+    }
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut start = open + 1;
+
+    for i in scan::structurals(bytes) {
+        match bytes[i] {
+            b'[' | b'{' => depth += 1,
+            b']' | b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    if !items.is_empty() || !input[start..i].trim().is_empty() {
+                        items.push((start, i));
+                    }
+                    return Some(items);
+                }
+            },
+            b',' if depth == 1 => {
+                items.push((start, i));
+                start = i + 1;
+            },
+            _ => {}
+        }
+    }
+    None
+}
+
+impl<'a> JSONParser<'a> {
+    /// Parse a document whose root is an array, spreading its items across the global
+    /// rayon thread pool, or the pool this is called from with `ThreadPool::install`.
+    /// A quick structural pass finds the top-level items, each task parses a contiguous
+    /// run of them, and the results are stitched back in order.
+    /// Any other document is parsed on the current thread, like `JSONParser::from`.
+    ///
+    /// Available with the `rayon` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let input = r#"[{ "id": 1 }, { "id": 2 }, { "id": 3 }, [4, 5], "six"]"#;
+    /// let json = JSONParser::parse_parallel(input).unwrap();
+    ///
+    /// assert_eq!(json.as_array().map(|items| items.len()), Some(5));
+    /// assert_eq!(json[4].as_str(), Some("six"));
+    ///
+    /// let tricky = format!(r#"["{}, ] \\\" [", {{ "a": "\\" }}, 3]"#, "x".repeat(60));
+    /// let json = JSONParser::parse_parallel(&tricky).unwrap();
+    ///
+    /// assert_eq!(json.as_array().map(|items| items.len()), Some(3));
+    /// assert_eq!(json[1]["a"].as_str(), Some("\\"));
+    /// ```
+    pub fn parse_parallel(input: &'a str) -> Result<JSONValue, ParseError> {
+        let items = match split_items(input) {
+            Some(items) if items.len() > 1 => items,
+            _ => return Self::parse_sequential(input)
+        };
+        // A few runs per thread, so that work stealing evens out items of uneven size.
+        let runs = (rayon::current_num_threads() * 4).min(items.len());
+        let per_run = (items.len() + runs - 1) / runs;
+        let results: Vec<_> = items.par_chunks(per_run).map(|run| {
+            let slice = &input[run[0].0..run[run.len() - 1].1];
+
+            Parser::new(Lexer::new(slice)).parse_sequence()
+        }).collect();
+        let mut array = Vec::with_capacity(items.len());
+
+        for result in results {
+            array.extend(result?);
+        }
+        Ok(JSONValue::Array(array.into()))
+    }
+
+    fn parse_sequential(input: &'a str) -> Result<JSONValue, ParseError> {
+        Parser::new(Lexer::new(input)).parse_document()
+    }
+}
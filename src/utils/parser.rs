@@ -1,34 +1,148 @@
+use core::cell::Cell;
 use core::fmt;
-use std::collections::HashMap;
-use std::ops::Index;
+use core::ops::{Bound, Index, RangeBounds};
+use alloc::sync::Arc;
 
+use crate::utils::compat::*;
+use crate::utils::arena::{Arena, ArenaValue};
+use crate::utils::cancellation::CancellationToken;
+use crate::utils::error::{Limit, ParseError};
 use crate::utils::lexer::{Lexer, Token, TokenKind};
+use crate::utils::options::ParserOptions;
+use crate::utils::pointer::parse_pointer;
+use crate::utils::preview::PREVIEW_LENGTH;
+use crate::utils::raw::RawValue;
+use crate::utils::serialize::SerializeOptions;
+use crate::utils::string::JSONString;
 
+/// A map keeping its keys in insertion order.
+/// Entries are stored in order, and small maps are searched linearly. Maps with more
+/// than `INDEXED_LEN` entries also keep a `KeyIndex`, a hash table of entry positions
+/// that holds no keys of its own. This keeps a map, and so a `JSONValue`, to a few words.
+///
+/// # Example
+///
+/// ```
+/// use std::mem::size_of;
+/// use jsonparser::{JSONValue, OrderedMap};
+///
+/// assert_eq!(size_of::<OrderedMap<JSONValue>>(), 4 * size_of::<usize>());
+/// assert_eq!(size_of::<JSONValue>(), 4 * size_of::<usize>());
+///
+/// let mut map = OrderedMap::new();
+///
+/// for i in 0..100 {
+///     map.insert(&i.to_string(), i);
+/// }
+/// map.append("7", 700);
+///
+/// assert_eq!(map.get("7"), Some(&700));
+/// assert_eq!(map.remove("7"), Some(700));
+/// assert_eq!(map.get("7"), None);
+/// assert_eq!(map.get_full("99"), Some((98, "99", &99)));
+/// ```
 #[derive(Clone)]
 pub struct OrderedMap<V> {
-    order: Vec<String>,
-    map: HashMap<String, V>,
+    entries: Vec<(Arc<str>, V)>,
+    /// Boxed so that small maps pay a single word for it.
+    index: Option<Box<KeyIndex>>
+}
+
+/// The number of entries above which a map indexes its keys.
+const INDEXED_LEN: usize = 8;
+
+/// The positions of the entries of an `OrderedMap`, in an open-addressing table probed
+/// linearly from the hash of their key. A slot only holds a position: keys are compared
+/// against the entries, so they are stored once. The table is kept at most half full.
+#[derive(Clone)]
+struct KeyIndex {
+    slots: Box<[usize]>
+}
+
+/// The value of a slot holding no position.
+const EMPTY_SLOT: usize = usize::MAX;
+
+impl KeyIndex {
+    fn build<V>(entries: &[(Arc<str>, V)]) -> KeyIndex {
+        let mut index = KeyIndex { slots: vec![EMPTY_SLOT; (entries.len() * 2).next_power_of_two()].into_boxed_slice() };
+
+        for (position, (key, _)) in entries.iter().enumerate() {
+            index.insert(entries, key, position);
+        }
+        index
+    }
+
+    /// Whether the table is too full to hold the given number of entries.
+    fn is_full(&self, len: usize) -> bool {
+        len * 2 > self.slots.len()
+    }
+
+    /// The slot holding the position of the key, or the empty slot where it belongs.
+    fn slot<V>(&self, entries: &[(Arc<str>, V)], key: &str) -> usize {
+        let mask = self.slots.len() - 1;
+        let mut slot = hash_key(key) as usize & mask;
+
+        loop {
+            match self.slots[slot] {
+                EMPTY_SLOT => return slot,
+                position if &*entries[position].0 == key => return slot,
+                _ => slot = (slot + 1) & mask
+            }
+        }
+    }
+
+    fn get<V>(&self, entries: &[(Arc<str>, V)], key: &str) -> Option<usize> {
+        match self.slots[self.slot(entries, key)] {
+            EMPTY_SLOT => None,
+            position => Some(position)
+        }
+    }
+
+    /// Record the position of a key, replacing the position of an earlier entry of it.
+    fn insert<V>(&mut self, entries: &[(Arc<str>, V)], key: &str, position: usize) {
+        let slot = self.slot(entries, key);
+
+        self.slots[slot] = position;
+    }
+}
+
+/// A 64-bit FNV-1a hash, as used by `JSONValue::digest`.
+fn hash_key(key: &str) -> u64 {
+    key.bytes().fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+impl<V: PartialEq> PartialEq for OrderedMap<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
 }
 
 impl<V: fmt::Debug> fmt::Debug for OrderedMap<V> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_map().entries(self.order.iter().map(|key| (key, self.map.get(key).unwrap()))).finish()
+        f.debug_map().entries(self.entries.iter().map(|(key, value)| (key, value))).finish()
     }
 }
 
+/// Compact JSON, as produced by `Serialize::serialize`.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::JSONParser;
+///
+/// let json = JSONParser::from(r#"{ "name": "say \"hi\"", "cars": [{ "model": "X" }], "age": null }"#).unwrap();
+///
+/// assert_eq!(format!("{}", json), r#"{"name":"say \"hi\"","cars":[{"model":"X"}],"age":null}"#);
+/// assert_eq!(json["cars"].to_string(), r#"[{"model":"X"}]"#);
+/// ```
 impl fmt::Display for OrderedMap<JSONValue> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{{")?;
-        for (i, key) in self.order.iter().enumerate() {
+        for (i, (key, value)) in self.iter().enumerate() {
             if i > 0 {
-                write!(f, ", ")?;
-            }
-
-            match self.map.get(key).expect("Key not found") {
-                JSONValue::String(_) => write!(f, "\"{}\": \"{}\"", key, self.map[key])?,
-                JSONValue::Null => write!(f, "\"{}\": null", key)?,
-                _ => write!(f, "\"{}\": {}", key, self.map[key])?
+                write!(f, ",")?;
             }
+            write!(f, "{}:{}", key.serialize(), value.serialize())?;
         }
         write!(f, "}}")
     }
@@ -56,42 +170,490 @@ impl JSONAccess for usize {
     }
 }
 
+/// A negative index counts from the end of the array, so `-1` is the last item.
+impl JSONAccess for isize {
+    fn get<'a>(&self, value: &'a JSONValue) -> Option<&'a JSONValue> {
+        match value {
+            JSONValue::Array(array) => array.get(resolve_index(*self, array.len())?),
+            _ => None,
+        }
+    }
+}
+
+/// Same as `isize`, so that integer literals such as `-1` can be used.
+impl JSONAccess for i32 {
+    fn get<'a>(&self, value: &'a JSONValue) -> Option<&'a JSONValue> {
+        (*self as isize).get(value)
+    }
+}
+
+/// Change the items of an array through a `Vec`, which reuses the allocation of the
+/// boxed slice. The slice is shrunk back to its length afterwards.
+pub(crate) fn edit_items<R>(items: &mut Box<[JSONValue]>, edit: impl FnOnce(&mut Vec<JSONValue>) -> R) -> R {
+    let mut vec = core::mem::take(items).into_vec();
+    let result = edit(&mut vec);
+
+    *items = vec.into_boxed_slice();
+    result
+}
+
+/// The position of a possibly negative index in a sequence of the given length.
+fn resolve_index(index: isize, len: usize) -> Option<usize> {
+    match index {
+        index if index >= 0 => Some(index as usize),
+        index => len.checked_sub(index.unsigned_abs())
+    }
+}
+
+impl<V> Default for OrderedMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<V> OrderedMap<V> {
     pub fn new() -> Self {
         Self {
-            order: Vec::new(),
-            map: HashMap::new(),
+            entries: Vec::new(),
+            index: None
         }
     }
 
+    /// Create an empty map with room for the given number of entries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::OrderedMap;
+    ///
+    /// let mut map = OrderedMap::with_capacity(2);
+    ///
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    ///
+    /// assert_eq!(map.len(), 2);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut map = Self::new();
+
+        map.reserve(capacity);
+        map
+    }
+
+    /// Reserve room for at least the given number of additional entries.
+    pub fn reserve(&mut self, additional: usize) {
+        self.entries.reserve(additional);
+    }
+
+    /// The position of the given key, or of its last entry when it is repeated.
+    fn position(&self, key: &str) -> Option<usize> {
+        match &self.index {
+            Some(index) => index.get(&self.entries, key),
+            None => self.entries.iter().rposition(|(k, _)| &**k == key)
+        }
+    }
+
+    /// Index the keys once the map is large enough, or drop the index once it is small again.
+    fn reindex(&mut self) {
+        self.index = if self.entries.len() > INDEXED_LEN {
+            Some(Box::new(KeyIndex::build(&self.entries)))
+        } else {
+            None
+        };
+    }
+
     pub fn insert(&mut self, key: &str, value: V) {
-        let key = key.to_string();
+        match self.position(key) {
+            Some(position) => self.entries[position].1 = value,
+            None => self.insert_shared(Arc::from(key), value)
+        }
+    }
 
-        if !self.map.contains_key(&key) {
-            self.order.push(key.clone());
+    /// Insert a value under an already shared key, reusing its allocation.
+    pub(crate) fn insert_shared(&mut self, key: Arc<str>, value: V) {
+        match self.position(&key) {
+            Some(position) => self.entries[position].1 = value,
+            None => self.append_shared(key, value)
+        }
+    }
+
+    /// Add an entry at the end, even if the key is already present, as a document with
+    /// repeated keys is parsed with `ParserOptions::preserve_duplicate_keys`. `get` and
+    /// `insert` then act on the last entry of the key, and `remove` removes them all.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::OrderedMap;
+    ///
+    /// let mut map = OrderedMap::new();
+    ///
+    /// map.append("a", 1);
+    /// map.append("b", 2);
+    /// map.append("a", 3);
+    ///
+    /// assert_eq!(map.len(), 3);
+    /// assert_eq!(map.get("a"), Some(&3));
+    /// assert_eq!(map.get_all("a").collect::<Vec<_>>(), vec![&1, &3]);
+    ///
+    /// assert_eq!(map.remove("a"), Some(3));
+    /// assert_eq!(map.get_all("a").count(), 0);
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    pub fn append(&mut self, key: &str, value: V) {
+        self.append_shared(Arc::from(key), value);
+    }
+
+    /// Add an entry under an already shared key at the end, reusing its allocation.
+    pub(crate) fn append_shared(&mut self, key: Arc<str>, value: V) {
+        self.entries.push((key, value));
+        match &mut self.index {
+            Some(index) if !index.is_full(self.entries.len()) => {
+                let position = self.entries.len() - 1;
+
+                index.insert(&self.entries, &self.entries[position].0, position);
+            },
+            _ if self.entries.len() > INDEXED_LEN => self.reindex(),
+            _ => {}
         }
-        self.map.insert(key, value);
     }
 
     pub fn get(&self, key: &str) -> Option<&V> {
-        self.map.get(key)
+        self.position(key).map(|position| &self.entries[position].1)
+    }
+
+    /// Returns the values of every entry of the given key, in order. A key is only
+    /// repeated when added with `append`.
+    pub fn get_all<'m>(&'m self, key: &'m str) -> impl Iterator<Item = &'m V> + 'm {
+        self.entries.iter().filter(move |(k, _)| &**k == key).map(|(_, value)| value)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut V> {
+        let position = self.position(key)?;
+
+        Some(&mut self.entries[position].1)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.position(key).is_some()
+    }
+
+    /// Remove a key from the map, preserving the order of the remaining keys. Every entry
+    /// of a repeated key is removed, and the last value is returned.
+    pub fn remove(&mut self, key: &str) -> Option<V> {
+        let position = self.position(key)?;
+        let (_, value) = self.entries.remove(position);
+
+        if self.entries[..position].iter().any(|(k, _)| &**k == key) {
+            self.entries.retain(|(k, _)| &**k != key);
+        }
+        // Removing shifts the following positions, so the index is rebuilt.
+        if self.index.is_some() {
+            self.reindex();
+        }
+        Some(value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the entry at the given position.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::OrderedMap;
+    ///
+    /// let mut map = OrderedMap::new();
+    ///
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    ///
+    /// assert_eq!(map.get_index(1), Some(("b", &2)));
+    /// assert_eq!(map.get_index(2), None);
+    /// ```
+    pub fn get_index(&self, index: usize) -> Option<(&str, &V)> {
+        self.entries.get(index).map(|(k, v)| (&**k, v))
+    }
+
+    /// Returns the first entry.
+    pub fn first(&self) -> Option<(&str, &V)> {
+        self.get_index(0)
+    }
+
+    /// Returns the last entry.
+    pub fn last(&self) -> Option<(&str, &V)> {
+        self.get_index(self.entries.len().checked_sub(1)?)
+    }
+
+    /// Returns the position, key and value of the given key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::OrderedMap;
+    ///
+    /// let mut map = OrderedMap::new();
+    ///
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    ///
+    /// assert_eq!(map.get_full("b"), Some((1, "b", &2)));
+    /// assert_eq!(map.first(), Some(("a", &1)));
+    /// assert_eq!(map.last(), Some(("b", &2)));
+    /// ```
+    pub fn get_full(&self, key: &str) -> Option<(usize, &str, &V)> {
+        let index = self.position(key)?;
+        let (key, value) = &self.entries[index];
+
+        Some((index, key, value))
+    }
+
+    /// Returns an iterator over the entries, in insertion order. Iterating over `&map`
+    /// does the same, while iterating over `map` consumes it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::OrderedMap;
+    ///
+    /// let mut map = OrderedMap::new();
+    ///
+    /// map.insert("b", 1);
+    /// map.insert("a", 2);
+    ///
+    /// assert_eq!(map.iter().collect::<Vec<_>>(), vec![("b", &1), ("a", &2)]);
+    ///
+    /// for (key, value) in &map {
+    ///     assert_eq!(map.get(key), Some(value));
+    /// }
+    ///
+    /// let entries: Vec<(String, i32)> = map.into_iter().collect();
+    ///
+    /// assert_eq!(entries, vec![("b".to_string(), 1), ("a".to_string(), 2)]);
+    /// ```
+    pub fn iter(&self) -> OrderedMapIter<'_, V> {
+        OrderedMapIter { entries: self.entries.iter() }
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&str, &mut V)> {
+        self.entries.iter_mut().map(|(k, v)| (&**k, v))
+    }
+
+    /// Sort the keys in ascending order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::OrderedMap;
+    ///
+    /// let mut map = OrderedMap::new();
+    ///
+    /// map.insert("b", 1);
+    /// map.insert("a", 2);
+    /// map.sort_keys();
+    ///
+    /// assert_eq!(map.iter().map(|(k, _)| k).collect::<Vec<_>>(), vec!["a", "b"]);
+    /// ```
+    pub fn sort_keys(&mut self) {
+        self.entries.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
+        self.reindex();
+    }
+
+    /// Sort the entries with a comparator receiving both keys and values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::OrderedMap;
+    ///
+    /// let mut map = OrderedMap::new();
+    ///
+    /// map.insert("a", 2);
+    /// map.insert("b", 1);
+    /// map.sort_by(|_, v1, _, v2| v1.cmp(v2));
+    ///
+    /// assert_eq!(map.iter().map(|(k, _)| k).collect::<Vec<_>>(), vec!["b", "a"]);
+    /// ```
+    pub fn sort_by<F: FnMut(&str, &V, &str, &V) -> core::cmp::Ordering>(&mut self, mut compare: F) {
+        self.entries.sort_by(|(k1, v1), (k2, v2)| compare(k1, v1, k2, v2));
+        self.reindex();
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = (&String, &V)> {
-        self.order.iter().map(move |k| (k, &self.map[k]))
+    /// Move a key to the given position, shifting the following keys.
+    /// The position is clamped to the last index. Returns false if the key is missing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::OrderedMap;
+    ///
+    /// let mut map = OrderedMap::new();
+    ///
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    /// map.insert("id", 3);
+    ///
+    /// assert!(map.move_key_to("id", 0));
+    /// assert_eq!(map.iter().map(|(k, _)| k).collect::<Vec<_>>(), vec!["id", "a", "b"]);
+    /// ```
+    pub fn move_key_to(&mut self, key: &str, index: usize) -> bool {
+        match self.position(key) {
+            Some(position) => {
+                let entry = self.entries.remove(position);
+
+                self.entries.insert(index.min(self.entries.len()), entry);
+                self.reindex();
+                true
+            },
+            None => false
+        }
     }
 }
 
-#[derive(Clone)]
+/// An iterator over the entries of an `OrderedMap`, in insertion order.
+pub struct OrderedMapIter<'a, V> {
+    entries: core::slice::Iter<'a, (Arc<str>, V)>
+}
+
+impl<'a, V> Iterator for OrderedMapIter<'a, V> {
+    type Item = (&'a str, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next().map(|(key, value)| (&**key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<V> ExactSizeIterator for OrderedMapIter<'_, V> {}
+
+/// An iterator moving the entries out of an `OrderedMap`, in insertion order.
+pub struct OrderedMapIntoIter<V> {
+    entries: alloc::vec::IntoIter<(Arc<str>, V)>
+}
+
+impl<V> Iterator for OrderedMapIntoIter<V> {
+    type Item = (String, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next().map(|(key, value)| (key.to_string(), value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<V> ExactSizeIterator for OrderedMapIntoIter<V> {}
+
+impl<'a, V> IntoIterator for &'a OrderedMap<V> {
+    type Item = (&'a str, &'a V);
+    type IntoIter = OrderedMapIter<'a, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Insert the entries in order, replacing the value of a key already present but keeping
+/// its position, as `insert` does.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::OrderedMap;
+///
+/// let mut map: OrderedMap<i32> = vec![("a".to_string(), 1), ("b".to_string(), 2)].into_iter().collect();
+///
+/// map.extend(vec![("c".to_string(), 3), ("a".to_string(), 4)]);
+///
+/// assert_eq!(map.iter().collect::<Vec<_>>(), vec![("a", &4), ("b", &2), ("c", &3)]);
+/// ```
+impl<V> Extend<(String, V)> for OrderedMap<V> {
+    fn extend<I: IntoIterator<Item = (String, V)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+
+        self.reserve(iter.size_hint().0);
+        for (key, value) in iter {
+            self.insert(&key, value);
+        }
+    }
+}
+
+impl<V> FromIterator<(String, V)> for OrderedMap<V> {
+    fn from_iter<I: IntoIterator<Item = (String, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+
+        map.extend(iter);
+        map
+    }
+}
+
+impl<V> IntoIterator for OrderedMap<V> {
+    type Item = (String, V);
+    type IntoIter = OrderedMapIntoIter<V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        OrderedMapIntoIter { entries: self.entries.into_iter() }
+    }
+}
+
+#[derive(Clone, PartialEq)]
 pub enum JSONValue {
     Object(OrderedMap<JSONValue>),
-    Array(Vec<JSONValue>),
-    String(String),
+    /// Boxed without spare capacity. `push` and the other array methods of `JSONValue`
+    /// change it in place, and `into_array` returns a `Vec` without copying.
+    Array(Box<[JSONValue]>),
+    /// Short strings are stored inline, see `JSONString`.
+    String(JSONString),
     Number(f64),
     Boolean(bool),
+    Null,
+    /// Valid JSON text kept as is, such as a pre-serialized blob or a subdocument
+    /// captured with `JSONParser::raw_at`. The serializer emits it verbatim, and
+    /// conversions to other formats parse it.
+    Raw(RawValue)
+}
+
+/// The type of a JSONValue, without its content.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum JSONKind {
+    Object,
+    Array,
+    String,
+    Number,
+    Boolean,
     Null
 }
 
+impl JSONKind {
+    /// Returns the JSON name of the type.
+    pub fn name(&self) -> &'static str {
+        match self {
+            JSONKind::Object => "object",
+            JSONKind::Array => "array",
+            JSONKind::String => "string",
+            JSONKind::Number => "number",
+            JSONKind::Boolean => "boolean",
+            JSONKind::Null => "null"
+        }
+    }
+}
+
+impl fmt::Display for JSONKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
 impl fmt::Debug for JSONValue {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -101,35 +663,183 @@ impl fmt::Debug for JSONValue {
             JSONValue::Number(value) => write!(f, "{:?}", value),
             JSONValue::Boolean(value) => write!(f, "{:?}", value),
             JSONValue::Null => write!(f, "null"),
+            JSONValue::Raw(raw) => write!(f, "{}", raw),
         }
     }
 }
 
+/// Objects and arrays are written as compact JSON, and other values as their content,
+/// so that a string is written without quotes. The alternate form, `{:#}`, writes
+/// `JSONValue::preview` of 80 characters instead.
 impl fmt::Display for JSONValue {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return write!(f, "{}", self.preview(PREVIEW_LENGTH));
+        }
         match self {
-            JSONValue::Object(obj) => write!(f, "{:?}", obj),
-            JSONValue::Array(array) => write!(f, "{:?}", array),
+            JSONValue::Object(obj) => write!(f, "{}", obj),
+            JSONValue::Array(_) => write!(f, "{}", self.serialize()),
             JSONValue::String(value) => write!(f, "{}", value),
             JSONValue::Number(value) => write!(f, "{}", value),
             JSONValue::Boolean(value) => write!(f, "{}", value),
             JSONValue::Null => write!(f, "null"),
+            JSONValue::Raw(raw) => write!(f, "{}", raw),
         }
     }
 }
 
 impl JSONValue {
+    /// Returns the value of an object at the given key, or the item of an array at the
+    /// given index. A negative index counts from the end of the array.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "logs": ["start", "run", "stop"] }"#).unwrap();
+    /// let logs = json.get("logs").unwrap();
+    ///
+    /// assert_eq!(logs.get(0).and_then(|v| v.as_str()), Some("start"));
+    /// assert_eq!(logs.get(-1).and_then(|v| v.as_str()), Some("stop"));
+    /// assert!(logs.get(-4).is_none());
+    /// ```
     pub fn get<A: JSONAccess>(&self, accessor: A) -> Option<&JSONValue> {
         accessor.get(self)
     }
 
+    /// Returns the type of the value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONKind, JSONValue};
+    ///
+    /// assert_eq!(JSONValue::Number(42.0).kind(), JSONKind::Number);
+    /// ```
+    pub fn kind(&self) -> JSONKind {
+        match self {
+            JSONValue::Object(_) => JSONKind::Object,
+            JSONValue::Array(_) => JSONKind::Array,
+            JSONValue::String(_) => JSONKind::String,
+            JSONValue::Number(_) => JSONKind::Number,
+            JSONValue::Boolean(_) => JSONKind::Boolean,
+            JSONValue::Null => JSONKind::Null,
+            JSONValue::Raw(raw) => raw.kind()
+        }
+    }
+
+    /// Create a raw value holding the given JSON text, after checking that it is valid.
+    /// The text is kept as is, so it is not parsed again when the value is serialized.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONKind, JSONParser, JSONValue, Serialize};
+    ///
+    /// let cached = JSONValue::raw(r#"{ "model": "Ford", "year": 1998 }"#).unwrap();
+    /// let mut json = JSONParser::from(r#"{ "id": 1 }"#).unwrap();
+    ///
+    /// assert_eq!(cached.kind(), JSONKind::Object);
+    ///
+    /// json.as_object_mut().unwrap().insert("car", cached);
+    ///
+    /// assert_eq!(json.serialize(), r#"{"id":1,"car":{ "model": "Ford", "year": 1998 }}"#);
+    /// assert!(JSONValue::raw("{ \"model\": }").is_err());
+    /// ```
+    pub fn raw(text: &str) -> Result<JSONValue, ParseError> {
+        RawValue::new(text).map(JSONValue::Raw)
+    }
+
+    /// Returns the JSON text of a raw value.
+    pub fn as_raw(&self) -> Option<&str> {
+        match self {
+            JSONValue::Raw(raw) => Some(raw.as_str()),
+            _ => None
+        }
+    }
+
+    /// Parse the text of a raw value, to read a deferred subdocument. Other values are cloned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONValue;
+    ///
+    /// let raw = JSONValue::raw("[1, 2]").unwrap();
+    ///
+    /// assert_eq!(raw.parse_raw()[1].as_f64(), Some(2.0));
+    /// ```
+    pub fn parse_raw(&self) -> JSONValue {
+        match self {
+            JSONValue::Raw(raw) => raw.to_value(),
+            value => value.clone()
+        }
+    }
+
+    /// Returns the JSON name of the type of the value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONValue;
+    ///
+    /// assert_eq!(JSONValue::Boolean(true).type_name(), "boolean");
+    /// ```
+    pub fn type_name(&self) -> &'static str {
+        self.kind().name()
+    }
+
+    /// Returns the number of keys of an object, items of an array, or characters of a string.
+    /// Returns 0 for numbers, booleans and null.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "name": "Zoë", "ids": [1, 2], "age": 30 }"#).unwrap();
+    ///
+    /// assert_eq!(json.len(), 3);
+    /// assert_eq!(json["name"].len(), 3);
+    /// assert_eq!(json["ids"].len(), 2);
+    /// assert_eq!(json["age"].len(), 0);
+    /// ```
+    pub fn len(&self) -> usize {
+        match self {
+            JSONValue::Object(obj) => obj.len(),
+            JSONValue::Array(array) => array.len(),
+            JSONValue::String(s) => s.chars().count(),
+            _ => 0,
+        }
+    }
+
+    /// Returns true if `len()` is 0, which includes numbers, booleans and null.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "ids": [], "name": "" }"#).unwrap();
+    ///
+    /// assert!(json["ids"].is_empty());
+    /// assert!(json["name"].is_empty());
+    /// assert!(!json.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     /// Returns the value as a string if it is a string.
     /// Returns None otherwise.
     ///
     /// # Example
     ///
-    /// ```no_run
-    /// let value = JSONValue::String("Hello, world!".to_string());
+    /// ```
+    /// use jsonparser::JSONValue;
+    ///
+    /// let value = JSONValue::String("Hello, world!".into());
     ///
     /// assert_eq!(value.as_str(), Some("Hello, world!"));
     /// ```
@@ -145,91 +855,390 @@ impl JSONValue {
     ///
     /// # Example
     ///
-    /// ```no_run
-    /// let value = JSONValue::Number(42.0);
+    /// ```
+    /// use jsonparser::JSONValue;
+    ///
+    /// let value = JSONValue::Number(42.0);
+    ///
+    /// assert_eq!(value.as_f64(), Some(42.0));
+    /// ```
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            JSONValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a boolean if it is a boolean.
+    /// Returns None otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONValue;
+    ///
+    /// let value = JSONValue::Boolean(true);
+    ///
+    /// assert_eq!(value.as_bool(), Some(true));
+    /// ```
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JSONValue::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an array if it is an array.
+    /// Returns None otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONValue;
+    ///
+    /// let value = JSONValue::Array(vec![JSONValue::Number(1.0), JSONValue::Number(2.0)].into());
+    ///
+    /// assert_eq!(value.as_array(), Some(&[JSONValue::Number(1.0), JSONValue::Number(2.0)][..]));
+    /// ```
+    pub fn as_array(&self) -> Option<&[JSONValue]> {
+        match self {
+            JSONValue::Array(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    /// Returns the items of an array in the given range, like a Python slice: negative
+    /// bounds count from the end of the array, and bounds past either end are clamped.
+    /// Returns None if the value is not an array.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONValue};
+    ///
+    /// let json = JSONParser::from(r#"{ "ids": [1, 2, 3, 4] }"#).unwrap();
+    /// let ids = |slice: &[JSONValue]| slice.iter().filter_map(|v| v.as_f64()).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(json["ids"].slice(1..3).map(ids), Some(vec![2.0, 3.0]));
+    /// assert_eq!(json["ids"].slice(-2..).map(ids), Some(vec![3.0, 4.0]));
+    /// assert_eq!(json["ids"].slice(..-3).map(ids), Some(vec![1.0]));
+    /// assert_eq!(json["ids"].slice(2..10).map(ids), Some(vec![3.0, 4.0]));
+    /// assert_eq!(json["ids"].slice(3..1).map(ids), Some(vec![]));
+    /// assert!(json.slice(..).is_none());
+    /// ```
+    pub fn slice<R: RangeBounds<isize>>(&self, range: R) -> Option<&[JSONValue]> {
+        let array = self.as_array()?;
+        let len = array.len();
+        let clamp = |index: isize| resolve_index(index, len).unwrap_or(0).min(len);
+        let start = match range.start_bound() {
+            Bound::Included(start) => clamp(*start),
+            Bound::Excluded(start) => clamp(*start).saturating_add(1).min(len),
+            Bound::Unbounded => 0
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => clamp(*end).saturating_add(1).min(len),
+            Bound::Excluded(end) => clamp(*end),
+            Bound::Unbounded => len
+        };
+
+        Some(&array[start..end.max(start)])
+    }
+
+    /// Returns the value as an object if it is an object.
+    /// Returns None otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONValue, OrderedMap};
+    ///
+    /// let mut object = OrderedMap::new();
+    ///
+    /// object.insert("name", JSONValue::String("John Doe".into()));
+    /// object.insert("age", JSONValue::Number(30.0));
+    /// let value = JSONValue::Object(object.clone());
+    ///
+    /// assert_eq!(value.as_object(), Some(&object));
+    /// ```
+    pub fn as_object(&self) -> Option<&OrderedMap<JSONValue>> {
+        match self {
+            JSONValue::Object(o) => Some(o),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an object if it is an object.
+    /// Returns None otherwise.
+    /// This method allows modifying the object.
+    ///
+    /// # Example
+    /// ```
+    /// use jsonparser::{JSONValue, OrderedMap};
+    ///
+    /// let mut object = OrderedMap::new();
+    ///
+    /// object.insert("name", JSONValue::String("John Doe".into()));
+    /// object.insert("age", JSONValue::Number(30.0));
+    /// let mut value = JSONValue::Object(object);
+    ///
+    /// value.as_object_mut().unwrap().insert("isStudent", JSONValue::Boolean(false));
+    /// ```
+    pub fn as_object_mut(&mut self) -> Option<&mut OrderedMap<JSONValue>> {
+        match self {
+            JSONValue::Object(o) => Some(o),
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over the key-value pairs of an object, in insertion order.
+    /// Returns an empty iterator for any other value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "name": "John Doe", "age": 30 }"#).unwrap();
+    /// let keys: Vec<&str> = json.entries().map(|(key, _)| key).collect();
+    ///
+    /// assert_eq!(keys, vec!["name", "age"]);
+    /// assert_eq!(json["name"].entries().count(), 0);
+    /// ```
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &JSONValue)> {
+        self.as_object().into_iter().flat_map(|obj| obj.iter())
+    }
+
+    /// Returns an iterator over the keys of an object, in insertion order.
+    /// Returns an empty iterator for any other value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "name": "John Doe", "age": 30 }"#).unwrap();
+    ///
+    /// assert_eq!(json.keys().collect::<Vec<_>>(), vec!["name", "age"]);
+    /// ```
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over the values of an object, in insertion order.
+    /// Returns an empty iterator for any other value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "name": "John Doe", "age": 30 }"#).unwrap();
+    ///
+    /// assert_eq!(json.values().filter_map(|value| value.as_f64()).sum::<f64>(), 30.0);
+    /// ```
+    pub fn values(&self) -> impl Iterator<Item = &JSONValue> {
+        self.entries().map(|(_, value)| value)
+    }
+
+    /// Returns an iterator over the items of an array.
+    /// Returns an empty iterator for any other value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "ids": [1, 2, 3] }"#).unwrap();
+    ///
+    /// assert_eq!(json["ids"].members().count(), 3);
+    /// assert_eq!(json.members().count(), 0);
+    /// ```
+    pub fn members(&self) -> impl Iterator<Item = &JSONValue> {
+        self.as_array().into_iter().flat_map(|array| array.iter())
+    }
+
+    /// Consumes the value and returns the string if it is a string.
+    /// Returns None otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONValue;
+    ///
+    /// let value = JSONValue::String("Hello, world!".into());
+    ///
+    /// assert_eq!(value.into_string(), Some("Hello, world!".to_string()));
+    /// ```
+    pub fn into_string(self) -> Option<String> {
+        match self {
+            JSONValue::String(s) => Some(s.into_string()),
+            _ => None,
+        }
+    }
+
+    /// Consumes the value and returns the array if it is an array.
+    /// Returns None otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONValue;
+    ///
+    /// let value = JSONValue::Array(vec![JSONValue::Number(1.0)].into());
+    ///
+    /// assert_eq!(value.into_array(), Some(vec![JSONValue::Number(1.0)]));
+    /// ```
+    pub fn into_array(self) -> Option<Vec<JSONValue>> {
+        match self {
+            JSONValue::Array(a) => Some(a.into_vec()),
+            _ => None,
+        }
+    }
+
+    /// Consumes the value and returns the object if it is an object.
+    /// Returns None otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "name": "John Doe" }"#).unwrap();
+    /// let object = json.into_object().unwrap();
+    ///
+    /// assert_eq!(object.get("name").and_then(|v| v.as_str()), Some("John Doe"));
+    /// ```
+    pub fn into_object(self) -> Option<OrderedMap<JSONValue>> {
+        match self {
+            JSONValue::Object(o) => Some(o),
+            _ => None,
+        }
+    }
+
+    /// Takes the value out, leaving null in its place.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let mut json = JSONParser::from(r#"{ "cars": ["Ford", "BMW"] }"#).unwrap();
+    /// let cars = json.pointer_mut("/cars").unwrap().take();
+    ///
+    /// assert_eq!(cars.into_array().map(|a| a.len()), Some(2));
+    /// assert!(json["cars"].is_null());
+    /// ```
+    pub fn take(&mut self) -> JSONValue {
+        core::mem::replace(self, JSONValue::Null)
+    }
+
+    /// Returns true if the value is an object.
+    /// Returns false otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONValue, OrderedMap};
+    ///
+    /// let value = JSONValue::Object(OrderedMap::new());
+    ///
+    /// assert_eq!(value.is_object(), true);
+    /// ```
+    pub fn is_object(&self) -> bool {
+        matches!(self, JSONValue::Object(_))
+    }
+
+    /// Returns true if the value is an array.
+    /// Returns false otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONValue;
     ///
-    /// assert_eq!(value.as_f64(), Some(42.0));
+    /// let value = JSONValue::Array(Box::new([]));
+    ///
+    /// assert_eq!(value.is_array(), true);
     /// ```
-    pub fn as_f64(&self) -> Option<f64> {
-        match self {
-            JSONValue::Number(n) => Some(*n),
-            _ => None,
-        }
+    pub fn is_array(&self) -> bool {
+        matches!(self, JSONValue::Array(_))
     }
 
-    /// Returns the value as a boolean if it is a boolean.
-    /// Returns None otherwise.
+    /// Returns true if the value is a string.
+    /// Returns false otherwise.
     ///
     /// # Example
     ///
-    /// ```no_run
-    /// let value = JSONValue::Boolean(true);
+    /// ```
+    /// use jsonparser::JSONValue;
     ///
-    /// assert_eq!(value.as_bool(), Some(true));
+    /// let value = JSONValue::String("Hello, world!".into());
+    ///
+    /// assert_eq!(value.is_string(), true);
     /// ```
-    pub fn as_bool(&self) -> Option<bool> {
-        match self {
-            JSONValue::Boolean(b) => Some(*b),
-            _ => None,
-        }
+    pub fn is_string(&self) -> bool {
+        matches!(self, JSONValue::String(_))
     }
 
-    /// Returns the value as an array if it is an array.
-    /// Returns None otherwise.
+    /// Returns true if the value is a number.
+    /// Returns false otherwise.
     ///
     /// # Example
     ///
-    /// ```no_run
-    /// let value = JSONValue::Array(vec![JSONValue::Number(1.0), JSONValue::Number(2.0)]);
+    /// ```
+    /// use jsonparser::JSONValue;
+    ///
+    /// let value = JSONValue::Number(42.0);
     ///
-    /// assert_eq!(value.as_array(), Some(&vec![JSONValue::Number(1.0), JSONValue::Number(2.0)]));
+    /// assert_eq!(value.is_number(), true);
     /// ```
-    pub fn as_array(&self) -> Option<&Vec<JSONValue>> {
-        match self {
-            JSONValue::Array(a) => Some(a),
-            _ => None,
-        }
+    pub fn is_number(&self) -> bool {
+        matches!(self, JSONValue::Number(_))
     }
 
-    /// Returns the value as an object if it is an object.
-    /// Returns None otherwise.
+    /// Returns true if the value is a boolean.
+    /// Returns false otherwise.
     ///
     /// # Example
     ///
-    /// ```no_run
-    /// let mut object = OrderedMap::new();
+    /// ```
+    /// use jsonparser::JSONValue;
     ///
-    /// object.insert("name", JSONValue::String("John Doe".to_string()));
-    /// object.insert("age", JSONValue::Number(30.0));
-    /// let value = JSONValue::Object(object);
+    /// let value = JSONValue::Boolean(true);
     ///
-    /// assert_eq!(value.as_object(), Some(&object));
+    /// assert_eq!(value.is_bool(), true);
     /// ```
-    pub fn as_object(&self) -> Option<&OrderedMap<JSONValue>> {
-        match self {
-            JSONValue::Object(o) => Some(o),
-            _ => None,
-        }
+    pub fn is_bool(&self) -> bool {
+        matches!(self, JSONValue::Boolean(_))
     }
 
-    /// Returns the value as an object if it is an object.
-    /// Returns None otherwise.
-    /// This method allows modifying the object.
+    /// Sort the keys of every object in the tree in ascending order.
     ///
     /// # Example
-    /// ```no_run
-    /// let mut object = OrderedMap::new();
     ///
-    /// object.insert("name", JSONValue::String("John Doe".to_string()));
-    /// object.insert("age", JSONValue::Number(30.0));
-    /// let mut value = JSONValue::Object(object);
+    /// ```
+    /// use jsonparser::{JSONParser, Serialize};
     ///
-    /// value.as_object_mut().unwrap().insert("isStudent", JSONValue::Boolean(false));
+    /// let mut json = JSONParser::from(r#"{ "b": { "d": 1, "c": 2 }, "a": [{ "f": 3, "e": 4 }] }"#).unwrap();
+    ///
+    /// json.sort_all_keys();
+    ///
+    /// assert_eq!(json.serialize(), r#"{"a":[{"e":4,"f":3}],"b":{"c":2,"d":1}}"#);
     /// ```
-    pub fn as_object_mut(&mut self) -> Option<&mut OrderedMap<JSONValue>> {
+    pub fn sort_all_keys(&mut self) {
         match self {
-            JSONValue::Object(o) => Some(o),
-            _ => None,
+            JSONValue::Object(obj) => {
+                obj.sort_keys();
+                for (_, value) in obj.iter_mut() {
+                    value.sort_all_keys();
+                }
+            },
+            JSONValue::Array(array) => {
+                for value in array.iter_mut() {
+                    value.sort_all_keys();
+                }
+            },
+            _ => {}
         }
     }
 
@@ -238,20 +1247,19 @@ impl JSONValue {
     ///
     /// # Example
     ///
-    /// ```no_run
+    /// ```
+    /// use jsonparser::JSONValue;
+    ///
     /// let value = JSONValue::Null;
     ///
     /// assert_eq!(value.is_null(), true);
     /// ```
     pub fn is_null(&self) -> bool {
-        match self {
-            JSONValue::Null => true,
-            _ => false,
-        }
+        matches!(self, JSONValue::Null)
     }
 }
 
-impl<'a> Index<usize> for JSONValue {
+impl Index<usize> for JSONValue {
     type Output = JSONValue;
 
     fn index(&self, index: usize) -> &Self::Output {
@@ -275,6 +1283,15 @@ impl<'a> Index<&'a str> for JSONValue {
 
 pub trait Serialize {
     fn serialize(&self) -> String;
+
+    /// Convert the value to a JSONValue.
+    /// The default implementation parses the output of `serialize`, returning Null if it is not valid JSON.
+    fn to_value(&self) -> JSONValue {
+        let text = self.serialize();
+        let mut parser = Parser::new(Lexer::new(&text));
+
+        parser.parse_document().unwrap_or(JSONValue::Null)
+    }
 }
 
 impl Serialize for JSONValue {
@@ -282,146 +1299,754 @@ impl Serialize for JSONValue {
     ///
     /// # Example
     ///
-    /// ```no_run
-    /// let value = JSONValue::Object(OrderedMap::new());
+    /// ```
+    /// use jsonparser::{JSONValue, OrderedMap, Serialize};
+    ///
+    /// let mut object = OrderedMap::new();
     ///
-    /// value.insert("name", JSONValue::String("John Doe".to_string()));
-    /// value.insert("age", JSONValue::Number(30.0));
+    /// object.insert("name", JSONValue::String("John Doe".into()));
+    /// object.insert("age", JSONValue::Number(30.0));
+    /// let value = JSONValue::Object(object);
     ///
-    /// assert_eq!(serialize(&value), r#"{"name":"John Doe","age":30}"#);
+    /// assert_eq!(value.serialize(), r#"{"name":"John Doe","age":30}"#);
     /// ```
     fn serialize(&self) -> String {
-        match self {
-            JSONValue::String(s) => format!("\"{}\"", s),
-            JSONValue::Number(n) => n.to_string(),
-            JSONValue::Boolean(b) => b.to_string(),
-            JSONValue::Null => "null".to_string(),
-            JSONValue::Array(arr) => {
-                let parts = arr.iter().map(|value| value.serialize()).collect::<Vec<String>>();
-
-                format!("[{}]", parts.join(","))
-            },
-            JSONValue::Object(obj) => {
-                let parts = obj.order.iter().map(|key| {
-                    let value = obj.get(key).unwrap();
+        self.serialize_with(SerializeOptions::new())
+    }
+
+    fn to_value(&self) -> JSONValue {
+        self.clone()
+    }
+}
 
-                    format!("\"{}\":{}", key, value.serialize())
-                }).collect::<Vec<String>>();
+pub(crate) fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
 
-                format!("{{{}}}", parts.join(","))
-            }
-        }
+    escaped.push('"');
+    for c in value.chars() {
+        escape_char(c, &mut escaped);
     }
+    escaped.push('"');
+    escaped
 }
 
+/// Append the character to the string, escaped as required inside a JSON string.
+pub(crate) fn escape_char(c: char, escaped: &mut String) {
+    match c {
+        '"' => escaped.push_str("\\\""),
+        '\\' => escaped.push_str("\\\\"),
+        '\n' => escaped.push_str("\\n"),
+        '\r' => escaped.push_str("\\r"),
+        '\t' => escaped.push_str("\\t"),
+        '\u{08}' => escaped.push_str("\\b"),
+        '\u{0C}' => escaped.push_str("\\f"),
+        c if c < '\u{20}' => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+        c => escaped.push(c)
+    }
+}
+
+/// Number of bytes read between two progress reports and cancellation checks.
+const PROGRESS_INTERVAL: usize = 64 * 1024;
+
+type ProgressCallback<'a> = Box<dyn FnMut(usize, Option<usize>) + 'a>;
+
 pub struct Parser<'a> {
     lexer: Lexer<'a>,
-    current_token: Option<Token>
+    current_token: Option<Token>,
+    options: ParserOptions,
+    nodes: usize,
+    /// Number of arrays and objects enclosing the current token.
+    depth: usize,
+    keys: Set<Arc<str>>,
+    progress: Option<ProgressCallback<'a>>,
+    cancellation: Option<CancellationToken>,
+    /// Offset from which progress is reported and cancellation checked again.
+    next_check: usize,
+    /// Pointers of the values kept as raw JSON text.
+    raw_pointers: Vec<String>,
+    /// Pointer of the value being parsed, only tracked when `raw_pointers` is not empty.
+    path: String,
+    /// Expected number of entries of the next object, taken from the previous item of
+    /// the array being parsed, as arrays of records tend to hold objects of one shape.
+    object_capacity: usize,
+    /// Items of the arrays being parsed, moved to a Vec of the exact length once an array
+    /// ends, so that arrays are not grown item by item.
+    values: Vec<JSONValue>
+}
+
+/// The allocations a parser hands over to the next one: the interned keys, the scratch
+/// buffer of the lexer and the stack of array items.
+#[derive(Debug, Default)]
+pub(crate) struct ParserBuffers {
+    pub(crate) keys: Set<Arc<str>>,
+    pub(crate) scratch: String,
+    pub(crate) values: Vec<JSONValue>
 }
 
 impl<'a> Parser<'a> {
     pub fn new(lexer: Lexer<'a>) -> Self {
-        Self { lexer, current_token: None }
+        Self::with_options(lexer, ParserOptions::new())
+    }
+
+    pub fn with_options(lexer: Lexer<'a>, options: ParserOptions) -> Self {
+        let lexer = if options.allow_control_characters {
+            lexer.allow_control_characters()
+        } else {
+            lexer
+        };
+
+        Self {
+            lexer,
+            current_token: None,
+            options,
+            nodes: 0,
+            depth: 0,
+            keys: Set::new(),
+            progress: None,
+            cancellation: None,
+            next_check: 0,
+            raw_pointers: Vec::new(),
+            path: String::new(),
+            object_capacity: 0,
+            values: Vec::new()
+        }
     }
 
-    pub fn parse(&mut self) -> Result<JSONValue, String> {
-        self.next_token();
+    /// Reuse the buffers of an earlier parse, taken back with `into_buffers`.
+    pub(crate) fn with_buffers(mut self, buffers: ParserBuffers) -> Self {
+        self.lexer = self.lexer.with_scratch(buffers.scratch);
+        self.keys = buffers.keys;
+        self.values = buffers.values;
+        self
+    }
+
+    pub(crate) fn into_buffers(mut self) -> ParserBuffers {
+        // Items left by a failed parse are dropped, keeping the capacity.
+        self.values.clear();
+        ParserBuffers { keys: self.keys, scratch: self.lexer.into_scratch(), values: self.values }
+    }
+
+    /// Accept unescaped control characters inside strings.
+    pub fn allow_control_characters(mut self) -> Self {
+        self.options.allow_control_characters = true;
+        self.lexer = self.lexer.allow_control_characters();
+        self
+    }
+
+    /// Call the callback with the number of bytes consumed and the size of the input, when
+    /// known, every 64 KiB and once the input is consumed.
+    pub fn on_progress<F: FnMut(usize, Option<usize>) + 'a>(mut self, callback: F) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Fail with `ParseError::Cancelled` once the token is cancelled, checked every 64 KiB.
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Keep the value at the given JSON Pointer as `JSONValue::Raw` when parsing with `parse`.
+    pub fn raw_at(mut self, pointer: &str) -> Self {
+        self.raw_pointers.push(pointer.to_string());
+        self
+    }
+
+    /// Parse the document. With the `tracing` feature, the parse runs in a `parse` span
+    /// recording the size of the input, and ends with an event recording the bytes read
+    /// and the number of nodes, or the error.
+    pub fn parse(&mut self) -> Result<JSONValue, ParseError> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("parse", size = self.lexer.size()).entered();
+        let result = self.parse_root();
+
+        #[cfg(feature = "tracing")]
+        match &result {
+            Ok(_) => tracing::debug!(bytes = self.lexer.offset(), nodes = self.nodes, "parsed document"),
+            Err(e) => tracing::debug!(bytes = self.lexer.offset(), error = %e, "failed to parse document")
+        }
+        result
+    }
+
+    fn parse_root(&mut self) -> Result<JSONValue, ParseError> {
+        for pointer in self.raw_pointers.iter() {
+            parse_pointer(pointer).map_err(ParseError::Syntax)?;
+        }
+        if let Some(size) = self.lexer.size() {
+            check_limit(Limit::DocumentSize, self.options.max_document_size, size)?;
+        }
+        self.next_token()?;
+        self.count_node()?;
         self.parse_object()
     }
 
-    fn next_token(&mut self) {
-        self.current_token = self.lexer.next_token();
+    /// Parse a document whose root can be any value.
+    pub(crate) fn parse_document(&mut self) -> Result<JSONValue, ParseError> {
+        self.next_token()?;
+        let value = self.parse_value()?;
+
+        match self.current_token {
+            Some(_) => Err(self.unexpected()),
+            None => Ok(value)
+        }
+    }
+
+    pub(crate) fn next_token(&mut self) -> Result<(), ParseError> {
+        self.current_token = self.lexer.next_token()?;
+        check_limit(Limit::DocumentSize, self.options.max_document_size, self.lexer.offset())?;
+        self.check_progress()
+    }
+
+    /// Report progress and check for cancellation, when enough input was read since the last time.
+    fn check_progress(&mut self) -> Result<(), ParseError> {
+        let offset = self.lexer.offset();
+
+        if offset < self.next_check && self.current_token.is_some() {
+            return Ok(());
+        }
+        self.next_check = offset + PROGRESS_INTERVAL;
+        if self.cancellation.as_ref().map_or(false, |token| token.is_cancelled()) {
+            return Err(ParseError::Cancelled);
+        }
+        if let Some(progress) = &mut self.progress {
+            progress(offset, self.lexer.size());
+        }
+        Ok(())
+    }
+
+    /// Enter an array or an object, failing when it is nested too deep. Every container is
+    /// walked by `parse_entries` or `parse_items`, which leave it again.
+    fn enter(&mut self) -> Result<(), ParseError> {
+        if self.depth >= self.options.max_depth {
+            return Err(ParseError::LimitExceeded { limit: Limit::Depth, max: self.options.max_depth });
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    pub(crate) fn count_node(&mut self) -> Result<(), ParseError> {
+        self.nodes += 1;
+        check_limit(Limit::Nodes, self.options.max_nodes, self.nodes)
     }
 
-    fn parse_value(&mut self) -> Result<JSONValue, String> {
+    pub(crate) fn parse_value(&mut self) -> Result<JSONValue, ParseError> {
+        if !self.raw_pointers.is_empty() && self.raw_pointers.contains(&self.path) {
+            return self.parse_raw();
+        }
+        self.count_node()?;
         match self.current_token {
-            Some(ref token) => match token.kind {
+            Some(ref mut token) => match token.kind {
                 TokenKind::OpenBrace => self.parse_object(),
                 TokenKind::OpenBracket => self.parse_array(),
                 TokenKind::QuotedString => {
-                    let value = token.text.clone().unwrap();
+                    check_limit(Limit::StringLength, self.options.max_string_length, token.text.as_ref().unwrap().len())?;
 
-                    self.next_token();
+                    // The token is replaced next, so its text is moved out rather than copied.
+                    let value = JSONString::from(token.text.take().unwrap());
+
+                    self.next_token()?;
                     Ok(JSONValue::String(value))
                 },
                 TokenKind::Number => {
-                    let value = token.text.clone().unwrap().parse::<f64>().map_err(|e| e.to_string())?;
+                    let value = token.text.as_deref().unwrap().parse::<f64>().map_err(|e| ParseError::Syntax(e.to_string()))?;
 
-                    self.next_token();
+                    self.next_token()?;
                     Ok(JSONValue::Number(value))
                 },
                 TokenKind::Keyword => {
                     let value = token.text.clone().unwrap();
 
-                    self.next_token();
+                    self.next_token()?;
                     match value.as_str() {
                         "true" => Ok(JSONValue::Boolean(true)),
                         "false" => Ok(JSONValue::Boolean(false)),
                         "null" => Ok(JSONValue::Null),
-                        _ => Err(format!("Unknown keyword: {}", value))
+                        _ => Err(ParseError::Syntax(format!("Unknown keyword: {}", value)))
                     }
                 },
-                _ => Err(format!("Unexpected token: {:?}", token))
+                _ => Err(ParseError::Syntax(format!("Unexpected token: {:?}", token)))
+            },
+            _ => Err(ParseError::Syntax("Unexpected end of input".to_string()))
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<JSONValue, ParseError> {
+        let mut object = OrderedMap::with_capacity(core::mem::take(&mut self.object_capacity));
+
+        self.parse_entries(|parser: &mut Self, key| {
+            let value = parser.parse_child(&**key)?;
+
+            if parser.options.preserve_duplicate_keys {
+                object.append_shared(key.clone(), value);
+            } else {
+                object.insert_shared(key.clone(), value);
+            }
+            Ok::<(), ParseError>(())
+        })?;
+        Ok(JSONValue::Object(object))
+    }
+
+    fn parse_array(&mut self) -> Result<JSONValue, ParseError> {
+        let start = self.values.len();
+        let result = self.parse_items(|parser: &mut Self| {
+            let len = parser.values.len() - start;
+
+            parser.object_capacity = match parser.values.last() {
+                Some(JSONValue::Object(previous)) if len > 0 => previous.len(),
+                _ => 0
+            };
+            let value = parser.parse_child(len)?;
+
+            parser.object_capacity = 0;
+            parser.values.push(value);
+            Ok::<(), ParseError>(())
+        });
+        let array = self.values.drain(start..).collect();
+
+        result.map(|_| JSONValue::Array(array))
+    }
+
+    /// Parse the value of a property or an item, tracking its pointer when raw values are kept.
+    fn parse_child<T: fmt::Display>(&mut self, token: T) -> Result<JSONValue, ParseError> {
+        if self.raw_pointers.is_empty() {
+            return self.parse_value();
+        }
+        let len = self.path.len();
+
+        self.path.push('/');
+        self.path.push_str(&token.to_string().replace('~', "~0").replace('/', "~1"));
+        let value = self.parse_value();
+
+        self.path.truncate(len);
+        value
+    }
+
+    /// Keep the value starting at the current token as raw JSON text. The text is copied
+    /// from the input when it is held in memory and read as strict JSON, and serialized
+    /// again otherwise, so that it always holds strict JSON.
+    fn parse_raw(&mut self) -> Result<JSONValue, ParseError> {
+        let raw_pointers = core::mem::take(&mut self.raw_pointers);
+        let start = self.lexer.token_start();
+        let result = if self.lexer.in_memory() && self.lexer.strict() {
+            self.skip_value().map(|_| {
+                let end = match self.current_token {
+                    Some(_) => self.lexer.token_start(),
+                    None => self.lexer.offset()
+                };
+
+                JSONValue::Raw(RawValue::from_valid(self.lexer.slice(start, end).unwrap_or_default().trim_end().to_string()))
+            })
+        } else {
+            self.parse_value().map(|value| JSONValue::Raw(RawValue::from_valid(value.serialize())))
+        };
+
+        self.raw_pointers = raw_pointers;
+        result
+    }
+
+    /// Parse the whole input into the arena and return a view of the root value.
+    pub fn parse_in<'b>(&mut self, arena: &'b mut Arena) -> Result<ArenaValue<'b>, ParseError> {
+        let root = self.parse_arena(arena, false)?;
+
+        Ok(arena.root(root, ""))
+    }
+
+    /// Parse like `parse_in`, keeping the strings and keys written without escapes as
+    /// ranges of the input instead of copying them into the arena.
+    pub fn parse_borrowed_in<'b>(&mut self, arena: &'b mut Arena) -> Result<ArenaValue<'b>, ParseError>
+    where
+        'a: 'b
+    {
+        let root = self.parse_arena(arena, true)?;
+
+        Ok(arena.root(root, self.lexer.slice(0, self.lexer.size().unwrap_or(0)).unwrap_or_default()))
+    }
+
+    /// Parse the whole input into the arena and return the index of the root value.
+    fn parse_arena(&mut self, arena: &mut Arena, borrow: bool) -> Result<usize, ParseError> {
+        let root = arena.len();
+
+        if let Some(size) = self.lexer.size() {
+            check_limit(Limit::DocumentSize, self.options.max_document_size, size)?;
+        }
+        self.next_token()?;
+        self.parse_into(arena, borrow)?;
+        Ok(root)
+    }
+
+    fn parse_into(&mut self, arena: &mut Arena, borrow: bool) -> Result<(), ParseError> {
+        match self.peek_kind() {
+            Some(kind @ JSONKind::Object) => {
+                let index = arena.open(kind);
+                let key = Cell::new(None);
+                let mut len = 0;
+
+                self.count_node()?;
+                self.parse_entries_with(|parser: &mut Self, _: &Arc<str>| {
+                    key.set(if borrow { parser.unescaped_span() } else { None });
+                }, |parser: &mut Self, name| {
+                    match key.get() {
+                        Some((start, end)) => arena.push_borrowed_key(start, end),
+                        None => arena.push_key(name)
+                    }
+                    len += 1;
+                    parser.parse_into(arena, borrow)
+                })?;
+                arena.close(index, len);
+                Ok(())
+            },
+            Some(kind @ JSONKind::Array) => {
+                let index = arena.open(kind);
+                let mut len = 0;
+
+                self.count_node()?;
+                self.parse_items(|parser: &mut Self| {
+                    len += 1;
+                    parser.parse_into(arena, borrow)
+                })?;
+                arena.close(index, len);
+                Ok(())
+            },
+            _ => {
+                let span = if borrow { self.unescaped_span() } else { None };
+                let value = self.parse_value()?;
+
+                match span {
+                    Some((start, end)) => arena.push_borrowed_string(start, end),
+                    None => arena.push_scalar(value)
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// The byte range of the input inside the quotes of the current token, when it is a
+    /// string without escapes, so that its text is the same as its value.
+    fn unescaped_span(&self) -> Option<(usize, usize)> {
+        match self.current_token {
+            Some(ref token) if token.kind == TokenKind::QuotedString => {},
+            _ => return None
+        }
+        let (start, end) = (self.lexer.token_start() + 1, self.lexer.offset() - 1);
+
+        self.lexer.slice(start, end).filter(|text| !text.contains('\\')).map(|_| (start, end))
+    }
+
+    /// Parse a comma-separated sequence of values running to the end of the input.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn parse_sequence(&mut self) -> Result<Vec<JSONValue>, ParseError> {
+        let mut values = Vec::new();
+
+        // The values are the items of the root array.
+        self.enter()?;
+        self.next_token()?;
+        loop {
+            values.push(self.parse_value()?);
+            match self.current_token {
+                Some(ref token) if token.kind == TokenKind::Comma => self.next_token()?,
+                Some(_) => return Err(self.unexpected()),
+                None => return Ok(values)
+            }
+        }
+    }
+
+    /// Parse only the value at the given JSON Pointer, skipping everything else.
+    /// Reading stops as soon as the value is found, so the rest of the input is not checked.
+    pub fn parse_pointer(&mut self, pointer: &str) -> Result<Option<JSONValue>, ParseError> {
+        let tokens = parse_pointer(pointer).map_err(ParseError::Syntax)?;
+
+        if let Some(size) = self.lexer.size() {
+            check_limit(Limit::DocumentSize, self.options.max_document_size, size)?;
+        }
+        self.next_token()?;
+        match self.find(&tokens) {
+            Ok(()) | Err(Lookup::Missing) => Ok(None),
+            Err(Lookup::Found(value)) => Ok(Some(value)),
+            Err(Lookup::Parse(e)) => Err(e)
+        }
+    }
+
+    /// Look for the value at the given reference tokens, returning it as `Lookup::Found`.
+    fn find(&mut self, tokens: &[String]) -> Result<(), Lookup> {
+        let (token, rest) = match tokens.split_first() {
+            Some(split) => split,
+            None => return Err(Lookup::Found(self.parse_value()?))
+        };
+
+        match self.peek_kind() {
+            Some(JSONKind::Object) => {
+                self.count_node()?;
+                self.parse_entries(|parser: &mut Self, key| {
+                    if &**key == token {
+                        parser.find(rest).and(Err(Lookup::Missing))
+                    } else {
+                        Ok(parser.skip_value()?)
+                    }
+                })
+            },
+            Some(JSONKind::Array) => {
+                let index = token.parse::<usize>().map_err(|_| Lookup::Missing)?;
+                let mut position = 0;
+
+                self.count_node()?;
+                self.parse_items(|parser: &mut Self| {
+                    position += 1;
+                    if position - 1 == index {
+                        parser.find(rest).and(Err(Lookup::Missing))
+                    } else {
+                        Ok(parser.skip_value()?)
+                    }
+                })
+            },
+            _ => Err(Lookup::Missing)
+        }
+    }
+
+    /// Whether the whole input was read.
+    pub(crate) fn at_end(&self) -> bool {
+        self.current_token.is_none()
+    }
+
+    pub(crate) fn current_is(&self, kind: TokenKind) -> bool {
+        matches!(self.current_token, Some(ref token) if token.kind == kind)
+    }
+
+    /// Returns the error for the current token, which is not expected at this point.
+    pub(crate) fn unexpected(&self) -> ParseError {
+        match self.current_token {
+            Some(ref token) => ParseError::Syntax(format!("Unexpected token: {:?}", token)),
+            None => ParseError::Syntax("Unexpected end of input".to_string())
+        }
+    }
+
+    /// Fail as soon as the strings read are longer than `max` bytes, until lifted with None.
+    #[cfg(feature = "std")]
+    pub(crate) fn limit_next_string(&mut self, max: Option<usize>) {
+        self.lexer.limit_next_string(max);
+    }
+
+    /// The start of the string cut short by `limit_next_string`, if any.
+    #[cfg(feature = "std")]
+    pub(crate) fn truncated_string(&self) -> Option<&str> {
+        self.lexer.truncated_string()
+    }
+
+    /// Consume the current token if it has the given kind, or fail.
+    pub(crate) fn expect(&mut self, kind: TokenKind) -> Result<(), ParseError> {
+        if !self.current_is(kind) {
+            return Err(self.unexpected());
+        }
+        self.next_token()
+    }
+
+    /// Returns the kind of the value starting at the current token, without consuming it.
+    pub(crate) fn peek_kind(&self) -> Option<JSONKind> {
+        let token = self.current_token.as_ref()?;
+
+        match token.kind {
+            TokenKind::OpenBrace => Some(JSONKind::Object),
+            TokenKind::OpenBracket => Some(JSONKind::Array),
+            TokenKind::QuotedString => Some(JSONKind::String),
+            TokenKind::Number => Some(JSONKind::Number),
+            TokenKind::Keyword => match token.text.as_deref() {
+                Some("true") | Some("false") => Some(JSONKind::Boolean),
+                Some("null") => Some(JSONKind::Null),
+                _ => None
+            },
+            _ => None
+        }
+    }
+
+    /// Consume the scalar starting at the current token, returning its JSON text. Numbers
+    /// keep their text as written, and strings are escaped again.
+    pub(crate) fn scalar_text(&mut self) -> Result<String, ParseError> {
+        let text = match (self.peek_kind(), &self.current_token) {
+            (Some(JSONKind::String), Some(token)) => escape(token.text.as_deref().unwrap()),
+            (Some(JSONKind::Number), Some(token)) => {
+                let text = token.text.clone().unwrap();
+
+                text.parse::<f64>().map_err(|e| ParseError::Syntax(e.to_string()))?;
+                text
+            },
+            (Some(JSONKind::Boolean), Some(token)) | (Some(JSONKind::Null), Some(token)) => token.text.clone().unwrap(),
+            _ => return Err(self.unexpected())
+        };
+
+        self.count_node()?;
+        self.next_token()?;
+        Ok(text)
+    }
+
+    /// Consume the value starting at the current token without building it.
+    pub(crate) fn skip_value(&mut self) -> Result<(), ParseError> {
+        match self.peek_kind() {
+            Some(JSONKind::Object) => {
+                self.count_node()?;
+                self.parse_entries(|parser: &mut Self, _| parser.skip_value())
+            },
+            Some(JSONKind::Array) => {
+                self.count_node()?;
+                self.parse_items(|parser: &mut Self| parser.skip_value())
+            },
+            Some(_) => {
+                self.count_node()?;
+                self.next_token()
             },
-            _ => Err("Unexpected end of input".to_string())
+            None => match self.current_token {
+                Some(ref token) => Err(ParseError::Syntax(format!("Unexpected token: {:?}", token))),
+                None => Err(ParseError::Syntax("Unexpected end of input".to_string()))
+            }
         }
     }
 
-    fn parse_object(&mut self) -> Result<JSONValue, String> {
-        let mut object = OrderedMap::new();
+    /// Walk the object starting at the current token, calling `on_entry` after each key.
+    /// The callback must consume exactly one value, with `parse_value` or `skip_value`.
+    pub(crate) fn parse_entries<E, F>(&mut self, on_entry: F) -> Result<(), E>
+    where
+        E: From<ParseError>,
+        F: FnMut(&mut Self, &Arc<str>) -> Result<(), E>
+    {
+        self.parse_entries_with(|_: &mut Self, _: &Arc<str>| {}, on_entry)
+    }
+
+    /// Walk the object like `parse_entries`, also calling `on_key` after each key, before
+    /// the token starting its value is read.
+    pub(crate) fn parse_entries_with<E, K, F>(&mut self, on_key: K, on_entry: F) -> Result<(), E>
+    where
+        E: From<ParseError>,
+        K: FnMut(&mut Self, &Arc<str>),
+        F: FnMut(&mut Self, &Arc<str>) -> Result<(), E>
+    {
+        self.enter()?;
+        let result = self.walk_entries(on_key, on_entry);
+
+        self.depth -= 1;
+        result
+    }
+
+    fn walk_entries<E, K, F>(&mut self, mut on_key: K, mut on_entry: F) -> Result<(), E>
+    where
+        E: From<ParseError>,
+        K: FnMut(&mut Self, &Arc<str>),
+        F: FnMut(&mut Self, &Arc<str>) -> Result<(), E>
+    {
+        match self.current_token {
+            Some(ref token) if token.kind == TokenKind::OpenBrace => {},
+            Some(ref token) => return Err(ParseError::Syntax(format!("Unexpected token: {:?}", token)).into()),
+            None => return Err(ParseError::Syntax("Unexpected end of input".to_string()).into())
+        }
+        let mut keys = 0;
 
-        self.next_token();
+        self.next_token()?;
         while let Some(ref token) = self.current_token {
             if token.kind == TokenKind::CloseBrace {
-                self.next_token();
-                return Ok(JSONValue::Object(object));
+                self.next_token()?;
+                return Ok(());
             }
             if token.kind != TokenKind::QuotedString {
-                return Err(format!("Unexpected token: {:?}", token));
+                return Err(ParseError::Syntax(format!("Unexpected token: {:?}", token)).into());
             }
-            let key = token.text.clone().unwrap();
+            let key = intern(&mut self.keys, token.text.as_deref().unwrap());
 
-            self.next_token();
+            check_limit(Limit::StringLength, self.options.max_string_length, key.len())?;
+            on_key(self, &key);
+            self.next_token()?;
             match self.current_token {
                 Some(ref token) if token.kind == TokenKind::Colon => {
-                    self.next_token();
+                    self.next_token()?;
                 },
-                _ => return Err("Expected ':' after object key".to_string()),
+                _ => return Err(ParseError::Syntax("Expected ':' after object key".to_string()).into()),
             }
-            let value = self.parse_value()?;
-
-            object.insert(key.as_str(), value);
+            on_entry(self, &key)?;
+            keys += 1;
+            check_limit(Limit::ObjectKeys, self.options.max_object_keys, keys)?;
             match self.current_token {
                 Some(ref token) if token.kind == TokenKind::Comma => {
-                    self.next_token();
+                    self.next_token()?;
                 },
                 Some(ref token) if token.kind == TokenKind::CloseBrace => continue,
-                _ => return Err("Expected ',' or '}' after object value".to_string()),
+                _ => return Err(ParseError::Syntax("Expected ',' or '}' after object value".to_string()).into()),
             }
         }
-        Err("Unexpected end of input".to_string())
+        Err(ParseError::Syntax("Unexpected end of input".to_string()).into())
+    }
+
+    /// Walk the array starting at the current token, calling `on_item` for each item.
+    /// The callback must consume exactly one value, with `parse_value` or `skip_value`.
+    pub(crate) fn parse_items<E, F>(&mut self, on_item: F) -> Result<(), E>
+    where
+        E: From<ParseError>,
+        F: FnMut(&mut Self) -> Result<(), E>
+    {
+        self.enter()?;
+        let result = self.walk_items(on_item);
+
+        self.depth -= 1;
+        result
     }
 
-    fn parse_array(&mut self) -> Result<JSONValue, String> {
-        let mut array = Vec::new();
+    fn walk_items<E, F>(&mut self, mut on_item: F) -> Result<(), E>
+    where
+        E: From<ParseError>,
+        F: FnMut(&mut Self) -> Result<(), E>
+    {
+        match self.current_token {
+            Some(ref token) if token.kind == TokenKind::OpenBracket => {},
+            Some(ref token) => return Err(ParseError::Syntax(format!("Unexpected token: {:?}", token)).into()),
+            None => return Err(ParseError::Syntax("Unexpected end of input".to_string()).into())
+        }
+        let mut items = 0;
 
-        self.next_token();
+        self.next_token()?;
         while let Some(ref token) = self.current_token {
             if token.kind == TokenKind::CloseBracket {
-                self.next_token();
-                return Ok(JSONValue::Array(array));
+                self.next_token()?;
+                return Ok(());
             }
-            let value = self.parse_value()?;
-
-            array.push(value);
+            on_item(self)?;
+            items += 1;
+            check_limit(Limit::ArrayLength, self.options.max_array_length, items)?;
             match self.current_token {
                 Some(ref token) if token.kind == TokenKind::Comma => {
-                    self.next_token();
+                    self.next_token()?;
                 },
                 Some(ref token) if token.kind == TokenKind::CloseBracket => continue,
-                _ => return Err("Expected ',' or ']' in array".to_string())
+                _ => return Err(ParseError::Syntax("Expected ',' or ']' in array".to_string()).into())
             }
         }
-        Err("Unexpected end of input".to_string())
+        Err(ParseError::Syntax("Unexpected end of input".to_string()).into())
+    }
+}
+
+/// The outcome of a pointer lookup, used to stop parsing early.
+enum Lookup {
+    Found(JSONValue),
+    Missing,
+    Parse(ParseError)
+}
+
+impl From<ParseError> for Lookup {
+    fn from(error: ParseError) -> Self {
+        Lookup::Parse(error)
+    }
+}
+
+/// Returns the shared copy of an object key, so repeated keys share one allocation.
+pub(crate) fn intern(keys: &mut Set<Arc<str>>, key: &str) -> Arc<str> {
+    match keys.get(key) {
+        Some(key) => key.clone(),
+        None => {
+            let key: Arc<str> = Arc::from(key);
+
+            keys.insert(key.clone());
+            key
+        }
+    }
+}
+
+fn check_limit(limit: Limit, max: Option<usize>, value: usize) -> Result<(), ParseError> {
+    match max {
+        Some(max) if value > max => Err(ParseError::LimitExceeded { limit, max }),
+        _ => Ok(())
     }
 }
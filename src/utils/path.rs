@@ -0,0 +1,82 @@
+use crate::utils::compat::*;
+use crate::JSONValue;
+
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) enum Segment {
+    Key(String),
+    Index(usize)
+}
+
+/// Parse a dot/bracket path such as `cars[1].model` into its segments.
+/// A backslash escapes the next character, so `a\.b` is the single key `a.b`.
+pub(crate) fn parse_path(path: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut key = String::new();
+    let mut pending = false;
+    let mut chars = path.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(c) => {
+                    key.push(c);
+                    pending = true;
+                },
+                None => return Err(format!("Invalid path '{}': trailing backslash", path))
+            },
+            '.' => {
+                if pending {
+                    segments.push(Segment::Key(core::mem::take(&mut key)));
+                    pending = false;
+                } else if !path[..path.len() - chars.as_str().len() - 1].ends_with(']') {
+                    return Err(format!("Invalid path '{}': empty key", path));
+                }
+            },
+            '[' => {
+                if pending {
+                    segments.push(Segment::Key(core::mem::take(&mut key)));
+                    pending = false;
+                }
+                let index: String = chars.by_ref().take_while(|c| *c != ']').collect();
+
+                match index.parse::<usize>() {
+                    Ok(index) => segments.push(Segment::Index(index)),
+                    Err(_) => return Err(format!("Invalid path '{}': bad index '{}'", path, index))
+                }
+            },
+            c => {
+                key.push(c);
+                pending = true;
+            }
+        }
+    }
+    if pending {
+        segments.push(Segment::Key(key));
+    }
+
+    Ok(segments)
+}
+
+impl JSONValue {
+    /// Returns the value at the given dot/bracket path, such as `cars[1].model`.
+    /// Use a backslash to escape dots and brackets inside keys.
+    /// Returns None if the path is invalid or does not resolve.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "cars": [{ "model": "Ford" }, { "model": "BMW" }], "a.b": 1 }"#).unwrap();
+    ///
+    /// assert_eq!(json.get_path("cars[1].model").and_then(|v| v.as_str()), Some("BMW"));
+    /// assert_eq!(json.get_path(r"a\.b").and_then(|v| v.as_f64()), Some(1.0));
+    /// assert!(json.get_path("cars[2]").is_none());
+    /// ```
+    pub fn get_path(&self, path: &str) -> Option<&JSONValue> {
+        parse_path(path).ok()?.iter().try_fold(self, |value, segment| match segment {
+            Segment::Key(key) => value.get(key.as_str()),
+            Segment::Index(index) => value.get(*index)
+        })
+    }
+}
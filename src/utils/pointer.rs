@@ -0,0 +1,235 @@
+use crate::utils::compat::*;
+use crate::utils::parser::edit_items;
+use crate::{JSONValue, OrderedMap};
+
+/// Split a JSON Pointer into its unescaped reference tokens.
+pub(crate) fn parse_pointer(pointer: &str) -> Result<Vec<String>, String> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !pointer.starts_with('/') {
+        return Err(format!("Invalid JSON Pointer '{}'", pointer));
+    }
+
+    Ok(pointer[1..].split('/').map(|token| token.replace("~1", "/").replace("~0", "~")).collect())
+}
+
+fn parse_index(token: &str, len: usize, pointer: &str) -> Result<usize, String> {
+    if token == "-" {
+        return Ok(len);
+    }
+    if token.len() > 1 && token.starts_with('0') {
+        return Err(format!("Invalid array index '{}' in '{}'", token, pointer));
+    }
+
+    token.parse::<usize>().map_err(|_| format!("Invalid array index '{}' in '{}'", token, pointer))
+}
+
+fn child<'a>(value: &'a JSONValue, token: &str) -> Option<&'a JSONValue> {
+    match value {
+        JSONValue::Object(obj) => obj.get(token),
+        JSONValue::Array(array) => token.parse::<usize>().ok().and_then(|index| array.get(index)),
+        _ => None
+    }
+}
+
+fn child_mut<'a>(value: &'a mut JSONValue, token: &str) -> Option<&'a mut JSONValue> {
+    match value {
+        JSONValue::Object(obj) => obj.get_mut(token),
+        JSONValue::Array(array) => token.parse::<usize>().ok().and_then(move |index| array.get_mut(index)),
+        _ => None
+    }
+}
+
+/// An empty container suited to hold the given reference token.
+fn container_for(token: &str) -> JSONValue {
+    if token == "-" || token.parse::<usize>().is_ok() {
+        JSONValue::Array(Box::new([]))
+    } else {
+        JSONValue::Object(OrderedMap::new())
+    }
+}
+
+fn child_or_create<'a>(value: &'a mut JSONValue, token: &str, next: &str, pointer: &str) -> Result<&'a mut JSONValue, String> {
+    if value.is_null() {
+        *value = container_for(token);
+    }
+
+    match value {
+        JSONValue::Object(obj) => {
+            if !obj.contains_key(token) {
+                obj.insert(token, container_for(next));
+            }
+            Ok(obj.get_mut(token).expect("Key was just inserted"))
+        },
+        JSONValue::Array(array) => {
+            let index = parse_index(token, array.len(), pointer)?;
+
+            if index == array.len() {
+                edit_items(array, |items| items.push(container_for(next)));
+            }
+            array.get_mut(index).ok_or_else(|| format!("Index {} out of bounds in '{}'", index, pointer))
+        },
+        _ => Err(format!("Path '{}' does not point into an object or array", pointer))
+    }
+}
+
+impl JSONValue {
+    /// Returns the value at the given JSON Pointer (RFC 6901).
+    /// Returns None if the pointer is invalid or does not resolve.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "cars": [{ "model": "Ford" }] }"#).unwrap();
+    ///
+    /// assert_eq!(json.pointer("/cars/0/model").and_then(|v| v.as_str()), Some("Ford"));
+    /// assert!(json.pointer("/cars/1").is_none());
+    /// ```
+    pub fn pointer(&self, pointer: &str) -> Option<&JSONValue> {
+        parse_pointer(pointer).ok()?.iter().try_fold(self, |value, token| child(value, token))
+    }
+
+    /// Returns a mutable reference to the value at the given JSON Pointer.
+    /// Returns None if the pointer is invalid or does not resolve.
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut JSONValue> {
+        parse_pointer(pointer).ok()?.iter().try_fold(self, |value, token| child_mut(value, token))
+    }
+
+    /// Set the value at the given JSON Pointer and return the previous one.
+    /// The parent must exist. A missing object key is added, and an array
+    /// index equal to the length (or `-`) appends to the array.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONValue};
+    ///
+    /// let mut json = JSONParser::from(r#"{ "address": { "zip": 62701 } }"#).unwrap();
+    ///
+    /// json.set_pointer("/address/zip", JSONValue::Number(62702.0)).unwrap();
+    /// json.set_pointer("/address/city", JSONValue::String("Springfield".into())).unwrap();
+    ///
+    /// assert_eq!(json["address"]["zip"].as_f64(), Some(62702.0));
+    /// assert_eq!(json["address"]["city"].as_str(), Some("Springfield"));
+    /// assert!(json.set_pointer("/missing/key", JSONValue::Null).is_err());
+    /// ```
+    pub fn set_pointer(&mut self, pointer: &str, value: JSONValue) -> Result<Option<JSONValue>, String> {
+        self.write_pointer(pointer, value, false, false)
+    }
+
+    /// Set the value at the given JSON Pointer like `set_pointer`, creating missing
+    /// intermediate containers. A container is created as an array when the next
+    /// token is an array index or `-`, and as an object otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONValue};
+    ///
+    /// let mut json = JSONParser::from("{}").unwrap();
+    ///
+    /// json.set_pointer_create("/address/lines/0", JSONValue::String("123 Main St".into())).unwrap();
+    ///
+    /// assert_eq!(json["address"]["lines"][0].as_str(), Some("123 Main St"));
+    /// ```
+    pub fn set_pointer_create(&mut self, pointer: &str, value: JSONValue) -> Result<Option<JSONValue>, String> {
+        self.write_pointer(pointer, value, true, false)
+    }
+
+    /// Insert a value at the given JSON Pointer. In arrays the value is inserted
+    /// before the given index, shifting the following items; in objects the key
+    /// is added or replaced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONValue};
+    ///
+    /// let mut json = JSONParser::from(r#"{ "ids": [1, 3] }"#).unwrap();
+    ///
+    /// json.insert_pointer("/ids/1", JSONValue::Number(2.0)).unwrap();
+    ///
+    /// assert_eq!(json["ids"].as_array().unwrap().len(), 3);
+    /// assert_eq!(json["ids"][1].as_f64(), Some(2.0));
+    /// ```
+    pub fn insert_pointer(&mut self, pointer: &str, value: JSONValue) -> Result<(), String> {
+        self.write_pointer(pointer, value, false, true).map(|_| ())
+    }
+
+    /// Remove the value at the given JSON Pointer and return it.
+    /// Returns None if the pointer does not resolve or targets the root.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let mut json = JSONParser::from(r#"{ "name": "John Doe", "ids": [1, 2] }"#).unwrap();
+    ///
+    /// assert!(json.remove_pointer("/name").is_some());
+    /// assert!(json.remove_pointer("/ids/0").is_some());
+    /// assert!(json.get("name").is_none());
+    /// assert_eq!(json["ids"].as_array().unwrap().len(), 1);
+    /// ```
+    pub fn remove_pointer(&mut self, pointer: &str) -> Option<JSONValue> {
+        let mut tokens = parse_pointer(pointer).ok()?;
+        let last = tokens.pop()?;
+        let parent = tokens.iter().try_fold(self, |value, token| child_mut(value, token))?;
+
+        match parent {
+            JSONValue::Object(obj) => obj.remove(&last),
+            JSONValue::Array(array) => {
+                let index = last.parse::<usize>().ok().filter(|index| *index < array.len())?;
+
+                Some(edit_items(array, |items| items.remove(index)))
+            },
+            _ => None
+        }
+    }
+
+    fn write_pointer(&mut self, pointer: &str, value: JSONValue, create: bool, insert: bool) -> Result<Option<JSONValue>, String> {
+        let mut tokens = parse_pointer(pointer)?;
+        let last = match tokens.pop() {
+            Some(last) => last,
+            None => return Ok(Some(core::mem::replace(self, value)))
+        };
+        let mut parent = self;
+
+        for (i, token) in tokens.iter().enumerate() {
+            parent = if create {
+                child_or_create(parent, token, tokens.get(i + 1).unwrap_or(&last), pointer)?
+            } else {
+                child_mut(parent, token).ok_or_else(|| format!("Path '{}' not found", pointer))?
+            };
+        }
+        if create && parent.is_null() {
+            *parent = container_for(&last);
+        }
+
+        match parent {
+            JSONValue::Object(obj) => match obj.get_mut(&last) {
+                Some(existing) => Ok(Some(core::mem::replace(existing, value))),
+                None => {
+                    obj.insert(&last, value);
+                    Ok(None)
+                }
+            },
+            JSONValue::Array(array) => {
+                let index = parse_index(&last, array.len(), pointer)?;
+
+                if index > array.len() {
+                    Err(format!("Index {} out of bounds in '{}'", index, pointer))
+                } else if insert || index == array.len() {
+                    edit_items(array, |items| items.insert(index, value));
+                    Ok(None)
+                } else {
+                    Ok(Some(core::mem::replace(&mut array[index], value)))
+                }
+            },
+            _ => Err(format!("Path '{}' does not point into an object or array", pointer))
+        }
+    }
+}
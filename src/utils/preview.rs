@@ -0,0 +1,90 @@
+use crate::utils::compat::*;
+use crate::utils::{JSONValue, Serialize};
+
+/// The length of the previews written by the alternate form of `Display`, `{:#}`.
+pub(crate) const PREVIEW_LENGTH: usize = 80;
+
+impl JSONValue {
+    /// A short summary of the value for logs, at most `max_len` characters long. The
+    /// entries of the root object or array are shown, but nested objects and arrays are
+    /// only counted, and a summary that is still too long is cut and ends with `...`.
+    /// Only the shown part of the value is read, so that large documents are cheap to preview.
+    ///
+    /// `format!("{:#}", value)` gives a preview of 80 characters.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "name": "John", "cars": [{ "model": "X" }, { "model": "Y" }], "address": {} }"#).unwrap();
+    ///
+    /// assert_eq!(json.preview(80), r#"{"name": "John", "cars": [... 2 items], "address": {}}"#);
+    /// assert_eq!(json.preview(20), r#"{"name": "John", ..."#);
+    /// assert_eq!(format!("{:#}", json["cars"]), r#"[{... 1 key}, {... 1 key}]"#);
+    /// ```
+    pub fn preview(&self, max_len: usize) -> String {
+        let mut output = String::new();
+        // A character takes at most 4 bytes, so past this length the summary is surely cut.
+        let budget = max_len.saturating_mul(4).saturating_add(4);
+
+        write_preview(self, true, budget, &mut output);
+
+        if output.chars().count() <= max_len {
+            return output;
+        }
+
+        let mut cut = output.chars().take(max_len.saturating_sub(3)).collect::<String>();
+
+        cut.push_str("...");
+        cut
+    }
+}
+
+fn write_preview(value: &JSONValue, root: bool, budget: usize, output: &mut String) {
+    match value {
+        JSONValue::Array(array) if array.is_empty() => output.push_str("[]"),
+        JSONValue::Object(obj) if obj.is_empty() => output.push_str("{}"),
+        JSONValue::Array(array) if !root => output.push_str(&format!("[... {}]", count(array.len(), "item"))),
+        JSONValue::Object(obj) if !root => output.push_str(&format!("{{... {}}}", count(obj.len(), "key"))),
+        JSONValue::Array(array) => {
+            output.push('[');
+            for (i, item) in array.iter().enumerate() {
+                if output.len() > budget {
+                    return;
+                }
+                if i > 0 {
+                    output.push_str(", ");
+                }
+                write_preview(item, false, budget, output);
+            }
+            output.push(']');
+        },
+        JSONValue::Object(obj) => {
+            output.push('{');
+            for (i, (key, value)) in obj.iter().enumerate() {
+                if output.len() > budget {
+                    return;
+                }
+                if i > 0 {
+                    output.push_str(", ");
+                }
+                output.push_str(&key.serialize());
+                output.push_str(": ");
+                write_preview(value, false, budget, output);
+            }
+            output.push('}');
+        },
+        JSONValue::String(s) => output.push_str(&s.chars().take(budget).collect::<String>().serialize()),
+        JSONValue::Raw(raw) => write_preview(&raw.to_value(), root, budget, output),
+        value => output.push_str(&value.serialize())
+    }
+}
+
+fn count(n: usize, noun: &str) -> String {
+    if n == 1 {
+        format!("1 {}", noun)
+    } else {
+        format!("{} {}s", n, noun)
+    }
+}
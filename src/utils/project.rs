@@ -0,0 +1,127 @@
+use crate::utils::compat::*;
+use crate::utils::pointer::parse_pointer;
+use crate::utils::{JSONValue, OrderedMap};
+
+/// Parse the paths into their tokens.
+fn parse_paths(paths: &[&str]) -> Result<Vec<Vec<String>>, String> {
+    paths.iter().map(|path| parse_pointer(path)).collect()
+}
+
+/// The rest of the paths matching the given key or index, `*` matching any of them.
+pub(crate) fn rest<'p>(paths: &[&'p [String]], token: &str) -> Vec<&'p [String]> {
+    paths.iter()
+        .filter(|path| path.first().map_or(false, |first| first == token || first == "*"))
+        .map(|path| &path[1..])
+        .collect()
+}
+
+impl JSONValue {
+    /// Copy the values at the given JSON Pointers into a new document, keeping their
+    /// structure, such as to return the sparse fieldsets requested by a client. A `*`
+    /// token matches every key of an object or item of an array. Paths to missing values
+    /// are ignored, and arrays keep the matching items only, in order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, Serialize};
+    ///
+    /// let json = JSONParser::from(r#"{
+    ///   "name": "John",
+    ///   "age": 30,
+    ///   "address": { "city": "Paris", "zip": "75001" },
+    ///   "cars": [{ "model": "Ford", "year": 2018 }, { "model": "BMW", "year": 2020 }]
+    /// }"#).unwrap();
+    ///
+    /// let projected = json.project(&["/name", "/address/city", "/cars/*/model", "/missing"]).unwrap();
+    ///
+    /// assert_eq!(projected.serialize(), r#"{"name":"John","address":{"city":"Paris"},"cars":[{"model":"Ford"},{"model":"BMW"}]}"#);
+    /// assert_eq!(json.project(&["name"]).unwrap_err(), "Invalid JSON Pointer 'name'");
+    /// ```
+    pub fn project(&self, paths: &[&str]) -> Result<JSONValue, String> {
+        let paths = parse_paths(paths)?;
+        let paths = paths.iter().map(|path| path.as_slice()).collect::<Vec<_>>();
+
+        Ok(project(self, &paths).unwrap_or_else(|| match self {
+            JSONValue::Object(_) => JSONValue::Object(OrderedMap::new()),
+            JSONValue::Array(_) => JSONValue::Array(Box::new([])),
+            _ => JSONValue::Null
+        }))
+    }
+
+    /// Copy the document without the values at the given JSON Pointers, the inverse of
+    /// `project`. A `*` token matches every key of an object or item of an array, and
+    /// excluding the root, with the empty pointer, gives null.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, Serialize};
+    ///
+    /// let json = JSONParser::from(r#"{ "name": "John", "password": "secret", "cars": [{ "model": "Ford", "vin": "1F" }] }"#).unwrap();
+    ///
+    /// assert_eq!(json.exclude(&["/password", "/cars/*/vin"]).unwrap().serialize(), r#"{"name":"John","cars":[{"model":"Ford"}]}"#);
+    /// ```
+    pub fn exclude(&self, paths: &[&str]) -> Result<JSONValue, String> {
+        let paths = parse_paths(paths)?;
+        let paths = paths.iter().map(|path| path.as_slice()).collect::<Vec<_>>();
+
+        Ok(exclude(self, &paths).unwrap_or(JSONValue::Null))
+    }
+}
+
+/// The parts of the value matched by the paths, or None when none of them matches.
+fn project(value: &JSONValue, paths: &[&[String]]) -> Option<JSONValue> {
+    if paths.is_empty() {
+        return None;
+    }
+    if paths.iter().any(|path| path.is_empty()) {
+        return Some(value.clone());
+    }
+
+    match value {
+        JSONValue::Object(obj) => {
+            let projected = obj.iter()
+                .filter_map(|(key, value)| project(value, &rest(paths, key)).map(|value| (key.to_string(), value)))
+                .collect::<OrderedMap<JSONValue>>();
+
+            if projected.is_empty() {
+                None
+            } else {
+                Some(JSONValue::Object(projected))
+            }
+        },
+        JSONValue::Array(array) => {
+            let projected = array.iter().enumerate()
+                .filter_map(|(index, item)| project(item, &rest(paths, &index.to_string())))
+                .collect::<Vec<_>>();
+
+            if projected.is_empty() {
+                None
+            } else {
+                Some(JSONValue::Array(projected.into()))
+            }
+        },
+        _ => None
+    }
+}
+
+/// The value without the parts matched by the paths, or None when the paths match it whole.
+fn exclude(value: &JSONValue, paths: &[&[String]]) -> Option<JSONValue> {
+    if paths.is_empty() {
+        return Some(value.clone());
+    }
+    if paths.iter().any(|path| path.is_empty()) {
+        return None;
+    }
+
+    match value {
+        JSONValue::Object(obj) => Some(JSONValue::Object(obj.iter()
+            .filter_map(|(key, value)| exclude(value, &rest(paths, key)).map(|value| (key.to_string(), value)))
+            .collect())),
+        JSONValue::Array(array) => Some(JSONValue::Array(array.iter().enumerate()
+            .filter_map(|(index, item)| exclude(item, &rest(paths, &index.to_string())))
+            .collect())),
+        value => Some(value.clone())
+    }
+}
@@ -0,0 +1,118 @@
+use crate::utils::JSONValue;
+
+/// The values removed by `JSONValue::prune`. By default nothing is removed.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::PruneOptions;
+///
+/// let options = PruneOptions::new().nulls().empty_strings();
+/// let everything = PruneOptions::all();
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PruneOptions {
+    nulls: bool,
+    empty_strings: bool,
+    empty_arrays: bool,
+    empty_objects: bool
+}
+
+impl PruneOptions {
+    /// Create a new PruneOptions instance removing nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a PruneOptions instance removing nulls and empty strings, arrays and objects.
+    pub fn all() -> Self {
+        Self::new().nulls().empty_strings().empty_arrays().empty_objects()
+    }
+
+    /// Remove nulls.
+    pub fn nulls(mut self) -> Self {
+        self.nulls = true;
+        self
+    }
+
+    /// Remove empty strings.
+    pub fn empty_strings(mut self) -> Self {
+        self.empty_strings = true;
+        self
+    }
+
+    /// Remove empty arrays, including those left empty by the pruning.
+    pub fn empty_arrays(mut self) -> Self {
+        self.empty_arrays = true;
+        self
+    }
+
+    /// Remove empty objects, including those left empty by the pruning.
+    pub fn empty_objects(mut self) -> Self {
+        self.empty_objects = true;
+        self
+    }
+
+    /// Prune the value, then return it unless it is removed itself, counting the removed values.
+    fn keep(&self, mut value: JSONValue, removed: &mut usize) -> Option<JSONValue> {
+        *removed += value.prune(*self);
+
+        let remove = match &value {
+            JSONValue::Null => self.nulls,
+            JSONValue::String(s) => self.empty_strings && s.is_empty(),
+            JSONValue::Array(array) => self.empty_arrays && array.is_empty(),
+            JSONValue::Object(obj) => self.empty_objects && obj.is_empty(),
+            _ => false
+        };
+
+        if remove {
+            *removed += 1;
+            None
+        } else {
+            Some(value)
+        }
+    }
+}
+
+impl JSONValue {
+    /// Remove the values selected by the options from every object and array in the tree,
+    /// and return the number of values removed. Objects and arrays are pruned before their
+    /// own emptiness is checked, so that a parent holding only removed values is removed
+    /// too. The root value itself is never removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, PruneOptions, Serialize};
+    ///
+    /// let mut json = JSONParser::from(r#"{
+    ///   "name": "John",
+    ///   "nickname": null,
+    ///   "bio": "",
+    ///   "tags": [null, "admin", ""],
+    ///   "address": { "street": null, "notes": [] }
+    /// }"#).unwrap();
+    ///
+    /// assert_eq!(json.prune(PruneOptions::new().nulls()), 3);
+    /// assert_eq!(json.serialize(), r#"{"name":"John","bio":"","tags":["admin",""],"address":{"notes":[]}}"#);
+    ///
+    /// assert_eq!(json.prune(PruneOptions::all()), 4);
+    /// assert_eq!(json.serialize(), r#"{"name":"John","tags":["admin"]}"#);
+    /// ```
+    pub fn prune(&mut self, options: PruneOptions) -> usize {
+        let mut removed = 0;
+
+        match self {
+            JSONValue::Object(obj) => {
+                *obj = core::mem::take(obj).into_iter()
+                    .filter_map(|(key, value)| options.keep(value, &mut removed).map(|value| (key, value)))
+                    .collect();
+            },
+            JSONValue::Array(array) => {
+                *array = core::mem::take(array).into_vec().into_iter().filter_map(|value| options.keep(value, &mut removed)).collect();
+            },
+            _ => {}
+        }
+        removed
+    }
+}
@@ -0,0 +1,146 @@
+use crate::utils::compat::*;
+use crate::utils::events::{Event, EventReader, Step};
+use crate::utils::{JSONValue, Limit, OrderedMap, ParseError, DEFAULT_MAX_DEPTH};
+
+/// What a `PushParser` needs after being fed a chunk.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PushStatus {
+    /// The document is not complete yet.
+    NeedMoreData,
+    /// The root value is complete, and `finish` returns it.
+    Complete
+}
+
+/// A container being built, with the key it belongs to in its parent object. The items
+/// of an array are gathered in `items` until it ends.
+struct Frame {
+    key: Option<String>,
+    value: JSONValue,
+    items: Vec<JSONValue>
+}
+
+/// A parser fed with chunks of input as they arrive, such as the frames of a network
+/// protocol, instead of requiring the whole document up front. Chunks may be split
+/// anywhere, even inside a token or a UTF-8 character. Any value is accepted as the
+/// root, unlike `JSONParser::from`. Arrays and objects nested deeper than
+/// `DEFAULT_MAX_DEPTH` are rejected.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{PushParser, PushStatus};
+///
+/// let mut parser = PushParser::new();
+///
+/// assert_eq!(parser.feed(br#"{ "name": "Jo"#).unwrap(), PushStatus::NeedMoreData);
+/// assert_eq!(parser.feed(br#"hn", "tags": [1, 2"#).unwrap(), PushStatus::NeedMoreData);
+/// assert_eq!(parser.feed(b"] }\n").unwrap(), PushStatus::Complete);
+///
+/// let json = parser.finish().unwrap();
+///
+/// assert_eq!(json["name"].as_str(), Some("John"));
+/// assert_eq!(json["tags"][1].as_f64(), Some(2.0));
+///
+/// let mut parser = PushParser::new();
+///
+/// parser.feed(b"[1, 2").unwrap();
+/// assert_eq!(parser.finish().unwrap_err().to_string(), "Unexpected end of input (offset: 5)");
+///
+/// let mut parser = PushParser::new();
+///
+/// assert_eq!(parser.feed(&vec![b'['; 1_000_000]).unwrap_err().to_string(), "Maximum nesting depth exceeded (max: 128)");
+/// ```
+pub struct PushParser {
+    events: EventReader,
+    stack: Vec<Frame>,
+    key: Option<String>,
+    root: Option<JSONValue>
+}
+
+impl Default for PushParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PushParser {
+    /// Create a new PushParser instance, waiting for its first chunk.
+    pub fn new() -> Self {
+        Self {
+            events: EventReader::new(),
+            stack: Vec::new(),
+            key: None,
+            root: None
+        }
+    }
+
+    /// Parse a chunk of input, as far as it goes. Returns `PushStatus::Complete` once the
+    /// root value is complete. A scalar root, such as a number, is only complete once
+    /// `finish` marks the end of the input. Anything but whitespace after the root value
+    /// is an error.
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<PushStatus, ParseError> {
+        self.events.feed(chunk);
+        self.advance()
+    }
+
+    /// Mark the end of the input and return the document, or the error making it incomplete.
+    pub fn finish(mut self) -> Result<JSONValue, ParseError> {
+        self.events.finish();
+        self.advance()?;
+
+        match self.root {
+            Some(root) => Ok(root),
+            None => unreachable!("A finished reader either fails or completes the root")
+        }
+    }
+
+    fn advance(&mut self) -> Result<PushStatus, ParseError> {
+        loop {
+            let event = match self.events.next_step()? {
+                Step::Event(event) => event,
+                Step::NeedMoreData if self.root.is_some() => return Ok(PushStatus::Complete),
+                Step::NeedMoreData => return Ok(PushStatus::NeedMoreData),
+                Step::Done => return Ok(PushStatus::Complete)
+            };
+
+            match event {
+                Event::StartObject | Event::StartArray => {
+                    if self.stack.len() >= DEFAULT_MAX_DEPTH {
+                        return Err(ParseError::LimitExceeded { limit: Limit::Depth, max: DEFAULT_MAX_DEPTH });
+                    }
+                    let value = match event {
+                        Event::StartObject => JSONValue::Object(OrderedMap::new()),
+                        _ => JSONValue::Array(Box::new([]))
+                    };
+
+                    self.stack.push(Frame { key: self.key.take(), value, items: Vec::new() });
+                },
+                Event::EndObject | Event::EndArray => {
+                    if let Some(mut frame) = self.stack.pop() {
+                        if frame.value.is_array() {
+                            frame.value = JSONValue::Array(frame.items.into());
+                        }
+                        self.attach(frame.key, frame.value);
+                    }
+                },
+                Event::Key(key) => self.key = Some(key),
+                Event::Value(value) => {
+                    let key = self.key.take();
+
+                    self.attach(key, value);
+                }
+            }
+        }
+    }
+
+    /// Add a complete value to the container being built, or make it the root.
+    fn attach(&mut self, key: Option<String>, value: JSONValue) {
+        match (self.stack.last_mut(), key) {
+            (Some(Frame { value: JSONValue::Object(obj), .. }), Some(key)) => {
+                obj.insert(&key, value);
+            },
+            (Some(Frame { value: JSONValue::Array(_), items, .. }), _) => items.push(value),
+            _ => self.root = Some(value)
+        }
+    }
+}
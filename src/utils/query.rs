@@ -0,0 +1,197 @@
+use crate::utils::compat::*;
+use crate::utils::parser::edit_items;
+use crate::utils::{JSONValue, OrderedMap, Serialize, DEFAULT_MAX_DEPTH};
+
+impl JSONValue {
+    /// Serialize an object as a URL query string, nesting with bracket notation
+    /// (`c[name]=y`, `b[0]=x`) and percent-encoding keys and values.
+    /// Null is written as an empty value, and empty arrays and objects are left out
+    /// since a query string cannot represent them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "q": "rust & json", "page": 2, "tags": ["a", "b"], "user": { "name": "Zoë" } }"#).unwrap();
+    ///
+    /// assert_eq!(json.to_query_string(), Ok("q=rust%20%26%20json&page=2&tags[0]=a&tags[1]=b&user[name]=Zo%C3%AB".to_string()));
+    /// assert!(json["page"].to_query_string().is_err());
+    /// ```
+    pub fn to_query_string(&self) -> Result<String, String> {
+        let obj = self.as_object().ok_or_else(|| format!("Expected an object, found {}", self.type_name()))?;
+        let mut pairs = Vec::new();
+
+        for (key, value) in obj.iter() {
+            write_pairs(&mut encode(key), value, &mut pairs);
+        }
+        Ok(pairs.join("&"))
+    }
+
+    /// Parse a URL query string into an object, reading bracket notation as nesting.
+    /// A numeric or empty bracket (`b[0]`, `b[]`) indexes or appends to an array, any other
+    /// bracket names an object key. Indices must not skip items, so that untrusted input
+    /// cannot allocate a huge array, and a key may nest at most `DEFAULT_MAX_DEPTH` levels.
+    /// Values are percent-decoded strings, with `+` read as a space, and a repeated key
+    /// keeps its last value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONValue};
+    ///
+    /// let json = JSONValue::from_query_string("a=1&b[0]=x&b[]=y&c[name]=John+Doe&d=caf%C3%A9").unwrap();
+    ///
+    /// assert_eq!(json, JSONParser::from(r#"{ "a": "1", "b": ["x", "y"], "c": { "name": "John Doe" }, "d": "café" }"#).unwrap());
+    /// assert!(JSONValue::from_query_string("a=1&a[b]=2").is_err());
+    ///
+    /// let nested = format!("a{}=1", "[x]".repeat(200_000));
+    ///
+    /// assert!(JSONValue::from_query_string(&nested).unwrap_err().ends_with("Maximum nesting depth exceeded (max: 128)"));
+    /// ```
+    pub fn from_query_string(input: &str) -> Result<JSONValue, String> {
+        let mut root = JSONValue::Object(OrderedMap::new());
+
+        for pair in input.trim_start_matches('?').split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = match pair.find('=') {
+                Some(i) => (&pair[..i], &pair[i + 1..]),
+                None => (pair, "")
+            };
+            let key = decode(key)?;
+            let segments = parse_key(&key);
+
+            if segments.len() > DEFAULT_MAX_DEPTH {
+                return Err(format!("Key '{}': Maximum nesting depth exceeded (max: {})", key, DEFAULT_MAX_DEPTH));
+            }
+            insert_at(&mut root, &segments, JSONValue::String(decode(value)?.into()))
+                .map_err(|e| format!("Key '{}': {}", key, e))?;
+        }
+        Ok(root)
+    }
+}
+
+fn write_pairs(path: &mut String, value: &JSONValue, pairs: &mut Vec<String>) {
+    let len = path.len();
+
+    match value {
+        JSONValue::Object(obj) => {
+            for (key, value) in obj.iter() {
+                path.push_str(&format!("[{}]", encode(key)));
+                write_pairs(path, value, pairs);
+                path.truncate(len);
+            }
+        },
+        JSONValue::Array(array) => {
+            for (index, value) in array.iter().enumerate() {
+                path.push_str(&format!("[{}]", index));
+                write_pairs(path, value, pairs);
+                path.truncate(len);
+            }
+        },
+        JSONValue::String(s) => pairs.push(format!("{}={}", path, encode(s))),
+        JSONValue::Null => pairs.push(format!("{}=", path)),
+        value => pairs.push(format!("{}={}", path, value.serialize()))
+    }
+}
+
+/// Percent-encode everything but the unreserved characters of RFC 3986.
+fn encode(input: &str) -> String {
+    let mut encoded = String::with_capacity(input.len());
+
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => encoded.push(byte as char),
+            byte => encoded.push_str(&format!("%{:02X}", byte))
+        }
+    }
+    encoded
+}
+
+/// Percent-decode a component, reading `+` as a space. Malformed escapes are kept as is.
+fn decode(input: &str) -> Result<String, String> {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escape = bytes.get(i + 1..i + 3)
+            .and_then(|hex| core::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match (bytes[i], escape) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            },
+            (b'+', _) => {
+                decoded.push(b' ');
+                i += 1;
+            },
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| format!("Invalid UTF-8 in '{}'", input))
+}
+
+/// Split a key such as `c[name][0]` into its segments. Brackets that are not
+/// well formed are kept as part of the segment they appear in.
+fn parse_key(key: &str) -> Vec<&str> {
+    let (name, mut rest) = match key.find('[') {
+        Some(i) if i > 0 => (&key[..i], &key[i..]),
+        _ => return vec![key]
+    };
+    let mut segments = vec![name];
+
+    while let Some(end) = rest.strip_prefix('[').and_then(|after| after.find(']')) {
+        segments.push(&rest[1..end + 1]);
+        rest = &rest[end + 2..];
+    }
+    if !rest.is_empty() {
+        return vec![key];
+    }
+    segments
+}
+
+/// Set the value found at the given segments, creating the containers along them.
+/// A numeric segment indexes an array, or appends to it when one past its end, and an empty one appends.
+fn insert_at(target: &mut JSONValue, segments: &[&str], value: JSONValue) -> Result<(), String> {
+    let (segment, rest) = match segments.split_first() {
+        Some(split) => split,
+        None => {
+            *target = value;
+            return Ok(());
+        }
+    };
+    let index = segment.parse::<usize>().ok();
+
+    if target.is_null() {
+        *target = match index {
+            Some(_) => JSONValue::Array(Box::new([])),
+            None if segment.is_empty() => JSONValue::Array(Box::new([])),
+            None => JSONValue::Object(OrderedMap::new())
+        };
+    }
+    match target {
+        JSONValue::Array(array) if index.is_some() || segment.is_empty() => {
+            let index = index.unwrap_or(array.len());
+
+            if index > array.len() {
+                return Err(format!("Index {} skips items of an array of length {}", index, array.len()));
+            }
+            if index == array.len() {
+                edit_items(array, |items| items.push(JSONValue::Null));
+            }
+            insert_at(&mut array[index], rest, value)
+        },
+        JSONValue::Object(obj) => {
+            if !obj.contains_key(segment) {
+                obj.insert(segment, JSONValue::Null);
+            }
+            insert_at(obj.get_mut(segment).expect("Key was just inserted"), rest, value)
+        },
+        target => Err(format!("Cannot set '{}' in {}", segment, target.type_name()))
+    }
+}
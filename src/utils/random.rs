@@ -0,0 +1,64 @@
+/// A seedable pseudo-random number generator, used by `JSONSchema::generate`. The same
+/// seed always gives the same numbers, so that generated documents can be reproduced.
+/// It is not suitable for cryptography.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::Rng;
+///
+/// let mut rng = Rng::new(42);
+/// let numbers = (0..3).map(|_| rng.below(10)).collect::<Vec<_>>();
+///
+/// assert!(numbers.iter().all(|n| *n < 10));
+/// assert_eq!((0..3).map(|_| Rng::new(42).below(10)).collect::<Vec<_>>(), vec![numbers[0]; 3]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64
+}
+
+impl Rng {
+    /// Create a new Rng instance from the given seed.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// The next 64 random bits, as computed by SplitMix64.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number from 0 up to, but excluding, the given bound. Returns 0 for a bound of 0.
+    pub fn below(&mut self, bound: usize) -> usize {
+        if bound == 0 {
+            return 0;
+        }
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// A number from `min` to `max`, both included.
+    pub fn between(&mut self, min: usize, max: usize) -> usize {
+        min + self.below(max.saturating_sub(min).saturating_add(1))
+    }
+
+    /// A number from 0 up to, but excluding, 1.
+    pub fn float(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// True with the given probability.
+    pub fn chance(&mut self, probability: f64) -> bool {
+        self.float() < probability
+    }
+
+    /// An item of the slice, or None if it is empty.
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        items.get(self.below(items.len()))
+    }
+}
@@ -0,0 +1,73 @@
+use core::fmt;
+
+use crate::utils::compat::*;
+use crate::utils::{JSONKind, JSONValue, Lexer, ParseError, Parser, ParserOptions};
+
+/// Valid JSON text kept as is, held by `JSONValue::Raw`, such as a pre-serialized blob
+/// or a subdocument captured with `JSONParser::raw_at`. It can only be built from text
+/// that parses, so reading it as a value never fails.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{JSONKind, RawValue};
+///
+/// let raw = RawValue::new(" [1, 2] ").unwrap();
+///
+/// assert_eq!(raw.as_str(), "[1, 2]");
+/// assert_eq!(raw.kind(), JSONKind::Array);
+/// assert_eq!(raw.to_value()[1].as_f64(), Some(2.0));
+/// assert!(RawValue::new("garbage").is_err());
+/// ```
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub struct RawValue(String);
+
+impl RawValue {
+    /// Create a raw value holding the given JSON text, trimmed, after checking that it is valid.
+    pub fn new(text: &str) -> Result<RawValue, ParseError> {
+        let mut parser = Parser::new(Lexer::new(text));
+
+        parser.next_token()?;
+        parser.skip_value()?;
+        if !parser.at_end() {
+            return Err(parser.unexpected());
+        }
+        Ok(RawValue(text.trim().to_string()))
+    }
+
+    /// Wrap text the caller has already checked to be strict JSON.
+    pub(crate) fn from_valid(text: String) -> RawValue {
+        RawValue(text)
+    }
+
+    /// Returns the JSON text.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the type of the value, read from the first character of the text.
+    pub fn kind(&self) -> JSONKind {
+        match self.0.as_bytes().first() {
+            Some(b'{') => JSONKind::Object,
+            Some(b'[') => JSONKind::Array,
+            Some(b'"') => JSONKind::String,
+            Some(b't') | Some(b'f') => JSONKind::Boolean,
+            Some(b'n') => JSONKind::Null,
+            _ => JSONKind::Number
+        }
+    }
+
+    /// Parse the text into a value. The nesting depth was already bounded when the text
+    /// was checked, so it is not limited again.
+    pub fn to_value(&self) -> JSONValue {
+        let options = ParserOptions::new().max_depth(usize::MAX);
+
+        Parser::with_options(Lexer::new(&self.0), options).parse_document().expect("raw values hold valid JSON")
+    }
+}
+
+impl fmt::Display for RawValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,106 @@
+use crate::utils::compat::*;
+use crate::utils::{JSONValue, Walk};
+
+/// A parsed redaction pattern, as a list of segments that may hold `*` wildcards.
+enum Pattern {
+    /// A dotted pattern, matching the last segments of a path.
+    Suffix(Vec<String>),
+    /// A JSON Pointer pattern, matching the whole path.
+    Pointer(Vec<String>)
+}
+
+impl Pattern {
+    fn new(pattern: &str) -> Self {
+        match pattern.strip_prefix('/') {
+            Some(pointer) => Pattern::Pointer(pointer.split('/').map(unescape).collect()),
+            None => Pattern::Suffix(pattern.split('.').map(|segment| segment.to_string()).collect())
+        }
+    }
+
+    fn matches(&self, path: &[String]) -> bool {
+        let (segments, tail) = match self {
+            Pattern::Pointer(segments) if segments.len() != path.len() => return false,
+            Pattern::Pointer(segments) => (segments, path),
+            Pattern::Suffix(segments) if segments.len() > path.len() => return false,
+            Pattern::Suffix(segments) => (segments, &path[path.len() - segments.len()..])
+        };
+
+        segments.iter().zip(tail.iter()).all(|(pattern, token)| glob(pattern, token))
+    }
+}
+
+fn unescape(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+/// Match a text against a pattern where `*` stands for any run of characters.
+fn glob(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let mut rest = match text.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false
+    };
+    let parts = parts.collect::<Vec<_>>();
+
+    match parts.split_last() {
+        None => rest.is_empty(),
+        Some((last, middle)) => {
+            for part in middle {
+                match rest.find(part) {
+                    Some(i) => rest = &rest[i + part.len()..],
+                    None => return false
+                }
+            }
+            rest.ends_with(last)
+        }
+    }
+}
+
+impl JSONValue {
+    /// Replace every value matching one of the patterns with a copy of the replacement,
+    /// returning the number of values replaced. The content of a replaced value is not
+    /// searched any further.
+    ///
+    /// A pattern starting with `/` is a JSON Pointer matched against the whole path of a value.
+    /// Any other pattern is a list of keys or indices separated by dots, matched against the
+    /// end of the path, so `password` matches that key at any depth and `*.token` matches
+    /// `token` in any object or array. In both forms `*` stands for any run of characters
+    /// within a key, so `/users/*/ssn` matches the `ssn` of every user.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONValue};
+    ///
+    /// let mut json = JSONParser::from(r#"{
+    ///   "password": "hunter2",
+    ///   "session": { "token": "abc", "user": { "password": "123" } },
+    ///   "users": [{ "name": "Ann", "ssn": "078-05-1120" }],
+    ///   "token": "top-level"
+    /// }"#).unwrap();
+    ///
+    /// let count = json.redact(&["password", "*.token", "/users/*/ssn"], JSONValue::String("[REDACTED]".into()));
+    ///
+    /// assert_eq!(count, 4);
+    /// assert_eq!(json["session"]["user"]["password"].as_str(), Some("[REDACTED]"));
+    /// assert_eq!(json["users"][0]["ssn"].as_str(), Some("[REDACTED]"));
+    /// assert_eq!(json["token"].as_str(), Some("top-level"));
+    /// ```
+    pub fn redact(&mut self, patterns: &[&str], replacement: JSONValue) -> usize {
+        let patterns = patterns.iter().map(|pattern| Pattern::new(pattern)).collect::<Vec<_>>();
+        let mut count = 0;
+
+        self.walk_mut(|path, value| {
+            let tokens = path.split('/').skip(1).map(unescape).collect::<Vec<_>>();
+
+            if tokens.is_empty() || !patterns.iter().any(|pattern| pattern.matches(&tokens)) {
+                return Walk::Continue;
+            }
+            *value = replacement.clone();
+            count += 1;
+            Walk::Skip
+        });
+        count
+    }
+}
@@ -0,0 +1,182 @@
+use crate::utils::compat::*;
+use crate::utils::lexer::Comments;
+use crate::utils::{Lexer, ParseError, Parser, TokenKind};
+
+/// Options used by `reformat`. By default nesting is indented with two spaces.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::FormatStyle;
+///
+/// let style = FormatStyle::new().indent(4);
+/// let style = FormatStyle::new().tabs();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct FormatStyle {
+    indent: usize,
+    tabs: bool
+}
+
+impl Default for FormatStyle {
+    fn default() -> Self {
+        Self { indent: 2, tabs: false }
+    }
+}
+
+impl FormatStyle {
+    /// Create a new FormatStyle instance with the default behavior.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of spaces per level of nesting.
+    pub fn indent(mut self, width: usize) -> Self {
+        self.indent = width;
+        self
+    }
+
+    /// Indent with one tab per level of nesting instead of spaces.
+    pub fn tabs(mut self) -> Self {
+        self.tabs = true;
+        self
+    }
+}
+
+/// The output being written, with what separates it from the next token.
+struct Writer {
+    style: FormatStyle,
+    output: String,
+    depth: usize,
+    /// The next token starts a new line.
+    newline: bool,
+    /// The next token is separated by a space, unless it starts a new line.
+    space: bool
+}
+
+impl Writer {
+    /// Start a new line at the current depth, keeping a blank line before it if asked.
+    fn start_line(&mut self, blank: bool) {
+        if !self.output.is_empty() {
+            self.output.push('\n');
+            if blank {
+                self.output.push('\n');
+            }
+            for _ in 0..self.depth {
+                if self.style.tabs {
+                    self.output.push('\t');
+                } else {
+                    self.output.push_str(&" ".repeat(self.style.indent));
+                }
+            }
+        }
+        self.newline = false;
+        self.space = false;
+    }
+
+    /// Separate the next token from the output.
+    fn start_token(&mut self, blank: bool, spaced: bool) {
+        if self.newline {
+            self.start_line(blank);
+        } else if self.space && spaced {
+            self.output.push(' ');
+        }
+        self.space = false;
+    }
+}
+
+/// Pretty-print a JSON document holding `//` and `/* */` comments, as found in JSONC
+/// configuration files. Comments stay attached to the values they annotate: a comment
+/// on its own line stays on its own line before the next value, and a comment after
+/// a value on the same line stays at the end of that line. Numbers and strings are
+/// written as in the input, and blank lines between values are kept, collapsed to one.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{reformat, FormatStyle};
+///
+/// let input = r#"{
+/// // Server settings
+/// "port": 8080, "host": "localhost", // Local only
+///
+///     "tags": [1.50, /* deprecated */ 2], "empty": {}
+/// }"#;
+///
+/// assert_eq!(reformat(input, FormatStyle::new()).unwrap(), r#"{
+///   // Server settings
+///   "port": 8080,
+///   "host": "localhost", // Local only
+///
+///   "tags": [
+///     1.50,
+///     /* deprecated */ 2
+///   ],
+///   "empty": {}
+/// }
+/// "#);
+///
+/// assert!(reformat(r#"{ "port": /* unset */ }"#, FormatStyle::new()).is_err());
+/// ```
+pub fn reformat(input: &str, style: FormatStyle) -> Result<String, ParseError> {
+    Parser::new(Lexer::new(input).comments(Comments::Skip)).parse_document()?;
+
+    let mut lexer = Lexer::new(input).comments(Comments::Keep);
+    let mut writer = Writer { style, output: String::new(), depth: 0, newline: false, space: false };
+    let mut previous_end = 0;
+
+    while let Some((token, span)) = lexer.next_spanned()? {
+        let blank = input[previous_end..span.start].matches('\n').count() > 1;
+        let own_line = input[previous_end..span.start].contains('\n');
+        let text = &input[span.start..span.end];
+
+        previous_end = span.end;
+        match token.kind {
+            TokenKind::Comment => {
+                let text = text.trim_end();
+                let line_comment = text.starts_with("//");
+
+                // A block comment after a comma or an opening bracket annotates the next value.
+                if own_line || writer.output.is_empty() || (writer.newline && !line_comment) {
+                    writer.start_line(blank);
+                } else {
+                    writer.output.push(' ');
+                }
+                writer.output.push_str(text);
+                if line_comment {
+                    writer.newline = true;
+                } else {
+                    writer.space = true;
+                }
+            },
+            TokenKind::OpenBrace | TokenKind::OpenBracket => {
+                writer.start_token(blank, true);
+                writer.output.push_str(text);
+                writer.depth += 1;
+                writer.newline = true;
+            },
+            TokenKind::CloseBrace | TokenKind::CloseBracket => {
+                writer.depth -= 1;
+                writer.newline = !writer.output.ends_with(['{', '['].as_ref());
+                writer.start_token(false, false);
+                writer.output.push_str(text);
+            },
+            TokenKind::Colon => {
+                writer.start_token(false, false);
+                writer.output.push(':');
+                writer.space = true;
+            },
+            TokenKind::Comma => {
+                writer.start_token(false, false);
+                writer.output.push(',');
+                writer.newline = true;
+            },
+            _ => {
+                writer.start_token(blank, true);
+                writer.output.push_str(text);
+            }
+        }
+    }
+    writer.output.push('\n');
+    Ok(writer.output)
+}
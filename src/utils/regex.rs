@@ -0,0 +1,295 @@
+use crate::utils::compat::*;
+use crate::utils::random::Rng;
+
+/// A regular expression, matched by backtracking. Supports literals, `.`, classes such
+/// as `[a-z_]` or `[^0-9]`, the escapes `\d \w \s \D \W \S`, groups, alternation with `|`,
+/// the anchors `^` and `$`, and the quantifiers `* + ?` and `{n}`, `{n,}`, `{n,m}`.
+pub(crate) struct Regex {
+    alternatives: Vec<Vec<Node>>
+}
+
+enum Node {
+    Char(char),
+    Any,
+    Class { ranges: Vec<(char, char)>, negated: bool },
+    Start,
+    End,
+    Group(Vec<Vec<Node>>),
+    Repeat { node: Box<Node>, min: usize, max: Option<usize> }
+}
+
+impl Regex {
+    pub(crate) fn new(pattern: &str) -> Result<Regex, String> {
+        let chars = pattern.chars().collect::<Vec<_>>();
+        let mut position = 0;
+        let alternatives = parse_alternatives(&chars, &mut position)?;
+
+        match chars.get(position) {
+            Some(c) => Err(format!("Unexpected '{}' at {}", c, position)),
+            None => Ok(Regex { alternatives })
+        }
+    }
+
+    /// Whether the expression matches anywhere in the text. Use `^` and `$` to match the whole text.
+    pub(crate) fn is_match(&self, text: &str) -> bool {
+        let chars = text.chars().collect::<Vec<_>>();
+
+        // An expression anchored at the start can only match from there.
+        let anchored = self.alternatives.iter().all(|sequence| matches!(sequence.first(), Some(Node::Start)));
+        let starts = if anchored { 0..=0 } else { 0..=chars.len() };
+
+        starts.into_iter().any(|start| match_alternatives(&self.alternatives, &chars, start, &mut |_| true))
+    }
+
+    /// Generate a text matched by the expression. Unbounded repetitions repeat at most
+    /// three more times than required, and negated classes pick a letter or a digit.
+    pub(crate) fn generate(&self, rng: &mut Rng) -> String {
+        let mut output = String::new();
+
+        generate_alternatives(&self.alternatives, rng, &mut output);
+        output
+    }
+}
+
+fn generate_alternatives(alternatives: &[Vec<Node>], rng: &mut Rng, output: &mut String) {
+    if let Some(sequence) = rng.pick(alternatives) {
+        for node in sequence {
+            generate_node(node, rng, output);
+        }
+    }
+}
+
+fn generate_node(node: &Node, rng: &mut Rng, output: &mut String) {
+    const ALPHANUMERIC: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+    match node {
+        Node::Char(c) => output.push(*c),
+        Node::Any => output.push(ALPHANUMERIC.as_bytes()[rng.below(ALPHANUMERIC.len())] as char),
+        Node::Class { ranges, negated: false } => {
+            if let Some((start, end)) = rng.pick(ranges) {
+                let c = char::from_u32(rng.between(*start as usize, *end as usize) as u32).unwrap_or(*start);
+
+                output.push(c);
+            }
+        },
+        Node::Class { ranges, negated: true } => {
+            let allowed = ALPHANUMERIC.chars()
+                .filter(|c| !ranges.iter().any(|(start, end)| start <= c && c <= end))
+                .collect::<Vec<_>>();
+
+            output.push(*rng.pick(&allowed).unwrap_or(&'~'));
+        },
+        Node::Start | Node::End => {},
+        Node::Group(alternatives) => generate_alternatives(alternatives, rng, output),
+        Node::Repeat { node, min, max } => {
+            for _ in 0..rng.between(*min, max.unwrap_or(min + 3)) {
+                generate_node(node, rng, output);
+            }
+        }
+    }
+}
+
+fn parse_alternatives(chars: &[char], position: &mut usize) -> Result<Vec<Vec<Node>>, String> {
+    let mut alternatives = vec![Vec::new()];
+
+    while let Some(&c) = chars.get(*position) {
+        let node = match c {
+            ')' => break,
+            '|' => {
+                *position += 1;
+                alternatives.push(Vec::new());
+                continue;
+            },
+            '*' | '+' | '?' | '{' => {
+                let sequence = alternatives.last_mut().expect("There is always an alternative");
+                let node = match sequence.pop() {
+                    Some(Node::Start) | Some(Node::End) | Some(Node::Repeat { .. }) | None => {
+                        return Err(format!("Nothing to repeat at {}", position));
+                    },
+                    Some(node) => node
+                };
+                let (min, max) = parse_quantifier(chars, position)?;
+
+                // A lazy quantifier matches the same texts.
+                if chars.get(*position) == Some(&'?') {
+                    *position += 1;
+                }
+                sequence.push(Node::Repeat { node: Box::new(node), min, max });
+                continue;
+            },
+            '(' => {
+                *position += 1;
+                if chars[*position..].starts_with(&['?', ':']) {
+                    *position += 2;
+                }
+                let group = parse_alternatives(chars, position)?;
+
+                if chars.get(*position) != Some(&')') {
+                    return Err("Unclosed group".to_string());
+                }
+                Node::Group(group)
+            },
+            '[' => parse_class(chars, position)?,
+            '\\' => {
+                *position += 1;
+                let c = *chars.get(*position).ok_or("Trailing backslash")?;
+
+                escape_class(c).unwrap_or(Node::Char(unescape(c)))
+            },
+            '.' => Node::Any,
+            '^' => Node::Start,
+            '$' => Node::End,
+            c => Node::Char(c)
+        };
+
+        *position += 1;
+        alternatives.last_mut().expect("There is always an alternative").push(node);
+    }
+    Ok(alternatives)
+}
+
+/// Read a quantifier, leaving the position after it.
+fn parse_quantifier(chars: &[char], position: &mut usize) -> Result<(usize, Option<usize>), String> {
+    let quantifier = match chars[*position] {
+        '*' => (0, None),
+        '+' => (1, None),
+        '?' => (0, Some(1)),
+        _ => {
+            let end = chars[*position..].iter().position(|c| *c == '}').ok_or("Unclosed quantifier")? + *position;
+            let bounds = chars[*position + 1..end].iter().collect::<String>();
+            let number = |n: &str| n.trim().parse::<usize>().map_err(|_| format!("Invalid quantifier '{{{}}}'", bounds));
+            let quantifier = match bounds.split_once(',') {
+                Some((min, "")) => (number(min)?, None),
+                Some((min, max)) => (number(min)?, Some(number(max)?)),
+                None => (number(&bounds)?, Some(number(&bounds)?))
+            };
+
+            *position = end;
+            quantifier
+        }
+    };
+
+    *position += 1;
+    Ok(quantifier)
+}
+
+/// Read a class such as `[a-z_]`, leaving the position on its closing bracket.
+fn parse_class(chars: &[char], position: &mut usize) -> Result<Node, String> {
+    let mut ranges = Vec::new();
+    let negated = chars.get(*position + 1) == Some(&'^');
+
+    *position += if negated { 2 } else { 1 };
+    // A closing bracket right after the opening one is a literal.
+    let start = *position;
+
+    loop {
+        let c = match chars.get(*position) {
+            Some(']') if *position > start => return Ok(Node::Class { ranges, negated }),
+            Some('\\') => {
+                *position += 1;
+                let c = *chars.get(*position).ok_or("Unclosed class")?;
+
+                match escape_class(c) {
+                    Some(Node::Class { ranges: escaped, negated: false }) => {
+                        ranges.extend(escaped);
+                        *position += 1;
+                        continue;
+                    },
+                    Some(_) => return Err(format!("Unsupported escape '\\{}' in a class", c)),
+                    None => unescape(c)
+                }
+            },
+            Some(c) => *c,
+            None => return Err("Unclosed class".to_string())
+        };
+
+        match (chars.get(*position + 1), chars.get(*position + 2)) {
+            (Some('-'), Some(end)) if *end != ']' => {
+                ranges.push((c, *end));
+                *position += 3;
+            },
+            _ => {
+                ranges.push((c, c));
+                *position += 1;
+            }
+        }
+    }
+}
+
+/// The character of an escape such as `\n`, or the escaped character itself, such as `.` for `\.`.
+fn unescape(c: char) -> char {
+    match c {
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        c => c
+    }
+}
+
+/// The class matched by an escape such as `\d`, or None for an escaped literal.
+fn escape_class(c: char) -> Option<Node> {
+    let ranges = match c.to_ascii_lowercase() {
+        'd' => vec![('0', '9')],
+        'w' => vec![('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')],
+        's' => vec![(' ', ' '), ('\t', '\t'), ('\n', '\n'), ('\r', '\r'), ('\x0C', '\x0C'), ('\x0B', '\x0B')],
+        _ => return None
+    };
+
+    Some(Node::Class { ranges, negated: c.is_ascii_uppercase() })
+}
+
+fn match_alternatives(alternatives: &[Vec<Node>], chars: &[char], position: usize, next: &mut dyn FnMut(usize) -> bool) -> bool {
+    alternatives.iter().any(|sequence| match_sequence(sequence, chars, position, next))
+}
+
+/// Match the nodes from the position, then call `next` with the position after them,
+/// backtracking until it returns true.
+fn match_sequence(nodes: &[Node], chars: &[char], position: usize, next: &mut dyn FnMut(usize) -> bool) -> bool {
+    match nodes.split_first() {
+        Some((node, rest)) => match_node(node, chars, position, &mut |end| match_sequence(rest, chars, end, next)),
+        None => next(position)
+    }
+}
+
+fn match_node(node: &Node, chars: &[char], position: usize, next: &mut dyn FnMut(usize) -> bool) -> bool {
+    match node {
+        Node::Start => position == 0 && next(position),
+        Node::End => position == chars.len() && next(position),
+        Node::Group(alternatives) => match_alternatives(alternatives, chars, position, next),
+        Node::Repeat { node, min, max } => match_repeat(node, *min, *max, chars, position, next),
+        _ => chars.get(position).map_or(false, |&c| match_char(node, c)) && next(position + 1)
+    }
+}
+
+/// Whether a node matching a single character matches the given one.
+fn match_char(node: &Node, c: char) -> bool {
+    match node {
+        Node::Char(expected) => c == *expected,
+        Node::Any => true,
+        Node::Class { ranges, negated } => ranges.iter().any(|&(start, end)| start <= c && c <= end) != *negated,
+        _ => false
+    }
+}
+
+/// Match as many repetitions as possible, then fewer until `next` accepts the position after them.
+fn match_repeat(node: &Node, min: usize, max: Option<usize>, chars: &[char], position: usize, next: &mut dyn FnMut(usize) -> bool) -> bool {
+    // A repeated character is counted in a loop and backtracked over the counted positions,
+    // so that long strings do not take a stack frame per character.
+    if matches!(node, Node::Char(_) | Node::Any | Node::Class { .. }) {
+        let limit = max.map_or(chars.len(), |max| chars.len().min(position.saturating_add(max)));
+        let count = chars[position..limit].iter().take_while(|&&c| match_char(node, c)).count();
+
+        return count >= min && (min..=count).rev().any(|count| next(position + count));
+    }
+    if max != Some(0) {
+        let more = match_node(node, chars, position, &mut |end| {
+            // A repetition matching nothing would repeat forever.
+            (end != position || min > 0) && match_repeat(node, min.saturating_sub(1), max.map(|max| max - 1), chars, end, next)
+        });
+
+        if more {
+            return true;
+        }
+    }
+    min == 0 && next(position)
+}
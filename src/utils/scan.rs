@@ -0,0 +1,306 @@
+//! Bulk byte scanning used by the lexer on in-memory input, and the search for the
+//! structural characters of a document used to split it for a parallel parse.
+//! With the `simd` feature, 16 bytes are classified at a time using SSE2 on
+//! x86_64 or NEON on aarch64; other targets use the scalar loops.
+
+fn is_string_stop(b: u8) -> bool {
+    b == b'"' || b == b'\\' || b < 0x20
+}
+
+fn is_whitespace(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\n' | b'\r')
+}
+
+/// Number of leading bytes that can be copied into a string as is,
+/// stopping at a quote, a backslash or a control character.
+pub(crate) fn string_run(bytes: &[u8]) -> usize {
+    let start = simd::string_run(bytes);
+
+    start + bytes[start..].iter().position(|&b| is_string_stop(b)).unwrap_or(bytes.len() - start)
+}
+
+/// Number of leading ASCII whitespace bytes.
+pub(crate) fn whitespace_run(bytes: &[u8]) -> usize {
+    let start = simd::whitespace_run(bytes);
+
+    start + bytes[start..].iter().position(|&b| !is_whitespace(b)).unwrap_or(bytes.len() - start)
+}
+
+/// Bitmasks of the characters of a block of up to 64 bytes, bit `i` standing for byte `i`.
+#[cfg(feature = "rayon")]
+#[derive(Default)]
+struct Masks {
+    /// The brackets, braces, colons and commas.
+    structural: u64,
+    quote: u64,
+    backslash: u64
+}
+
+#[cfg(feature = "rayon")]
+fn classify(block: &[u8]) -> Masks {
+    if let Some(masks) = simd::classify(block) {
+        return masks;
+    }
+    let mut masks = Masks::default();
+
+    for (i, &b) in block.iter().enumerate() {
+        match b {
+            b'{' | b'}' | b'[' | b']' | b':' | b',' => masks.structural |= 1 << i,
+            b'"' => masks.quote |= 1 << i,
+            b'\\' => masks.backslash |= 1 << i,
+            _ => {}
+        }
+    }
+    masks
+}
+
+/// Iterator over the offsets of the structural characters of a JSON text, the brackets,
+/// braces, colons and commas outside of strings. As in the first stage of simdjson, each
+/// block of 64 bytes is classified at once, and the strings left out are found from the
+/// quotes that are not escaped, carrying over from one block to the next whether a string
+/// or an escape is still open. The text is not checked to be valid JSON.
+#[cfg(feature = "rayon")]
+pub(crate) struct Structurals<'a> {
+    bytes: &'a [u8],
+    /// Offset of the block whose remaining structural characters are set in `mask`.
+    block: usize,
+    mask: u64,
+    /// Offset of the next block to classify.
+    next: usize,
+    /// Whether the blocks read so far end inside a string.
+    in_string: bool,
+    /// Whether the blocks read so far end with a backslash escaping the next byte.
+    escaped: bool
+}
+
+#[cfg(feature = "rayon")]
+pub(crate) fn structurals(bytes: &[u8]) -> Structurals<'_> {
+    Structurals { bytes, block: 0, mask: 0, next: 0, in_string: false, escaped: false }
+}
+
+#[cfg(feature = "rayon")]
+impl<'a> Structurals<'a> {
+    /// The structural characters of a block that lie outside of strings.
+    fn outside_strings(&mut self, block: &[u8]) -> u64 {
+        let masks = classify(block);
+        let quotes = masks.quote & !self.escaped_bytes(masks.backslash);
+        // Each bit is set from an opening quote up to its closing quote excluded.
+        let mut strings = quotes;
+
+        for shift in [1, 2, 4, 8, 16, 32] {
+            strings ^= strings << shift;
+        }
+        if self.in_string {
+            strings = !strings;
+        }
+        self.in_string = strings >> 63 == 1;
+        masks.structural & !strings
+    }
+
+    /// The bytes escaped by a backslash, given the backslashes of the block.
+    fn escaped_bytes(&mut self, backslash: u64) -> u64 {
+        if backslash == 0 && !self.escaped {
+            return 0;
+        }
+        let mut escaped = 0;
+
+        for i in 0..64 {
+            if self.escaped {
+                escaped |= 1 << i;
+                self.escaped = false;
+            } else {
+                self.escaped = backslash & (1 << i) != 0;
+            }
+        }
+        escaped
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<'a> Iterator for Structurals<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.mask == 0 {
+            if self.next >= self.bytes.len() {
+                return None;
+            }
+            let end = (self.next + 64).min(self.bytes.len());
+
+            self.mask = self.outside_strings(&self.bytes[self.next..end]);
+            self.block = self.next;
+            self.next = end;
+        }
+        let bit = self.mask.trailing_zeros() as usize;
+
+        self.mask &= self.mask - 1;
+        Some(self.block + bit)
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod simd {
+    use core::arch::x86_64::*;
+
+    /// Scan whole 16-byte blocks, returning the offset of the first block holding a match.
+    fn scan(bytes: &[u8], matches: impl Fn(__m128i) -> __m128i) -> usize {
+        let mut offset = 0;
+
+        while offset + 16 <= bytes.len() {
+            // SAFETY: SSE2 is part of the x86_64 baseline and the load reads 16 in-bounds bytes.
+            let mask = unsafe {
+                let block = _mm_loadu_si128(bytes.as_ptr().add(offset) as *const __m128i);
+
+                _mm_movemask_epi8(matches(block))
+            };
+
+            if mask != 0 {
+                return offset + mask.trailing_zeros() as usize;
+            }
+            offset += 16;
+        }
+        offset
+    }
+
+    pub(super) fn string_run(bytes: &[u8]) -> usize {
+        scan(bytes, |block| unsafe {
+            let quote = _mm_cmpeq_epi8(block, _mm_set1_epi8(b'"' as i8));
+            let backslash = _mm_cmpeq_epi8(block, _mm_set1_epi8(b'\\' as i8));
+            let limit = _mm_set1_epi8(0x1F);
+            let control = _mm_cmpeq_epi8(_mm_max_epu8(block, limit), limit);
+
+            _mm_or_si128(_mm_or_si128(quote, backslash), control)
+        })
+    }
+
+    pub(super) fn whitespace_run(bytes: &[u8]) -> usize {
+        scan(bytes, |block| unsafe {
+            let space = _mm_cmpeq_epi8(block, _mm_set1_epi8(b' ' as i8));
+            let tab = _mm_cmpeq_epi8(block, _mm_set1_epi8(b'\t' as i8));
+            let newline = _mm_cmpeq_epi8(block, _mm_set1_epi8(b'\n' as i8));
+            let ret = _mm_cmpeq_epi8(block, _mm_set1_epi8(b'\r' as i8));
+            let whitespace = _mm_or_si128(_mm_or_si128(space, tab), _mm_or_si128(newline, ret));
+
+            _mm_xor_si128(whitespace, _mm_set1_epi8(-1))
+        })
+    }
+
+    /// Classify a whole block of 64 bytes, 16 at a time. Returns None for a shorter block.
+    #[cfg(feature = "rayon")]
+    pub(super) fn classify(block: &[u8]) -> Option<super::Masks> {
+        if block.len() < 64 {
+            return None;
+        }
+        let mut masks = super::Masks::default();
+
+        for chunk in 0..4 {
+            // SAFETY: SSE2 is part of the x86_64 baseline and the load reads 16 in-bounds bytes.
+            let (structural, quote, backslash) = unsafe {
+                let bytes = _mm_loadu_si128(block.as_ptr().add(chunk * 16) as *const __m128i);
+                let eq = |c: u8| _mm_cmpeq_epi8(bytes, _mm_set1_epi8(c as i8));
+                let brackets = _mm_or_si128(_mm_or_si128(eq(b'{'), eq(b'}')), _mm_or_si128(eq(b'['), eq(b']')));
+                let structural = _mm_or_si128(brackets, _mm_or_si128(eq(b':'), eq(b',')));
+
+                (_mm_movemask_epi8(structural), _mm_movemask_epi8(eq(b'"')), _mm_movemask_epi8(eq(b'\\')))
+            };
+
+            masks.structural |= (structural as u64) << (chunk * 16);
+            masks.quote |= (quote as u64) << (chunk * 16);
+            masks.backslash |= (backslash as u64) << (chunk * 16);
+        }
+        Some(masks)
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "aarch64"))]
+mod simd {
+    use core::arch::aarch64::*;
+
+    /// Scan whole 16-byte blocks, returning the offset of the first block holding a match.
+    fn scan(bytes: &[u8], matches: impl Fn(uint8x16_t) -> uint8x16_t) -> usize {
+        let mut offset = 0;
+
+        while offset + 16 <= bytes.len() {
+            // SAFETY: NEON is part of the aarch64 baseline and the load reads 16 in-bounds bytes.
+            let found = unsafe { vmaxvq_u8(matches(vld1q_u8(bytes.as_ptr().add(offset)))) != 0 };
+
+            if found {
+                return offset;
+            }
+            offset += 16;
+        }
+        offset
+    }
+
+    pub(super) fn string_run(bytes: &[u8]) -> usize {
+        scan(bytes, |block| unsafe {
+            let quote = vceqq_u8(block, vdupq_n_u8(b'"'));
+            let backslash = vceqq_u8(block, vdupq_n_u8(b'\\'));
+            let control = vcleq_u8(block, vdupq_n_u8(0x1F));
+
+            vorrq_u8(vorrq_u8(quote, backslash), control)
+        })
+    }
+
+    pub(super) fn whitespace_run(bytes: &[u8]) -> usize {
+        scan(bytes, |block| unsafe {
+            let space = vceqq_u8(block, vdupq_n_u8(b' '));
+            let tab = vceqq_u8(block, vdupq_n_u8(b'\t'));
+            let newline = vceqq_u8(block, vdupq_n_u8(b'\n'));
+            let ret = vceqq_u8(block, vdupq_n_u8(b'\r'));
+
+            vmvnq_u8(vorrq_u8(vorrq_u8(space, tab), vorrq_u8(newline, ret)))
+        })
+    }
+
+    /// Gather the top bit of each byte into a 16-bit mask, as `_mm_movemask_epi8` does on x86_64.
+    #[cfg(feature = "rayon")]
+    unsafe fn movemask(matches: uint8x16_t) -> u64 {
+        const BITS: [u8; 16] = [1, 2, 4, 8, 16, 32, 64, 128, 1, 2, 4, 8, 16, 32, 64, 128];
+        let bits = vandq_u8(matches, vld1q_u8(BITS.as_ptr()));
+
+        vaddv_u8(vget_low_u8(bits)) as u64 | (vaddv_u8(vget_high_u8(bits)) as u64) << 8
+    }
+
+    /// Classify a whole block of 64 bytes, 16 at a time. Returns None for a shorter block.
+    #[cfg(feature = "rayon")]
+    pub(super) fn classify(block: &[u8]) -> Option<super::Masks> {
+        if block.len() < 64 {
+            return None;
+        }
+        let mut masks = super::Masks::default();
+
+        for chunk in 0..4 {
+            // SAFETY: NEON is part of the aarch64 baseline and the load reads 16 in-bounds bytes.
+            let (structural, quote, backslash) = unsafe {
+                let bytes = vld1q_u8(block.as_ptr().add(chunk * 16));
+                let eq = |c: u8| vceqq_u8(bytes, vdupq_n_u8(c));
+                let brackets = vorrq_u8(vorrq_u8(eq(b'{'), eq(b'}')), vorrq_u8(eq(b'['), eq(b']')));
+                let structural = vorrq_u8(brackets, vorrq_u8(eq(b':'), eq(b',')));
+
+                (movemask(structural), movemask(eq(b'"')), movemask(eq(b'\\')))
+            };
+
+            masks.structural |= structural << (chunk * 16);
+            masks.quote |= quote << (chunk * 16);
+            masks.backslash |= backslash << (chunk * 16);
+        }
+        Some(masks)
+    }
+}
+
+#[cfg(not(all(feature = "simd", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+mod simd {
+    pub(super) fn string_run(_: &[u8]) -> usize {
+        0
+    }
+
+    pub(super) fn whitespace_run(_: &[u8]) -> usize {
+        0
+    }
+
+    #[cfg(feature = "rayon")]
+    pub(super) fn classify(_: &[u8]) -> Option<super::Masks> {
+        None
+    }
+}
@@ -0,0 +1,74 @@
+use crate::utils::compat::*;
+use crate::utils::walk::push_token;
+use crate::{JSONValue, Walk};
+
+impl JSONValue {
+    /// Returns every node matching the predicate, with its JSON Pointer path,
+    /// in depth-first order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "age": 30, "cars": [{ "year": 2018 }] }"#).unwrap();
+    /// let numbers = json.find_all(|value| value.as_f64().is_some());
+    ///
+    /// assert_eq!(numbers.len(), 2);
+    /// assert_eq!(numbers[1].0, "/cars/0/year");
+    /// ```
+    pub fn find_all<F: Fn(&JSONValue) -> bool>(&self, predicate: F) -> Vec<(String, &JSONValue)> {
+        let mut found = Vec::new();
+
+        self.walk(|path, value| {
+            if predicate(value) {
+                found.push((path.to_string(), value));
+            }
+            Walk::Continue
+        });
+        found
+    }
+
+    /// Returns every value stored under the given key in any object of the tree,
+    /// with its JSON Pointer path.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "password": "a", "users": [{ "password": "b" }] }"#).unwrap();
+    /// let paths: Vec<String> = json.find_by_key("password").into_iter().map(|(path, _)| path).collect();
+    ///
+    /// assert_eq!(paths, vec!["/password", "/users/0/password"]);
+    /// ```
+    pub fn find_by_key(&self, key: &str) -> Vec<(String, &JSONValue)> {
+        let mut found = Vec::new();
+
+        self.walk(|path, value| {
+            if let Some(value) = value.as_object().and_then(|obj| obj.get(key)) {
+                let mut path = path.to_string();
+
+                push_token(&mut path, key);
+                found.push((path, value));
+            }
+            Walk::Continue
+        });
+        found
+    }
+
+    /// Returns the JSON Pointer paths of every node matching the predicate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "name": "John Doe", "spouse": null }"#).unwrap();
+    ///
+    /// assert_eq!(json.find_path(|value| value.is_null()), vec!["/spouse"]);
+    /// ```
+    pub fn find_path<F: Fn(&JSONValue) -> bool>(&self, predicate: F) -> Vec<String> {
+        self.find_all(predicate).into_iter().map(|(path, _)| path).collect()
+    }
+}
@@ -0,0 +1,162 @@
+#[cfg(feature = "std")]
+use std::io::{ErrorKind, Read};
+
+use crate::utils::compat::*;
+use crate::utils::{JSONValue, Serialize};
+#[cfg(feature = "std")]
+use crate::utils::{Lexer, ParseError, Parser};
+#[cfg(feature = "std")]
+use crate::JSONParser;
+
+/// The record separator starting each JSON text of a sequence.
+const RS: u8 = 0x1E;
+#[cfg(feature = "std")]
+const CHUNK_SIZE: usize = 8192;
+
+#[cfg(feature = "std")]
+impl<'a> JSONParser<'a> {
+    /// Read a JSON text sequence (RFC 7464, `application/json-seq`) from the given reader
+    /// as an iterator over its records, each a JSON text preceded by an RS character.
+    ///
+    /// A record that cannot be parsed gives an error prefixed with its number, and the
+    /// iterator resumes at the next RS, as the RFC requires. Empty records are skipped, and
+    /// a top-level number, boolean or null not followed by whitespace is reported as
+    /// truncated. The iterator ends after a read error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let input = "\x1e{ \"level\": \"info\" }\n\x1e{ \"level\": \x1e\x1e[1, 2]\n\x1e42";
+    /// let mut records = JSONParser::stream_json_seq(input.as_bytes());
+    ///
+    /// assert_eq!(records.next().unwrap().unwrap()["level"].as_str(), Some("info"));
+    /// assert_eq!(records.next().unwrap().unwrap_err().to_string(), "Record 2: Unexpected end of input");
+    /// assert_eq!(records.next().unwrap().unwrap().len(), 2);
+    /// assert_eq!(records.next().unwrap().unwrap_err().to_string(), "Record 4: Truncated value");
+    /// assert!(records.next().is_none());
+    /// ```
+    pub fn stream_json_seq<R: Read>(reader: R) -> JsonSeqStream<R> {
+        JsonSeqStream {
+            reader,
+            buffer: Vec::new(),
+            chunk: vec![0; CHUNK_SIZE],
+            scanned: 0,
+            record: 0,
+            eof: false
+        }
+    }
+}
+
+/// An iterator over the records of a JSON text sequence read from a reader.
+/// Created by `JSONParser::stream_json_seq`.
+#[cfg(feature = "std")]
+pub struct JsonSeqStream<R> {
+    reader: R,
+    /// Input read past the last complete record, starting with its RS if any.
+    buffer: Vec<u8>,
+    chunk: Vec<u8>,
+    /// Length of the buffer already searched for the next RS.
+    scanned: usize,
+    /// Number of the last record taken from the buffer.
+    record: usize,
+    eof: bool
+}
+
+#[cfg(feature = "std")]
+impl<R> JsonSeqStream<R> {
+    /// Take the next complete record from the buffer, without its RS, or the rest of the
+    /// buffer at the end of the input.
+    fn take_record(&mut self) -> Option<Vec<u8>> {
+        let start = if self.buffer.first() == Some(&RS) { 1 } else { 0 };
+        let from = self.scanned.max(start);
+        let end = match self.buffer[from..].iter().position(|b| *b == RS) {
+            Some(i) => from + i,
+            None if self.eof && !self.buffer.is_empty() => self.buffer.len(),
+            None => {
+                self.scanned = self.buffer.len();
+                return None;
+            }
+        };
+        let record = self.buffer[start..end].to_vec();
+
+        self.buffer.drain(..end);
+        self.scanned = 0;
+        Some(record)
+    }
+
+    /// Parse a record, or return None when it is blank.
+    fn parse_record(&mut self, record: &[u8]) -> Result<Option<JSONValue>, ParseError> {
+        if record.iter().all(u8::is_ascii_whitespace) {
+            return Ok(None);
+        }
+        self.record += 1;
+
+        let text = core::str::from_utf8(record).map_err(|_| ParseError::Syntax("Invalid UTF-8 input".to_string()))?;
+        let value = Parser::new(Lexer::new(text)).parse_document()?;
+        let scalar = matches!(value, JSONValue::Number(_) | JSONValue::Boolean(_) | JSONValue::Null);
+
+        if scalar && !text.ends_with(|c: char| c.is_ascii_whitespace()) {
+            return Err(ParseError::Syntax("Truncated value".to_string()));
+        }
+        Ok(Some(value))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Read> Iterator for JsonSeqStream<R> {
+    type Item = Result<JSONValue, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(record) = self.take_record() {
+                match self.parse_record(&record) {
+                    Ok(None) => continue,
+                    Ok(Some(value)) => return Some(Ok(value)),
+                    Err(e) => return Some(Err(ParseError::Syntax(format!("Record {}: {}", self.record, e))))
+                }
+            }
+            if self.eof {
+                return None;
+            }
+            match self.reader.read(&mut self.chunk) {
+                Ok(0) => self.eof = true,
+                Ok(read) => self.buffer.extend_from_slice(&self.chunk[..read]),
+                Err(e) if e.kind() == ErrorKind::Interrupted => {},
+                Err(e) => {
+                    self.eof = true;
+                    self.buffer.clear();
+                    return Some(Err(ParseError::Io(format!("Failed to read input: {}", e))));
+                }
+            }
+        }
+    }
+}
+
+impl JSONValue {
+    /// Serialize the value as a record of a JSON text sequence (RFC 7464): an RS character,
+    /// the compact JSON text and a line feed. Records are concatenated to write a sequence.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONValue};
+    ///
+    /// let mut output = JSONParser::from(r#"{ "level": "info" }"#).unwrap().to_json_seq();
+    ///
+    /// output.push_str(&JSONValue::Number(42.0).to_json_seq());
+    /// assert_eq!(output, "\x1e{\"level\":\"info\"}\n\x1e42\n");
+    ///
+    /// let records = JSONParser::stream_json_seq(output.as_bytes()).collect::<Result<Vec<_>, _>>().unwrap();
+    ///
+    /// assert_eq!(records.len(), 2);
+    /// ```
+    pub fn to_json_seq(&self) -> String {
+        let mut record = String::from(RS as char);
+
+        record.push_str(&self.serialize());
+        record.push('\n');
+        record
+    }
+}
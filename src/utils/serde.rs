@@ -0,0 +1,102 @@
+use serde_json::{Map, Number, Value};
+
+use crate::utils::compat::*;
+use crate::utils::{JSONValue, OrderedMap, RawValue};
+
+/// Convert a `serde_json::Value`, keeping the order in which its map yields the keys:
+/// the insertion order with the `preserve_order` feature of serde_json, and the sorted
+/// order without it.
+///
+/// Numbers become f64, so integers beyond 2^53 lose precision as when they are parsed.
+/// A number that has no f64 value, which serde_json only produces with its
+/// `arbitrary_precision` feature, is kept as a raw value holding its text.
+///
+/// Available with the `serde_json` feature.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::JSONValue;
+///
+/// let value = JSONValue::from(serde_json::json!({ "name": "John", "age": 30, "tags": ["a"] }));
+///
+/// assert_eq!(value["name"].as_str(), Some("John"));
+/// assert_eq!(value["age"].as_f64(), Some(30.0));
+/// assert_eq!(value["tags"][0].as_str(), Some("a"));
+/// ```
+impl From<Value> for JSONValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => JSONValue::Null,
+            Value::Bool(b) => JSONValue::Boolean(b),
+            Value::Number(n) => match n.as_f64() {
+                Some(f) => JSONValue::Number(f),
+                None => JSONValue::Raw(RawValue::from_valid(n.to_string()))
+            },
+            Value::String(s) => JSONValue::String(s.into()),
+            Value::Array(array) => JSONValue::Array(array.into_iter().map(JSONValue::from).collect()),
+            Value::Object(map) => {
+                let mut obj = OrderedMap::with_capacity(map.len());
+
+                for (key, value) in map {
+                    obj.insert(&key, JSONValue::from(value));
+                }
+                JSONValue::Object(obj)
+            }
+        }
+    }
+}
+
+/// Convert to a `serde_json::Value`. The keys keep their order with the `preserve_order`
+/// feature of serde_json, and are sorted without it.
+///
+/// Numbers holding an integer that fits an i64 become integers, so that they serialize
+/// without a fraction as with this crate, and -0 becomes 0. Other numbers become
+/// floats, except NaN and infinities which become null as in serde_json. Raw values are
+/// parsed.
+///
+/// Available with the `serde_json` feature.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{JSONParser, JSONValue};
+///
+/// let json = JSONParser::from(r#"{ "id": 7, "missing": null, "ratio": 0.5 }"#).unwrap();
+/// let value = serde_json::Value::from(json.clone());
+///
+/// assert_eq!(value, serde_json::json!({ "id": 7, "missing": null, "ratio": 0.5 }));
+/// assert!(value["id"].is_i64());
+///
+/// assert_eq!(JSONValue::from(value), json);
+/// assert_eq!(serde_json::Value::from(JSONValue::Number(f64::NAN)), serde_json::Value::Null);
+/// ```
+impl From<JSONValue> for Value {
+    fn from(value: JSONValue) -> Self {
+        match value {
+            JSONValue::Null => Value::Null,
+            JSONValue::Boolean(b) => Value::Bool(b),
+            JSONValue::Number(n) => number(n),
+            JSONValue::String(s) => Value::String(s.into_string()),
+            JSONValue::Array(array) => Value::Array(array.into_vec().into_iter().map(Value::from).collect()),
+            JSONValue::Object(obj) => {
+                let mut map = Map::with_capacity(obj.len());
+
+                for (key, value) in obj {
+                    map.insert(key, Value::from(value));
+                }
+                Value::Object(map)
+            },
+            JSONValue::Raw(raw) => Value::from(raw.to_value())
+        }
+    }
+}
+
+fn number(n: f64) -> Value {
+    // 2^63 is exactly representable, so every number below it in magnitude fits an i64.
+    if n.fract() == 0.0 && n.abs() < 9_223_372_036_854_775_808.0 {
+        Value::Number(Number::from(n as i64))
+    } else {
+        Number::from_f64(n).map_or(Value::Null, Value::Number)
+    }
+}
@@ -0,0 +1,337 @@
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::utils::compat::*;
+use crate::utils::parser::{escape, escape_char};
+use crate::utils::{JSONValue, Serialize};
+#[cfg(feature = "std")]
+use crate::utils::OrderedMap;
+
+/// Options used by `JSONValue::serialize_with`. By default numbers are written with
+/// the shortest digits that parse back to the same value, integral numbers without
+/// a fractional part (`30`, not `30.0`) and never in scientific notation, and strings
+/// only escape the characters JSON requires.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::SerializeOptions;
+///
+/// let options = SerializeOptions::new()
+///     .precision(2)
+///     .integers_without_fraction()
+///     .exponent_thresholds(-6, 21)
+///     .escape_non_ascii()
+///     .html_safe();
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SerializeOptions {
+    precision: Option<usize>,
+    integers_without_fraction: bool,
+    exponent_thresholds: Option<(i32, i32)>,
+    escape_non_ascii: bool,
+    escape_slash: bool,
+    html_safe: bool
+}
+
+impl SerializeOptions {
+    /// Create a new SerializeOptions instance with the default behavior.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write numbers with a fixed number of digits after the decimal point, rounding
+    /// them, instead of the shortest round-trip digits.
+    pub fn precision(mut self, digits: usize) -> Self {
+        self.precision = Some(digits);
+        self
+    }
+
+    /// Write integral numbers without a fractional part, even with a fixed precision.
+    pub fn integers_without_fraction(mut self) -> Self {
+        self.integers_without_fraction = true;
+        self
+    }
+
+    /// Write numbers in scientific notation (`1.5e-7`) when their decimal exponent is
+    /// below `min` or at least `max`. JavaScript uses `-6` and `21`.
+    pub fn exponent_thresholds(mut self, min: i32, max: i32) -> Self {
+        self.exponent_thresholds = Some((min, max));
+        self
+    }
+
+    /// Escape every non-ASCII character as `\uXXXX`, using surrogate pairs beyond
+    /// U+FFFF, for consumers that only accept ASCII.
+    pub fn escape_non_ascii(mut self) -> Self {
+        self.escape_non_ascii = true;
+        self
+    }
+
+    /// Escape `/` as `\/`.
+    pub fn escape_slash(mut self) -> Self {
+        self.escape_slash = true;
+        self
+    }
+
+    /// Escape `<`, `>`, `&`, U+2028 and U+2029 as `\uXXXX`, so that the output can be
+    /// embedded in an HTML `<script>` tag.
+    pub fn html_safe(mut self) -> Self {
+        self.html_safe = true;
+        self
+    }
+
+    fn escape_string(&self, value: &str) -> String {
+        let mut escaped = String::with_capacity(value.len() + 2);
+        let mut units = [0; 2];
+
+        escaped.push('"');
+        for c in value.chars() {
+            match c {
+                '/' if self.escape_slash => escaped.push_str("\\/"),
+                '<' | '>' | '&' | '\u{2028}' | '\u{2029}' if self.html_safe => {
+                    escaped.push_str(&format!("\\u{:04x}", c as u32));
+                },
+                c if !c.is_ascii() && self.escape_non_ascii => {
+                    for unit in c.encode_utf16(&mut units) {
+                        escaped.push_str(&format!("\\u{:04x}", unit));
+                    }
+                },
+                c => escape_char(c, &mut escaped)
+            }
+        }
+        escaped.push('"');
+        escaped
+    }
+
+    fn format_number(&self, n: f64) -> String {
+        if !n.is_finite() {
+            return n.to_string();
+        }
+        if let Some((min, max)) = self.exponent_thresholds {
+            let exponent = decimal_exponent(n);
+
+            if exponent < min || exponent >= max {
+                return match self.precision {
+                    Some(digits) => format!("{:.*e}", digits, n),
+                    None => format!("{:e}", n)
+                };
+            }
+        }
+        match self.precision {
+            Some(_) if self.integers_without_fraction && n.fract() == 0.0 => n.to_string(),
+            Some(digits) => format!("{:.*}", digits, n),
+            None => n.to_string()
+        }
+    }
+}
+
+/// The power of ten of the leading digit of the number, 0 for zero.
+fn decimal_exponent(n: f64) -> i32 {
+    let scientific = format!("{:e}", n);
+
+    scientific.rsplit('e').next().and_then(|exponent| exponent.parse().ok()).unwrap_or(0)
+}
+
+impl JSONValue {
+    /// Serialize the value to a string, formatting numbers and escaping strings as set by the options.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, SerializeOptions};
+    ///
+    /// let json = JSONParser::from(r#"{ "id": 62701, "price": 9.999, "tiny": 0.00000015, "huge": 1e300 }"#).unwrap();
+    ///
+    /// assert_eq!(json["id"].serialize_with(SerializeOptions::new()), "62701");
+    ///
+    /// let options = SerializeOptions::new().precision(2);
+    ///
+    /// assert_eq!(json["id"].serialize_with(options), "62701.00");
+    /// assert_eq!(json["price"].serialize_with(options), "10.00");
+    /// assert_eq!(json["id"].serialize_with(options.integers_without_fraction()), "62701");
+    ///
+    /// let options = SerializeOptions::new().exponent_thresholds(-6, 21);
+    ///
+    /// assert_eq!(json["tiny"].serialize_with(options), "1.5e-7");
+    /// assert_eq!(json["huge"].serialize_with(options), "1e300");
+    /// assert_eq!(json.serialize_with(options), r#"{"id":62701,"price":9.999,"tiny":1.5e-7,"huge":1e300}"#);
+    ///
+    /// let json = JSONParser::from(r#"{ "html": "</script><b>café & 🎉</b>" }"#).unwrap();
+    ///
+    /// assert_eq!(json.serialize_with(SerializeOptions::new()), r#"{"html":"</script><b>café & 🎉</b>"}"#);
+    /// assert_eq!(
+    ///     json.serialize_with(SerializeOptions::new().html_safe().escape_slash()),
+    ///     r#"{"html":"\u003c\/script\u003e\u003cb\u003ecafé \u0026 🎉\u003c\/b\u003e"}"#
+    /// );
+    /// assert_eq!(
+    ///     json.serialize_with(SerializeOptions::new().escape_non_ascii()),
+    ///     r#"{"html":"</script><b>caf\u00e9 & \ud83c\udf89</b>"}"#
+    /// );
+    /// ```
+    pub fn serialize_with(&self, options: SerializeOptions) -> String {
+        match self {
+            JSONValue::String(s) => options.escape_string(s),
+            JSONValue::Number(n) => options.format_number(*n),
+            JSONValue::Boolean(b) => b.to_string(),
+            JSONValue::Null => "null".to_string(),
+            JSONValue::Raw(raw) => raw.as_str().to_string(),
+            JSONValue::Array(arr) => {
+                let parts = arr.iter().map(|value| value.serialize_with(options)).collect::<Vec<String>>();
+
+                format!("[{}]", parts.join(","))
+            },
+            JSONValue::Object(obj) => {
+                let parts = obj.iter().map(|(key, value)| {
+                    format!("{}:{}", options.escape_string(key), value.serialize_with(options))
+                }).collect::<Vec<String>>();
+
+                format!("{{{}}}", parts.join(","))
+            }
+        }
+    }
+}
+
+impl Serialize for str {
+    fn serialize(&self) -> String {
+        escape(self)
+    }
+
+    fn to_value(&self) -> JSONValue {
+        JSONValue::String(self.into())
+    }
+}
+
+impl Serialize for String {
+    fn serialize(&self) -> String {
+        escape(self)
+    }
+
+    fn to_value(&self) -> JSONValue {
+        JSONValue::String(self.clone().into())
+    }
+}
+
+impl Serialize for bool {
+    fn serialize(&self) -> String {
+        self.to_string()
+    }
+
+    fn to_value(&self) -> JSONValue {
+        JSONValue::Boolean(*self)
+    }
+}
+
+macro_rules! serialize_number {
+    ($($t:ty),*) => {
+        $(
+            impl Serialize for $t {
+                fn serialize(&self) -> String {
+                    self.to_value().serialize()
+                }
+
+                fn to_value(&self) -> JSONValue {
+                    JSONValue::Number(*self as f64)
+                }
+            }
+        )*
+    };
+}
+
+serialize_number!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+impl<T: Serialize + ?Sized> Serialize for &T {
+    fn serialize(&self) -> String {
+        (**self).serialize()
+    }
+
+    fn to_value(&self) -> JSONValue {
+        (**self).to_value()
+    }
+}
+
+impl<T: Serialize> Serialize for Option<T> {
+    fn serialize(&self) -> String {
+        match self {
+            Some(value) => value.serialize(),
+            None => "null".to_string()
+        }
+    }
+
+    fn to_value(&self) -> JSONValue {
+        match self {
+            Some(value) => value.to_value(),
+            None => JSONValue::Null
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for [T] {
+    fn serialize(&self) -> String {
+        let parts = self.iter().map(|value| value.serialize()).collect::<Vec<String>>();
+
+        format!("[{}]", parts.join(","))
+    }
+
+    fn to_value(&self) -> JSONValue {
+        JSONValue::Array(self.iter().map(|value| value.to_value()).collect())
+    }
+}
+
+impl<T: Serialize> Serialize for Vec<T> {
+    fn serialize(&self) -> String {
+        self.as_slice().serialize()
+    }
+
+    fn to_value(&self) -> JSONValue {
+        self.as_slice().to_value()
+    }
+}
+
+/// Entries are sorted by key so that the output does not depend on the hash order.
+#[cfg(feature = "std")]
+impl<T: Serialize, S> Serialize for HashMap<String, T, S> {
+    fn serialize(&self) -> String {
+        let mut entries = self.iter().collect::<Vec<_>>();
+
+        entries.sort_by_key(|(key, _)| *key);
+        let parts = entries.iter().map(|(key, value)| {
+            format!("{}:{}", escape(key), value.serialize())
+        }).collect::<Vec<String>>();
+
+        format!("{{{}}}", parts.join(","))
+    }
+
+    fn to_value(&self) -> JSONValue {
+        let mut object = OrderedMap::new();
+
+        for (key, value) in self {
+            object.insert(key, value.to_value());
+        }
+        object.sort_keys();
+        JSONValue::Object(object)
+    }
+}
+
+macro_rules! serialize_tuple {
+    ($($name:ident : $index:tt),*) => {
+        /// Tuples serialize to arrays holding each element in order.
+        impl<$($name: Serialize),*> Serialize for ($($name,)*) {
+            fn serialize(&self) -> String {
+                let parts: Vec<String> = vec![$(self.$index.serialize()),*];
+
+                format!("[{}]", parts.join(","))
+            }
+
+            fn to_value(&self) -> JSONValue {
+                JSONValue::Array(vec![$(self.$index.to_value()),*].into())
+            }
+        }
+    };
+}
+
+serialize_tuple!(A: 0);
+serialize_tuple!(A: 0, B: 1);
+serialize_tuple!(A: 0, B: 1, C: 2);
+serialize_tuple!(A: 0, B: 1, C: 2, D: 3);
+serialize_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4);
+serialize_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
@@ -0,0 +1,98 @@
+use crate::utils::parser::ParserBuffers;
+use crate::utils::{JSONValue, Lexer, ParseError, Parser, ParserOptions};
+
+/// The default number of interned keys past which a session forgets them.
+const MAX_INTERNED_KEYS: usize = 4096;
+
+/// A parser that can be reused across many documents.
+/// Object keys interned by one parse are kept for the next ones, so repeated
+/// messages sharing the same shape reuse both the key strings and the intern table.
+/// The buffer in which strings with escapes are decoded and the stack of array items
+/// keep their capacity from parse to parse as well.
+///
+/// Documents of changing shapes would grow the intern table without bound, so it is
+/// cleared once it holds more than `max_interned_keys` keys after a parse.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::ParserSession;
+///
+/// let mut session = ParserSession::new();
+///
+/// for message in [r#"{ "id": 1 }"#, r#"{ "id": 2 }"#, r#"{ "id": 3 }"#] {
+///     let json = session.parse(message).unwrap();
+///
+///     assert!(json["id"].as_f64().is_some());
+/// }
+///
+/// assert_eq!(session.interned_keys(), 1);
+/// ```
+#[derive(Debug)]
+pub struct ParserSession {
+    options: ParserOptions,
+    buffers: ParserBuffers,
+    max_interned_keys: usize
+}
+
+impl Default for ParserSession {
+    fn default() -> Self {
+        Self::with_options(ParserOptions::new())
+    }
+}
+
+impl ParserSession {
+    /// Create a new ParserSession instance with the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new ParserSession instance applying the given options to every parse.
+    pub fn with_options(options: ParserOptions) -> Self {
+        Self { options, buffers: ParserBuffers::default(), max_interned_keys: MAX_INTERNED_KEYS }
+    }
+
+    /// Set the number of interned keys past which they are forgotten, 4096 by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::ParserSession;
+    ///
+    /// let mut session = ParserSession::new().max_interned_keys(2);
+    ///
+    /// session.parse(r#"{ "a": 1, "b": 2 }"#).unwrap();
+    /// assert_eq!(session.interned_keys(), 2);
+    ///
+    /// session.parse(r#"{ "c": 3 }"#).unwrap();
+    /// assert_eq!(session.interned_keys(), 0);
+    /// ```
+    pub fn max_interned_keys(mut self, max: usize) -> Self {
+        self.max_interned_keys = max;
+        self
+    }
+
+    /// Parse the JSON input to a JSONValue, reusing the buffers of earlier parses.
+    pub fn parse(&mut self, input: &str) -> Result<JSONValue, ParseError> {
+        let buffers = core::mem::take(&mut self.buffers);
+        let mut parser = Parser::with_options(Lexer::new(input), self.options).with_buffers(buffers);
+        let result = parser.parse();
+
+        self.buffers = parser.into_buffers();
+        if self.buffers.keys.len() > self.max_interned_keys {
+            self.buffers.keys.clear();
+        }
+        result
+    }
+
+    /// Number of distinct keys currently interned by the session.
+    pub fn interned_keys(&self) -> usize {
+        self.buffers.keys.len()
+    }
+
+    /// Forget the interned keys while keeping the allocated capacity,
+    /// for instance when the shape of incoming documents changes.
+    pub fn reset(&mut self) {
+        self.buffers.keys.clear();
+    }
+}
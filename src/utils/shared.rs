@@ -0,0 +1,214 @@
+use alloc::sync::Arc;
+
+use crate::utils::compat::*;
+use crate::utils::pointer::parse_pointer;
+use crate::utils::{JSONKind, JSONString, JSONValue, OrderedMap, Serialize};
+
+/// A JSON value whose strings, arrays and objects are reference-counted, so that cloning
+/// it is O(1) and clones share their subtrees, such as for caches holding many
+/// near-identical documents. Changing a value through `as_object_mut`, `as_array_mut` or
+/// `pointer_mut` copies the containers on its path that are shared, and only those.
+///
+/// Raw values are parsed when converting from a `JSONValue`.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{JSONParser, SharedJSONValue};
+///
+/// let json = JSONParser::from(r#"{ "user": { "name": "John" }, "items": [1, 2, 3] }"#).unwrap();
+/// let cached = SharedJSONValue::from(&json);
+///
+/// let mut copy = cached.clone();
+/// *copy.pointer_mut("/user/name").unwrap() = SharedJSONValue::from("Jane");
+///
+/// assert_eq!(cached.pointer("/user/name").and_then(|name| name.as_str()), Some("John"));
+/// assert_eq!(copy.pointer("/user/name").and_then(|name| name.as_str()), Some("Jane"));
+///
+/// // The items were not changed, so both documents still share them.
+/// assert!(copy.get("items").unwrap().ptr_eq(cached.get("items").unwrap()));
+/// assert!(!copy.get("user").unwrap().ptr_eq(cached.get("user").unwrap()));
+///
+/// assert_eq!(copy.to_value()["user"]["name"].as_str(), Some("Jane"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum SharedJSONValue {
+    Object(Arc<OrderedMap<SharedJSONValue>>),
+    Array(Arc<Vec<SharedJSONValue>>),
+    String(Arc<str>),
+    Number(f64),
+    Boolean(bool),
+    Null
+}
+
+impl From<&JSONValue> for SharedJSONValue {
+    fn from(value: &JSONValue) -> Self {
+        match value {
+            JSONValue::Object(obj) => SharedJSONValue::Object(Arc::new(obj.iter().map(|(key, value)| (key.to_string(), value.into())).collect())),
+            JSONValue::Array(array) => SharedJSONValue::Array(Arc::new(array.iter().map(SharedJSONValue::from).collect())),
+            JSONValue::String(s) => SharedJSONValue::String(Arc::from(s.as_str())),
+            JSONValue::Number(n) => SharedJSONValue::Number(*n),
+            JSONValue::Boolean(b) => SharedJSONValue::Boolean(*b),
+            JSONValue::Null => SharedJSONValue::Null,
+            JSONValue::Raw(raw) => SharedJSONValue::from(&raw.to_value())
+        }
+    }
+}
+
+impl From<JSONValue> for SharedJSONValue {
+    fn from(value: JSONValue) -> Self {
+        SharedJSONValue::from(&value)
+    }
+}
+
+impl From<&str> for SharedJSONValue {
+    fn from(value: &str) -> Self {
+        SharedJSONValue::String(Arc::from(value))
+    }
+}
+
+impl SharedJSONValue {
+    /// Returns the kind of the value.
+    pub fn kind(&self) -> JSONKind {
+        match self {
+            SharedJSONValue::Object(_) => JSONKind::Object,
+            SharedJSONValue::Array(_) => JSONKind::Array,
+            SharedJSONValue::String(_) => JSONKind::String,
+            SharedJSONValue::Number(_) => JSONKind::Number,
+            SharedJSONValue::Boolean(_) => JSONKind::Boolean,
+            SharedJSONValue::Null => JSONKind::Null
+        }
+    }
+
+    /// Returns the string, if the value is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            SharedJSONValue::String(s) => Some(s),
+            _ => None
+        }
+    }
+
+    /// Returns the number, if the value is a number.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            SharedJSONValue::Number(n) => Some(*n),
+            _ => None
+        }
+    }
+
+    /// Returns the boolean, if the value is a boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            SharedJSONValue::Boolean(b) => Some(*b),
+            _ => None
+        }
+    }
+
+    /// Returns true if the value is null.
+    pub fn is_null(&self) -> bool {
+        *self == SharedJSONValue::Null
+    }
+
+    /// Returns the number of entries of an object, items of an array or characters of a
+    /// string, and 0 for other values.
+    pub fn len(&self) -> usize {
+        match self {
+            SharedJSONValue::Object(obj) => obj.len(),
+            SharedJSONValue::Array(array) => array.len(),
+            SharedJSONValue::String(s) => s.chars().count(),
+            _ => 0
+        }
+    }
+
+    /// Returns true if `len()` is 0.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the value of the given key, if the value is an object holding it.
+    pub fn get(&self, key: &str) -> Option<&SharedJSONValue> {
+        match self {
+            SharedJSONValue::Object(obj) => obj.get(key),
+            _ => None
+        }
+    }
+
+    /// Returns the item at the given index, if the value is an array holding it.
+    pub fn get_index(&self, index: usize) -> Option<&SharedJSONValue> {
+        match self {
+            SharedJSONValue::Array(array) => array.get(index),
+            _ => None
+        }
+    }
+
+    /// Returns the value at the given JSON Pointer, as `JSONValue::pointer` does.
+    pub fn pointer(&self, pointer: &str) -> Option<&SharedJSONValue> {
+        parse_pointer(pointer).ok()?.iter().try_fold(self, |value, token| match value {
+            SharedJSONValue::Object(obj) => obj.get(token),
+            SharedJSONValue::Array(array) => token.parse::<usize>().ok().and_then(|index| array.get(index)),
+            _ => None
+        })
+    }
+
+    /// Returns a mutable reference to the value at the given JSON Pointer, copying the
+    /// shared containers on its path. Nothing is copied when the pointer does not resolve.
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut SharedJSONValue> {
+        self.pointer(pointer)?;
+        parse_pointer(pointer).ok()?.iter().try_fold(self, |value, token| match value {
+            SharedJSONValue::Object(obj) => Arc::make_mut(obj).get_mut(token),
+            SharedJSONValue::Array(array) => token.parse::<usize>().ok().and_then(move |index| Arc::make_mut(array).get_mut(index)),
+            _ => None
+        })
+    }
+
+    /// Returns the entries of an object for changing them, copying them first if they
+    /// are shared. The entries themselves are still shared with the copies.
+    pub fn as_object_mut(&mut self) -> Option<&mut OrderedMap<SharedJSONValue>> {
+        match self {
+            SharedJSONValue::Object(obj) => Some(Arc::make_mut(obj)),
+            _ => None
+        }
+    }
+
+    /// Returns the items of an array for changing them, copying them first if they are
+    /// shared. The items themselves are still shared with the copies.
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<SharedJSONValue>> {
+        match self {
+            SharedJSONValue::Array(array) => Some(Arc::make_mut(array)),
+            _ => None
+        }
+    }
+
+    /// Returns true if both values are the same string, array or object in memory, as
+    /// for a value and its clone until either is changed.
+    pub fn ptr_eq(&self, other: &SharedJSONValue) -> bool {
+        match (self, other) {
+            (SharedJSONValue::Object(a), SharedJSONValue::Object(b)) => Arc::ptr_eq(a, b),
+            (SharedJSONValue::Array(a), SharedJSONValue::Array(b)) => Arc::ptr_eq(a, b),
+            (SharedJSONValue::String(a), SharedJSONValue::String(b)) => Arc::ptr_eq(a, b),
+            _ => false
+        }
+    }
+
+    /// Copy the value into a `JSONValue`.
+    pub fn to_value(&self) -> JSONValue {
+        match self {
+            SharedJSONValue::Object(obj) => JSONValue::Object(obj.iter().map(|(key, value)| (key.to_string(), value.to_value())).collect()),
+            SharedJSONValue::Array(array) => JSONValue::Array(array.iter().map(SharedJSONValue::to_value).collect()),
+            SharedJSONValue::String(s) => JSONValue::String(JSONString::from(&**s)),
+            SharedJSONValue::Number(n) => JSONValue::Number(*n),
+            SharedJSONValue::Boolean(b) => JSONValue::Boolean(*b),
+            SharedJSONValue::Null => JSONValue::Null
+        }
+    }
+}
+
+impl Serialize for SharedJSONValue {
+    fn serialize(&self) -> String {
+        self.to_value().serialize()
+    }
+
+    fn to_value(&self) -> JSONValue {
+        SharedJSONValue::to_value(self)
+    }
+}
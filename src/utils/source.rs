@@ -0,0 +1,132 @@
+#[cfg(feature = "std")]
+use std::io::{ErrorKind, Read};
+
+/// A source of characters for the lexer, either a string slice or a reader
+/// decoded as UTF-8 on the fly.
+pub(crate) enum Source<'a> {
+    Str(core::str::Chars<'a>),
+    #[cfg(feature = "std")]
+    Reader(ReadChars<'a>)
+}
+
+impl<'a> Source<'a> {
+    /// Returns the error that stopped a reader, if any.
+    pub(crate) fn error(&self) -> Option<&str> {
+        match self {
+            Source::Str(_) => None,
+            #[cfg(feature = "std")]
+            Source::Reader(reader) => reader.error.as_deref()
+        }
+    }
+
+    /// The remaining input, when it is held in memory.
+    pub(crate) fn as_str(&self) -> Option<&'a str> {
+        match self {
+            Source::Str(chars) => Some(chars.as_str()),
+            #[cfg(feature = "std")]
+            Source::Reader(_) => None
+        }
+    }
+
+    /// Skip the next `len` bytes of an in-memory input, which must end on a char boundary.
+    pub(crate) fn advance(&mut self, len: usize) {
+        match self {
+            Source::Str(chars) => *chars = chars.as_str()[len..].chars(),
+            #[cfg(feature = "std")]
+            Source::Reader(_) => {}
+        }
+    }
+}
+
+impl<'a> Iterator for Source<'a> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        match self {
+            Source::Str(chars) => chars.next(),
+            #[cfg(feature = "std")]
+            Source::Reader(reader) => reader.next()
+        }
+    }
+}
+
+/// Decode UTF-8 characters from a reader through an internal buffer.
+#[cfg(feature = "std")]
+pub(crate) struct ReadChars<'a> {
+    reader: Box<dyn Read + 'a>,
+    buffer: Vec<u8>,
+    start: usize,
+    end: usize,
+    error: Option<String>
+}
+
+#[cfg(feature = "std")]
+impl<'a> ReadChars<'a> {
+    pub(crate) fn new<R: Read + 'a>(reader: R) -> Self {
+        Self {
+            reader: Box::new(reader),
+            buffer: vec![0; 8192],
+            start: 0,
+            end: 0,
+            error: None
+        }
+    }
+
+    /// Make sure at least `needed` bytes are buffered. Returns false at the end of the input.
+    fn fill(&mut self, needed: usize) -> bool {
+        if self.end - self.start >= needed {
+            return true;
+        }
+        self.buffer.copy_within(self.start..self.end, 0);
+        self.end -= self.start;
+        self.start = 0;
+
+        while self.end < needed {
+            match self.reader.read(&mut self.buffer[self.end..]) {
+                Ok(0) => return false,
+                Ok(read) => self.end += read,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.error = Some(format!("Failed to read input: {}", e));
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> Iterator for ReadChars<'a> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        if self.error.is_some() || !self.fill(1) {
+            return None;
+        }
+        let width = match self.buffer[self.start] {
+            0x00..=0x7F => 1,
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => 0
+        };
+
+        if width == 0 || !self.fill(width) {
+            if self.error.is_none() {
+                self.error = Some("Invalid UTF-8 input".to_string());
+            }
+            return None;
+        }
+        match core::str::from_utf8(&self.buffer[self.start..self.start + width]) {
+            Ok(s) => {
+                self.start += width;
+                s.chars().next()
+            },
+            Err(_) => {
+                self.error = Some("Invalid UTF-8 input".to_string());
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use core::fmt;
+use core::mem::size_of;
+use alloc::sync::Arc;
+
+use crate::utils::compat::*;
+use crate::utils::{JSONValue, Walk};
+
+/// A structural summary of a value, returned by `JSONValue::stats`.
+/// Paths are JSON Pointers, the root having the empty path.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Stats {
+    pub objects: usize,
+    pub arrays: usize,
+    pub strings: usize,
+    pub numbers: usize,
+    pub booleans: usize,
+    pub nulls: usize,
+    /// The number of nested objects and arrays on the deepest path, 0 for a scalar.
+    pub max_depth: usize,
+    /// The path and length of the array with the most items.
+    pub largest_array: Option<(String, usize)>,
+    /// The path and length in bytes of the longest string value.
+    pub longest_string: Option<(String, usize)>,
+    /// An estimate of the heap and inline memory used by the value, in bytes.
+    pub estimated_memory: usize,
+    /// How many times each object key appears, most frequent first, then by key.
+    pub key_frequency: Vec<(String, usize)>
+}
+
+impl Stats {
+    /// The total number of values, containers included.
+    pub fn nodes(&self) -> usize {
+        self.objects + self.arrays + self.strings + self.numbers + self.booleans + self.nulls
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let located = |value: &Option<(String, usize)>| match value {
+            Some((path, len)) => format!("{} at {}", len, if path.is_empty() { "/" } else { path }),
+            None => "none".to_string()
+        };
+
+        writeln!(f, "nodes: {}", self.nodes())?;
+        writeln!(f, "  objects: {}", self.objects)?;
+        writeln!(f, "  arrays: {}", self.arrays)?;
+        writeln!(f, "  strings: {}", self.strings)?;
+        writeln!(f, "  numbers: {}", self.numbers)?;
+        writeln!(f, "  booleans: {}", self.booleans)?;
+        writeln!(f, "  nulls: {}", self.nulls)?;
+        writeln!(f, "max depth: {}", self.max_depth)?;
+        writeln!(f, "largest array: {}", located(&self.largest_array))?;
+        writeln!(f, "longest string: {}", located(&self.longest_string))?;
+        writeln!(f, "estimated memory: {} bytes", self.estimated_memory)?;
+        write!(f, "keys: {}", self.key_frequency.len())?;
+        for (key, count) in self.key_frequency.iter() {
+            write!(f, "\n  {}: {}", key, count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Keep the located length when it is larger than the current one.
+fn keep_largest(current: &mut Option<(String, usize)>, path: &str, len: usize) {
+    if current.as_ref().map_or(true, |(_, max)| len > *max) {
+        *current = Some((path.to_string(), len));
+    }
+}
+
+impl JSONValue {
+    /// Summarize the structure of the value in a single pass: the number of values
+    /// of each type, the maximum depth, the largest array, the longest string, an
+    /// estimate of the memory used, and how often each key appears.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{ "name": "John Doe", "cars": [{ "model": "Ford" }, { "model": "BMW" }, null] }"#).unwrap();
+    /// let stats = json.stats();
+    ///
+    /// assert_eq!(stats.nodes(), 8);
+    /// assert_eq!((stats.objects, stats.arrays, stats.strings, stats.nulls), (3, 1, 3, 1));
+    /// assert_eq!(stats.max_depth, 3);
+    /// assert_eq!(stats.largest_array, Some(("/cars".to_string(), 3)));
+    /// assert_eq!(stats.longest_string, Some(("/name".to_string(), 8)));
+    /// assert_eq!(stats.key_frequency[0], ("model".to_string(), 2));
+    /// ```
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats::default();
+        let mut keys: Map<&str, usize> = Map::new();
+
+        self.walk(|path, value| {
+            let depth = path.matches('/').count();
+
+            stats.estimated_memory += size_of::<JSONValue>();
+            match value {
+                JSONValue::Object(obj) => {
+                    stats.objects += 1;
+                    stats.max_depth = stats.max_depth.max(depth + 1);
+                    for (key, _) in obj.iter() {
+                        // The key is stored once, behind a reference count, next to its value.
+                        stats.estimated_memory += key.len() + 2 * size_of::<usize>() + size_of::<Arc<str>>();
+                        *keys.entry(key).or_insert(0) += 1;
+                    }
+                },
+                JSONValue::Array(array) => {
+                    stats.arrays += 1;
+                    stats.max_depth = stats.max_depth.max(depth + 1);
+                    keep_largest(&mut stats.largest_array, path, array.len());
+                },
+                JSONValue::String(s) => {
+                    stats.strings += 1;
+                    if !s.is_inline() {
+                        stats.estimated_memory += s.len();
+                    }
+                    keep_largest(&mut stats.longest_string, path, s.len());
+                },
+                JSONValue::Number(_) => stats.numbers += 1,
+                JSONValue::Boolean(_) => stats.booleans += 1,
+                JSONValue::Null => stats.nulls += 1,
+                JSONValue::Raw(raw) => stats.estimated_memory += raw.as_str().len()
+            }
+            Walk::Continue
+        });
+
+        stats.key_frequency = keys.into_iter().map(|(key, count)| (key.to_string(), count)).collect();
+        stats.key_frequency.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+        stats
+    }
+}
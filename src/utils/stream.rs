@@ -0,0 +1,62 @@
+use crate::utils::compat::*;
+use crate::utils::lexer::TokenKind;
+use crate::utils::Parser;
+use crate::{JSONValue, ParseError};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum State {
+    Start,
+    Items,
+    Done
+}
+
+/// An iterator over the items of a top-level JSON array, parsing one item at a time.
+/// Created by `JSONParser::stream_array`. The iterator ends after the first error.
+pub struct ArrayStream<'a> {
+    parser: Parser<'a>,
+    state: State
+}
+
+impl<'a> ArrayStream<'a> {
+    #[cfg(feature = "std")]
+    pub(crate) fn new(parser: Parser<'a>) -> Self {
+        Self { parser, state: State::Start }
+    }
+
+    fn read_item(&mut self) -> Result<Option<JSONValue>, ParseError> {
+        if self.state == State::Start {
+            self.parser.next_token()?;
+            self.parser.expect(TokenKind::OpenBracket)?;
+            self.state = State::Items;
+            if self.parser.current_is(TokenKind::CloseBracket) {
+                return Ok(None);
+            }
+        }
+        let item = self.parser.parse_value()?;
+
+        if self.parser.current_is(TokenKind::Comma) {
+            self.parser.next_token()?;
+        } else if self.parser.current_is(TokenKind::CloseBracket) {
+            self.state = State::Done;
+        } else {
+            return Err(ParseError::Syntax("Expected ',' or ']' in array".to_string()));
+        }
+        Ok(Some(item))
+    }
+}
+
+impl<'a> Iterator for ArrayStream<'a> {
+    type Item = Result<JSONValue, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.state == State::Done {
+            return None;
+        }
+        let item = self.read_item();
+
+        if !matches!(item, Ok(Some(_))) {
+            self.state = State::Done;
+        }
+        item.transpose()
+    }
+}
@@ -0,0 +1,205 @@
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+
+use crate::utils::compat::*;
+
+/// The longest string, in bytes, stored without an allocation.
+const INLINE_LEN: usize = 22;
+
+/// The string held by `JSONValue::String`. Strings of up to 22 bytes, which are most of
+/// the strings of a typical document, are stored inline instead of in an allocation of
+/// their own. Longer strings are boxed without spare capacity. Either way it takes three
+/// words, like a `String`, and reads as a `&str`.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{JSONString, JSONValue};
+///
+/// let name = JSONString::from("John");
+/// let bio = JSONString::from("x".repeat(100));
+///
+/// assert!(name.is_inline());
+/// assert!(!bio.is_inline());
+/// assert_eq!(name, "John");
+/// assert_eq!(bio.len(), 100);
+/// assert_eq!(JSONValue::String(name).as_str(), Some("John"));
+/// assert_eq!(std::mem::size_of::<JSONString>(), 3 * std::mem::size_of::<usize>());
+/// ```
+#[derive(Clone)]
+pub struct JSONString(Repr);
+
+#[derive(Clone)]
+enum Repr {
+    Inline { len: u8, bytes: [u8; INLINE_LEN] },
+    Heap(Box<str>)
+}
+
+impl JSONString {
+    /// Create an empty string.
+    pub fn new() -> Self {
+        JSONString(Repr::Inline { len: 0, bytes: [0; INLINE_LEN] })
+    }
+
+    /// Returns the string as a `&str`.
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            // SAFETY: the inline bytes are copied from a `&str` up to `len`, which ends on a
+            // character boundary, and are never changed afterwards.
+            Repr::Inline { len, bytes } => unsafe { core::str::from_utf8_unchecked(&bytes[..*len as usize]) },
+            Repr::Heap(s) => s
+        }
+    }
+
+    /// Whether the string is stored inline rather than in an allocation.
+    pub fn is_inline(&self) -> bool {
+        matches!(self.0, Repr::Inline { .. })
+    }
+
+    /// Consumes the string and returns it as a `String`. A boxed string is reused as is.
+    pub fn into_string(self) -> String {
+        match self.0 {
+            Repr::Heap(s) => s.into_string(),
+            Repr::Inline { .. } => self.as_str().to_string()
+        }
+    }
+
+    fn inline(s: &str) -> Option<Self> {
+        if s.len() > INLINE_LEN {
+            return None;
+        }
+
+        let mut bytes = [0; INLINE_LEN];
+
+        bytes[..s.len()].copy_from_slice(s.as_bytes());
+        Some(JSONString(Repr::Inline { len: s.len() as u8, bytes }))
+    }
+}
+
+impl Default for JSONString {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for JSONString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for JSONString {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for JSONString {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<&str> for JSONString {
+    fn from(s: &str) -> Self {
+        Self::inline(s).unwrap_or_else(|| JSONString(Repr::Heap(Box::from(s))))
+    }
+}
+
+impl From<String> for JSONString {
+    fn from(s: String) -> Self {
+        Self::inline(&s).unwrap_or_else(|| JSONString(Repr::Heap(s.into_boxed_str())))
+    }
+}
+
+impl From<JSONString> for String {
+    fn from(s: JSONString) -> Self {
+        s.into_string()
+    }
+}
+
+impl FromIterator<char> for JSONString {
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        JSONString::from(iter.into_iter().collect::<String>())
+    }
+}
+
+impl PartialEq for JSONString {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for JSONString {}
+
+impl PartialEq<str> for JSONString {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for JSONString {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for JSONString {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<JSONString> for str {
+    fn eq(&self, other: &JSONString) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<JSONString> for &str {
+    fn eq(&self, other: &JSONString) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl PartialEq<JSONString> for String {
+    fn eq(&self, other: &JSONString) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialOrd for JSONString {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for JSONString {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+/// Hashes like the `str` it holds, as `Borrow<str>` requires.
+impl Hash for JSONString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl fmt::Debug for JSONString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for JSONString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,208 @@
+//! Conformance checks for the tests of crates using the parser: running a
+//! [JSONTestSuite](https://github.com/nst/JSONTestSuite) corpus and asserting that
+//! values survive a serialize and parse round trip.
+
+use core::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::utils::{decode, JSONValue, Lexer, Parser, Serialize};
+
+/// What the suite expects of the parser for a case, given by the prefix of its file name.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Expectation {
+    /// `y_`: the input is valid JSON and must be accepted.
+    Accept,
+    /// `n_`: the input is invalid JSON and must be rejected.
+    Reject,
+    /// `i_`: the specification leaves it to the parser, which may accept or reject it.
+    Either
+}
+
+impl Expectation {
+    /// The expectation given by the prefix of a file name, if it has one.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.get(..2) {
+            Some("y_") => Some(Expectation::Accept),
+            Some("n_") => Some(Expectation::Reject),
+            Some("i_") => Some(Expectation::Either),
+            _ => None
+        }
+    }
+}
+
+/// The outcome of a case of the suite.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CaseReport {
+    pub name: String,
+    pub expected: Expectation,
+    /// The error of the parser, None when the input was accepted.
+    pub error: Option<String>,
+    /// Why the accepted value did not survive a round trip, as given by `check_round_trip`.
+    pub round_trip_error: Option<String>
+}
+
+impl CaseReport {
+    /// Whether the parser accepted the input.
+    pub fn accepted(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Whether the parser behaved as expected. An accepted input must also survive a round trip.
+    pub fn passed(&self) -> bool {
+        let expected = match self.expected {
+            Expectation::Accept => self.accepted(),
+            Expectation::Reject => !self.accepted(),
+            Expectation::Either => true
+        };
+
+        expected && self.round_trip_error.is_none()
+    }
+}
+
+impl fmt::Display for CaseReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = if self.passed() { "PASS" } else { "FAIL" };
+
+        match (&self.error, &self.round_trip_error) {
+            (_, Some(e)) => write!(f, "{} {}: accepted, but {}", status, self.name, e),
+            (Some(e), None) => write!(f, "{} {}: rejected ({})", status, self.name, e),
+            (None, None) => write!(f, "{} {}: accepted", status, self.name)
+        }
+    }
+}
+
+/// The outcomes of all the cases of a corpus, sorted by name.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct CorpusReport {
+    pub cases: Vec<CaseReport>
+}
+
+impl CorpusReport {
+    /// The cases where the parser did not behave as expected.
+    pub fn failures(&self) -> impl Iterator<Item = &CaseReport> {
+        self.cases.iter().filter(|case| !case.passed())
+    }
+
+    /// Whether every case passed.
+    pub fn is_success(&self) -> bool {
+        self.failures().next().is_none()
+    }
+}
+
+/// A line per failed case, followed by the number of cases that passed.
+impl fmt::Display for CorpusReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut failed = 0;
+
+        for case in self.failures() {
+            writeln!(f, "{}", case)?;
+            failed += 1;
+        }
+        write!(f, "{}/{} cases passed", self.cases.len() - failed, self.cases.len())
+    }
+}
+
+/// Parse an input as a case of the suite, named after its file. Returns None when the
+/// name has no `y_`, `n_` or `i_` prefix.
+///
+/// The input is decoded as `JSONParser::from_bytes` does, and its root can be any value.
+/// It is parsed with the default options, so the deeply nested cases of the suite fail on
+/// the `DEFAULT_MAX_DEPTH` limit rather than overflowing the stack.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::testutils::run_case;
+///
+/// assert!(run_case("y_structure_lonely_int.json", b"42").unwrap().passed());
+/// assert!(run_case("n_array_unclosed.json", b"[\"\"").unwrap().passed());
+/// assert!(run_case("n_structure_100000_opening_arrays.json", "[".repeat(100_000).as_bytes()).unwrap().passed());
+///
+/// let case = run_case("y_object_simple.json", b"{\"a\":[]").unwrap();
+///
+/// assert!(!case.passed());
+/// assert!(case.to_string().starts_with("FAIL y_object_simple.json: rejected"));
+///
+/// assert!(run_case("README.md", b"").is_none());
+/// ```
+pub fn run_case(name: &str, input: &[u8]) -> Option<CaseReport> {
+    let expected = Expectation::from_name(name)?;
+    let parsed = decode(input).and_then(|text| Parser::new(Lexer::new(&text)).parse_document().map_err(|e| e.to_string()));
+    let (error, round_trip_error) = match parsed {
+        Ok(value) => (None, check_round_trip(&value).err()),
+        Err(e) => (Some(e), None)
+    };
+
+    Some(CaseReport { name: name.to_string(), expected, error, round_trip_error })
+}
+
+/// Run every case of a directory holding a corpus, such as the `test_parsing` directory
+/// of JSONTestSuite. Files without a `y_`, `n_` or `i_` prefix are ignored.
+///
+/// # Example
+///
+/// ```no_run
+/// use jsonparser::testutils::run_corpus;
+///
+/// let report = run_corpus("JSONTestSuite/test_parsing").unwrap();
+///
+/// assert!(report.is_success(), "{}", report);
+/// ```
+pub fn run_corpus<P: AsRef<Path>>(dir: P) -> io::Result<CorpusReport> {
+    let mut cases = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+
+        if Expectation::from_name(&name).is_some() {
+            let input = fs::read(entry.path())?;
+
+            cases.extend(run_case(&name, &input));
+        }
+    }
+    cases.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(CorpusReport { cases })
+}
+
+/// Check that serializing the value and parsing the result gives the value back.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::JSONValue;
+/// use jsonparser::testutils::check_round_trip;
+///
+/// assert_eq!(check_round_trip(&JSONValue::Number(0.1)), Ok(()));
+/// assert_eq!(check_round_trip(&JSONValue::Number(f64::NAN)), Err("serializing gives invalid JSON: NaN".to_string()));
+/// ```
+pub fn check_round_trip(value: &JSONValue) -> Result<(), String> {
+    let text = value.serialize();
+    let parsed = Parser::new(Lexer::new(&text)).parse_document();
+
+    match parsed {
+        Ok(parsed) if parsed == *value => Ok(()),
+        Ok(parsed) => Err(format!("parsing {} gives {}", text, parsed.serialize())),
+        Err(_) => Err(format!("serializing gives invalid JSON: {}", text))
+    }
+}
+
+/// Panic with the reason when the value does not survive a round trip, as checked by
+/// `check_round_trip`. Meant for property tests, such as with values built by
+/// `JSONValue::arbitrary` when the `arbitrary` feature is enabled.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::JSONParser;
+/// use jsonparser::testutils::assert_round_trip;
+///
+/// assert_round_trip(&JSONParser::from(r#"{ "name": "café", "tags": [1e3, -0.5, null] }"#).unwrap());
+/// ```
+pub fn assert_round_trip(value: &JSONValue) {
+    if let Err(e) = check_round_trip(value) {
+        panic!("round trip failed: {}", e);
+    }
+}
@@ -0,0 +1,607 @@
+use crate::utils::compat::*;
+use crate::utils::parser::{edit_items, escape};
+use crate::utils::{JSONValue, OrderedMap, DEFAULT_MAX_DEPTH};
+
+fn is_bare_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn toml_key(key: &str) -> String {
+    if is_bare_key(key) {
+        return key.to_string();
+    }
+    escape(key)
+}
+
+/// Whether an array is written as an array of tables rather than inline.
+fn is_table_array(value: &JSONValue) -> bool {
+    match value {
+        JSONValue::Array(array) => !array.is_empty() && array.iter().all(|item| item.is_object()),
+        _ => false
+    }
+}
+
+impl JSONValue {
+    /// Serialize an object as a TOML document. Nested objects become tables and
+    /// arrays of objects become arrays of tables. Null has no TOML equivalent, and
+    /// the items of an array must all have the same type, so both are reported as
+    /// errors giving the path of the offending value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{
+    ///   "package": { "name": "demo", "version": "0.1.0", "authors": ["Ann"] },
+    ///   "dependencies": { "log": "0.4", "my-crate": { "path": "../my crate", "optional": true } },
+    ///   "bin": [{ "name": "cli" }, { "name": "server" }]
+    /// }"#).unwrap();
+    ///
+    /// assert_eq!(json.to_toml_string(), Ok(concat!(
+    ///     "[package]\n",
+    ///     "name = \"demo\"\n",
+    ///     "version = \"0.1.0\"\n",
+    ///     "authors = [\"Ann\"]\n",
+    ///     "\n",
+    ///     "[dependencies]\n",
+    ///     "log = \"0.4\"\n",
+    ///     "\n",
+    ///     "[dependencies.my-crate]\n",
+    ///     "path = \"../my crate\"\n",
+    ///     "optional = true\n",
+    ///     "\n",
+    ///     "[[bin]]\n",
+    ///     "name = \"cli\"\n",
+    ///     "\n",
+    ///     "[[bin]]\n",
+    ///     "name = \"server\"\n"
+    /// ).to_string()));
+    ///
+    /// let json = JSONParser::from(r#"{ "server": { "ports": [80, "443"] } }"#).unwrap();
+    ///
+    /// assert_eq!(json.to_toml_string(), Err("Array at 'server.ports' mixes number and string".to_string()));
+    /// ```
+    pub fn to_toml_string(&self) -> Result<String, String> {
+        let obj = self.as_object().ok_or_else(|| format!("Expected an object, found {}", self.type_name()))?;
+        let mut output = String::new();
+
+        write_table(obj, &mut Vec::new(), &mut output)?;
+        Ok(output)
+    }
+
+    /// Parse a TOML document into an object. Tables become objects, and dates and
+    /// times, which JSON lacks, are kept as strings in their TOML form.
+    /// Infinite and NaN floats have no JSON equivalent and are rejected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONValue};
+    ///
+    /// let json = JSONValue::from_toml(r#"
+    ///     [package] # Cargo-style manifest
+    ///     name = "demo"
+    ///     edition = '2021'
+    ///     keywords = [
+    ///         "json",
+    ///         "toml",
+    ///     ]
+    ///
+    ///     [dependencies]
+    ///     log = { version = "0.4", features = ["std"] }
+    ///     serde.version = "1"
+    ///
+    ///     [[bin]]
+    ///     name = "cli"
+    ///     released = 2024-05-27T07:32:00Z
+    ///     threads = 0x10
+    /// "#).unwrap();
+    ///
+    /// assert_eq!(json, JSONParser::from(r#"{
+    ///   "package": { "name": "demo", "edition": "2021", "keywords": ["json", "toml"] },
+    ///   "dependencies": { "log": { "version": "0.4", "features": ["std"] }, "serde": { "version": "1" } },
+    ///   "bin": [{ "name": "cli", "released": "2024-05-27T07:32:00Z", "threads": 16 }]
+    /// }"#).unwrap());
+    /// assert!(JSONValue::from_toml("name = \"a\"\nname = \"b\"").is_err());
+    ///
+    /// let nested = format!("a = {}{}", "[".repeat(200_000), "]".repeat(200_000));
+    ///
+    /// assert_eq!(JSONValue::from_toml(&nested).unwrap_err(), "Maximum nesting depth exceeded (max: 128) (line: 1)");
+    /// ```
+    pub fn from_toml(input: &str) -> Result<JSONValue, String> {
+        TomlParser { input, offset: 0, depth: 0 }.document()
+    }
+}
+
+fn path_name(path: &[String], key: &str) -> String {
+    let mut segments = path.to_vec();
+
+    segments.push(key.to_string());
+    segments.join(".")
+}
+
+fn write_table(obj: &OrderedMap<JSONValue>, path: &mut Vec<String>, output: &mut String) -> Result<(), String> {
+    for (key, value) in obj.iter().filter(|(_, value)| !value.is_object() && !is_table_array(value)) {
+        output.push_str(&format!("{} = {}\n", toml_key(key), inline(value, &path_name(path, key))?));
+    }
+    for (key, value) in obj.iter() {
+        path.push(toml_key(key));
+        match value {
+            JSONValue::Object(table) => {
+                // A table holding only tables is defined implicitly by their headers.
+                if table.is_empty() || table.iter().any(|(_, value)| !value.is_object() && !is_table_array(value)) {
+                    if !output.is_empty() {
+                        output.push('\n');
+                    }
+                    output.push_str(&format!("[{}]\n", path.join(".")));
+                }
+                write_table(table, path, output)?;
+            },
+            JSONValue::Array(array) if is_table_array(value) => {
+                for table in array.iter().filter_map(|item| item.as_object()) {
+                    if !output.is_empty() {
+                        output.push('\n');
+                    }
+                    output.push_str(&format!("[[{}]]\n", path.join(".")));
+                    write_table(table, path, output)?;
+                }
+            },
+            _ => {}
+        }
+        path.pop();
+    }
+    Ok(())
+}
+
+fn inline(value: &JSONValue, path: &str) -> Result<String, String> {
+    let text = match value {
+        JSONValue::Null => return Err(format!("Null at '{}' has no TOML equivalent", path)),
+        JSONValue::Boolean(b) => b.to_string(),
+        JSONValue::String(s) => escape(s),
+        JSONValue::Number(n) if n.is_nan() => "nan".to_string(),
+        JSONValue::Number(n) if n.is_infinite() => if *n > 0.0 { "inf" } else { "-inf" }.to_string(),
+        JSONValue::Number(n) if n.fract() == 0.0 && n.abs() < 9007199254740992.0 => format!("{}", *n as i64),
+        JSONValue::Number(n) if n.fract() == 0.0 => format!("{:e}", n),
+        JSONValue::Number(n) => n.to_string(),
+        JSONValue::Raw(raw) => return inline(&raw.to_value(), path),
+        JSONValue::Array(array) => {
+            if let Some(first) = array.first() {
+                if let Some(other) = array.iter().find(|item| item.type_name() != first.type_name()) {
+                    return Err(format!("Array at '{}' mixes {} and {}", path, first.type_name(), other.type_name()));
+                }
+            }
+            let items = array.iter()
+                .enumerate()
+                .map(|(i, item)| inline(item, &format!("{}[{}]", path, i)))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            format!("[{}]", items.join(", "))
+        },
+        JSONValue::Object(obj) if obj.is_empty() => "{}".to_string(),
+        JSONValue::Object(obj) => {
+            let entries = obj.iter()
+                .map(|(key, value)| inline(value, &format!("{}.{}", path, key)).map(|value| format!("{} = {}", toml_key(key), value)))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            format!("{{ {} }}", entries.join(", "))
+        }
+    };
+
+    Ok(text)
+}
+
+/// Find the table at the given path, descending into the last table of arrays of tables.
+fn table_at<'a>(root: &'a mut OrderedMap<JSONValue>, path: &[String]) -> Result<&'a mut OrderedMap<JSONValue>, String> {
+    let mut table = root;
+
+    for (i, segment) in path.iter().enumerate() {
+        if !table.contains_key(segment) {
+            table.insert(segment, JSONValue::Object(OrderedMap::new()));
+        }
+        let in_tables = matches!(table.get(segment), Some(JSONValue::Array(array)) if array.last().map_or(false, |item| item.is_object()));
+        let mut value = table.get_mut(segment).expect("Key was just inserted");
+
+        if in_tables {
+            if let JSONValue::Array(array) = value {
+                value = array.last_mut().expect("Array is not empty");
+            }
+        }
+        table = match value {
+            JSONValue::Object(obj) => obj,
+            value => return Err(format!("Key '{}' is already defined as {}", path[..=i].join("."), value.type_name()))
+        };
+    }
+    Ok(table)
+}
+
+struct TomlParser<'a> {
+    input: &'a str,
+    offset: usize,
+    /// The nesting depth of the value being read, counting tables, dotted keys, arrays
+    /// and inline tables.
+    depth: usize
+}
+
+impl<'a> TomlParser<'a> {
+    fn error(&self, message: &str) -> String {
+        let line = self.input[..self.offset].matches('\n').count() + 1;
+
+        format!("{} (line: {})", message, line)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.offset..].chars().next()
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.offset..]
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        let found = self.rest().starts_with(prefix);
+
+        if found {
+            self.offset += prefix.len();
+        }
+        found
+    }
+
+    /// Go the given number of levels deeper, failing past `DEFAULT_MAX_DEPTH`.
+    fn nest(&mut self, levels: usize) -> Result<(), String> {
+        if self.depth + levels > DEFAULT_MAX_DEPTH {
+            return Err(self.error(&format!("Maximum nesting depth exceeded (max: {})", DEFAULT_MAX_DEPTH)));
+        }
+        self.depth += levels;
+        Ok(())
+    }
+
+    fn expect(&mut self, prefix: &str) -> Result<(), String> {
+        if !self.eat(prefix) {
+            return Err(self.error(&format!("Expected '{}'", prefix)));
+        }
+        Ok(())
+    }
+
+    fn skip_spaces(&mut self) {
+        while let Some(' ') | Some('\t') = self.peek() {
+            self.offset += 1;
+        }
+    }
+
+    /// Skip whitespace, newlines and comments.
+    fn skip_blank(&mut self) {
+        loop {
+            self.skip_spaces();
+            match self.peek() {
+                Some('\n') => self.offset += 1,
+                Some('\r') if self.rest().starts_with("\r\n") => self.offset += 2,
+                Some('#') => self.skip_comment(),
+                _ => break
+            }
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        if self.peek() == Some('#') {
+            self.offset += self.rest().find('\n').unwrap_or(self.rest().len());
+        }
+    }
+
+    fn end_of_line(&mut self) -> Result<(), String> {
+        self.skip_spaces();
+        self.skip_comment();
+        if self.eat("\n") || self.eat("\r\n") || self.peek().is_none() {
+            return Ok(());
+        }
+        Err(self.error("Expected the end of the line"))
+    }
+
+    fn document(&mut self) -> Result<JSONValue, String> {
+        let mut root = OrderedMap::new();
+        let mut current = Vec::new();
+        let mut defined = Set::new();
+
+        loop {
+            self.skip_blank();
+            if self.peek().is_none() {
+                break;
+            }
+            if self.eat("[[") {
+                current = self.header("]]")?;
+                let (last, parent) = current.split_last().expect("Keys are never empty");
+                let parent = table_at(&mut root, parent).map_err(|e| self.error(&e))?;
+
+                if !parent.contains_key(last) {
+                    parent.insert(last, JSONValue::Array(Box::new([])));
+                }
+                match parent.get_mut(last) {
+                    Some(JSONValue::Array(array)) if array.iter().all(|item| item.is_object()) => {
+                        edit_items(array, |items| items.push(JSONValue::Object(OrderedMap::new())));
+                    },
+                    _ => return Err(self.error(&format!("Key '{}' is not an array of tables", current.join("."))))
+                }
+                // Tables below this one may be defined again for the new item.
+                let prefix = format!("{}\n", current.join("\n"));
+
+                defined.retain(|path: &String| !path.starts_with(&prefix));
+            } else if self.eat("[") {
+                current = self.header("]")?;
+                if !defined.insert(current.join("\n")) {
+                    return Err(self.error(&format!("Table '{}' is defined twice", current.join("."))));
+                }
+                table_at(&mut root, &current).map_err(|e| self.error(&e))?;
+            } else {
+                let table = table_at(&mut root, &current).map_err(|e| self.error(&e))?;
+
+                self.depth = current.len();
+                self.key_value(table)?;
+            }
+            self.end_of_line()?;
+        }
+        Ok(JSONValue::Object(root))
+    }
+
+    fn header(&mut self, close: &str) -> Result<Vec<String>, String> {
+        self.skip_spaces();
+        let key = self.key()?;
+
+        self.skip_spaces();
+        self.expect(close)?;
+        if key.len() > DEFAULT_MAX_DEPTH {
+            return Err(self.error(&format!("Maximum nesting depth exceeded (max: {})", DEFAULT_MAX_DEPTH)));
+        }
+        Ok(key)
+    }
+
+    /// Parse a possibly dotted key.
+    fn key(&mut self) -> Result<Vec<String>, String> {
+        let mut segments = vec![self.simple_key()?];
+
+        loop {
+            self.skip_spaces();
+            if !self.eat(".") {
+                return Ok(segments);
+            }
+            self.skip_spaces();
+            segments.push(self.simple_key()?);
+        }
+    }
+
+    fn simple_key(&mut self) -> Result<String, String> {
+        match self.peek() {
+            Some('"') => self.basic_string(),
+            Some('\'') => self.literal_string(),
+            _ => {
+                let len = self.rest().find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-')).unwrap_or(self.rest().len());
+
+                if len == 0 {
+                    return Err(self.error("Expected a key"));
+                }
+                self.offset += len;
+                Ok(self.input[self.offset - len..self.offset].to_string())
+            }
+        }
+    }
+
+    fn key_value(&mut self, table: &mut OrderedMap<JSONValue>) -> Result<(), String> {
+        let key = self.key()?;
+
+        self.skip_spaces();
+        self.expect("=")?;
+        self.skip_spaces();
+        self.nest(key.len())?;
+        let value = self.value()?;
+
+        self.depth -= key.len();
+
+        let (last, parent) = key.split_last().expect("Keys are never empty");
+        let table = table_at(table, parent).map_err(|e| self.error(&e))?;
+
+        if table.contains_key(last) {
+            return Err(self.error(&format!("Key '{}' is defined twice", key.join("."))));
+        }
+        table.insert(last, value);
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<JSONValue, String> {
+        match self.peek() {
+            Some('"') | Some('\'') if self.rest().starts_with("\"\"\"") || self.rest().starts_with("'''") => {
+                self.multiline_string().map(|s| JSONValue::String(s.into()))
+            },
+            Some('"') => self.basic_string().map(|s| JSONValue::String(s.into())),
+            Some('\'') => self.literal_string().map(|s| JSONValue::String(s.into())),
+            Some('[') | Some('{') => {
+                self.nest(1)?;
+                let value = if self.peek() == Some('[') { self.array() } else { self.inline_table() };
+
+                self.depth -= 1;
+                value
+            },
+            _ if self.eat("true") => Ok(JSONValue::Boolean(true)),
+            _ if self.eat("false") => Ok(JSONValue::Boolean(false)),
+            _ => self.number_or_date()
+        }
+    }
+
+    fn array(&mut self) -> Result<JSONValue, String> {
+        let mut array = Vec::new();
+
+        self.expect("[")?;
+        loop {
+            self.skip_blank();
+            if self.eat("]") {
+                return Ok(JSONValue::Array(array.into()));
+            }
+            array.push(self.value()?);
+            self.skip_blank();
+            if !self.eat(",") {
+                self.skip_blank();
+                self.expect("]")?;
+                return Ok(JSONValue::Array(array.into()));
+            }
+        }
+    }
+
+    fn inline_table(&mut self) -> Result<JSONValue, String> {
+        let mut table = OrderedMap::new();
+
+        self.expect("{")?;
+        self.skip_spaces();
+        if self.eat("}") {
+            return Ok(JSONValue::Object(table));
+        }
+        loop {
+            self.skip_spaces();
+            self.key_value(&mut table)?;
+            self.skip_spaces();
+            if !self.eat(",") {
+                self.expect("}")?;
+                return Ok(JSONValue::Object(table));
+            }
+        }
+    }
+
+    fn basic_string(&mut self) -> Result<String, String> {
+        let mut value = String::new();
+
+        self.expect("\"")?;
+        loop {
+            match self.peek() {
+                Some('"') => {
+                    self.offset += 1;
+                    return Ok(value);
+                },
+                Some('\\') => value.push(self.escape()?),
+                Some('\n') | None => return Err(self.error("Unterminated string")),
+                Some(c) => {
+                    self.offset += c.len_utf8();
+                    value.push(c);
+                }
+            }
+        }
+    }
+
+    fn literal_string(&mut self) -> Result<String, String> {
+        self.expect("'")?;
+        let len = self.rest().find(['\'', '\n']).unwrap_or(self.rest().len());
+
+        self.offset += len;
+        let value = self.input[self.offset - len..self.offset].to_string();
+
+        self.expect("'").map_err(|_| self.error("Unterminated string"))?;
+        Ok(value)
+    }
+
+    /// Parse a multi-line basic or literal string. A newline right after the opening
+    /// delimiter is trimmed, and in basic strings a backslash at the end of a line
+    /// trims the whitespace that follows it.
+    fn multiline_string(&mut self) -> Result<String, String> {
+        let delimiter = &self.rest()[..3];
+        let literal = delimiter == "'''";
+        let mut value = String::new();
+
+        self.offset += 3;
+        let _ = self.eat("\n") || self.eat("\r\n");
+        loop {
+            if self.rest().starts_with(delimiter) {
+                // Up to two quotes may directly precede the closing delimiter.
+                let extra = self.rest()[3..].chars().take(2).take_while(|&c| c == delimiter.as_bytes()[0] as char).count();
+
+                value.push_str(&self.rest()[..extra]);
+                self.offset += extra + 3;
+                return Ok(value);
+            }
+            match self.peek() {
+                Some('\\') if !literal => {
+                    let after = self.rest()[1..].trim_start_matches([' ', '\t']);
+
+                    if after.starts_with('\n') || after.starts_with("\r\n") {
+                        self.offset = self.input.len() - after.trim_start().len();
+                    } else {
+                        value.push(self.escape()?);
+                    }
+                },
+                Some(c) => {
+                    self.offset += c.len_utf8();
+                    value.push(c);
+                },
+                None => return Err(self.error("Unterminated string"))
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, String> {
+        self.expect("\\")?;
+        let c = self.peek().ok_or_else(|| self.error("Unterminated string"))?;
+
+        self.offset += c.len_utf8();
+        let len = match c {
+            'b' => return Ok('\u{08}'),
+            't' => return Ok('\t'),
+            'n' => return Ok('\n'),
+            'f' => return Ok('\u{0C}'),
+            'r' => return Ok('\r'),
+            'e' => return Ok('\u{1B}'),
+            '"' => return Ok('"'),
+            '\\' => return Ok('\\'),
+            'u' => 4,
+            'U' => 8,
+            c => return Err(self.error(&format!("Invalid escape sequence '\\{}'", c)))
+        };
+        let code = self.rest().get(..len)
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .and_then(core::char::from_u32)
+            .ok_or_else(|| self.error("Invalid unicode escape"))?;
+
+        self.offset += len;
+        Ok(code)
+    }
+
+    fn number_or_date(&mut self) -> Result<JSONValue, String> {
+        let start = self.offset;
+        let token_len = |rest: &str| rest.find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.' | '_' | ':'))).unwrap_or(rest.len());
+        let mut len = token_len(self.rest());
+
+        if len == 0 {
+            return Err(self.error("Expected a value"));
+        }
+        self.offset += len;
+        let token = &self.input[start..self.offset];
+        let is_date = token.len() >= 10 && token.as_bytes()[4] == b'-' && token.as_bytes()[7] == b'-';
+        let is_time = token.len() >= 8 && token.as_bytes()[2] == b':';
+
+        // A space may separate the date from the time.
+        if is_date && token.len() == 10 && self.rest().starts_with(' ') && self.rest()[1..].starts_with(|c: char| c.is_ascii_digit()) {
+            len = token_len(&self.rest()[1..]);
+            self.offset += len + 1;
+        }
+        if is_date || is_time {
+            return Ok(JSONValue::String(self.input[start..self.offset].into()));
+        }
+
+        let digits = token.replace('_', "");
+        let (sign, unsigned) = match digits.strip_prefix('-') {
+            Some(unsigned) => (-1.0, unsigned),
+            None => (1.0, digits.strip_prefix('+').unwrap_or(&digits))
+        };
+        let radix = match unsigned.get(..2) {
+            Some("0x") => 16,
+            Some("0o") => 8,
+            Some("0b") => 2,
+            _ => 10
+        };
+        let number = match radix {
+            10 if matches!(unsigned, "inf" | "nan") => return Err(self.error(&format!("'{}' has no JSON equivalent", token))),
+            10 if unsigned.starts_with(|c: char| c.is_ascii_digit()) => unsigned.parse::<f64>().ok(),
+            10 => None,
+            radix => u64::from_str_radix(&unsigned[2..], radix).ok().map(|n| n as f64)
+        };
+
+        number.map(|n| JSONValue::Number(sign * n)).ok_or_else(|| {
+            self.offset = start;
+            self.error(&format!("Invalid value '{}'", token))
+        })
+    }
+}
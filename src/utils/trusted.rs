@@ -0,0 +1,230 @@
+//! The parser behind `JSONParser::from_trusted`, for input already known to be valid
+//! JSON. It reads the bytes of the input directly instead of going through tokens:
+//! strings without escapes are copied as one slice, numbers are read from the input
+//! without checking their grammar, and invalid escapes are replaced instead of rejected.
+
+use alloc::sync::Arc;
+
+use crate::utils::compat::*;
+use crate::utils::parser::intern;
+use crate::utils::scan;
+use crate::utils::{JSONString, JSONValue, Limit, OrderedMap, ParseError, DEFAULT_MAX_DEPTH};
+
+pub(crate) struct TrustedParser<'a> {
+    input: &'a str,
+    offset: usize,
+    depth: usize,
+    keys: Set<Arc<str>>
+}
+
+impl<'a> TrustedParser<'a> {
+    pub(crate) fn new(input: &'a str) -> Self {
+        Self { input, offset: 0, depth: 0, keys: Set::new() }
+    }
+
+    /// Parse a document whose root is an object, like `Parser::parse`.
+    pub(crate) fn parse(&mut self) -> Result<JSONValue, ParseError> {
+        self.skip_whitespace();
+        match self.bump() {
+            Some(b'{') => self.parse_object(),
+            byte => Err(self.unexpected(byte))
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<JSONValue, ParseError> {
+        self.skip_whitespace();
+        match self.bump() {
+            Some(b'{') => self.parse_object(),
+            Some(b'[') => self.parse_array(),
+            Some(b'"') => self.parse_string().map(JSONValue::String),
+            Some(b't') => self.parse_keyword("rue", JSONValue::Boolean(true)),
+            Some(b'f') => self.parse_keyword("alse", JSONValue::Boolean(false)),
+            Some(b'n') => self.parse_keyword("ull", JSONValue::Null),
+            Some(b'-') | Some(b'0'..=b'9') => Ok(self.parse_number()),
+            byte => Err(self.unexpected(byte))
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<JSONValue, ParseError> {
+        let mut object = OrderedMap::new();
+
+        self.enter()?;
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.offset += 1;
+        } else {
+            loop {
+                self.skip_whitespace();
+                match self.bump() {
+                    Some(b'"') => {},
+                    byte => return Err(self.unexpected(byte))
+                }
+                let key = self.parse_string()?;
+                let key = intern(&mut self.keys, &key);
+
+                self.skip_whitespace();
+                match self.bump() {
+                    Some(b':') => {},
+                    byte => return Err(self.unexpected(byte))
+                }
+                object.insert_shared(key, self.parse_value()?);
+                self.skip_whitespace();
+                match self.bump() {
+                    Some(b',') => {},
+                    Some(b'}') => break,
+                    byte => return Err(self.unexpected(byte))
+                }
+            }
+        }
+        self.depth -= 1;
+        Ok(JSONValue::Object(object))
+    }
+
+    fn parse_array(&mut self) -> Result<JSONValue, ParseError> {
+        let mut array = Vec::new();
+
+        self.enter()?;
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.offset += 1;
+        } else {
+            loop {
+                array.push(self.parse_value()?);
+                self.skip_whitespace();
+                match self.bump() {
+                    Some(b',') => {},
+                    Some(b']') => break,
+                    byte => return Err(self.unexpected(byte))
+                }
+            }
+        }
+        self.depth -= 1;
+        Ok(JSONValue::Array(array.into()))
+    }
+
+    /// Read a string after its opening quote. Control characters are kept, and escapes
+    /// that cannot be decoded become U+FFFD.
+    fn parse_string(&mut self) -> Result<JSONString, ParseError> {
+        let mut result = String::new();
+
+        loop {
+            let start = self.offset;
+
+            self.offset += scan::string_run(&self.input.as_bytes()[start..]);
+            match self.bump() {
+                // Most strings have no escape and are copied at once.
+                Some(b'"') if result.is_empty() => return Ok(JSONString::from(&self.input[start..self.offset - 1])),
+                Some(b'"') => {
+                    result.push_str(&self.input[start..self.offset - 1]);
+                    return Ok(result.into());
+                },
+                Some(b'\\') => {
+                    result.push_str(&self.input[start..self.offset - 1]);
+                    result.push(self.parse_escape());
+                },
+                Some(_) => result.push_str(&self.input[start..self.offset]),
+                None => return Err(ParseError::Syntax("Unterminated string".to_string()))
+            }
+        }
+    }
+
+    /// Decode an escape after its backslash. Only ASCII characters are consumed, so the
+    /// offset stays on a character boundary.
+    fn parse_escape(&mut self) -> char {
+        let c = match self.peek() {
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\u{08}',
+            Some(b'f') => '\u{0C}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                self.offset += 1;
+                return self.parse_unicode_escape();
+            },
+            _ => return '\u{FFFD}'
+        };
+
+        self.offset += 1;
+        c
+    }
+
+    fn parse_unicode_escape(&mut self) -> char {
+        let high = match self.parse_hex() {
+            Some(high) => high,
+            None => return '\u{FFFD}'
+        };
+
+        if (0xD800..0xDC00).contains(&high) && self.input[self.offset..].starts_with("\\u") {
+            self.offset += 2;
+            if let Some(low @ 0xDC00..=0xDFFF) = self.parse_hex() {
+                return char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)).unwrap_or('\u{FFFD}');
+            }
+        }
+        char::from_u32(high).unwrap_or('\u{FFFD}')
+    }
+
+    fn parse_hex(&mut self) -> Option<u32> {
+        let digits = self.input.get(self.offset..self.offset + 4)?;
+        let code = u32::from_str_radix(digits, 16).ok()?;
+
+        self.offset += 4;
+        Some(code)
+    }
+
+    /// Read a number after its first character, which is not checked against the JSON
+    /// grammar. Text that is not a number becomes NaN.
+    fn parse_number(&mut self) -> JSONValue {
+        let start = self.offset - 1;
+        let len = self.input.as_bytes()[self.offset..].iter()
+            .position(|b| !matches!(b, b'0'..=b'9' | b'.' | b'-' | b'+' | b'e' | b'E'))
+            .unwrap_or(self.input.len() - self.offset);
+
+        self.offset += len;
+        JSONValue::Number(self.input[start..self.offset].parse().unwrap_or(f64::NAN))
+    }
+
+    fn parse_keyword(&mut self, rest: &str, value: JSONValue) -> Result<JSONValue, ParseError> {
+        if !self.input[self.offset..].starts_with(rest) {
+            return Err(self.unexpected(self.input.as_bytes().get(self.offset - 1).copied()));
+        }
+        self.offset += rest.len();
+        Ok(value)
+    }
+
+    fn enter(&mut self) -> Result<(), ParseError> {
+        if self.depth >= DEFAULT_MAX_DEPTH {
+            return Err(ParseError::LimitExceeded { limit: Limit::Depth, max: DEFAULT_MAX_DEPTH });
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.offset += scan::whitespace_run(&self.input.as_bytes()[self.offset..]);
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.offset).copied()
+    }
+
+    fn bump(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+
+        self.offset += 1;
+        Some(byte)
+    }
+
+    fn unexpected(&self, byte: Option<u8>) -> ParseError {
+        match byte {
+            Some(_) => {
+                let c = self.input[self.offset - 1..].chars().next().unwrap_or('\u{FFFD}');
+
+                ParseError::Syntax(format!("Unexpected character '{}' at offset {}", c, self.offset - 1))
+            },
+            None => ParseError::Syntax("Unexpected end of input".to_string())
+        }
+    }
+}
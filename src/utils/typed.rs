@@ -0,0 +1,270 @@
+use crate::utils::compat::*;
+use crate::utils::docs::RuleDoc;
+use crate::utils::random::Rng;
+use crate::utils::messages::ValidationContext;
+use crate::{AnyType, ArrayType, BooleanType, DateType, JSONKind, JSONValue, NotType, NullType, NumberType, ObjectType, OrderedMap, Redaction, StringType, Validator};
+
+/// A Rust type read from a JSON value, such as one accepted by a JSONSchema.
+/// Implement it for a struct to read it with `JSONSchema::parse_into`.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{FromJSON, JSONValue, OrderedMap};
+///
+/// struct User {
+///     name: String,
+///     tags: Vec<String>,
+///     nickname: Option<String>
+/// }
+///
+/// impl FromJSON for User {
+///     fn from_json(value: JSONValue) -> Result<Self, String> {
+///         let mut obj = OrderedMap::<JSONValue>::from_json(value)?;
+///
+///         Ok(User { name: obj.take_as("name")?, tags: obj.take_as("tags")?, nickname: obj.take_as("nickname")? })
+///     }
+/// }
+///
+/// let user = User::from_json(JSONValue::Object([
+///     ("name".to_string(), JSONValue::String("John".into())),
+///     ("tags".to_string(), JSONValue::Array(vec![JSONValue::String("admin".into())].into()))
+/// ].into_iter().collect())).unwrap();
+///
+/// assert_eq!(user.name, "John");
+/// assert_eq!(user.tags, ["admin"]);
+/// assert_eq!(user.nickname, None);
+///
+/// assert_eq!(User::from_json(JSONValue::Null).err(), Some("expected Object, found Null".to_string()));
+/// ```
+pub trait FromJSON: Sized {
+    fn from_json(value: JSONValue) -> Result<Self, String>;
+}
+
+fn mismatch(expected: JSONKind, value: &JSONValue) -> String {
+    format!("expected {:?}, found {:?}", expected, value.kind())
+}
+
+impl FromJSON for JSONValue {
+    fn from_json(value: JSONValue) -> Result<Self, String> {
+        Ok(value)
+    }
+}
+
+impl FromJSON for String {
+    fn from_json(value: JSONValue) -> Result<Self, String> {
+        match value {
+            JSONValue::String(s) => Ok(s.into_string()),
+            value => Err(mismatch(JSONKind::String, &value))
+        }
+    }
+}
+
+impl FromJSON for f64 {
+    fn from_json(value: JSONValue) -> Result<Self, String> {
+        value.as_f64().ok_or_else(|| mismatch(JSONKind::Number, &value))
+    }
+}
+
+impl FromJSON for bool {
+    fn from_json(value: JSONValue) -> Result<Self, String> {
+        value.as_bool().ok_or_else(|| mismatch(JSONKind::Boolean, &value))
+    }
+}
+
+impl FromJSON for () {
+    fn from_json(value: JSONValue) -> Result<Self, String> {
+        match value {
+            JSONValue::Null => Ok(()),
+            value => Err(mismatch(JSONKind::Null, &value))
+        }
+    }
+}
+
+/// Null reads as None.
+impl<T: FromJSON> FromJSON for Option<T> {
+    fn from_json(value: JSONValue) -> Result<Self, String> {
+        match value {
+            JSONValue::Null => Ok(None),
+            value => T::from_json(value).map(Some)
+        }
+    }
+}
+
+impl<T: FromJSON> FromJSON for Vec<T> {
+    fn from_json(value: JSONValue) -> Result<Self, String> {
+        match value {
+            JSONValue::Array(items) => items.into_vec().into_iter().enumerate()
+                .map(|(index, item)| T::from_json(item).map_err(|e| format!("In item {}, {}", index, e)))
+                .collect(),
+            value => Err(mismatch(JSONKind::Array, &value))
+        }
+    }
+}
+
+impl<T: FromJSON> FromJSON for OrderedMap<T> {
+    fn from_json(value: JSONValue) -> Result<Self, String> {
+        match value {
+            JSONValue::Object(obj) => obj.into_iter()
+                .map(|(key, value)| match T::from_json(value) {
+                    Ok(value) => Ok((key, value)),
+                    Err(e) => Err(format!("In {}, {}", key, e))
+                })
+                .collect(),
+            value => Err(mismatch(JSONKind::Object, &value))
+        }
+    }
+}
+
+impl OrderedMap<JSONValue> {
+    /// Remove the value of the given key and read it as the given type. A missing key
+    /// reads as null, so that it can be read as an Option.
+    pub fn take_as<T: FromJSON>(&mut self, key: &str) -> Result<T, String> {
+        T::from_json(self.remove(key).unwrap_or(JSONValue::Null)).map_err(|e| format!("In {}, {}", key, e))
+    }
+}
+
+/// A rule producing a Rust value from the values it accepts, so that a value is read
+/// once while it is validated instead of being unwrapped again afterwards.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{JSONParser, NumberType, ObjectType, StringType, TypedValidator};
+///
+/// struct User {
+///     name: String,
+///     age: f64
+/// }
+///
+/// let age = NumberType::new().gt(18.0).round();
+///
+/// assert_eq!(age.parse("age", &JSONParser::from(r#"{ "age": 30.4 }"#).unwrap()["age"]), Ok(30.0));
+///
+/// let user = ObjectType::new()
+///   .property("name", StringType::new().boxed())
+///   .property("age", NumberType::new().gt(18.0).boxed())
+///   .map(|mut obj| Ok(User { name: obj.take_as("name")?, age: obj.take_as("age")? }));
+///
+/// let json = JSONParser::from(r#"{ "user": { "name": "John", "age": 30 } }"#).unwrap();
+/// let parsed = user.parse("user", &json["user"]).unwrap();
+///
+/// assert_eq!(parsed.name, "John");
+/// assert_eq!(parsed.age, 30.0);
+///
+/// let json = JSONParser::from(r#"{ "user": { "name": "John", "age": 16 } }"#).unwrap();
+/// assert_eq!(user.parse("user", &json["user"]).err(), Some("age must be greater than 18".to_string()));
+/// ```
+pub trait TypedValidator: Validator {
+    type Output;
+
+    /// Build the output from a value accepted by the rule, once transformed.
+    fn output(&self, value: JSONValue) -> Result<Self::Output, String>;
+
+    /// Transform and validate a copy of the value, then build the output from it.
+    fn parse(&self, name: &str, value: &JSONValue) -> Result<Self::Output, String> {
+        let mut value = value.clone();
+
+        self.transform_in_place(name, &mut value)?;
+        self.validate(name, &value)?;
+        self.output(value)
+    }
+
+    /// Build another output from the output of the rule, such as a struct from an object.
+    fn map<T, F: Send + Sync + Fn(Self::Output) -> Result<T, String>>(self, map: F) -> Mapped<Self, F> where Self: Sized {
+        Mapped { rule: self, map }
+    }
+}
+
+macro_rules! typed_validator {
+    ($rule:ty, $output:ty) => {
+        impl TypedValidator for $rule {
+            type Output = $output;
+
+            fn output(&self, value: JSONValue) -> Result<Self::Output, String> {
+                FromJSON::from_json(value)
+            }
+        }
+    };
+}
+
+typed_validator!(StringType, String);
+typed_validator!(NumberType, f64);
+typed_validator!(BooleanType, bool);
+typed_validator!(DateType, String);
+typed_validator!(ArrayType, Vec<JSONValue>);
+typed_validator!(ObjectType<'_>, OrderedMap<JSONValue>);
+typed_validator!(NullType, ());
+typed_validator!(AnyType, JSONValue);
+typed_validator!(NotType<'_>, JSONValue);
+
+/// A rule whose output is built from the output of another rule, created by `TypedValidator::map`.
+pub struct Mapped<V, F> {
+    rule: V,
+    map: F
+}
+
+impl<V: Validator, F: Send + Sync> Mapped<V, F> {
+    /// Convert the Mapped to a Box<dyn Validator>.
+    pub fn boxed<'a>(self) -> Box<dyn Validator + 'a> where Self: 'a {
+        Box::new(self)
+    }
+}
+
+impl<V: Validator, F: Send + Sync> Validator for Mapped<V, F> {
+    fn validate(&self, name: &str, value: &JSONValue) -> Result<(), String> {
+        self.rule.validate(name, value)
+    }
+
+    fn validate_in(&self, context: &ValidationContext, value: &JSONValue) -> Result<(), String> {
+        self.rule.validate_in(context, value)
+    }
+
+    fn transform(&self, name: &str, value: &JSONValue) -> Result<JSONValue, String> {
+        self.rule.transform(name, value)
+    }
+
+    fn expected_kind(&self) -> Option<JSONKind> {
+        self.rule.expected_kind()
+    }
+
+    fn transforms(&self) -> bool {
+        self.rule.transforms()
+    }
+
+    fn transform_in_place(&self, name: &str, value: &mut JSONValue) -> Result<(), String> {
+        self.rule.transform_in_place(name, value)
+    }
+
+    fn is_sensitive(&self) -> bool {
+        self.rule.is_sensitive()
+    }
+
+    fn is_warning(&self) -> bool {
+        self.rule.is_warning()
+    }
+
+    fn document(&self) -> RuleDoc {
+        self.rule.document()
+    }
+
+    fn generate(&self, rng: &mut Rng) -> JSONValue {
+        self.rule.generate(rng)
+    }
+
+    fn redact_nested(&self, value: &mut JSONValue, redaction: &Redaction) {
+        self.rule.redact_nested(value, redaction)
+    }
+
+    fn default_value(&self) -> Option<JSONValue> {
+        self.rule.default_value()
+    }
+}
+
+impl<V: TypedValidator, T, F: Send + Sync + Fn(V::Output) -> Result<T, String>> TypedValidator for Mapped<V, F> {
+    type Output = T;
+
+    fn output(&self, value: JSONValue) -> Result<T, String> {
+        (self.map)(self.rule.output(value)?)
+    }
+}
@@ -1,7 +1,92 @@
-use crate::{JSONValue, OrderedMap};
+use alloc::sync::Arc;
+use core::any::Any;
+use core::fmt::Display;
+#[cfg(feature = "std")]
+use std::io::Read;
+
+use crate::utils::compat::*;
+use crate::utils::date::Timestamp;
+use crate::utils::docs::{self, RuleDoc};
+use crate::utils::parser::edit_items;
+use crate::utils::messages::{EnglishFormatter, MessageFormatter, ValidationContext};
+use crate::utils::random::Rng;
+use crate::utils::regex::Regex;
+use crate::utils::typed::FromJSON;
+use crate::utils::walk::push_token;
+#[cfg(feature = "std")]
+use crate::utils::{Lexer, Parser};
+use crate::{CancellationToken, JSONKind, JSONValue, OrderedMap, Serialize};
+#[cfg(feature = "std")]
+use crate::ParseError;
+
+/// An error raised while validating a stream, either from parsing or from a rule.
+#[cfg(feature = "std")]
+enum StreamError {
+    Parse(ParseError),
+    Invalid(String)
+}
+
+#[cfg(feature = "std")]
+impl From<ParseError> for StreamError {
+    fn from(error: ParseError) -> Self {
+        StreamError::Parse(error)
+    }
+}
+
+/// How `JSONSchema::redact` hides the values of sensitive rules.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Redaction {
+    /// Replace the value with the given one.
+    Mask(JSONValue),
+    /// Remove the property, or the array item.
+    Strip
+}
+
+impl Default for Redaction {
+    fn default() -> Self {
+        Redaction::Mask(JSONValue::String("[REDACTED]".into()))
+    }
+}
+
+/// The failures found by `JSONSchema::validate_all`.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct ValidationReport {
+    pub errors: Vec<String>,
+    /// The failures of the rules marked with `warn`.
+    pub warnings: Vec<String>
+}
+
+impl ValidationReport {
+    /// Whether the value is valid, warnings aside.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Hide the value of a sensitive rule, or redact the values nested in it.
+fn redact_entry(obj: &mut OrderedMap<JSONValue>, key: &str, rule: &dyn Validator, redaction: &Redaction) {
+    match (rule.is_sensitive(), redaction) {
+        (true, Redaction::Strip) => {
+            obj.remove(key);
+        },
+        (true, Redaction::Mask(mask)) => {
+            if let Some(value) = obj.get_mut(key) {
+                *value = mask.clone();
+            }
+        },
+        (false, _) => {
+            if let Some(value) = obj.get_mut(key) {
+                rule.redact_nested(value, redaction);
+            }
+        }
+    }
+}
 
 pub struct JSONSchema<'a> {
-	rules: OrderedMap<Box<dyn Validator + 'a>>
+	rules: OrderedMap<Box<dyn Validator + 'a>>,
+	redaction: Redaction,
+	cancellation: Option<CancellationToken>,
+	formatter: Box<dyn MessageFormatter + 'a>
 }
 
 impl<'a> JSONSchema<'a> {
@@ -24,687 +109,4119 @@ impl<'a> JSONSchema<'a> {
             ordered_rules.insert(key, rule);
         }
 
-        Self { rules: ordered_rules }
+        Self { rules: ordered_rules, redaction: Redaction::default(), cancellation: None, formatter: Box::new(EnglishFormatter) }
     }
 
-    /// Validate the given JSONValue against the schema.
+    /// Create a JSONSchema from a JSON definition, so that the same rules can be shared
+    /// with other programs. The definition is an object mapping each key to a rule, itself
+    /// an object with a `type` (`string`, `number`, `boolean`, `date`, `array`, `object`,
+    /// `null`, `any`, or `not` taking the rule it excludes in `rule`)
+    /// and options named after the builder methods of the matching type, such as
+    /// `min_length` or `gt`. Options without an argument, such as `trim`, are applied when
+    /// `true`. Array rules take rules for `every` and `some`, and `[index, rule]` for `at`.
+    /// A `message` option replaces the message of the option before it, as the builder does.
+    /// Object rules take their rules in `properties`, and the keys to rename in `rename`.
+    /// Custom transforms cannot be defined.
     ///
     /// # Example
     ///
-    /// ```no_run
-    /// use jsonparser::{JSONValue, JSONSchema, StringType, NumberType};
+    /// ```
+    /// use jsonparser::{JSONParser, JSONSchema};
+    ///
+    /// let definition = JSONParser::from(r#"{
+    ///   "name": { "type": "string", "min_length": 3, "trim": true },
+    ///   "age": { "type": "number", "gt": 18, "integer": true },
+    ///   "tags": { "type": "array", "max_length": 2, "every": { "type": "string" } },
+    ///   "address": { "type": "object", "properties": { "city": { "type": "string" } } }
+    /// }"#).unwrap();
+    /// let schema = JSONSchema::from_json(&definition).unwrap();
+    ///
+    /// let json = JSONParser::from(r#"{ "name": "  John  ", "age": 30, "tags": ["a"], "address": { "city": "Paris" } }"#).unwrap();
+    ///
+    /// assert_eq!(schema.validate(&json).unwrap()["name"].as_str(), Some("John"));
+    ///
+    /// let definition = JSONParser::from(r#"{ "name": { "type": "string", "max": 3 } }"#).unwrap();
+    ///
+    /// assert_eq!(JSONSchema::from_json(&definition).err(), Some("In rule 'name', unknown option 'max' for a string".to_string()));
+    /// ```
+    pub fn from_json(definition: &JSONValue) -> Result<JSONSchema<'static>, String> {
+        let definitions = definition.as_object().ok_or("Expected an object of rules")?;
+        let mut rules = OrderedMap::new();
+
+        for (key, definition) in definitions.iter() {
+            let rule = rule_from_json(definition).map_err(|e| format!("In rule '{}', {}", key, e))?;
+
+            rules.insert(key, rule);
+        }
+
+        Ok(JSONSchema { rules, redaction: Redaction::default(), cancellation: None, formatter: Box::new(EnglishFormatter) })
+    }
+
+    /// Set how `redact` hides sensitive values. By default they are masked with `"[REDACTED]"`.
+    pub fn redaction(mut self, redaction: Redaction) -> Self {
+        self.redaction = redaction;
+        self
+    }
+
+    /// Stop validating with a `"Cancelled"` error once the token is cancelled. The token is
+    /// checked before each rule, and every 64 KiB of input by `validate_stream`.
+    ///
+    /// # Example
     ///
+    /// ```
+    /// use jsonparser::{CancellationToken, JSONParser, JSONSchema, StringType};
+    ///
+    /// let token = CancellationToken::new();
     /// let schema = JSONSchema::new([
-    ///   ("name", StringType::new().min_length(3).trim().boxed()),
-    ///   ("age", NumberType::new().gt(18.0).boxed())
-    /// ]);
+    ///   ("name", StringType::new().boxed())
+    /// ]).cancellation(token.clone());
     ///
-    /// let json = JSONValue::Object({ /* ... */ });
+    /// let json = JSONParser::from(r#"{ "name": "John Doe" }"#).unwrap();
     ///
-    /// match schema.validate(&json) {
-    ///   Ok(value: JSONValue) => println!("{:?}", value),
-    ///   Err(e) => eprintln!("Invalid JSON: {}", e)
-    /// }
+    /// token.cancel();
+    ///
+    /// assert_eq!(schema.validate(&json).unwrap_err(), "Cancelled");
     /// ```
-    pub fn validate(&self, value: &JSONValue) -> Result<JSONValue, String> {
-        let transformed = &self.transform(value)?;
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
 
-        match transformed {
-            JSONValue::Object(obj) => {
-                for (key, rule) in self.rules.iter() {
-                    match obj.get(key as &str) {
-                        Some(value) => rule.validate(key, value)?,
-                        None => return Err(format!("Key '{}' not found", key))
-                    }
-                }
-                Ok(transformed.clone())
-            },
-            _ => Err("Expected an object for validation".to_string()),
+    /// Prepare the schema for validating many values, such as every request of a server.
+    /// See `CompiledSchema`.
+    pub fn compile(self) -> CompiledSchema<'a> {
+        let rules = self.rules.into_iter().map(|(key, rule)| {
+            let mut path = String::new();
+
+            push_token(&mut path, &key);
+            CompiledRule { transforms: rule.transforms(), key, path, rule }
+        }).collect();
+
+        CompiledSchema { rules, cancellation: self.cancellation, formatter: self.formatter }
+    }
+
+    /// Start building an object valid against the schema, property by property.
+    /// See `SchemaBuilder`.
+    pub fn builder(&self) -> SchemaBuilder<'_, 'a> {
+        SchemaBuilder { schema: self, object: OrderedMap::new() }
+    }
+
+    /// Set how validation errors are turned into messages, such as to translate them.
+    /// By default they are in English, as formatted by `EnglishFormatter`.
+    pub fn formatter<F: MessageFormatter + 'a>(mut self, formatter: F) -> Self {
+        self.formatter = Box::new(formatter);
+        self
+    }
+
+    fn check_cancelled(&self) -> Result<(), String> {
+        match &self.cancellation {
+            Some(token) if token.is_cancelled() => Err("Cancelled".to_string()),
+            _ => Ok(())
         }
     }
 
-    /// Transform the given JSONValue according to the schema.
-    fn transform(&self, value: &JSONValue) -> Result<JSONValue, String> {
-        match value {
-            JSONValue::Object(obj) => {
-                let mut transformed = obj.clone();
+    /// Return a copy of the value where the values of the rules marked as sensitive,
+    /// at any depth, are masked or stripped. The value is not validated, and values
+    /// without a rule are kept as is.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{ArrayType, JSONParser, JSONSchema, ObjectType, Redaction, Serialize, StringType};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("name", StringType::new().boxed()),
+    ///   ("password", StringType::new().min_length(8).sensitive().boxed()),
+    ///   ("cards", ArrayType::new().every(ObjectType::new()
+    ///     .property("number", StringType::new().sensitive().boxed())
+    ///   .boxed()).boxed())
+    /// ]);
+    ///
+    /// let json = JSONParser::from(r#"{ "name": "John", "password": "hunter22", "cards": [{ "number": "4111", "brand": "visa" }] }"#).unwrap();
+    ///
+    /// assert!(schema.validate(&json).is_ok());
+    /// assert_eq!(schema.redact(&json).serialize(), r#"{"name":"John","password":"[REDACTED]","cards":[{"number":"[REDACTED]","brand":"visa"}]}"#);
+    ///
+    /// let schema = schema.redaction(Redaction::Strip);
+    ///
+    /// assert_eq!(schema.redact(&json).serialize(), r#"{"name":"John","cards":[{"brand":"visa"}]}"#);
+    /// ```
+    pub fn redact(&self, value: &JSONValue) -> JSONValue {
+        let mut redacted = value.clone();
 
-                for (key, rule) in self.rules.iter() {
-                    if let Some(value) = obj.get(key as &str) {
-                        transformed.insert(key, rule.transform(key, value)?);
-                    }
-                }
-                Ok(JSONValue::Object(transformed))
-            },
-            _ => Err("Expected an object for transformation".to_string()),
+        if let JSONValue::Object(obj) = &mut redacted {
+            for (key, rule) in self.rules.iter() {
+                redact_entry(obj, key, rule.as_ref(), &self.redaction);
+            }
         }
+        redacted
     }
-}
 
-pub trait Validator {
-    fn validate(&self, name: &str, value: &JSONValue) -> Result<(), String>;
-    fn transform(&self, _: &str, value: &JSONValue) -> Result<JSONValue, String> {
-        Ok(value.clone())
+    /// Render a reference of the fields of the schema as a Markdown table, with their type,
+    /// description, constraints and examples. Nested fields are listed after their parent,
+    /// such as `address.city`, and the items of an array under `[]`, such as `tags[]`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{ArrayType, JSONSchema, JSONValue, NumberType, StringType};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("age", NumberType::new().gte(0.0).integer().describe("The age in years").example(JSONValue::Number(30.0)).boxed()),
+    ///   ("nickname", StringType::new().max_length(20).deprecated().boxed()),
+    ///   ("tags", ArrayType::new().unique().every(StringType::new().pattern("^[a-z]+$").boxed()).boxed())
+    /// ]);
+    ///
+    /// assert_eq!(schema.to_markdown(), "\
+    /// | Field | Type | Description | Constraints | Examples |
+    /// | --- | --- | --- | --- | --- |
+    /// | `age` | number | The age in years | gte: 0, integer | `30` |
+    /// | `nickname` | string | **Deprecated.** | max_length: 20 |  |
+    /// | `tags` | array |  | unique |  |
+    /// | `tags[]` | string |  | pattern: '^[a-z]+$' |  |
+    /// ");
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        docs::to_markdown(&self.document())
     }
-}
 
-pub struct StringType {
-    min_length: Option<usize>,
-    max_length: Option<usize>,
-    length: Option<usize>,
-    starts_with: Option<String>,
-    ends_with: Option<String>,
-    includes: Option<String>,
-    trim: bool,
-    trim_start: bool,
-    trim_end: bool,
-    lowercase: bool,
-    uppercase: bool,
-    transform: Option<Box<dyn Fn(&str) -> String>>
-}
+    /// Render a reference of the fields of the schema as an HTML table, as `to_markdown` does.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONSchema, StringType};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("name", StringType::new().min_length(3).describe("The <full> name").boxed())
+    /// ]);
+    ///
+    /// assert!(schema.to_html().contains("<tr><td><code>name</code></td><td>string</td><td>The &lt;full&gt; name</td><td>min_length: 3</td><td></td></tr>"));
+    /// ```
+    pub fn to_html(&self) -> String {
+        docs::to_html(&self.document())
+    }
 
-impl StringType {
-    /// Create a new StringType instance.
-    pub fn new() -> Self {
-        Self {
-            min_length: None,
-            max_length: None,
-            length: None,
-            starts_with: None,
-            ends_with: None,
-            includes: None,
-            trim: false,
-            trim_start: false,
-            trim_end: false,
-            lowercase: false,
-            uppercase: false,
-            transform: None
+    /// Generate TypeScript interface declarations for the documents accepted by the schema,
+    /// such as for a frontend sharing the payloads, the root one under the given name. An
+    /// interface is declared for each object with properties, named after its key, or
+    /// after the singular of the key for the items of an array. Properties with a default
+    /// value or marked with `warn` are optional. Dates are strings, and rules without a
+    /// TypeScript equivalent, such as `not` or custom rules, are `unknown`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{ArrayType, BooleanType, DateType, JSONSchema, NumberType, ObjectType, StringType};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("name", StringType::new().min_length(3).describe("The full name").boxed()),
+    ///   ("age", NumberType::new().gt(18.0).boxed()),
+    ///   ("active", BooleanType::new().or_default(true).boxed()),
+    ///   ("born", DateType::new().boxed()),
+    ///   ("cars", ArrayType::new().every(ObjectType::new()
+    ///     .property("model", StringType::new().boxed())
+    ///     .property("first-owner", StringType::new().deprecated().boxed())
+    ///   .boxed()).boxed())
+    /// ]);
+    ///
+    /// assert_eq!(schema.to_typescript("User"), "\
+    /// export interface User {
+    ///   /** The full name */
+    ///   name: string;
+    ///   age: number;
+    ///   active?: boolean;
+    ///   born: string;
+    ///   cars: Car[];
+    /// }
+    ///
+    /// export interface Car {
+    ///   model: string;
+    ///   /** @deprecated */
+    ///   \"first-owner\": string;
+    /// }
+    /// ");
+    /// ```
+    pub fn to_typescript(&self, name: &str) -> String {
+        let document = self.document();
+        let fields = self.rules.iter().zip(document.iter())
+            .map(|((_, rule), (key, doc))| (key.as_str(), doc, rule.default_value().is_some() || rule.is_warning()))
+            .collect();
+
+        docs::to_typescript(name, fields)
+    }
+
+    /// Describe the documents accepted by the schema as an OpenAPI 3.1 schema object, such as
+    /// for the `components` of an API description. Constraints are written with the matching
+    /// JSON Schema keywords, dates as strings with the `date-time` format, and the values
+    /// of `eq`, `truthy` and `falsy` as an `enum`. Properties with a default value or marked
+    /// with `warn` are not required, and a boolean with a default value accepts null, as
+    /// with OpenAPI 3.1 a nullable value has a `null` type rather than `nullable`. Transforms,
+    /// date bounds and case-insensitive matches cannot be described and are left out, and
+    /// custom rules accept any value unless they implement `Validator::openapi_schema`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{ArrayType, BooleanType, DateType, JSONSchema, NumberType, Serialize, StringType};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("name", StringType::new().min_length(3).starts_with("Mr").describe("The full name").boxed()),
+    ///   ("age", NumberType::new().gt(18.0).integer().boxed()),
+    ///   ("born", DateType::new().boxed()),
+    ///   ("terms", BooleanType::new().truthy().boxed()),
+    ///   ("newsletter", BooleanType::new().or_default(false).boxed()),
+    ///   ("tags", ArrayType::new().max_length(5).unique().every(StringType::new().boxed()).boxed())
+    /// ]);
+    ///
+    /// assert_eq!(schema.to_openapi_schema().serialize(), [
+    ///     r#"{"type":"object","properties":{"#,
+    ///     r#""name":{"type":"string","description":"The full name","minLength":3,"pattern":"^Mr"},"#,
+    ///     r#""age":{"type":"integer","exclusiveMinimum":18},"#,
+    ///     r#""born":{"type":"string","format":"date-time"},"#,
+    ///     r#""terms":{"type":"boolean","enum":[true]},"#,
+    ///     r#""newsletter":{"type":["boolean","null"],"default":false},"#,
+    ///     r#""tags":{"type":"array","maxItems":5,"uniqueItems":true,"items":{"type":"string"}}},"#,
+    ///     r#""required":["name","age","born","terms","tags"]}"#
+    /// ].concat());
+    /// ```
+    pub fn to_openapi_schema(&self) -> JSONValue {
+        let mut schema = OrderedMap::new();
+        let required = self.rules.iter()
+            .filter(|(_, rule)| rule.default_value().is_none() && !rule.is_warning())
+            .map(|(key, _)| JSONValue::String(key.into()))
+            .collect::<Vec<_>>();
+
+        schema.insert("type", JSONValue::String("object".into()));
+        schema.insert("properties", JSONValue::Object(self.rules.iter().map(|(key, rule)| (key.to_string(), rule.openapi_schema())).collect()));
+        if !required.is_empty() {
+            schema.insert("required", JSONValue::Array(required.into()));
         }
+        JSONValue::Object(schema)
     }
 
-    /// Set the minimum length of the string.
-    pub fn min_length(mut self, min: usize) -> Self {
-        self.min_length = Some(min);
-        self
+    /// Generate a document accepted by the schema, such as for fixtures or property-based
+    /// tests. Values follow the lengths, ranges, patterns and other constraints of their
+    /// rule, and are generated again a few times when the rule still rejects them.
+    /// Properties with a default value or marked with `warn` are left out half of the time.
+    /// Custom rules generate null unless they implement `Validator::generate`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{ArrayType, BooleanType, JSONSchema, NumberType, Rng, StringType};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("name", StringType::new().min_length(3).max_length(8).boxed()),
+    ///   ("age", NumberType::new().gt(18.0).lte(100.0).integer().boxed()),
+    ///   ("code", StringType::new().pattern("^[A-Z]{3}-\\d{2}$").boxed()),
+    ///   ("tags", ArrayType::new().min_length(1).unique().every(StringType::new().boxed()).boxed()),
+    ///   ("active", BooleanType::new().or_default(true).boxed())
+    /// ]);
+    ///
+    /// let mut rng = Rng::new(7);
+    ///
+    /// for _ in 0..20 {
+    ///     let document = schema.generate(&mut rng);
+    ///
+    ///     assert!(schema.validate(&document).is_ok(), "{:?}", document);
+    /// }
+    /// assert_eq!(schema.generate(&mut Rng::new(1)), schema.generate(&mut Rng::new(1)));
+    /// ```
+    pub fn generate(&self, rng: &mut Rng) -> JSONValue {
+        let mut obj = OrderedMap::with_capacity(self.rules.len());
+
+        for (key, rule) in self.rules.iter() {
+            let optional = rule.default_value().is_some() || rule.is_warning();
+
+            if !optional || rng.chance(0.5) {
+                obj.insert(key, generate_valid(rule.as_ref(), rng));
+            }
+        }
+        JSONValue::Object(obj)
     }
 
-    /// Set the maximum length of the string.
-    pub fn max_length(mut self, max: usize) -> Self {
-        self.max_length = Some(max);
-        self
+    fn document(&self) -> Vec<(String, RuleDoc)> {
+        self.rules.iter().map(|(key, rule)| (key.to_string(), rule.document())).collect()
     }
 
-    /// Set the exact length of the string.
-    pub fn length(mut self, length: usize) -> Self {
-        self.length = Some(length);
-        self
+    /// Validate the given JSONValue against the schema.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONSchema, StringType, NumberType};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("name", StringType::new().min_length(3).trim().boxed()),
+    ///   ("age", NumberType::new().gt(18.0).boxed())
+    /// ]);
+    ///
+    /// let json = JSONParser::from(r#"{ "name": "John Doe", "age": 30 }"#).unwrap();
+    ///
+    /// match schema.validate(&json) {
+    ///   Ok(value) => println!("{:?}", value),
+    ///   Err(e) => eprintln!("Invalid JSON: {}", e)
+    /// }
+    /// ```
+    pub fn validate(&self, value: &JSONValue) -> Result<JSONValue, String> {
+        let mut transformed = value.clone();
+
+        self.validate_in_place(&mut transformed)?;
+        Ok(transformed)
     }
 
-    /// Set the expected starting of the string.
-    pub fn starts_with(mut self, value: &str) -> Self {
-        self.starts_with = Some(value.to_string());
-        self
+    /// Transform the given JSONValue in place and validate it against the schema,
+    /// without cloning it. On error, the value may be partially transformed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONSchema, StringType};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("name", StringType::new().trim().boxed())
+    /// ]);
+    ///
+    /// let mut json = JSONParser::from(r#"{ "name": "  John Doe  " }"#).unwrap();
+    ///
+    /// assert!(schema.validate_in_place(&mut json).is_ok());
+    /// assert_eq!(json["name"].as_str(), Some("John Doe"));
+    /// ```
+    pub fn validate_in_place(&self, value: &mut JSONValue) -> Result<(), String> {
+        self.validate_in_context(value, ValidationContext::new("", self.formatter.as_ref()))
     }
 
-    /// Set the expected ending of the string.
-    pub fn ends_with(mut self, value: &str) -> Self {
-        self.ends_with = Some(value.to_string());
+    /// Apply the transformations of the rules to a copy of the given JSONValue, without
+    /// validating it, such as to normalize a payload before storing it. Properties whose
+    /// rule cannot transform them, such as those of the wrong type, are left as they are,
+    /// and missing properties are not filled with defaults.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONSchema, ObjectType, StringType};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("name", StringType::new().trim().min_length(10).boxed()),
+    ///   ("email", StringType::new().to_lowercase().boxed()),
+    ///   ("address", ObjectType::new().rename("zip_code", "zip").boxed())
+    /// ]);
+    ///
+    /// let json = JSONParser::from(r#"{ "name": " John ", "email": 42, "address": { "zip_code": "75001" } }"#).unwrap();
+    ///
+    /// assert!(schema.validate(&json).is_err());
+    /// assert_eq!(schema.transform_only(&json).to_string(), r#"{"name":"John","email":42,"address":{"zip":"75001"}}"#);
+    /// ```
+    pub fn transform_only(&self, value: &JSONValue) -> JSONValue {
+        let mut transformed = value.clone();
+
+        if let JSONValue::Object(obj) = &mut transformed {
+            for (key, rule) in self.rules.iter() {
+                if let Some(value) = obj.get_mut(key).filter(|_| rule.transforms()) {
+                    if let Ok(new) = transform_cloned(rule.as_ref(), key, value) {
+                        *value = new;
+                    }
+                }
+            }
+        }
+        transformed
+    }
+
+    /// Validate the given JSONValue against the schema, passing the given data to the rules.
+    /// Rules implementing `Validator::validate_in` read it with `ValidationContext::data`,
+    /// so that they can depend on the request being validated instead of only on what
+    /// they captured when the schema was built.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONSchema, JSONValue, ValidationContext, Validator};
+    ///
+    /// struct Countries(Vec<&'static str>);
+    ///
+    /// struct CountryType;
+    ///
+    /// impl Validator for CountryType {
+    ///     fn validate(&self, name: &str, _: &JSONValue) -> Result<(), String> {
+    ///         Err(format!("No countries to check {} against", name))
+    ///     }
+    ///
+    ///     fn validate_in(&self, context: &ValidationContext, value: &JSONValue) -> Result<(), String> {
+    ///         let countries = match context.data::<Countries>() {
+    ///             Some(countries) => countries,
+    ///             None => return self.validate(context.name(), value)
+    ///         };
+    ///
+    ///         match value.as_str() {
+    ///             Some(code) if countries.0.contains(&code) => Ok(()),
+    ///             _ => Err(format!("{} is not an allowed country", context.path()))
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("country", Box::new(CountryType) as Box<dyn Validator>)
+    /// ]);
+    ///
+    /// let json = JSONParser::from(r#"{ "country": "FR" }"#).unwrap();
+    ///
+    /// assert!(schema.validate_with_ctx(&json, &Countries(vec!["FR", "DE"])).is_ok());
+    /// assert_eq!(schema.validate_with_ctx(&json, &Countries(vec!["US"])).unwrap_err(), "/country is not an allowed country");
+    /// assert_eq!(schema.validate(&json).unwrap_err(), "No countries to check country against");
+    /// ```
+    pub fn validate_with_ctx<T: Any>(&self, value: &JSONValue, data: &T) -> Result<JSONValue, String> {
+        let mut transformed = value.clone();
+
+        self.validate_in_context(&mut transformed, ValidationContext::new("", self.formatter.as_ref()).with_data(data))?;
+        Ok(transformed)
+    }
+
+    /// Validate the given JSONValue against the schema, then read it as the given type,
+    /// so that the values are not unwrapped again after validation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{FromJSON, JSONParser, JSONSchema, JSONValue, NumberType, OrderedMap, StringType};
+    ///
+    /// struct User {
+    ///     name: String,
+    ///     age: f64
+    /// }
+    ///
+    /// impl FromJSON for User {
+    ///     fn from_json(value: JSONValue) -> Result<Self, String> {
+    ///         let mut obj = OrderedMap::<JSONValue>::from_json(value)?;
+    ///
+    ///         Ok(User { name: obj.take_as("name")?, age: obj.take_as("age")? })
+    ///     }
+    /// }
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("name", StringType::new().trim().boxed()),
+    ///   ("age", NumberType::new().gt(18.0).boxed())
+    /// ]);
+    ///
+    /// let json = JSONParser::from(r#"{ "name": "  John  ", "age": 30 }"#).unwrap();
+    /// let user = schema.parse_into::<User>(&json).unwrap();
+    ///
+    /// assert_eq!(user.name, "John");
+    /// assert_eq!(user.age, 30.0);
+    ///
+    /// let json = JSONParser::from(r#"{ "name": "John", "age": 16 }"#).unwrap();
+    /// assert_eq!(schema.parse_into::<User>(&json).err(), Some("age must be greater than 18".to_string()));
+    /// ```
+    pub fn parse_into<T: FromJSON>(&self, value: &JSONValue) -> Result<T, String> {
+        T::from_json(self.validate(value)?)
+    }
+
+    /// Transform the given JSONValue and validate it against the schema, collecting every
+    /// failure instead of stopping at the first one. Failures of the rules marked with
+    /// `warn` are reported as warnings, so that a stricter rule can be rolled out before
+    /// it is enforced; `validate` ignores them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONSchema, NumberType, StringType};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("name", StringType::new().min_length(3).boxed()),
+    ///   ("age", NumberType::new().gt(18.0).boxed()),
+    ///   ("email", StringType::new().includes("@").warn().boxed())
+    /// ]);
+    ///
+    /// let json = JSONParser::from(r#"{ "name": "Jo", "age": 16, "email": "jo" }"#).unwrap();
+    /// let report = schema.validate_all(&json);
+    ///
+    /// assert!(!report.is_valid());
+    /// assert_eq!(report.errors, ["name is too short (min: 3)", "age must be greater than 18"]);
+    /// assert_eq!(report.warnings, ["email does not include '@'"]);
+    ///
+    /// let json = JSONParser::from(r#"{ "name": "John", "age": 30 }"#).unwrap();
+    /// let report = schema.validate_all(&json);
+    ///
+    /// assert!(report.is_valid());
+    /// assert_eq!(report.warnings, ["Key 'email' not found"]);
+    /// assert!(schema.validate(&json).is_ok());
+    /// ```
+    pub fn validate_all(&self, value: &JSONValue) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        let mut transformed = value.clone();
+        let root = ValidationContext::new("", self.formatter.as_ref());
+        let result = self.validate_entries(&mut transformed, &root, &mut |warning, error| {
+            if warning {
+                report.warnings.push(error);
+            } else {
+                report.errors.push(error);
+            }
+            Ok(())
+        });
+
+        if let Err(e) = result {
+            report.errors.push(e);
+        }
+        report
+    }
+
+    fn validate_in_context(&self, value: &mut JSONValue, root: ValidationContext) -> Result<(), String> {
+        self.validate_entries(value, &root, &mut |warning, error| if warning { Ok(()) } else { Err(error) })
+    }
+
+    /// Transform and validate each property in turn, passing each failure to `report`
+    /// along with whether it is a warning. Stops when `report` returns an error.
+    fn validate_entries(&self, value: &mut JSONValue, root: &ValidationContext, report: &mut dyn FnMut(bool, String) -> Result<(), String>) -> Result<(), String> {
+        let obj = value.as_object_mut().ok_or("Expected an object for validation")?;
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("validate", rules = self.rules.len()).entered();
+
+        for (key, rule) in self.rules.iter() {
+            self.check_cancelled()?;
+            let context = root.child(key, key);
+            let result = match (obj.get_mut(key), rule.default_value()) {
+                (Some(value), _) => transform_value(rule.as_ref(), &context, value).and_then(|_| rule.validate_in(&context, value)),
+                // Defaults are not transformed.
+                (None, Some(default)) => {
+                    let result = rule.validate_in(&context, &default);
+
+                    obj.insert(key, default);
+                    result
+                },
+                (None, None) => Err(root.error("required", vec![("key", key.to_string())]))
+            };
+
+            result.or_else(|e| {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(path = context.path(), warning = rule.is_warning(), error = %e, "validation failed");
+                report(rule.is_warning(), e)
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Validate a JSON document read from the given reader as its tokens arrive.
+    /// Properties without a rule are skipped without being built, type mismatches
+    /// are detected before a property is built, and the first failure stops reading.
+    /// Strings and arrays stop being read once past the `Validator::max_len` of their rule.
+    /// Transformations are applied before each property is validated, then discarded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::io::Read;
+    /// use jsonparser::{ArrayType, JSONSchema, NumberType, StringType};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("name", StringType::new().min_length(3).boxed()),
+    ///   ("age", NumberType::new().gt(18.0).boxed())
+    /// ]);
+    ///
+    /// let input = r#"{ "name": "John Doe", "age": 30, "cars": [{ "model": "Ford" }] }"#;
+    /// assert!(schema.validate_stream(input.as_bytes()).is_ok());
+    ///
+    /// let input = r#"{ "name": 42, "age": 30 }"#;
+    /// assert_eq!(schema.validate_stream(input.as_bytes()).unwrap_err(), "Type of name mismatch, expected String");
+    ///
+    /// // Reading stops past the first bytes of the string and items of the array, so
+    /// // that the megabyte that follows is never read.
+    /// let schema = JSONSchema::new([
+    ///   ("name", StringType::new().max_length(8).boxed()),
+    ///   ("tags", ArrayType::new().max_length(2).boxed())
+    /// ]);
+    /// let input = format!(r#"{{ "name": "{}"#, "a".repeat(100));
+    /// let reader = input.as_bytes().chain(std::io::repeat(b'a').take(1 << 20));
+    /// assert_eq!(schema.validate_stream(reader).unwrap_err(), "name is too long (max: 8)");
+    ///
+    /// let input = r#"{ "name": "John", "tags": [1, 2, 3, "#;
+    /// let reader = input.as_bytes().chain(std::io::repeat(b' ').take(1 << 20));
+    /// assert_eq!(schema.validate_stream(reader).unwrap_err(), "tags is too long (max: 2)");
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn validate_stream<R: Read>(&self, reader: R) -> Result<(), String> {
+        let mut parser = Parser::new(Lexer::from_reader(reader));
+        let root = ValidationContext::new("", self.formatter.as_ref());
+        let mut seen = Vec::new();
+        let mut bounded = None;
+
+        if let Some(token) = &self.cancellation {
+            parser = parser.cancellation(token.clone());
+        }
+        let result = parser.next_token().map_err(StreamError::from).and_then(|_| {
+            parser.parse_entries_with(|parser: &mut Parser, key| {
+                // Bound the string before its token is read.
+                let max = self.rules.get(key)
+                    .filter(|rule| rule.expected_kind() == Some(JSONKind::String) && !rule.is_warning())
+                    .and_then(|rule| rule.max_len());
+
+                bounded = max.map(|_| key.clone());
+                parser.limit_next_string(max);
+            }, |parser: &mut Parser, key| {
+                parser.limit_next_string(None);
+                let rule = match self.rules.get(key) {
+                    Some(rule) => rule,
+                    None => return parser.skip_value().map_err(StreamError::from)
+                };
+                let context = root.child(key, key);
+
+                seen.push(key.to_string());
+                if let (Some(expected), Some(found)) = (rule.expected_kind(), parser.peek_kind()) {
+                    if expected != found && rule.is_warning() {
+                        return parser.skip_value().map_err(StreamError::from);
+                    }
+                    if expected != found {
+                        return Err(StreamError::Invalid(context.error("type", vec![("expected", format!("{:?}", expected))])));
+                    }
+                }
+                let mut value = match rule.max_len().filter(|_| rule.expected_kind() == Some(JSONKind::Array) && !rule.is_warning()) {
+                    Some(max) => parse_bounded_array(parser, rule.as_ref(), &context, max)?,
+                    None => parser.parse_value()?
+                };
+
+                match transform_value(rule.as_ref(), &context, &mut value).and_then(|_| rule.validate_in(&context, &value)) {
+                    Err(e) if !rule.is_warning() => Err(StreamError::Invalid(e)),
+                    _ => Ok(())
+                }
+            })
+        });
+
+        match result {
+            Ok(()) => {},
+            Err(StreamError::Parse(e)) => {
+                let truncated = parser.truncated_string().zip(bounded.and_then(|key| Some((self.rules.get(&key)?, key))));
+
+                return match truncated {
+                    // The start of a string past the bound of its rule fails the rule.
+                    Some((start, (rule, key))) => {
+                        let value = JSONValue::String(start.into());
+
+                        Err(rule.validate_in(&root.child(&key, &key), &value).err().unwrap_or_else(|| e.to_string()))
+                    },
+                    None => Err(e.to_string())
+                };
+            },
+            Err(StreamError::Invalid(e)) => return Err(e)
+        }
+        for (key, rule) in self.rules.iter() {
+            if rule.default_value().is_none() && !rule.is_warning() && !seen.iter().any(|k| k == key) {
+                return Err(root.error("required", vec![("key", key.to_string())]));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Read the array starting at the current token for `JSONSchema::validate_stream`,
+/// stopping one item past `max` with the error of the rule on the items read.
+#[cfg(feature = "std")]
+fn parse_bounded_array(parser: &mut Parser, rule: &dyn Validator, context: &ValidationContext, max: usize) -> Result<JSONValue, StreamError> {
+    let mut items = Vec::new();
+
+    parser.parse_items(|parser: &mut Parser| {
+        items.push(parser.parse_value()?);
+        if items.len() <= max {
+            return Ok(());
+        }
+        let value = JSONValue::Array(core::mem::take(&mut items).into());
+
+        Err(StreamError::Invalid(rule.validate_in(context, &value).err().unwrap_or_else(|| {
+            context.error("max_length", vec![("max", max.to_string())])
+        })))
+    })?;
+    Ok(JSONValue::Array(items.into()))
+}
+
+/// A builder of objects valid against a schema, created by `JSONSchema::builder`, such
+/// as to construct outbound payloads. Each property is transformed and validated by its
+/// rule as it is set, and `build` fails until every required property is set.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{BooleanType, JSONSchema, NumberType, StringType};
+///
+/// let schema = JSONSchema::new([
+///   ("name", StringType::new().trim().min_length(3).boxed()),
+///   ("age", NumberType::new().gt(18.0).boxed()),
+///   ("admin", BooleanType::new().or_default(false).boxed())
+/// ]);
+///
+/// let json = schema.builder().set("name", " John ")?.set("age", 30)?.set("team", "core")?.build()?;
+/// assert_eq!(json.to_string(), r#"{"name":"John","age":30,"team":"core","admin":false}"#);
+///
+/// assert_eq!(schema.builder().set("age", 12).err().unwrap(), "age must be greater than 18");
+/// assert_eq!(schema.builder().set("name", "John")?.build().unwrap_err(), "Key 'age' not found");
+/// # Ok::<(), String>(())
+/// ```
+pub struct SchemaBuilder<'s, 'a> {
+    schema: &'s JSONSchema<'a>,
+    object: OrderedMap<JSONValue>
+}
+
+impl<'s, 'a> SchemaBuilder<'s, 'a> {
+    /// Set a property, transformed and validated by its rule. Properties without a rule
+    /// are set as they are, as the schema accepts them.
+    pub fn set<V: Serialize>(mut self, key: &str, value: V) -> Result<Self, String> {
+        let mut value = value.to_value();
+
+        if let Some(rule) = self.schema.rules.get(key) {
+            let root = ValidationContext::new("", self.schema.formatter.as_ref());
+            let context = root.child(key, key);
+            let result = transform_value(rule.as_ref(), &context, &mut value).and_then(|_| rule.validate_in(&context, &value));
+
+            if !rule.is_warning() {
+                result?;
+            }
+        }
+        self.object.insert(key, value);
+        Ok(self)
+    }
+
+    /// Build the object, filling the missing properties with the defaults of their rule,
+    /// or fail when a required property is missing.
+    pub fn build(mut self) -> Result<JSONValue, String> {
+        let root = ValidationContext::new("", self.schema.formatter.as_ref());
+
+        for (key, rule) in self.schema.rules.iter() {
+            if self.object.contains_key(key) {
+                continue;
+            }
+            match rule.default_value() {
+                Some(default) => {
+                    let result = rule.validate_in(&root.child(key, key), &default);
+
+                    if !rule.is_warning() {
+                        result?;
+                    }
+                    self.object.insert(key, default);
+                },
+                None if rule.is_warning() => {},
+                None => return Err(root.error("required", vec![("key", key.to_string())]))
+            }
+        }
+        Ok(JSONValue::Object(self.object))
+    }
+}
+
+struct CompiledRule<'a> {
+    key: String,
+    /// The JSON Pointer of the property.
+    path: String,
+    transforms: bool,
+    rule: Box<dyn Validator + 'a>
+}
+
+/// A JSONSchema prepared for validating many values, created by `JSONSchema::compile`.
+/// The rules are kept in a flat list along with the path of their property, and
+/// checking a value only copies the properties whose rule transforms them, so that
+/// validating a valid value allocates nothing for most rules.
+/// Like its rules, a compiled schema is `Send + Sync`, so that it can be built once and
+/// shared by the request handlers of a server.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{JSONParser, JSONSchema, NumberType, StringType};
+///
+/// let schema = JSONSchema::new([
+///   ("name", StringType::new().trim().min_length(3).boxed()),
+///   ("age", NumberType::new().gt(18.0).boxed())
+/// ]).compile();
+///
+/// let json = JSONParser::from(r#"{ "name": " Jo ", "age": 30 }"#).unwrap();
+/// assert_eq!(schema.check(&json).unwrap_err(), "name is too short (min: 3)");
+///
+/// let mut json = JSONParser::from(r#"{ "name": " John ", "age": 30 }"#).unwrap();
+/// assert!(schema.check(&json).is_ok());
+/// assert_eq!(json["name"].as_str(), Some(" John "));
+///
+/// assert!(schema.validate_in_place(&mut json).is_ok());
+/// assert_eq!(json["name"].as_str(), Some("John"));
+/// ```
+pub struct CompiledSchema<'a> {
+    rules: Vec<CompiledRule<'a>>,
+    cancellation: Option<CancellationToken>,
+    formatter: Box<dyn MessageFormatter + 'a>
+}
+
+impl<'a> CompiledSchema<'a> {
+    /// Check the given JSONValue against the schema without changing it. Transformations
+    /// are applied to copies of the properties they change, then discarded.
+    pub fn check(&self, value: &JSONValue) -> Result<(), String> {
+        self.check_in_context(value, &ValidationContext::new("", self.formatter.as_ref()), true)
+    }
+
+    /// Check the given JSONValue against the schema, passing the given data to the rules,
+    /// as `JSONSchema::validate_with_ctx` does.
+    pub fn check_with_ctx<T: Any>(&self, value: &JSONValue, data: &T) -> Result<(), String> {
+        self.check_in_context(value, &ValidationContext::new("", self.formatter.as_ref()).with_data(data), true)
+    }
+
+    /// Transform the given JSONValue in place and validate it against the schema, as
+    /// `JSONSchema::validate_in_place` does.
+    pub fn validate_in_place(&self, value: &mut JSONValue) -> Result<(), String> {
+        let root = ValidationContext::new("", self.formatter.as_ref());
+        let obj = match value {
+            JSONValue::Object(obj) => obj,
+            _ => return Err("Expected an object for validation".to_string())
+        };
+
+        for compiled in &self.rules {
+            self.check_cancelled()?;
+            match (obj.get_mut(&compiled.key), compiled.rule.default_value()) {
+                (Some(value), _) if compiled.transforms => {
+                    let result = transform_value(compiled.rule.as_ref(), &root.at(&compiled.key, &compiled.path), value);
+
+                    if !compiled.rule.is_warning() {
+                        result?;
+                    }
+                },
+                (Some(_), _) | (None, None) => {},
+                (None, Some(default)) => obj.insert(&compiled.key, default)
+            }
+        }
+        self.check_in_context(value, &root, false)
+    }
+
+    /// Check the value, transforming copies of the properties if they are not transformed yet.
+    fn check_in_context(&self, value: &JSONValue, root: &ValidationContext, transform: bool) -> Result<(), String> {
+        let obj = value.as_object().ok_or("Expected an object for validation")?;
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("check", rules = self.rules.len()).entered();
+
+        for compiled in &self.rules {
+            self.check_cancelled()?;
+            if let Err(e) = self.check_rule(compiled, obj, root, transform) {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(path = compiled.path.as_str(), warning = compiled.rule.is_warning(), error = %e, "validation failed");
+                if !compiled.rule.is_warning() {
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check_rule(&self, compiled: &CompiledRule, obj: &OrderedMap<JSONValue>, root: &ValidationContext, transform: bool) -> Result<(), String> {
+        let context = root.at(&compiled.key, &compiled.path);
+        let rule = compiled.rule.as_ref();
+
+        match obj.get(&compiled.key) {
+            Some(value) if transform && compiled.transforms => {
+                let mut transformed = value.clone();
+
+                transform_value(rule, &context, &mut transformed)?;
+                rule.validate_in(&context, &transformed)
+            },
+            Some(value) => rule.validate_in(&context, value),
+            // Defaults are not transformed.
+            None => match rule.default_value() {
+                Some(default) => rule.validate_in(&context, &default),
+                None => Err(root.error("required", vec![("key", compiled.key.clone())]))
+            }
+        }
+    }
+
+    fn check_cancelled(&self) -> Result<(), String> {
+        match &self.cancellation {
+            Some(token) if token.is_cancelled() => Err("Cancelled".to_string()),
+            _ => Ok(())
+        }
+    }
+}
+
+/// A rule checking a value. Rules are `Send + Sync`, so that a schema can be built once
+/// and shared by the threads of a server; closures given to the builders must be too.
+pub trait Validator: Send + Sync {
+    fn validate(&self, name: &str, value: &JSONValue) -> Result<(), String>;
+
+    /// Validate the value in the given context, formatting errors with its formatter.
+    /// Defaults to `validate` with the name of the context; implement it to format
+    /// errors, or to pass the context on to nested rules.
+    fn validate_in(&self, context: &ValidationContext, value: &JSONValue) -> Result<(), String> {
+        self.validate(context.name(), value)
+    }
+
+    fn transform(&self, _: &str, value: &JSONValue) -> Result<JSONValue, String> {
+        Ok(value.clone())
+    }
+
+    /// The kind of value accepted by the validator, used to reject mismatches early.
+    fn expected_kind(&self) -> Option<JSONKind> {
+        None
+    }
+
+    /// A length past which a value of the expected kind always fails, so that
+    /// `JSONSchema::validate_stream` stops reading it there: bytes for a string and items
+    /// for an array. Validating the start of a longer value must fail.
+    fn max_len(&self) -> Option<usize> {
+        None
+    }
+
+    /// Whether `transform_in_place` may change the value, so that a `CompiledSchema`
+    /// only copies the values it transforms.
+    fn transforms(&self) -> bool {
+        true
+    }
+
+    /// Transform the value in place. Defaults to replacing it with the result of `transform`;
+    /// implement it directly to avoid cloning.
+    fn transform_in_place(&self, name: &str, value: &mut JSONValue) -> Result<(), String> {
+        *value = self.transform(name, value)?;
+        Ok(())
+    }
+
+    /// Whether `JSONSchema::redact` hides the values checked by the validator.
+    fn is_sensitive(&self) -> bool {
+        false
+    }
+
+    /// Whether failures of the validator are reported as warnings rather than errors.
+    fn is_warning(&self) -> bool {
+        false
+    }
+
+    /// Describe the validator for `JSONSchema::to_markdown` and `JSONSchema::to_html`.
+    fn document(&self) -> RuleDoc {
+        RuleDoc::new("custom")
+    }
+
+    /// Describe the values accepted by the validator as an OpenAPI 3.1 schema object, for
+    /// `JSONSchema::to_openapi_schema`. Defaults to the empty schema, accepting any value.
+    fn openapi_schema(&self) -> JSONValue {
+        JSONValue::Object(OrderedMap::new())
+    }
+
+    /// Generate a value the validator is likely to accept, for `JSONSchema::generate`.
+    /// Defaults to null.
+    fn generate(&self, _: &mut Rng) -> JSONValue {
+        JSONValue::Null
+    }
+
+    /// Hide the sensitive values nested in the value, for validators holding other rules.
+    fn redact_nested(&self, _: &mut JSONValue, _: &Redaction) {}
+
+    /// The value used by `JSONSchema::validate` when the property is missing.
+    fn default_value(&self) -> Option<JSONValue> {
+        None
+    }
+}
+
+/// A rule shared by several schemas, or by several properties.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use jsonparser::{JSONParser, JSONSchema, ObjectType, StringType, Validator};
+///
+/// let email = Arc::new(StringType::new().includes("@"));
+/// let schema = Arc::new(JSONSchema::new([
+///   ("email", Box::new(email.clone()) as Box<dyn Validator>),
+///   ("manager", ObjectType::new().property("email", Box::new(email)).boxed())
+/// ]).compile());
+///
+/// let handles = (0..2).map(|_| {
+///     let schema = Arc::clone(&schema);
+///
+///     thread::spawn(move || {
+///         let json = JSONParser::from(r#"{ "email": "john@example.com", "manager": { "email": "jane" } }"#).unwrap();
+///
+///         schema.check(&json)
+///     })
+/// }).collect::<Vec<_>>();
+///
+/// for handle in handles {
+///     assert_eq!(handle.join().unwrap().unwrap_err(), "email does not include '@'");
+/// }
+/// ```
+impl<V: Validator + ?Sized> Validator for Arc<V> {
+    fn validate(&self, name: &str, value: &JSONValue) -> Result<(), String> {
+        (**self).validate(name, value)
+    }
+
+    fn validate_in(&self, context: &ValidationContext, value: &JSONValue) -> Result<(), String> {
+        (**self).validate_in(context, value)
+    }
+
+    fn transform(&self, name: &str, value: &JSONValue) -> Result<JSONValue, String> {
+        (**self).transform(name, value)
+    }
+
+    fn expected_kind(&self) -> Option<JSONKind> {
+        (**self).expected_kind()
+    }
+
+    fn max_len(&self) -> Option<usize> {
+        (**self).max_len()
+    }
+
+    fn transforms(&self) -> bool {
+        (**self).transforms()
+    }
+
+    fn transform_in_place(&self, name: &str, value: &mut JSONValue) -> Result<(), String> {
+        (**self).transform_in_place(name, value)
+    }
+
+    fn is_sensitive(&self) -> bool {
+        (**self).is_sensitive()
+    }
+
+    fn is_warning(&self) -> bool {
+        (**self).is_warning()
+    }
+
+    fn document(&self) -> RuleDoc {
+        (**self).document()
+    }
+
+    fn openapi_schema(&self) -> JSONValue {
+        (**self).openapi_schema()
+    }
+
+    fn generate(&self, rng: &mut Rng) -> JSONValue {
+        (**self).generate(rng)
+    }
+
+    fn redact_nested(&self, value: &mut JSONValue, redaction: &Redaction) {
+        (**self).redact_nested(value, redaction)
+    }
+
+    fn default_value(&self) -> Option<JSONValue> {
+        (**self).default_value()
+    }
+}
+
+/// Generate a value accepted by the rule, generating again a few times when the rule
+/// rejects it, and keeping the last value otherwise.
+fn generate_valid(rule: &dyn Validator, rng: &mut Rng) -> JSONValue {
+    let mut value = rule.generate(rng);
+
+    for _ in 1..16 {
+        let mut transformed = value.clone();
+
+        if rule.transform_in_place("", &mut transformed).and_then(|_| rule.validate("", &transformed)).is_ok() {
+            break;
+        }
+        value = rule.generate(rng);
+    }
+    value
+}
+
+/// Generate a null, a boolean, a number or a string.
+fn generate_scalar(rng: &mut Rng) -> JSONValue {
+    match rng.below(4) {
+        0 => JSONValue::Null,
+        1 => JSONValue::Boolean(rng.chance(0.5)),
+        2 => JSONValue::Number(rng.below(100) as f64),
+        _ => {
+            let len = rng.between(1, 8);
+
+            JSONValue::String(generate_letters(rng, len).into())
+        }
+    }
+}
+
+fn generate_letters(rng: &mut Rng, len: usize) -> String {
+    (0..len).map(|_| (b'a' + rng.below(26) as u8) as char).collect()
+}
+
+/// Transform the value in place. Transformations fail on the values the rule rejects,
+/// so the error is formatted by validating the value in the context when possible.
+fn transform_value(rule: &dyn Validator, context: &ValidationContext, value: &mut JSONValue) -> Result<(), String> {
+    rule.transform_in_place(context.name(), value)
+        .map_err(|e| rule.validate_in(context, value).err().unwrap_or(e))
+}
+
+/// Clone the value and transform the copy in place.
+fn transform_cloned<V: Validator + ?Sized>(validator: &V, name: &str, value: &JSONValue) -> Result<JSONValue, String> {
+    let mut transformed = value.clone();
+
+    validator.transform_in_place(name, &mut transformed)?;
+    Ok(transformed)
+}
+
+/// A custom error message, built from the failing value.
+type MessageFn = Box<dyn Fn(&JSONValue) -> String + Send + Sync>;
+
+fn fixed_message(message: &str) -> MessageFn {
+    let message = message.to_string();
+
+    Box::new(move |_| message.clone())
+}
+
+/// The custom error messages of a rule, each replacing the message of a constraint.
+/// Constraints are identified by a code, such as `min_length` or `type` for a type mismatch.
+#[derive(Default)]
+struct Messages {
+    /// The code of the constraint added last, which the next message applies to.
+    last: Option<&'static str>,
+    /// Messages by constraint code, None standing for every failure of the rule.
+    custom: Vec<(Option<&'static str>, MessageFn)>
+}
+
+impl Messages {
+    fn constraint(&mut self, code: &'static str) {
+        self.last = Some(code);
+    }
+
+    fn set(&mut self, message: MessageFn) {
+        let code = self.last;
+
+        self.custom.retain(|(c, _)| *c != code);
+        self.custom.push((code, message));
+    }
+
+    /// The custom message for the failing constraint, or the default one.
+    fn error<F: FnOnce() -> String>(&self, code: &'static str, value: &JSONValue, default: F) -> String {
+        let custom = self.custom.iter().find(|(c, _)| *c == Some(code))
+            .or_else(|| self.custom.iter().find(|(c, _)| c.is_none()));
+
+        match custom {
+            Some((_, message)) => message(value),
+            None => default()
+        }
+    }
+}
+
+/// The documentation of a rule, shown by `JSONSchema::to_markdown` and `JSONSchema::to_html`.
+#[derive(Default)]
+struct Metadata {
+    description: Option<String>,
+    examples: Vec<JSONValue>,
+    deprecated: bool
+}
+
+impl Metadata {
+    fn document(&self, kind: &str) -> RuleDoc {
+        RuleDoc {
+            description: self.description.clone(),
+            examples: self.examples.clone(),
+            deprecated: self.deprecated,
+            ..RuleDoc::new(kind)
+        }
+    }
+
+    /// Start the OpenAPI schema of a rule with the given type and its documentation.
+    fn openapi_schema(&self, kind: &str) -> OrderedMap<JSONValue> {
+        let mut schema = OrderedMap::new();
+
+        schema.insert("type", JSONValue::String(kind.into()));
+        if let Some(description) = &self.description {
+            schema.insert("description", JSONValue::String(description.clone().into()));
+        }
+        if !self.examples.is_empty() {
+            schema.insert("examples", JSONValue::Array(self.examples.clone().into()));
+        }
+        if self.deprecated {
+            schema.insert("deprecated", JSONValue::Boolean(true));
+        }
+        schema
+    }
+}
+
+/// Add a numeric keyword to the OpenAPI schema of a rule, when it is set.
+fn openapi_number(schema: &mut OrderedMap<JSONValue>, keyword: &str, value: Option<f64>) {
+    if let Some(value) = value {
+        schema.insert(keyword, JSONValue::Number(value));
+    }
+}
+
+/// Escape the characters read specially by regular expressions.
+fn escape_regex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if "\\^$.|?*+()[]{}".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Add a constraint with an argument to the documentation of a rule, when it is set.
+fn document_option<T: Display>(doc: &mut RuleDoc, name: &str, value: Option<T>) {
+    if let Some(value) = value {
+        doc.constraints.push(format!("{}: {}", name, value));
+    }
+}
+
+/// Add a constraint without an argument to the documentation of a rule, when it is set.
+fn document_flag(doc: &mut RuleDoc, name: &str, set: bool) {
+    if set {
+        doc.constraints.push(name.to_string());
+    }
+}
+
+type StringTransform = Box<dyn Fn(&str) -> String + Send + Sync>;
+
+/// A substring expected by a StringType, and whether its case is ignored.
+type Pattern = (String, bool);
+
+pub struct StringType {
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    length: Option<usize>,
+    count_chars: bool,
+    non_empty: bool,
+    starts_with: Option<Pattern>,
+    ends_with: Option<Pattern>,
+    includes: Option<Pattern>,
+    /// The source of the regular expression, and the expression or the reason it is invalid.
+    pattern: Option<(String, Result<Regex, String>)>,
+    trim: bool,
+    trim_start: bool,
+    trim_end: bool,
+    lowercase: bool,
+    uppercase: bool,
+    messages: Messages,
+    metadata: Metadata,
+    sensitive: bool,
+    warning: bool,
+    transform: Option<StringTransform>
+}
+
+impl Default for StringType {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StringType {
+    /// Create a new StringType instance.
+    pub fn new() -> Self {
+        Self {
+            min_length: None,
+            max_length: None,
+            length: None,
+            count_chars: false,
+            non_empty: false,
+            starts_with: None,
+            ends_with: None,
+            includes: None,
+            pattern: None,
+            trim: false,
+            trim_start: false,
+            trim_end: false,
+            lowercase: false,
+            uppercase: false,
+            messages: Messages::default(),
+            metadata: Metadata::default(),
+            sensitive: false,
+            warning: false,
+            transform: None
+        }
+    }
+
+    /// Set the minimum length of the string.
+    pub fn min_length(mut self, min: usize) -> Self {
+        self.min_length = Some(min);
+        self.messages.constraint("min_length");
+        self
+    }
+
+    /// Set the maximum length of the string.
+    pub fn max_length(mut self, max: usize) -> Self {
+        self.max_length = Some(max);
+        self.messages.constraint("max_length");
+        self
+    }
+
+    /// Set the exact length of the string.
+    pub fn length(mut self, length: usize) -> Self {
+        self.length = Some(length);
+        self.messages.constraint("length");
+        self
+    }
+
+    /// Measure lengths in characters, so that `"café"` has a length of 4.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONSchema, StringType};
+    ///
+    /// let json = JSONParser::from(r#"{ "name": "Zoë" }"#).unwrap();
+    ///
+    /// let schema = JSONSchema::new([("name", StringType::new().max_length(3).boxed())]);
+    /// assert_eq!(schema.validate(&json).unwrap_err(), "name is too long (max: 3)");
+    ///
+    /// let schema = JSONSchema::new([("name", StringType::new().max_length(3).len_chars().boxed())]);
+    /// assert!(schema.validate(&json).is_ok());
+    /// ```
+    pub fn len_chars(mut self) -> Self {
+        self.count_chars = true;
+        self
+    }
+
+    /// Measure lengths in bytes of UTF-8, as done by default.
+    pub fn len_bytes(mut self) -> Self {
+        self.count_chars = false;
+        self
+    }
+
+    /// Reject the empty string. Combine with `trim` to also reject blank strings.
+    pub fn non_empty(mut self) -> Self {
+        self.non_empty = true;
+        self.messages.constraint("non_empty");
+        self
+    }
+
+    /// Set the expected starting of the string.
+    pub fn starts_with(mut self, value: &str) -> Self {
+        self.starts_with = Some((value.to_string(), false));
+        self.messages.constraint("starts_with");
+        self
+    }
+
+    /// Set the expected starting of the string, ignoring case.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONSchema, StringType};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("url", StringType::new().starts_with_ignore_case("https://").ends_with_ignore_case(".org").boxed()),
+    ///   ("title", StringType::new().trim().non_empty().includes_ignore_case("rust").boxed())
+    /// ]);
+    ///
+    /// let json = JSONParser::from(r#"{ "url": "HTTPS://rust-lang.ORG", "title": "The Rust Book" }"#).unwrap();
+    /// assert!(schema.validate(&json).is_ok());
+    ///
+    /// let json = JSONParser::from(r#"{ "url": "https://rust-lang.org", "title": "  " }"#).unwrap();
+    /// assert_eq!(schema.validate(&json).unwrap_err(), "title is empty");
+    /// ```
+    pub fn starts_with_ignore_case(mut self, value: &str) -> Self {
+        self.starts_with = Some((value.to_string(), true));
+        self.messages.constraint("starts_with");
+        self
+    }
+
+    /// Set the expected ending of the string.
+    pub fn ends_with(mut self, value: &str) -> Self {
+        self.ends_with = Some((value.to_string(), false));
+        self.messages.constraint("ends_with");
+        self
+    }
+
+    /// Set the expected ending of the string, ignoring case.
+    pub fn ends_with_ignore_case(mut self, value: &str) -> Self {
+        self.ends_with = Some((value.to_string(), true));
+        self.messages.constraint("ends_with");
+        self
+    }
+
+    /// Set the string to include a specific substring.
+    pub fn includes(mut self, value: &str) -> Self {
+        self.includes = Some((value.to_string(), false));
+        self.messages.constraint("includes");
+        self
+    }
+
+    /// Set the string to include a specific substring, ignoring case.
+    pub fn includes_ignore_case(mut self, value: &str) -> Self {
+        self.includes = Some((value.to_string(), true));
+        self.messages.constraint("includes");
+        self
+    }
+
+    /// Set a regular expression the string must match. The expression matches anywhere
+    /// in the string unless anchored with `^` and `$`. It supports classes such as `[a-z_]`
+    /// and `\d`, groups, alternation, and the quantifiers `* + ?` and `{n,m}`. An invalid
+    /// expression fails every validation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONSchema, StringType};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("zip", StringType::new().pattern(r"^\d{5}(-\d{4})?$").boxed())
+    /// ]);
+    ///
+    /// let json = JSONParser::from(r#"{ "zip": "62701-1234" }"#).unwrap();
+    /// assert!(schema.validate(&json).is_ok());
+    ///
+    /// let json = JSONParser::from(r#"{ "zip": "6270" }"#).unwrap();
+    /// assert_eq!(schema.validate(&json).unwrap_err(), r"zip does not match the pattern '^\d{5}(-\d{4})?$'");
+    ///
+    /// // A repeated character or class is matched in a loop, however long the string.
+    /// let schema = JSONSchema::new([("name", StringType::new().pattern("^[a-z]+$").boxed())]);
+    /// let json = JSONParser::from(&format!(r#"{{ "name": "{}" }}"#, "a".repeat(1_000_000))).unwrap();
+    ///
+    /// assert!(schema.validate(&json).is_ok());
+    /// ```
+    pub fn pattern(mut self, pattern: &str) -> Self {
+        self.pattern = Some((pattern.to_string(), Regex::new(pattern)));
+        self.messages.constraint("pattern");
+        self
+    }
+
+    /// Trim the string before validation.
+    pub fn trim(mut self) -> Self {
+        self.trim = true;
+        self
+    }
+
+    /// Trim the start of the string before validation.
+    pub fn trim_start(mut self) -> Self {
+        self.trim_start = true;
+        self
+    }
+
+    /// Trim the end of the string before validation.
+    pub fn trim_end(mut self) -> Self {
+        self.trim_end = true;
+        self
+    }
+
+    /// Convert the string to lowercase before validation.
+    pub fn to_lowercase(mut self) -> Self {
+        self.lowercase = true;
+        self
+    }
+
+    /// Convert the string to uppercase before validation.
+    pub fn to_uppercase(mut self) -> Self {
+        self.uppercase = true;
+        self
+    }
+
+    /// Set a custom transformation function for the string.
+    pub fn transform<F: 'static + Send + Sync + Fn(&str) -> String>(mut self, transform: F) -> Self {
+        self.transform = Some(Box::new(transform));
+        self
+    }
+
+    /// Mark the values checked by this rule as sensitive, to be hidden by `JSONSchema::redact`.
+    pub fn sensitive(mut self) -> Self {
+        self.sensitive = true;
+        self
+    }
+
+    /// Report failures of this rule as warnings, which do not fail validation.
+    /// See `JSONSchema::validate_all`.
+    pub fn warn(mut self) -> Self {
+        self.warning = true;
+        self
+    }
+
+    /// Describe the values checked by this rule, for the documentation generated from the schema.
+    pub fn describe(mut self, description: &str) -> Self {
+        self.metadata.description = Some(description.to_string());
+        self
+    }
+
+    /// Add an example of a valid value, for the documentation generated from the schema.
+    pub fn example(mut self, example: JSONValue) -> Self {
+        self.metadata.examples.push(example);
+        self
+    }
+
+    /// Mark the property as deprecated in the documentation generated from the schema.
+    pub fn deprecated(mut self) -> Self {
+        self.metadata.deprecated = true;
+        self
+    }
+
+    /// Replace the error message of the last constraint added, or of every failure of
+    /// the rule when called before any constraint.
+    pub fn message(mut self, message: &str) -> Self {
+        self.messages.set(fixed_message(message));
+        self
+    }
+
+    /// Replace the error message like `message`, building it from the failing value.
+    pub fn message_with<F: 'static + Send + Sync + Fn(&JSONValue) -> String>(mut self, message: F) -> Self {
+        self.messages.set(Box::new(message));
+        self
+    }
+
+    /// Convert the StringType to a Box<dyn Validator>.
+    pub fn boxed(self) -> Box<dyn Validator> {
+        Box::new(self)
+    }
+}
+
+impl Validator for StringType {
+    fn expected_kind(&self) -> Option<JSONKind> {
+        Some(JSONKind::String)
+    }
+
+    /// Transformations may change the length, so only rules without any are bounded.
+    fn max_len(&self) -> Option<usize> {
+        let max = match (self.max_length, self.length) {
+            (Some(max), Some(length)) => max.min(length),
+            (max, length) => max.or(length)?
+        };
+
+        if self.transforms() {
+            return None;
+        }
+        // A character takes at most four bytes.
+        Some(if self.count_chars { max.saturating_mul(4) } else { max })
+    }
+
+    fn is_sensitive(&self) -> bool {
+        self.sensitive
+    }
+
+    fn is_warning(&self) -> bool {
+        self.warning
+    }
+
+    fn generate(&self, rng: &mut Rng) -> JSONValue {
+        if let Some((_, Ok(regex))) = &self.pattern {
+            return JSONValue::String(regex.generate(rng).into());
+        }
+        let text = |pattern: &Option<Pattern>| pattern.as_ref().map_or(String::new(), |(text, _)| text.clone());
+        let (prefix, infix, suffix) = (text(&self.starts_with), text(&self.includes), text(&self.ends_with));
+        let len = |s: &str| if self.count_chars { s.chars().count() } else { s.len() };
+        let min = self.length.or(self.min_length).unwrap_or(0).max(self.non_empty as usize);
+        let max = self.length.or(self.max_length).unwrap_or(min + 10).max(min);
+        let random = rng.between(min, max).saturating_sub(len(&prefix) + len(&infix) + len(&suffix));
+        let middle = rng.below(random + 1);
+
+        JSONValue::String((prefix + &generate_letters(rng, middle) + &infix + &generate_letters(rng, random - middle) + &suffix).into())
+    }
+
+    fn document(&self) -> RuleDoc {
+        let mut doc = self.metadata.document("string");
+
+        document_option(&mut doc, "min_length", self.min_length);
+        document_option(&mut doc, "max_length", self.max_length);
+        document_option(&mut doc, "length", self.length);
+        document_flag(&mut doc, "len_chars", self.count_chars);
+        document_flag(&mut doc, "non_empty", self.non_empty);
+        for (name, pattern) in [("starts_with", &self.starts_with), ("ends_with", &self.ends_with), ("includes", &self.includes)] {
+            if let Some((text, ignore_case)) = pattern {
+                let suffix = if *ignore_case { "_ignore_case" } else { "" };
+
+                doc.constraints.push(format!("{}{}: '{}'", name, suffix, text));
+            }
+        }
+        document_option(&mut doc, "pattern", self.pattern.as_ref().map(|(source, _)| format!("'{}'", source)));
+        document_flag(&mut doc, "trim", self.trim);
+        document_flag(&mut doc, "trim_start", self.trim_start);
+        document_flag(&mut doc, "trim_end", self.trim_end);
+        document_flag(&mut doc, "to_lowercase", self.lowercase);
+        document_flag(&mut doc, "to_uppercase", self.uppercase);
+        doc
+    }
+
+    fn openapi_schema(&self) -> JSONValue {
+        let mut schema = self.metadata.openapi_schema("string");
+        let min = self.length.or(self.min_length).unwrap_or(0).max(self.non_empty as usize);
+        let mut patterns = self.pattern.iter().map(|(source, _)| source.clone()).collect::<Vec<_>>();
+
+        openapi_number(&mut schema, "minLength", Some(min as f64).filter(|min| *min > 0.0));
+        openapi_number(&mut schema, "maxLength", self.length.or(self.max_length).map(|max| max as f64));
+        // Case-insensitive matches cannot be written without flags, so they are left out.
+        for (pattern, start, end) in [(&self.starts_with, "^", ""), (&self.includes, "", ""), (&self.ends_with, "", "$")] {
+            if let Some((text, false)) = pattern {
+                patterns.push(format!("{}{}{}", start, escape_regex(text), end));
+            }
+        }
+        match patterns.len() {
+            0 => {},
+            1 => schema.insert("pattern", JSONValue::String(patterns.remove(0).into())),
+            _ => schema.insert("allOf", JSONValue::Array(patterns.into_iter().map(|pattern| {
+                JSONValue::Object(vec![("pattern".to_string(), JSONValue::String(pattern.into()))].into_iter().collect())
+            }).collect()))
+        }
+        JSONValue::Object(schema)
+    }
+
+    fn validate(&self, key: &str, value: &JSONValue) -> Result<(), String> {
+        self.validate_in(&ValidationContext::new(key, &EnglishFormatter), value)
+    }
+
+    fn validate_in(&self, context: &ValidationContext, value: &JSONValue) -> Result<(), String> {
+        match value {
+            JSONValue::String(s) => {
+                let len = if self.count_chars { s.chars().count() } else { s.len() };
+
+                if self.non_empty && s.is_empty() {
+                    return Err(self.messages.error("non_empty", value, || context.error("non_empty", Vec::new())));
+                }
+
+                if let Some(min) = self.min_length {
+                    if len < min {
+                        return Err(self.messages.error("min_length", value, || context.error("min_length", vec![("min", min.to_string())])));
+                    }
+                }
+
+                if let Some(max) = self.max_length {
+                    if len > max {
+                        return Err(self.messages.error("max_length", value, || context.error("max_length", vec![("max", max.to_string())])));
+                    }
+                }
+
+                if let Some(length) = self.length {
+                    if len != length {
+                        return Err(self.messages.error("length", value, || context.error("length", vec![("length", length.to_string())])));
+                    }
+                }
+
+                if let Some((starts_with, ignore_case)) = &self.starts_with {
+                    if !matches_pattern(s, starts_with, *ignore_case, |s, p| s.starts_with(p)) {
+                        return Err(self.messages.error("starts_with", value, || context.error("starts_with", vec![("prefix", starts_with.clone())])));
+                    }
+                }
+
+                if let Some((ends_with, ignore_case)) = &self.ends_with {
+                    if !matches_pattern(s, ends_with, *ignore_case, |s, p| s.ends_with(p)) {
+                        return Err(self.messages.error("ends_with", value, || context.error("ends_with", vec![("suffix", ends_with.clone())])));
+                    }
+                }
+
+                if let Some((includes, ignore_case)) = &self.includes {
+                    if !matches_pattern(s, includes, *ignore_case, |s, p| s.contains(p)) {
+                        return Err(self.messages.error("includes", value, || context.error("includes", vec![("substring", includes.clone())])));
+                    }
+                }
+
+                match &self.pattern {
+                    Some((source, Err(e))) => return Err(format!("Invalid pattern '{}' for {}: {}", source, context.name(), e)),
+                    Some((source, Ok(regex))) if !regex.is_match(s) => {
+                        return Err(self.messages.error("pattern", value, || context.error("pattern", vec![("pattern", source.clone())])));
+                    },
+                    _ => {}
+                }
+
+                Ok(())
+            },
+            _ => Err(self.messages.error("type", value, || context.error("type", vec![("expected", "String".to_string())])))
+        }
+    }
+
+    fn transform(&self, key: &str, value: &JSONValue) -> Result<JSONValue, String> {
+        transform_cloned(self, key, value)
+    }
+
+    fn transforms(&self) -> bool {
+        self.trim || self.trim_start || self.trim_end || self.lowercase || self.uppercase || self.transform.is_some()
+    }
+
+    fn transform_in_place(&self, key: &str, value: &mut JSONValue) -> Result<(), String> {
+        match value {
+            JSONValue::String(s) => {
+                if self.trim && s.trim().len() != s.len() {
+                    *s = s.trim().into();
+                }
+
+                if self.trim_start && s.trim_start().len() != s.len() {
+                    *s = s.trim_start().into();
+                }
+
+                if self.trim_end && s.trim_end().len() != s.len() {
+                    *s = s.trim_end().into();
+                }
+
+                if self.lowercase {
+                    *s = s.to_lowercase().into();
+                }
+
+                if self.uppercase {
+                    *s = s.to_uppercase().into();
+                }
+
+                if let Some(transform) = &self.transform {
+                    *s = transform(s).into();
+                }
+
+                Ok(())
+            },
+            _ => Err(self.messages.error("type", value, || format!("Type of {} mismatch, expected String", key)))
+        }
+    }
+}
+
+/// Check a string against a pattern, comparing their lowercase forms if the case is ignored.
+fn matches_pattern<F: Fn(&str, &str) -> bool>(s: &str, pattern: &str, ignore_case: bool, matches: F) -> bool {
+    if ignore_case {
+        matches(&s.to_lowercase(), &pattern.to_lowercase())
+    } else {
+        matches(s, pattern)
+    }
+}
+
+pub struct NumberType {
+    gt: Option<f64>,
+    gte: Option<f64>,
+    lt: Option<f64>,
+    lte: Option<f64>,
+    eq: Option<f64>,
+    multiple_of: Option<f64>,
+    integer: Option<bool>,
+    finite: bool,
+    positive: bool,
+    negative: bool,
+    floor: bool,
+    ceil: bool,
+    round: bool,
+    clamp: Option<(f64, f64)>,
+    messages: Messages,
+    metadata: Metadata,
+    sensitive: bool,
+    warning: bool,
+    transform: Option<Box<dyn Fn(f64) -> f64 + Send + Sync>>
+}
+
+impl Default for NumberType {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NumberType {
+    /// Create a new NumberType instance.
+    pub fn new() -> Self {
+        Self {
+            gt: None,
+            gte: None,
+            lt: None,
+            lte: None,
+            eq: None,
+            multiple_of: None,
+            integer: None,
+            finite: false,
+            positive: false,
+            negative: false,
+            floor: false,
+            ceil: false,
+            round: false,
+            clamp: None,
+            messages: Messages::default(),
+            metadata: Metadata::default(),
+            sensitive: false,
+            warning: false,
+            transform: None
+        }
+    }
+
+    /// Set an exclusive minimum: the number must be greater than the value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONSchema, NumberType};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("age", NumberType::new().gt(18.0).boxed()),
+    ///   ("score", NumberType::new().gte(0.0).lte(100.0).boxed())
+    /// ]);
+    ///
+    /// let json = JSONParser::from(r#"{ "age": 19, "score": 100 }"#).unwrap();
+    /// assert!(schema.validate(&json).is_ok());
+    ///
+    /// let json = JSONParser::from(r#"{ "age": 18, "score": 100 }"#).unwrap();
+    /// assert_eq!(schema.validate(&json).unwrap_err(), "age must be greater than 18");
+    /// ```
+    pub fn gt(mut self, value: f64) -> Self {
+        self.gt = Some(value);
+        self.messages.constraint("gt");
+        self
+    }
+
+    /// Set an inclusive minimum: the number must be greater than or equal to the value.
+    pub fn gte(mut self, value: f64) -> Self {
+        self.gte = Some(value);
+        self.messages.constraint("gte");
+        self
+    }
+
+    /// Set an exclusive maximum: the number must be less than the value.
+    pub fn lt(mut self, value: f64) -> Self {
+        self.lt = Some(value);
+        self.messages.constraint("lt");
+        self
+    }
+
+    /// Set an inclusive maximum: the number must be less than or equal to the value.
+    pub fn lte(mut self, value: f64) -> Self {
+        self.lte = Some(value);
+        self.messages.constraint("lte");
+        self
+    }
+
+    /// Set the expected value of the number.
+    pub fn eq(mut self, value: f64) -> Self {
+        self.eq = Some(value);
+        self.messages.constraint("eq");
+        self
+    }
+
+    /// Set a positive step the number must be a multiple of. Small floating-point
+    /// errors are tolerated, so that `0.3` is a multiple of `0.1`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONSchema, NumberType};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("price", NumberType::new().multiple_of(0.05).positive().boxed())
+    /// ]);
+    ///
+    /// let json = JSONParser::from(r#"{ "price": 1.15 }"#).unwrap();
+    /// assert!(schema.validate(&json).is_ok());
+    ///
+    /// let json = JSONParser::from(r#"{ "price": 1.12 }"#).unwrap();
+    /// assert_eq!(schema.validate(&json).unwrap_err(), "price is not a multiple of 0.05");
+    ///
+    /// let json = JSONParser::from(r#"{ "price": 0 }"#).unwrap();
+    /// assert_eq!(schema.validate(&json).unwrap_err(), "price is not positive");
+    /// ```
+    pub fn multiple_of(mut self, step: f64) -> Self {
+        self.multiple_of = Some(step);
+        self.messages.constraint("multiple_of");
+        self
+    }
+
+    /// Set whether the number should be an integer.
+    pub fn integer(mut self) -> Self {
+        self.integer = Some(true);
+        self.messages.constraint("integer");
+        self
+    }
+
+    /// Reject infinite numbers and NaN, such as an exponent too large for a 64-bit float.
+    pub fn finite(mut self) -> Self {
+        self.finite = true;
+        self.messages.constraint("finite");
+        self
+    }
+
+    /// Require the number to be greater than zero.
+    pub fn positive(mut self) -> Self {
+        self.positive = true;
+        self.messages.constraint("positive");
+        self
+    }
+
+    /// Require the number to be less than zero.
+    pub fn negative(mut self) -> Self {
+        self.negative = true;
+        self.messages.constraint("negative");
+        self
+    }
+
+    /// Round the number down before validation.
+    pub fn floor(mut self) -> Self {
+        self.floor = true;
+        self
+    }
+
+    /// Round the number up before validation.
+    pub fn ceil(mut self) -> Self {
+        self.ceil = true;
+        self
+    }
+
+    /// Round the number to the nearest integer before validation.
+    pub fn round(mut self) -> Self {
+        self.round = true;
+        self
+    }
+
+    /// Bring the number within the given bounds before validation, after any rounding.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONSchema, NumberType};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("volume", NumberType::new().round().clamp(0.0, 10.0).boxed())
+    /// ]);
+    ///
+    /// let json = JSONParser::from(r#"{ "volume": 12.4 }"#).unwrap();
+    ///
+    /// assert_eq!(schema.validate(&json).unwrap()["volume"].as_f64(), Some(10.0));
+    /// ```
+    pub fn clamp(mut self, min: f64, max: f64) -> Self {
+        self.clamp = Some((min, max));
+        self
+    }
+
+    /// Set a custom transformation function for the number.
+    pub fn transform<F: 'static + Send + Sync + Fn(f64) -> f64>(mut self, transform: F) -> Self {
+        self.transform = Some(Box::new(transform));
+        self
+    }
+
+    /// Mark the values checked by this rule as sensitive, to be hidden by `JSONSchema::redact`.
+    pub fn sensitive(mut self) -> Self {
+        self.sensitive = true;
+        self
+    }
+
+    /// Report failures of this rule as warnings, which do not fail validation.
+    /// See `JSONSchema::validate_all`.
+    pub fn warn(mut self) -> Self {
+        self.warning = true;
+        self
+    }
+
+    /// Describe the values checked by this rule, for the documentation generated from the schema.
+    pub fn describe(mut self, description: &str) -> Self {
+        self.metadata.description = Some(description.to_string());
+        self
+    }
+
+    /// Add an example of a valid value, for the documentation generated from the schema.
+    pub fn example(mut self, example: JSONValue) -> Self {
+        self.metadata.examples.push(example);
+        self
+    }
+
+    /// Mark the property as deprecated in the documentation generated from the schema.
+    pub fn deprecated(mut self) -> Self {
+        self.metadata.deprecated = true;
+        self
+    }
+
+    /// Replace the error message of the last constraint added, or of every failure of
+    /// the rule when called before any constraint.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONSchema, JSONValue, NumberType};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("age", NumberType::new().message("Age must be a number")
+    ///     .gte(18.0).message("Age must be over 18")
+    ///     .integer().message_with(|value| format!("{} is not a whole number of years", value))
+    ///   .boxed())
+    /// ]);
+    ///
+    /// let json = JSONParser::from(r#"{ "age": 16 }"#).unwrap();
+    /// assert_eq!(schema.validate(&json).unwrap_err(), "Age must be over 18");
+    ///
+    /// let json = JSONParser::from(r#"{ "age": 18.5 }"#).unwrap();
+    /// assert_eq!(schema.validate(&json).unwrap_err(), "18.5 is not a whole number of years");
+    ///
+    /// let json = JSONParser::from(r#"{ "age": "18" }"#).unwrap();
+    /// assert_eq!(schema.validate(&json).unwrap_err(), "Age must be a number");
+    /// ```
+    pub fn message(mut self, message: &str) -> Self {
+        self.messages.set(fixed_message(message));
+        self
+    }
+
+    /// Replace the error message like `message`, building it from the failing value.
+    pub fn message_with<F: 'static + Send + Sync + Fn(&JSONValue) -> String>(mut self, message: F) -> Self {
+        self.messages.set(Box::new(message));
+        self
+    }
+
+    /// Convert the NumberType to a Box<dyn Validator>.
+    pub fn boxed(self) -> Box<dyn Validator> {
+        Box::new(self)
+    }
+}
+
+impl Validator for NumberType {
+    fn expected_kind(&self) -> Option<JSONKind> {
+        Some(JSONKind::Number)
+    }
+
+    fn is_sensitive(&self) -> bool {
+        self.sensitive
+    }
+
+    fn is_warning(&self) -> bool {
+        self.warning
+    }
+
+    fn generate(&self, rng: &mut Rng) -> JSONValue {
+        if let Some(expected) = self.eq {
+            return JSONValue::Number(expected);
+        }
+        let mut min = self.gte.into_iter().chain(self.gt).fold(f64::NEG_INFINITY, f64::max);
+        let mut max = self.lte.into_iter().chain(self.lt).fold(f64::INFINITY, f64::min);
+
+        if self.positive {
+            min = min.max(0.0);
+        }
+        if self.negative {
+            max = max.min(0.0);
+        }
+        match (min.is_finite(), max.is_finite()) {
+            (false, false) => {
+                min = 0.0;
+                max = 100.0;
+            },
+            (true, false) => max = min + 100.0,
+            (false, true) => min = max - 100.0,
+            (true, true) => {}
+        }
+
+        let step = self.multiple_of.map(f64::abs).or(if self.integer == Some(true) { Some(1.0) } else { None });
+        let n = match step {
+            Some(step) if step > 0.0 => {
+                let (low, high) = ((min / step).ceil(), (max / step).floor());
+
+                (low + (rng.float() * (high - low + 1.0)).floor().min(high - low).max(0.0)) * step
+            },
+            _ => min + rng.float() * (max - min)
+        };
+
+        JSONValue::Number(n)
+    }
+
+    fn document(&self) -> RuleDoc {
+        let mut doc = self.metadata.document("number");
+
+        document_option(&mut doc, "gt", self.gt);
+        document_option(&mut doc, "gte", self.gte);
+        document_option(&mut doc, "lt", self.lt);
+        document_option(&mut doc, "lte", self.lte);
+        document_option(&mut doc, "eq", self.eq);
+        document_option(&mut doc, "multiple_of", self.multiple_of);
+        document_flag(&mut doc, "integer", self.integer == Some(true));
+        document_flag(&mut doc, "finite", self.finite);
+        document_flag(&mut doc, "positive", self.positive);
+        document_flag(&mut doc, "negative", self.negative);
+        document_flag(&mut doc, "floor", self.floor);
+        document_flag(&mut doc, "ceil", self.ceil);
+        document_flag(&mut doc, "round", self.round);
+        document_option(&mut doc, "clamp", self.clamp.map(|(min, max)| format!("[{}, {}]", min, max)));
+        doc
+    }
+
+    fn openapi_schema(&self) -> JSONValue {
+        let mut schema = self.metadata.openapi_schema(if self.integer == Some(true) { "integer" } else { "number" });
+        let positive = if self.positive { Some(0.0) } else { None };
+        let negative = if self.negative { Some(0.0) } else { None };
+
+        openapi_number(&mut schema, "exclusiveMinimum", self.gt.into_iter().chain(positive).reduce(f64::max));
+        openapi_number(&mut schema, "minimum", self.gte);
+        openapi_number(&mut schema, "exclusiveMaximum", self.lt.into_iter().chain(negative).reduce(f64::min));
+        openapi_number(&mut schema, "maximum", self.lte);
+        openapi_number(&mut schema, "multipleOf", self.multiple_of);
+        if let Some(expected) = self.eq {
+            schema.insert("enum", JSONValue::Array(vec![JSONValue::Number(expected)].into()));
+        }
+        JSONValue::Object(schema)
+    }
+
+    fn validate(&self, key: &str, value: &JSONValue) -> Result<(), String> {
+        self.validate_in(&ValidationContext::new(key, &EnglishFormatter), value)
+    }
+
+    fn validate_in(&self, context: &ValidationContext, value: &JSONValue) -> Result<(), String> {
+        match value {
+            JSONValue::Number(n) => {
+                if self.finite && !n.is_finite() {
+                    return Err(self.messages.error("finite", value, || context.error("finite", Vec::new())));
+                }
+
+                if let Some(gt) = self.gt {
+                    if *n <= gt {
+                        return Err(self.messages.error("gt", value, || context.error("gt", vec![("gt", gt.to_string())])));
+                    }
+                }
+
+                if let Some(min) = self.gte {
+                    if n < &min {
+                        return Err(self.messages.error("gte", value, || context.error("gte", vec![("min", min.to_string())])));
+                    }
+                }
+
+                if let Some(lt) = self.lt {
+                    if *n >= lt {
+                        return Err(self.messages.error("lt", value, || context.error("lt", vec![("lt", lt.to_string())])));
+                    }
+                }
+
+                if let Some(max) = self.lte {
+                    if n > &max {
+                        return Err(self.messages.error("lte", value, || context.error("lte", vec![("max", max.to_string())])));
+                    }
+                }
+
+                if let Some(expected) = self.eq {
+                    if *n != expected {
+                        return Err(self.messages.error("eq", value, || context.error("eq", vec![("expected", expected.to_string())])));
+                    }
+                }
+
+                if self.positive && *n <= 0.0 {
+                    return Err(self.messages.error("positive", value, || context.error("positive", Vec::new())));
+                }
+
+                if self.negative && *n >= 0.0 {
+                    return Err(self.messages.error("negative", value, || context.error("negative", Vec::new())));
+                }
+
+                if let Some(step) = self.multiple_of {
+                    let quotient = n / step;
+                    let error = (quotient - quotient.round()).abs();
+
+                    if error.is_nan() || error > 1e-9 * quotient.abs().max(1.0) {
+                        return Err(self.messages.error("multiple_of", value, || context.error("multiple_of", vec![("step", step.to_string())])));
+                    }
+                }
+
+                if let Some(integer) = self.integer {
+                    if integer && !n.fract().eq(&0.0) {
+                        return Err(self.messages.error("integer", value, || context.error("integer", Vec::new())));
+                    }
+                }
+
+                Ok(())
+            },
+            _ => Err(self.messages.error("type", value, || context.error("type", vec![("expected", "Number".to_string())])))
+        }
+    }
+
+    fn transform(&self, key: &str, value: &JSONValue) -> Result<JSONValue, String> {
+        transform_cloned(self, key, value)
+    }
+
+    fn transforms(&self) -> bool {
+        self.floor || self.ceil || self.round || self.clamp.is_some() || self.transform.is_some()
+    }
+
+    fn transform_in_place(&self, key: &str, value: &mut JSONValue) -> Result<(), String> {
+        match value {
+            JSONValue::Number(n) => {
+                if self.floor {
+                    *n = n.floor();
+                }
+
+                if self.ceil {
+                    *n = n.ceil();
+                }
+
+                if self.round {
+                    *n = n.round();
+                }
+
+                if let Some((min, max)) = self.clamp {
+                    *n = n.max(min).min(max);
+                }
+
+                if let Some(transform) = &self.transform {
+                    *n = transform(*n);
+                }
+
+                Ok(())
+            },
+            _ => Err(self.messages.error("type", value, || format!("Type of {} mismatch, expected Number", key)))
+        }
+    }
+}
+
+type ArrayTransform = Box<dyn Fn(Vec<JSONValue>) -> Vec<JSONValue> + Send + Sync>;
+
+/// Which items of an array must differ from each other.
+enum Uniqueness {
+    Items,
+    /// The values of the given key, for arrays of objects.
+    Key(String)
+}
+
+pub struct ArrayType {
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    length: Option<usize>,
+    empty: Option<bool>,
+    every: Option<Box<dyn Validator>>,
+    some: Option<Box<dyn Validator>>,
+    at: Option<(usize, Box<dyn Validator>)>,
+    unique: Option<Uniqueness>,
+    contains: Option<Box<dyn Validator>>,
+    min_contains: usize,
+    max_contains: Option<usize>,
+    compact: bool,
+    dedupe: bool,
+    sorted: bool,
+    reverse: bool,
+    truncate: Option<usize>,
+    messages: Messages,
+    metadata: Metadata,
+    sensitive: bool,
+    warning: bool,
+    transform: Option<ArrayTransform>
+}
+
+impl Default for ArrayType {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ArrayType {
+    /// Create a new ArrayType instance.
+    pub fn new() -> Self {
+        Self {
+            min_length: None,
+            max_length: None,
+            length: None,
+            empty: None,
+            every: None,
+            some: None,
+            at: None,
+            unique: None,
+            contains: None,
+            min_contains: 1,
+            max_contains: None,
+            compact: false,
+            dedupe: false,
+            sorted: false,
+            reverse: false,
+            truncate: None,
+            messages: Messages::default(),
+            metadata: Metadata::default(),
+            sensitive: false,
+            warning: false,
+            transform: None
+        }
+    }
+
+    /// Set the minimum length of the array.
+    pub fn min_length(mut self, min: usize) -> Self {
+        self.min_length = Some(min);
+        self.messages.constraint("min_length");
+        self
+    }
+
+    /// Set the maximum length of the array.
+    pub fn max_length(mut self, max: usize) -> Self {
+        self.max_length = Some(max);
+        self.messages.constraint("max_length");
+        self
+    }
+
+    /// Set the exact length of the array.
+    pub fn length(mut self, length: usize) -> Self {
+        self.length = Some(length);
+        self.messages.constraint("length");
+        self
+    }
+
+    /// Set whether the array can be empty.
+    pub fn empty(mut self) -> Self {
+        self.empty = Some(true);
+        self.messages.constraint("empty");
+        self
+    }
+
+    /// Set a rule for every items in the array.
+    pub fn every(mut self, rule: Box<dyn Validator>) -> Self {
+        self.every = Some(rule);
+        self.messages.constraint("every");
+        self
+    }
+
+    /// Set a rule for at least one item in the array.
+    pub fn some(mut self, rule: Box<dyn Validator>) -> Self {
+        self.some = Some(rule);
+        self.messages.constraint("some");
+        self
+    }
+
+    /// Set a rule for a specific item in the array.
+    pub fn at(mut self, index: usize, rule: Box<dyn Validator>) -> Self {
+        self.at = Some((index, rule));
+        self.messages.constraint("at");
+        self
+    }
+
+    /// Require the items of the array to be distinct. Objects holding the same entries
+    /// in a different order are duplicates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{ArrayType, JSONParser, JSONSchema};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("tags", ArrayType::new().unique().boxed()),
+    ///   ("users", ArrayType::new().unique_by("id").boxed())
+    /// ]);
+    ///
+    /// let json = JSONParser::from(r#"{ "tags": ["a", "b"], "users": [{ "id": 1 }, { "id": 2 }] }"#).unwrap();
+    /// assert!(schema.validate(&json).is_ok());
+    ///
+    /// let json = JSONParser::from(r#"{ "tags": ["a", "b"], "users": [{ "id": 1 }, { "id": 1, "name": "John" }] }"#).unwrap();
+    /// assert_eq!(schema.validate(&json).unwrap_err(), "In users, item 1 has a duplicate 'id'");
+    /// ```
+    pub fn unique(mut self) -> Self {
+        self.unique = Some(Uniqueness::Items);
+        self.messages.constraint("unique");
+        self
+    }
+
+    /// Require the items of an array of objects to have distinct values for the given key.
+    /// Items without the key are ignored.
+    pub fn unique_by(mut self, key: &str) -> Self {
+        self.unique = Some(Uniqueness::Key(key.to_string()));
+        self.messages.constraint("unique");
+        self
+    }
+
+    /// Set a rule that at least one item in the array must match, or the number of items
+    /// set with `min`. Unlike `some`, the number of matching items can be bounded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{ArrayType, JSONParser, JSONSchema, StringType};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("emails", ArrayType::new().contains(StringType::new().ends_with("@example.com").boxed()).min(2).max(3).boxed())
+    /// ]);
+    ///
+    /// let json = JSONParser::from(r#"{ "emails": ["a@example.com", "b@test.com", "c@example.com"] }"#).unwrap();
+    /// assert!(schema.validate(&json).is_ok());
+    ///
+    /// let json = JSONParser::from(r#"{ "emails": ["a@example.com", "b@test.com"] }"#).unwrap();
+    /// assert_eq!(schema.validate(&json).unwrap_err(), "emails has 1 matching items (min: 2)");
+    /// ```
+    pub fn contains(mut self, rule: Box<dyn Validator>) -> Self {
+        self.contains = Some(rule);
+        self.messages.constraint("contains");
+        self
+    }
+
+    /// Set the minimum number of items matching the `contains` rule. Defaults to 1.
+    pub fn min(mut self, count: usize) -> Self {
+        self.min_contains = count;
+        self
+    }
+
+    /// Set the maximum number of items matching the `contains` rule.
+    pub fn max(mut self, count: usize) -> Self {
+        self.max_contains = Some(count);
+        self
+    }
+
+    /// Remove the null items before validation.
+    pub fn compact(mut self) -> Self {
+        self.compact = true;
+        self
+    }
+
+    /// Remove the duplicate items before validation, keeping the first occurrence of each.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{ArrayType, JSONParser, JSONSchema, Serialize};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("ids", ArrayType::new().compact().dedupe().sorted().reverse().boxed())
+    /// ]);
+    ///
+    /// let json = JSONParser::from(r#"{ "ids": [3, null, 1, 3, 2, 1] }"#).unwrap();
+    ///
+    /// assert_eq!(schema.validate(&json).unwrap()["ids"].serialize(), "[3,2,1]");
+    /// ```
+    pub fn dedupe(mut self) -> Self {
+        self.dedupe = true;
+        self
+    }
+
+    /// Sort the items before validation, following `JSONValue::cmp_values`.
+    pub fn sorted(mut self) -> Self {
+        self.sorted = true;
+        self
+    }
+
+    /// Reverse the order of the items before validation, after sorting them if asked.
+    pub fn reverse(mut self) -> Self {
+        self.reverse = true;
+        self
+    }
+
+    /// Truncate the array before validation.
+    pub fn truncate(mut self, length: usize) -> Self {
+        self.truncate = Some(length);
+        self
+    }
+
+    /// Set a custom transformation function for the array.
+    pub fn transform<F: 'static + Send + Sync + Fn(Vec<JSONValue>) -> Vec<JSONValue>>(mut self, transform: F) -> Self {
+        self.transform = Some(Box::new(transform));
+        self
+    }
+
+    /// Mark the values checked by this rule as sensitive, to be hidden by `JSONSchema::redact`.
+    pub fn sensitive(mut self) -> Self {
+        self.sensitive = true;
+        self
+    }
+
+    /// Report failures of this rule as warnings, which do not fail validation.
+    /// See `JSONSchema::validate_all`.
+    pub fn warn(mut self) -> Self {
+        self.warning = true;
+        self
+    }
+
+    /// Describe the values checked by this rule, for the documentation generated from the schema.
+    pub fn describe(mut self, description: &str) -> Self {
+        self.metadata.description = Some(description.to_string());
+        self
+    }
+
+    /// Add an example of a valid value, for the documentation generated from the schema.
+    pub fn example(mut self, example: JSONValue) -> Self {
+        self.metadata.examples.push(example);
+        self
+    }
+
+    /// Mark the property as deprecated in the documentation generated from the schema.
+    pub fn deprecated(mut self) -> Self {
+        self.metadata.deprecated = true;
+        self
+    }
+
+    /// Replace the error message of the last constraint added, or of every failure of
+    /// the rule when called before any constraint.
+    pub fn message(mut self, message: &str) -> Self {
+        self.messages.set(fixed_message(message));
+        self
+    }
+
+    /// Replace the error message like `message`, building it from the failing value.
+    pub fn message_with<F: 'static + Send + Sync + Fn(&JSONValue) -> String>(mut self, message: F) -> Self {
+        self.messages.set(Box::new(message));
+        self
+    }
+
+    /// Convert the ArrayType to a Box<dyn Validator>.
+    pub fn boxed(self) -> Box<dyn Validator> {
+        Box::new(self)
+    }
+}
+
+impl Validator for ArrayType {
+    fn expected_kind(&self) -> Option<JSONKind> {
+        Some(JSONKind::Array)
+    }
+
+    /// Transformations may drop items, so only rules without any are bounded.
+    fn max_len(&self) -> Option<usize> {
+        let max = match (self.max_length, self.length) {
+            (Some(max), Some(length)) => max.min(length),
+            (max, length) => max.or(length)?
+        };
+
+        if self.transforms() { None } else { Some(max) }
+    }
+
+    fn is_sensitive(&self) -> bool {
+        self.sensitive
+    }
+
+    fn is_warning(&self) -> bool {
+        self.warning
+    }
+
+    fn generate(&self, rng: &mut Rng) -> JSONValue {
+        let min = self.length.or(self.min_length).unwrap_or(0)
+            .max((self.empty == Some(true) || self.some.is_some()) as usize)
+            .max(self.at.as_ref().map_or(0, |(index, _)| index + 1))
+            .max(if self.contains.is_some() { self.min_contains } else { 0 });
+        let max = self.length.or(self.max_length).unwrap_or(min + 3).max(min);
+        let mut items = (0..rng.between(min, max)).map(|_| match &self.every {
+            Some(rule) => generate_valid(rule.as_ref(), rng),
+            None => generate_scalar(rng)
+        }).collect::<Vec<_>>();
+
+        if let Some(rule) = &self.contains {
+            for item in items.iter_mut().take(self.min_contains) {
+                *item = generate_valid(rule.as_ref(), rng);
+            }
+        }
+        if let (Some(rule), Some(item)) = (&self.some, items.last_mut()) {
+            *item = generate_valid(rule.as_ref(), rng);
+        }
+        if let Some((index, rule)) = &self.at {
+            items[*index] = generate_valid(rule.as_ref(), rng);
+        }
+        JSONValue::Array(items.into())
+    }
+
+    fn document(&self) -> RuleDoc {
+        let mut doc = self.metadata.document("array");
+
+        document_option(&mut doc, "min_length", self.min_length);
+        document_option(&mut doc, "max_length", self.max_length);
+        document_option(&mut doc, "length", self.length);
+        document_flag(&mut doc, "non_empty", self.empty == Some(true));
+        match &self.unique {
+            Some(Uniqueness::Items) => doc.constraints.push("unique".to_string()),
+            Some(Uniqueness::Key(field)) => doc.constraints.push(format!("unique_by: '{}'", field)),
+            None => {}
+        }
+        document_option(&mut doc, "some", self.some.as_ref().map(|rule| rule.document().summary()));
+        document_option(&mut doc, "at", self.at.as_ref().map(|(index, rule)| format!("{}, {}", index, rule.document().summary())));
+        if let Some(rule) = &self.contains {
+            doc.constraints.push(format!("contains: {}", rule.document().summary()));
+            document_option(&mut doc, "min", Some(self.min_contains).filter(|min| *min != 1));
+            document_option(&mut doc, "max", self.max_contains);
+        }
+        document_flag(&mut doc, "compact", self.compact);
+        document_flag(&mut doc, "dedupe", self.dedupe);
+        document_flag(&mut doc, "sorted", self.sorted);
+        document_flag(&mut doc, "reverse", self.reverse);
+        document_option(&mut doc, "truncate", self.truncate);
+        if let Some(rule) = &self.every {
+            doc.children.push(("[]".to_string(), rule.document()));
+        }
+        doc
+    }
+
+    fn openapi_schema(&self) -> JSONValue {
+        let mut schema = self.metadata.openapi_schema("array");
+        let min = self.length.or(self.min_length).unwrap_or(0)
+            .max((self.empty == Some(true)) as usize)
+            .max(self.at.as_ref().map_or(0, |(index, _)| index + 1));
+
+        openapi_number(&mut schema, "minItems", Some(min as f64).filter(|min| *min > 0.0));
+        openapi_number(&mut schema, "maxItems", self.length.or(self.max_length).map(|max| max as f64));
+        if let Some(Uniqueness::Items) = self.unique {
+            schema.insert("uniqueItems", JSONValue::Boolean(true));
+        }
+        if let Some((index, rule)) = &self.at {
+            let mut prefix = vec![JSONValue::Object(OrderedMap::new()); *index];
+
+            prefix.push(rule.openapi_schema());
+            schema.insert("prefixItems", JSONValue::Array(prefix.into()));
+        }
+        if let Some(rule) = &self.every {
+            schema.insert("items", rule.openapi_schema());
+        }
+        if let Some(rule) = &self.contains {
+            schema.insert("contains", rule.openapi_schema());
+            openapi_number(&mut schema, "minContains", Some(self.min_contains as f64).filter(|min| *min != 1.0));
+            openapi_number(&mut schema, "maxContains", self.max_contains.map(|max| max as f64));
+        }
+        if let Some(rule) = &self.some {
+            let contains = JSONValue::Object(vec![("contains".to_string(), rule.openapi_schema())].into_iter().collect());
+
+            schema.insert("allOf", JSONValue::Array(vec![contains].into()));
+        }
+        JSONValue::Object(schema)
+    }
+
+    fn validate(&self, key: &str, value: &JSONValue) -> Result<(), String> {
+        self.validate_in(&ValidationContext::new(key, &EnglishFormatter), value)
+    }
+
+    fn validate_in(&self, context: &ValidationContext, value: &JSONValue) -> Result<(), String> {
+        match value {
+            JSONValue::Array(arr) => {
+                if let Some(min) = self.min_length {
+                    if arr.len() < min {
+                        return Err(self.messages.error("min_length", value, || context.error("min_length", vec![("min", min.to_string())])));
+                    }
+                }
+
+                if let Some(max) = self.max_length {
+                    if arr.len() > max {
+                        return Err(self.messages.error("max_length", value, || context.error("max_length", vec![("max", max.to_string())])));
+                    }
+                }
+
+                if let Some(length) = self.length {
+                    if arr.len() != length {
+                        return Err(self.messages.error("length", value, || context.error("length", vec![("length", length.to_string())])));
+                    }
+                }
+
+                if let Some(empty) = self.empty {
+                    if empty && arr.is_empty() {
+                        return Err(self.messages.error("empty", value, || context.error("empty", Vec::new())));
+                    }
+                }
+
+                if let Some(rule) = &self.every {
+                    for (index, item) in arr.iter().enumerate() {
+                        rule.validate_in(&context.child(context.name(), &index.to_string()), item).map_err(|e| self.messages.error("every", value, || e))?;
+                    }
+                }
+
+                if let Some(unique) = &self.unique {
+                    let mut seen = Set::new();
+
+                    for (index, item) in arr.iter().enumerate() {
+                        let compared = match unique {
+                            Uniqueness::Items => Some(item),
+                            Uniqueness::Key(field) => item.get(field.as_str())
+                        };
+
+                        if compared.map_or(false, |compared| !seen.insert(compared.canonical())) {
+                            return Err(self.messages.error("unique", value, || {
+                                let mut params = vec![("index", index.to_string())];
+
+                                if let Uniqueness::Key(field) = unique {
+                                    params.push(("key", field.clone()));
+                                }
+                                context.error("unique", params)
+                            }));
+                        }
+                    }
+                }
+
+                if let Some(rule) = &self.contains {
+                    let count = arr.iter().enumerate()
+                        .filter(|(index, item)| rule.validate_in(&context.child(context.name(), &index.to_string()), item).is_ok())
+                        .count();
+
+                    if count < self.min_contains {
+                        return Err(self.messages.error("contains", value, || context.error("contains", vec![("count", count.to_string()), ("min", self.min_contains.to_string())])));
+                    }
+                    if let Some(max) = self.max_contains {
+                        if count > max {
+                            return Err(self.messages.error("contains", value, || context.error("contains", vec![("count", count.to_string()), ("max", max.to_string())])));
+                        }
+                    }
+                }
+
+                if let Some(rule) = &self.some {
+                    for (index, item) in arr.iter().enumerate() {
+                        if rule.validate_in(&context.child(context.name(), &index.to_string()), item).is_ok() {
+                            return Ok(());
+                        }
+                    }
+                    return Err(self.messages.error("some", value, || context.error("some", Vec::new())));
+                }
+
+                if let Some((index, rule)) = &self.at {
+                    if let Some(item) = arr.get(*index) {
+                        rule.validate_in(&context.child(context.name(), &index.to_string()), item).map_err(|e| self.messages.error("at", value, || e))?;
+                    } else {
+                        return Err(self.messages.error("at", value, || context.error("at", vec![("index", index.to_string())])));
+                    }
+                }
+
+                Ok(())
+            },
+            _ => Err(self.messages.error("type", value, || context.error("type", vec![("expected", "Array".to_string())])))
+        }
+    }
+
+    fn transform(&self, key: &str, value: &JSONValue) -> Result<JSONValue, String> {
+        transform_cloned(self, key, value)
+    }
+
+    fn transforms(&self) -> bool {
+        self.compact || self.dedupe || self.sorted || self.reverse || self.truncate.is_some() || self.transform.is_some()
+    }
+
+    fn transform_in_place(&self, key: &str, value: &mut JSONValue) -> Result<(), String> {
+        match value {
+            JSONValue::Array(arr) => {
+                edit_items(arr, |arr| {
+                    if self.compact {
+                        arr.retain(|item| *item != JSONValue::Null);
+                    }
+
+                    if self.dedupe {
+                        let mut seen = Set::new();
+
+                        arr.retain(|item| seen.insert(item.canonical()));
+                    }
+
+                    if self.sorted {
+                        arr.sort_by(JSONValue::cmp_values);
+                    }
+
+                    if self.reverse {
+                        arr.reverse();
+                    }
+
+                    if let Some(len) = self.truncate {
+                        arr.truncate(len);
+                    }
+
+                    if let Some(transform) = &self.transform {
+                        *arr = transform(core::mem::take(arr));
+                    }
+                });
+                Ok(())
+            },
+            _ => Err(self.messages.error("type", value, || format!("Type of {} mismatch, expected Array", key)))
+        }
+    }
+
+    fn redact_nested(&self, value: &mut JSONValue, redaction: &Redaction) {
+        let arr = match value {
+            JSONValue::Array(arr) => arr,
+            _ => return
+        };
+
+        if let Some((index, rule)) = &self.at {
+            match (rule.is_sensitive(), redaction) {
+                (true, Redaction::Strip) if *index < arr.len() => {
+                    edit_items(arr, |items| items.remove(*index));
+                },
+                (true, Redaction::Mask(mask)) => {
+                    if let Some(item) = arr.get_mut(*index) {
+                        *item = mask.clone();
+                    }
+                },
+                (false, _) => {
+                    if let Some(item) = arr.get_mut(*index) {
+                        rule.redact_nested(item, redaction);
+                    }
+                },
+                _ => {}
+            }
+        }
+        if let Some(rule) = &self.every {
+            match (rule.is_sensitive(), redaction) {
+                (true, Redaction::Strip) => *arr = Box::new([]),
+                (true, Redaction::Mask(mask)) => arr.iter_mut().for_each(|item| *item = mask.clone()),
+                (false, _) => arr.iter_mut().for_each(|item| rule.redact_nested(item, redaction))
+            }
+        }
+    }
+}
+
+pub struct BooleanType {
+    value: Option<bool>,
+    coerce: bool,
+    not: bool,
+    default: Option<bool>,
+    messages: Messages,
+    metadata: Metadata,
+    sensitive: bool,
+    warning: bool,
+    transform: Option<Box<dyn Fn(bool) -> bool + Send + Sync>>
+}
+
+impl Default for BooleanType {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BooleanType {
+    /// Create a new BooleanType instance.
+    pub fn new() -> Self {
+        Self {
+            value: None,
+            coerce: false,
+            not: false,
+            default: None,
+            messages: Messages::default(),
+            metadata: Metadata::default(),
+            sensitive: false,
+            warning: false,
+            transform: None
+        }
+    }
+
+    /// Set the expected value to true.
+    pub fn truthy(mut self) -> Self {
+        self.value = Some(true);
+        self.messages.constraint("truthy");
+        self
+    }
+
+    /// Set the expected value to false.
+    pub fn falsy(mut self) -> Self {
+        self.value = Some(false);
+        self.messages.constraint("falsy");
+        self
+    }
+
+    /// Accept the strings `"true"`, `"false"`, `"1"` and `"0"`, and the numbers `1` and `0`,
+    /// converting them to a boolean before validation, as sent by HTML forms and legacy systems.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{BooleanType, JSONParser, JSONSchema};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("newsletter", BooleanType::new().coerce().boxed()),
+    ///   ("terms", BooleanType::new().coerce().truthy().boxed())
+    /// ]);
+    ///
+    /// let json = JSONParser::from(r#"{ "newsletter": "false", "terms": 1 }"#).unwrap();
+    /// let validated = schema.validate(&json).unwrap();
+    ///
+    /// assert_eq!(validated["newsletter"].as_bool(), Some(false));
+    /// assert_eq!(validated["terms"].as_bool(), Some(true));
+    ///
+    /// let json = JSONParser::from(r#"{ "newsletter": "yes", "terms": true }"#).unwrap();
+    ///
+    /// assert_eq!(schema.validate(&json).unwrap_err(), "Type of newsletter mismatch, expected Boolean");
+    /// ```
+    pub fn coerce(mut self) -> Self {
+        self.coerce = true;
+        self
+    }
+
+    /// Negate the boolean before validation, such as to turn an `opt_out` flag into a subscription.
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(mut self) -> Self {
+        self.not = true;
+        self
+    }
+
+    /// Use the given value when the property is missing or null. The default value is
+    /// validated but not transformed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{BooleanType, JSONParser, JSONSchema};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("subscribed", BooleanType::new().not().boxed()),
+    ///   ("admin", BooleanType::new().or_default(false).boxed()),
+    ///   ("active", BooleanType::new().or_default(true).boxed())
+    /// ]);
+    ///
+    /// let json = JSONParser::from(r#"{ "subscribed": true, "active": null }"#).unwrap();
+    /// let validated = schema.validate(&json).unwrap();
+    ///
+    /// assert_eq!(validated["subscribed"].as_bool(), Some(false));
+    /// assert_eq!(validated["admin"].as_bool(), Some(false));
+    /// assert_eq!(validated["active"].as_bool(), Some(true));
+    ///
+    /// // Without a default value, as with `BooleanType::default()`, null is rejected.
+    /// let json = JSONParser::from(r#"{ "active": null }"#).unwrap();
+    /// let schema = JSONSchema::new([("active", BooleanType::default().boxed())]);
+    ///
+    /// assert!(schema.validate(&json).is_err());
+    ///
+    /// // The option is named `or_default` in a JSON definition.
+    /// let definition = JSONParser::from(r#"{ "active": { "type": "boolean", "or_default": true } }"#).unwrap();
+    /// let schema = JSONSchema::from_json(&definition).unwrap();
+    ///
+    /// assert_eq!(schema.validate(&json).unwrap()["active"].as_bool(), Some(true));
+    /// ```
+    pub fn or_default(mut self, value: bool) -> Self {
+        self.default = Some(value);
+        self
+    }
+
+    /// Set a custom transformation function for the boolean.
+    pub fn transform<F: 'static + Send + Sync + Fn(bool) -> bool>(mut self, transform: F) -> Self {
+        self.transform = Some(Box::new(transform));
         self
     }
 
-    /// Set the string to include a specific substring.
-    pub fn includes(mut self, value: &str) -> Self {
-        self.includes = Some(value.to_string());
+    /// Mark the values checked by this rule as sensitive, to be hidden by `JSONSchema::redact`.
+    pub fn sensitive(mut self) -> Self {
+        self.sensitive = true;
         self
     }
 
-    /// Trim the string before validation.
-    pub fn trim(mut self) -> Self {
-        self.trim = true;
+    /// Report failures of this rule as warnings, which do not fail validation.
+    /// See `JSONSchema::validate_all`.
+    pub fn warn(mut self) -> Self {
+        self.warning = true;
         self
     }
 
-    /// Trim the start of the string before validation.
-    pub fn trim_start(mut self) -> Self {
-        self.trim_start = true;
+    /// Describe the values checked by this rule, for the documentation generated from the schema.
+    pub fn describe(mut self, description: &str) -> Self {
+        self.metadata.description = Some(description.to_string());
         self
     }
 
-    /// Trim the end of the string before validation.
-    pub fn trim_end(mut self) -> Self {
-        self.trim_end = true;
+    /// Add an example of a valid value, for the documentation generated from the schema.
+    pub fn example(mut self, example: JSONValue) -> Self {
+        self.metadata.examples.push(example);
         self
     }
 
-    /// Convert the string to lowercase before validation.
-    pub fn to_lowercase(mut self) -> Self {
-        self.lowercase = true;
+    /// Mark the property as deprecated in the documentation generated from the schema.
+    pub fn deprecated(mut self) -> Self {
+        self.metadata.deprecated = true;
         self
     }
 
-    /// Convert the string to uppercase before validation.
-    pub fn to_uppercase(mut self) -> Self {
-        self.uppercase = true;
+    /// Replace the error message of the last constraint added, or of every failure of
+    /// the rule when called before any constraint.
+    pub fn message(mut self, message: &str) -> Self {
+        self.messages.set(fixed_message(message));
         self
     }
 
-    /// Set a custom transformation function for the string.
-    pub fn transform<F: 'static + Fn(&str) -> String>(mut self, transform: F) -> Self {
-        self.transform = Some(Box::new(transform));
+    /// Replace the error message like `message`, building it from the failing value.
+    pub fn message_with<F: 'static + Send + Sync + Fn(&JSONValue) -> String>(mut self, message: F) -> Self {
+        self.messages.set(Box::new(message));
         self
     }
 
-    /// Convert the StringType to a Box<dyn Validator>.
+    /// Convert the BooleanType to a Box<dyn Validator>.
     pub fn boxed(self) -> Box<dyn Validator> {
         Box::new(self)
     }
 }
 
-impl Validator for StringType {
-    fn validate(&self, key: &str, value: &JSONValue) -> Result<(), String> {
-        match value {
-            JSONValue::String(s) => {
-                if let Some(min) = self.min_length {
-                    if s.len() < min {
-                        return Err(format!("{} is too short (min: {})", key, min));
-                    }
-                }
+impl Validator for BooleanType {
+    fn expected_kind(&self) -> Option<JSONKind> {
+        if self.coerce || self.default.is_some() {
+            None
+        } else {
+            Some(JSONKind::Boolean)
+        }
+    }
 
-                if let Some(max) = self.max_length {
-                    if s.len() > max {
-                        return Err(format!("{} is too long (max: {})", key, max));
-                    }
-                }
+    fn default_value(&self) -> Option<JSONValue> {
+        self.default.map(JSONValue::Boolean)
+    }
 
-                if let Some(length) = self.length {
-                    if s.len() != length {
-                        return Err(format!("{} is not the correct length (length: {})", key, length));
-                    }
-                }
+    fn is_sensitive(&self) -> bool {
+        self.sensitive
+    }
 
-                if let Some(starts_with) = &self.starts_with {
-                    if !s.starts_with(starts_with) {
-                        return Err(format!("{} does not start with '{}'", key, starts_with));
-                    }
-                }
+    fn is_warning(&self) -> bool {
+        self.warning
+    }
 
-                if let Some(ends_with) = &self.ends_with {
-                    if !s.ends_with(ends_with) {
-                        return Err(format!("{} does not end with '{}'", key, ends_with));
-                    }
-                }
+    fn generate(&self, rng: &mut Rng) -> JSONValue {
+        let b = self.value.unwrap_or_else(|| rng.chance(0.5));
+
+        // The value is negated before it is checked.
+        JSONValue::Boolean(b != self.not)
+    }
+
+    fn document(&self) -> RuleDoc {
+        let mut doc = self.metadata.document("boolean");
+
+        document_flag(&mut doc, "truthy", self.value == Some(true));
+        document_flag(&mut doc, "falsy", self.value == Some(false));
+        document_flag(&mut doc, "coerce", self.coerce);
+        document_flag(&mut doc, "not", self.not);
+        document_option(&mut doc, "default", self.default);
+        doc
+    }
+
+    fn openapi_schema(&self) -> JSONValue {
+        let mut schema = self.metadata.openapi_schema("boolean");
+        let mut kinds = vec!["boolean"];
+
+        if self.coerce {
+            kinds.extend(["string", "number"]);
+        }
+        // Null is replaced with the default value.
+        if self.default.is_some() {
+            kinds.push("null");
+        }
+        if kinds.len() > 1 {
+            schema.insert("type", JSONValue::Array(kinds.iter().map(|kind| JSONValue::String((*kind).into())).collect()));
+        }
+        if let (Some(expected), false) = (self.value, self.coerce) {
+            // The value is negated before it is checked.
+            schema.insert("enum", JSONValue::Array(vec![JSONValue::Boolean(expected != self.not)].into()));
+        }
+        if let Some(default) = self.default {
+            schema.insert("default", JSONValue::Boolean(default));
+        }
+        JSONValue::Object(schema)
+    }
+
+    fn validate(&self, key: &str, value: &JSONValue) -> Result<(), String> {
+        self.validate_in(&ValidationContext::new(key, &EnglishFormatter), value)
+    }
+
+    fn validate_in(&self, context: &ValidationContext, value: &JSONValue) -> Result<(), String> {
+        match value {
+            JSONValue::Boolean(b) => {
+                if let Some(expected) = self.value {
+                    if b != &expected {
+                        let code = if expected { "truthy" } else { "falsy" };
 
-                if let Some(includes) = &self.includes {
-                    if !s.contains(includes) {
-                        return Err(format!("{} does not include '{}'", key, includes));
+                        return Err(self.messages.error(code, value, || context.error(code, vec![("expected", expected.to_string())])));
                     }
                 }
 
                 Ok(())
             },
-            _ => Err(format!("Type of {} mismatch, expected String", key))
+            _ => Err(self.messages.error("type", value, || context.error("type", vec![("expected", "Boolean".to_string())])))
         }
     }
 
     fn transform(&self, key: &str, value: &JSONValue) -> Result<JSONValue, String> {
-        match value {
-            JSONValue::String(s) => {
-                let mut transformed = s.clone();
-
-                if self.trim {
-                    transformed = transformed.trim().to_string();
-                }
+        transform_cloned(self, key, value)
+    }
 
-                if self.trim_start {
-                    transformed = transformed.trim_start().to_string();
-                }
+    fn transforms(&self) -> bool {
+        self.default.is_some() || self.coerce || self.not || self.transform.is_some()
+    }
 
-                if self.trim_end {
-                    transformed = transformed.trim_end().to_string();
-                }
+    fn transform_in_place(&self, key: &str, value: &mut JSONValue) -> Result<(), String> {
+        if let (JSONValue::Null, Some(default)) = (&*value, self.default) {
+            *value = JSONValue::Boolean(default);
+            return Ok(());
+        }
+        if self.coerce {
+            let coerced = match value {
+                JSONValue::String(s) if s == "true" || s == "1" => Some(true),
+                JSONValue::String(s) if s == "false" || s == "0" => Some(false),
+                JSONValue::Number(n) if *n == 1.0 => Some(true),
+                JSONValue::Number(n) if *n == 0.0 => Some(false),
+                _ => None
+            };
 
-                if self.lowercase {
-                    transformed = transformed.to_lowercase();
-                }
+            if let Some(b) = coerced {
+                *value = JSONValue::Boolean(b);
+            }
+        }
 
-                if self.uppercase {
-                    transformed = transformed.to_uppercase();
+        match value {
+            JSONValue::Boolean(b) => {
+                if self.not {
+                    *b = !*b;
                 }
 
                 if let Some(transform) = &self.transform {
-                    transformed = transform(&transformed);
+                    *b = transform(*b);
                 }
 
-                Ok(JSONValue::String(transformed))
+                Ok(())
             },
-            _ => Err(format!("Type of {} mismatch, expected String", key))
+            _ => Err(self.messages.error("type", value, || format!("Type of {} mismatch, expected Boolean", key)))
         }
     }
 }
 
-pub struct NumberType {
-    min: Option<f64>,
-    max: Option<f64>,
-    integer: Option<bool>,
-    floor: bool,
-    ceil: bool,
-    round: bool,
-    transform: Option<Box<dyn Fn(f64) -> f64>>
+pub struct ObjectType<'a> {
+    rules: OrderedMap<Box<dyn Validator + 'a>>,
+    min_properties: Option<usize>,
+    max_properties: Option<usize>,
+    keys: Option<Box<dyn Validator + 'a>>,
+    renames: Vec<(String, String)>,
+    key_map: Option<StringTransform>,
+    messages: Messages,
+    metadata: Metadata,
+    sensitive: bool,
+    warning: bool
 }
 
-impl NumberType {
-    /// Create a new NumberType instance.
+impl<'a> Default for ObjectType<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> ObjectType<'a> {
+    /// Create a new ObjectType instance.
     pub fn new() -> Self {
         Self {
-            min: None,
-            max: None,
-            integer: None,
-            floor: false,
-            ceil: false,
-            round: false,
-            transform: None
+            rules: OrderedMap::new(),
+            min_properties: None,
+            max_properties: None,
+            keys: None,
+            renames: Vec::new(),
+            key_map: None,
+            messages: Messages::default(),
+            metadata: Metadata::default(),
+            sensitive: false,
+            warning: false
         }
     }
 
-    /// Set the minimum value of the number.
-    pub fn gt(mut self, value: f64) -> Self {
-        self.min = Some(value);
+    /// Add a rule for a property in the object.
+    pub fn property(mut self, key: &str, rule: Box<dyn Validator>) -> Self {
+        self.rules.insert(key, rule);
+        self.messages.constraint("properties");
         self
     }
 
-    /// Set the maximum value of the number.
-    pub fn lt(mut self, value: f64) -> Self {
-        self.max = Some(value);
+    /// Set the minimum number of properties of the object.
+    pub fn min_properties(mut self, min: usize) -> Self {
+        self.min_properties = Some(min);
+        self.messages.constraint("min_properties");
         self
     }
 
-    /// Set whether the number should be an integer.
-    pub fn integer(mut self) -> Self {
-        self.integer = Some(true);
+    /// Set the maximum number of properties of the object.
+    pub fn max_properties(mut self, max: usize) -> Self {
+        self.max_properties = Some(max);
+        self.messages.constraint("max_properties");
         self
     }
 
-    /// Round the number down before validation.
-    pub fn floor(mut self) -> Self {
-        self.floor = true;
+    /// Set a rule every key of the object must match, validated as a string, for maps
+    /// with dynamic keys.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONSchema, ObjectType, StringType};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("labels", ObjectType::new().keys(StringType::new().pattern("^[a-z_]+$").boxed()).max_properties(2).boxed())
+    /// ]);
+    ///
+    /// let json = JSONParser::from(r#"{ "labels": { "team": "core", "cost_center": "42" } }"#).unwrap();
+    /// assert!(schema.validate(&json).is_ok());
+    ///
+    /// let json = JSONParser::from(r#"{ "labels": { "Team": "core" } }"#).unwrap();
+    /// assert_eq!(schema.validate(&json).unwrap_err(), "In labels, Team does not match the pattern '^[a-z_]+$'");
+    ///
+    /// let json = JSONParser::from(r#"{ "labels": { "a": 1, "b": 2, "c": 3 } }"#).unwrap();
+    /// assert_eq!(schema.validate(&json).unwrap_err(), "labels has too many properties (max: 2)");
+    /// ```
+    pub fn keys(mut self, rule: Box<dyn Validator + 'a>) -> Self {
+        self.keys = Some(rule);
+        self.messages.constraint("keys");
         self
     }
 
-    /// Round the number up before validation.
-    pub fn ceil(mut self) -> Self {
-        self.ceil = true;
+    /// Rename a key of the object before validation, keeping its position. The rules of the
+    /// properties and of `keys` apply to the new name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONSchema, ObjectType, StringType};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("address", ObjectType::new()
+    ///     .rename("zip_code", "zip")
+    ///     .map_key(|key| key.to_lowercase())
+    ///     .property("zip", StringType::new().length(5).boxed())
+    ///     .boxed())
+    /// ]);
+    ///
+    /// let json = JSONParser::from(r#"{ "address": { "City": "Paris", "zip_code": "75001" } }"#).unwrap();
+    ///
+    /// assert_eq!(schema.validate(&json).unwrap()["address"].to_string(), r#"{"city":"Paris","zip":"75001"}"#);
+    /// ```
+    pub fn rename(mut self, from: &str, to: &str) -> Self {
+        self.renames.push((from.to_string(), to.to_string()));
         self
     }
 
-    /// Round the number to the nearest integer before validation.
-    pub fn round(mut self) -> Self {
-        self.round = true;
+    /// Set a function renaming every key of the object before validation, such as to
+    /// convert them to snake case. It runs after the renames set with `rename`.
+    pub fn map_key<F: 'static + Send + Sync + Fn(&str) -> String>(mut self, map: F) -> Self {
+        self.key_map = Some(Box::new(map));
         self
     }
 
-    /// Set a custom transformation function for the number.
-    pub fn transform<F: 'static + Fn(f64) -> f64>(mut self, transform: F) -> Self {
-        self.transform = Some(Box::new(transform));
+    /// The key replacing the given one, as set by `rename` and `map_key`.
+    fn renamed(&self, key: String) -> String {
+        let key = match self.renames.iter().find(|(from, _)| *from == key) {
+            Some((_, to)) => to.clone(),
+            None => key
+        };
+
+        match &self.key_map {
+            Some(map) => map(&key),
+            None => key
+        }
+    }
+
+    /// Mark the values checked by this rule as sensitive, to be hidden by `JSONSchema::redact`.
+    pub fn sensitive(mut self) -> Self {
+        self.sensitive = true;
         self
     }
 
-    /// Convert the NumberType to a Box<dyn Validator>.
-    pub fn boxed(self) -> Box<dyn Validator> {
+    /// Report failures of this rule as warnings, which do not fail validation.
+    /// See `JSONSchema::validate_all`.
+    pub fn warn(mut self) -> Self {
+        self.warning = true;
+        self
+    }
+
+    /// Describe the values checked by this rule, for the documentation generated from the schema.
+    pub fn describe(mut self, description: &str) -> Self {
+        self.metadata.description = Some(description.to_string());
+        self
+    }
+
+    /// Add an example of a valid value, for the documentation generated from the schema.
+    pub fn example(mut self, example: JSONValue) -> Self {
+        self.metadata.examples.push(example);
+        self
+    }
+
+    /// Mark the property as deprecated in the documentation generated from the schema.
+    pub fn deprecated(mut self) -> Self {
+        self.metadata.deprecated = true;
+        self
+    }
+
+    /// Replace the error message of the last constraint added, or of every failure of
+    /// the rule when called before any constraint.
+    pub fn message(mut self, message: &str) -> Self {
+        self.messages.set(fixed_message(message));
+        self
+    }
+
+    /// Replace the error message like `message`, building it from the failing value.
+    pub fn message_with<F: 'static + Send + Sync + Fn(&JSONValue) -> String>(mut self, message: F) -> Self {
+        self.messages.set(Box::new(message));
+        self
+    }
+
+    /// Convert the ObjectType to a Box<dyn Validator>.
+    pub fn boxed(self) -> Box<dyn Validator + 'a> {
         Box::new(self)
     }
 }
 
-impl Validator for NumberType {
+impl<'a> Validator for ObjectType<'a> {
+    fn expected_kind(&self) -> Option<JSONKind> {
+        Some(JSONKind::Object)
+    }
+
+    fn is_sensitive(&self) -> bool {
+        self.sensitive
+    }
+
+    fn is_warning(&self) -> bool {
+        self.warning
+    }
+
+    fn generate(&self, rng: &mut Rng) -> JSONValue {
+        let mut obj = OrderedMap::with_capacity(self.rules.len());
+
+        for (key, rule) in self.rules.iter() {
+            obj.insert(key, generate_valid(rule.as_ref(), rng));
+        }
+        for index in 0..self.min_properties.unwrap_or(0) * 4 {
+            if obj.len() >= self.min_properties.unwrap_or(0) {
+                break;
+            }
+            let mut key = match &self.keys {
+                Some(rule) => generate_valid(rule.as_ref(), rng).into_string().unwrap_or_default(),
+                None => "key".to_string()
+            };
+
+            if obj.contains_key(&key) {
+                key.push_str(&index.to_string());
+            }
+            if !obj.contains_key(&key) {
+                obj.insert(&key, generate_scalar(rng));
+            }
+        }
+        JSONValue::Object(obj)
+    }
+
+    fn document(&self) -> RuleDoc {
+        let mut doc = self.metadata.document("object");
+
+        document_option(&mut doc, "min_properties", self.min_properties);
+        document_option(&mut doc, "max_properties", self.max_properties);
+        document_option(&mut doc, "keys", self.keys.as_ref().map(|rule| rule.document().summary()));
+        for (from, to) in &self.renames {
+            doc.constraints.push(format!("rename: {} -> {}", from, to));
+        }
+        for (key, rule) in self.rules.iter() {
+            doc.children.push((key.to_string(), rule.document()));
+        }
+        doc
+    }
+
+    fn openapi_schema(&self) -> JSONValue {
+        let mut schema = self.metadata.openapi_schema("object");
+
+        openapi_number(&mut schema, "minProperties", self.min_properties.map(|min| min as f64));
+        openapi_number(&mut schema, "maxProperties", self.max_properties.map(|max| max as f64));
+        if let Some(rule) = &self.keys {
+            schema.insert("propertyNames", rule.openapi_schema());
+        }
+        if !self.rules.is_empty() {
+            schema.insert("properties", JSONValue::Object(self.rules.iter().map(|(key, rule)| (key.to_string(), rule.openapi_schema())).collect()));
+            schema.insert("required", JSONValue::Array(self.rules.iter().map(|(key, _)| JSONValue::String(key.into())).collect()));
+        }
+        JSONValue::Object(schema)
+    }
+
     fn validate(&self, key: &str, value: &JSONValue) -> Result<(), String> {
+        self.validate_in(&ValidationContext::new(key, &EnglishFormatter), value)
+    }
+
+    fn validate_in(&self, context: &ValidationContext, value: &JSONValue) -> Result<(), String> {
         match value {
-            JSONValue::Number(n) => {
-                if let Some(min) = self.min {
-                    if n < &min {
-                        return Err(format!("{} is too small (min: {})", key, min));
+            JSONValue::Object(obj) => {
+                if let Some(min) = self.min_properties {
+                    if obj.len() < min {
+                        return Err(self.messages.error("min_properties", value, || context.error("min_properties", vec![("min", min.to_string())])));
                     }
                 }
 
-                if let Some(max) = self.max {
-                    if n > &max {
-                        return Err(format!("{} is too large (max: {})", key, max));
+                if let Some(max) = self.max_properties {
+                    if obj.len() > max {
+                        return Err(self.messages.error("max_properties", value, || context.error("max_properties", vec![("max", max.to_string())])));
                     }
                 }
 
-                if let Some(integer) = self.integer {
-                    if integer && !n.fract().eq(&0.0) {
-                        return Err(format!("{} is not an integer", key));
+                if let Some(rule) = &self.keys {
+                    for (subkey, _) in obj.iter() {
+                        rule.validate_in(&context.child(subkey, subkey), &JSONValue::String(subkey.into()))
+                            .map_err(|e| self.messages.error("keys", value, || context.error("keys", vec![("key", subkey.to_string()), ("error", e)])))?;
                     }
                 }
 
+                for (subkey, rule) in self.rules.iter() {
+                    match obj.get(subkey as &str) {
+                        Some(value) => rule.validate_in(&context.child(subkey, subkey), value)?,
+                        None => return Err(self.messages.error("properties", value, || context.error("required", vec![("key", subkey.to_string())])))
+                    }
+                }
                 Ok(())
             },
-            _ => Err(format!("Type of {} mismatch, expected Number", key))
+            _ => Err(self.messages.error("type", value, || context.error("type", vec![("expected", "Object".to_string())])))
         }
     }
 
-    fn transform(&self, key: &str, value: &JSONValue) -> Result<JSONValue, String> {
+    fn transforms(&self) -> bool {
+        !self.renames.is_empty() || self.key_map.is_some()
+    }
+
+    fn transform_in_place(&self, key: &str, value: &mut JSONValue) -> Result<(), String> {
+        if !self.transforms() {
+            return Ok(());
+        }
+
         match value {
-            JSONValue::Number(n) => {
-                let mut transformed = n.clone();
+            JSONValue::Object(obj) => {
+                *obj = core::mem::take(obj).into_iter().map(|(subkey, value)| (self.renamed(subkey), value)).collect();
+                Ok(())
+            },
+            _ => Err(self.messages.error("type", value, || format!("Type of {} mismatch, expected Object", key)))
+        }
+    }
 
-                if self.floor {
-                    transformed = transformed.floor();
-                }
+    fn redact_nested(&self, value: &mut JSONValue, redaction: &Redaction) {
+        if let JSONValue::Object(obj) = value {
+            for (subkey, rule) in self.rules.iter() {
+                redact_entry(obj, subkey, rule.as_ref(), redaction);
+            }
+        }
+    }
+}
 
-                if self.ceil {
-                    transformed = transformed.ceil();
-                }
+pub struct NullType;
 
-                if self.round {
-                    transformed = transformed.round();
-                }
+impl Default for NullType {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-                if let Some(transform) = &self.transform {
-                    transformed = transform(transformed);
-                }
+impl NullType {
+    /// Create a new NullType instance.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Convert the NullType to a Box<dyn Validator>.
+    pub fn boxed(self) -> Box<dyn Validator> {
+        Box::new(self)
+    }
+}
+
+impl Validator for NullType {
+    fn expected_kind(&self) -> Option<JSONKind> {
+        Some(JSONKind::Null)
+    }
+
+    fn document(&self) -> RuleDoc {
+        RuleDoc::new("null")
+    }
+
+    fn openapi_schema(&self) -> JSONValue {
+        JSONValue::Object(Metadata::default().openapi_schema("null"))
+    }
+
+    fn generate(&self, _: &mut Rng) -> JSONValue {
+        JSONValue::Null
+    }
+
+    fn validate(&self, key: &str, value: &JSONValue) -> Result<(), String> {
+        self.validate_in(&ValidationContext::new(key, &EnglishFormatter), value)
+    }
+
+    fn validate_in(&self, context: &ValidationContext, value: &JSONValue) -> Result<(), String> {
+        match value {
+            JSONValue::Null => Ok(()),
+            _ => Err(context.error("type", vec![("expected", "Null".to_string())])),
+        }
+    }
+
+    fn transforms(&self) -> bool {
+        false
+    }
 
-                Ok(JSONValue::Number(transformed))
-            },
-            _ => Err(format!("Type of {} mismatch, expected Number", key))
-        }
+    fn transform_in_place(&self, _: &str, _: &mut JSONValue) -> Result<(), String> {
+        Ok(())
     }
 }
 
-pub struct ArrayType {
-    min_length: Option<usize>,
-    max_length: Option<usize>,
-    length: Option<usize>,
-    empty: Option<bool>,
-    every: Option<Box<dyn Validator>>,
-    some: Option<Box<dyn Validator>>,
-    at: Option<(usize, Box<dyn Validator>)>,
-    truncate: Option<usize>,
-    transform: Option<Box<dyn Fn(Vec<JSONValue>) -> Vec<JSONValue>>>
+/// A bound of a DateType, with the text it was read from.
+type DateBound = (String, Result<Timestamp, String>);
+
+/// A rule for strings holding an ISO 8601 date, such as `2024-02-29`, or a date and time,
+/// such as `2024-02-29T13:45:30.5+02:00`. Seconds, fractions and the offset are optional,
+/// and a value without an offset is read as UTC.
+pub struct DateType {
+    before: Option<DateBound>,
+    after: Option<DateBound>,
+    between: Option<(DateBound, DateBound)>,
+    to_utc: bool,
+    messages: Messages,
+    metadata: Metadata,
+    sensitive: bool,
+    warning: bool
 }
 
-impl ArrayType {
-    /// Create a new ArrayType instance.
+impl Default for DateType {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DateType {
+    /// Create a new DateType instance.
     pub fn new() -> Self {
         Self {
-            min_length: None,
-            max_length: None,
-            length: None,
-            empty: None,
-            every: None,
-            some: None,
-            at: None,
-            truncate: None,
-            transform: None
+            before: None,
+            after: None,
+            between: None,
+            to_utc: false,
+            messages: Messages::default(),
+            metadata: Metadata::default(),
+            sensitive: false,
+            warning: false
         }
     }
 
-    /// Set the minimum length of the array.
-    pub fn min_length(mut self, min: usize) -> Self {
-        self.min_length = Some(min);
+    /// Require the date to be strictly before the given one. An invalid bound fails every validation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{DateType, JSONParser, JSONSchema};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("birthday", DateType::new().after("1900-01-01").before("2024-01-01").boxed()),
+    ///   ("created_at", DateType::new().between("2024-01-01", "2024-12-31T23:59:59Z").boxed())
+    /// ]);
+    ///
+    /// let json = JSONParser::from(r#"{ "birthday": "1990-04-12", "created_at": "2024-06-01T08:30:00+02:00" }"#).unwrap();
+    /// assert!(schema.validate(&json).is_ok());
+    ///
+    /// let json = JSONParser::from(r#"{ "birthday": "1990-02-30", "created_at": "2024-06-01" }"#).unwrap();
+    /// assert_eq!(schema.validate(&json).unwrap_err(), "birthday is not a valid date");
+    ///
+    /// let json = JSONParser::from(r#"{ "birthday": "2024-01-01", "created_at": "2024-06-01" }"#).unwrap();
+    /// assert_eq!(schema.validate(&json).unwrap_err(), "birthday must be before 2024-01-01");
+    /// ```
+    pub fn before(mut self, date: &str) -> Self {
+        self.before = Some((date.to_string(), Timestamp::parse(date)));
+        self.messages.constraint("before");
         self
     }
 
-    /// Set the maximum length of the array.
-    pub fn max_length(mut self, max: usize) -> Self {
-        self.max_length = Some(max);
+    /// Require the date to be strictly after the given one.
+    pub fn after(mut self, date: &str) -> Self {
+        self.after = Some((date.to_string(), Timestamp::parse(date)));
+        self.messages.constraint("after");
         self
     }
 
-    /// Set the exact length of the array.
-    pub fn length(mut self, length: usize) -> Self {
-        self.length = Some(length);
+    /// Require the date to be within the given dates, both included.
+    pub fn between(mut self, start: &str, end: &str) -> Self {
+        self.between = Some(((start.to_string(), Timestamp::parse(start)), (end.to_string(), Timestamp::parse(end))));
+        self.messages.constraint("between");
         self
     }
 
-    /// Set whether the array can be empty.
-    pub fn empty(mut self) -> Self {
-        self.empty = Some(true);
+    /// Convert the date to an RFC 3339 date and time in UTC before validation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{DateType, JSONParser, JSONSchema};
+    ///
+    /// let schema = JSONSchema::new([
+    ///   ("created_at", DateType::new().to_utc().boxed()),
+    ///   ("day", DateType::new().to_utc().boxed())
+    /// ]);
+    ///
+    /// let json = JSONParser::from(r#"{ "created_at": "2024-03-01T01:30:00.250+02:00", "day": "2024-03-01" }"#).unwrap();
+    /// let validated = schema.validate(&json).unwrap();
+    ///
+    /// assert_eq!(validated["created_at"].as_str(), Some("2024-02-29T23:30:00.25Z"));
+    /// assert_eq!(validated["day"].as_str(), Some("2024-03-01T00:00:00Z"));
+    /// ```
+    pub fn to_utc(mut self) -> Self {
+        self.to_utc = true;
         self
     }
 
-    /// Set a rule for every items in the array.
-    pub fn every(mut self, rule: Box<dyn Validator>) -> Self {
-        self.every = Some(rule);
+    /// Mark the values checked by this rule as sensitive, to be hidden by `JSONSchema::redact`.
+    pub fn sensitive(mut self) -> Self {
+        self.sensitive = true;
         self
     }
 
-    /// Set a rule for at least one item in the array.
-    pub fn some(mut self, rule: Box<dyn Validator>) -> Self {
-        self.some = Some(rule);
+    /// Report failures of this rule as warnings, which do not fail validation.
+    /// See `JSONSchema::validate_all`.
+    pub fn warn(mut self) -> Self {
+        self.warning = true;
         self
     }
 
-    /// Set a rule for a specific item in the array.
-    pub fn at(mut self, index: usize, rule: Box<dyn Validator>) -> Self {
-        self.at = Some((index, rule));
+    /// Describe the values checked by this rule, for the documentation generated from the schema.
+    pub fn describe(mut self, description: &str) -> Self {
+        self.metadata.description = Some(description.to_string());
         self
     }
 
-    /// Truncate the array before validation.
-    pub fn truncate(mut self, length: usize) -> Self {
-        self.truncate = Some(length);
+    /// Add an example of a valid value, for the documentation generated from the schema.
+    pub fn example(mut self, example: JSONValue) -> Self {
+        self.metadata.examples.push(example);
         self
     }
 
-    /// Set a custom transformation function for the array.
-    pub fn transform<F: 'static + Fn(Vec<JSONValue>) -> Vec<JSONValue>>(mut self, transform: F) -> Self {
-        self.transform = Some(Box::new(transform));
+    /// Mark the property as deprecated in the documentation generated from the schema.
+    pub fn deprecated(mut self) -> Self {
+        self.metadata.deprecated = true;
         self
     }
 
-    /// Convert the ArrayType to a Box<dyn Validator>.
+    /// Replace the error message of the last constraint added, or of every failure of
+    /// the rule when called before any constraint.
+    pub fn message(mut self, message: &str) -> Self {
+        self.messages.set(fixed_message(message));
+        self
+    }
+
+    /// Replace the error message like `message`, building it from the failing value.
+    pub fn message_with<F: 'static + Send + Sync + Fn(&JSONValue) -> String>(mut self, message: F) -> Self {
+        self.messages.set(Box::new(message));
+        self
+    }
+
+    /// Convert the DateType to a Box<dyn Validator>.
     pub fn boxed(self) -> Box<dyn Validator> {
         Box::new(self)
     }
 }
 
-impl Validator for ArrayType {
-    fn validate(&self, key: &str, value: &JSONValue) -> Result<(), String> {
-        match value {
-            JSONValue::Array(arr) => {
-                if let Some(min) = self.min_length {
-                    if arr.len() < min {
-                        return Err(format!("{} is too short (min: {})", key, min));
-                    }
-                }
+/// The instant of a bound, or an error naming the rule it belongs to.
+fn date_bound<'b>(key: &str, (text, bound): &'b DateBound) -> Result<&'b Timestamp, String> {
+    bound.as_ref().map_err(|_| format!("Invalid date bound '{}' for {}", text, key))
+}
 
-                if let Some(max) = self.max_length {
-                    if arr.len() > max {
-                        return Err(format!("{} is too long (max: {})", key, max));
-                    }
-                }
+impl Validator for DateType {
+    fn expected_kind(&self) -> Option<JSONKind> {
+        Some(JSONKind::String)
+    }
 
-                if let Some(length) = self.length {
-                    if arr.len() != length {
-                        return Err(format!("{} is not the correct length (length: {})", key, length));
-                    }
-                }
+    fn is_sensitive(&self) -> bool {
+        self.sensitive
+    }
 
-                if let Some(empty) = self.empty {
-                    if empty && arr.is_empty() {
-                        return Err(format!("{} is empty", key));
-                    }
-                }
+    fn is_warning(&self) -> bool {
+        self.warning
+    }
 
-                if let Some(rule) = &self.every {
-                    for item in arr {
-                        rule.validate(key, item)?;
+    fn generate(&self, rng: &mut Rng) -> JSONValue {
+        const YEAR: i64 = 365 * 86400;
+        let seconds = |bound: &DateBound| bound.1.as_ref().ok().map(|date| date.seconds());
+        let lows = [self.after.as_ref().and_then(seconds).map(|s| s + 1), self.between.as_ref().and_then(|(start, _)| seconds(start))];
+        let highs = [self.before.as_ref().and_then(seconds).map(|s| s - 1), self.between.as_ref().and_then(|(_, end)| seconds(end))];
+        let low = lows.iter().flatten().max().copied();
+        let high = highs.iter().flatten().min().copied();
+        let (low, high) = match (low, high) {
+            (Some(low), Some(high)) => (low, high),
+            (Some(low), None) => (low, low + 10 * YEAR),
+            (None, Some(high)) => (high - 10 * YEAR, high),
+            // From 2000-01-01 to 2030-01-01.
+            (None, None) => (946684800, 1893456000)
+        };
+        let offset = rng.next_u64() % ((high - low).max(0) as u64 + 1);
+
+        JSONValue::String(Timestamp::from_seconds(low + offset as i64).to_rfc3339().into())
+    }
+
+    fn document(&self) -> RuleDoc {
+        let mut doc = self.metadata.document("date");
+
+        document_option(&mut doc, "before", self.before.as_ref().map(|(text, _)| text));
+        document_option(&mut doc, "after", self.after.as_ref().map(|(text, _)| text));
+        document_option(&mut doc, "between", self.between.as_ref().map(|((start, _), (end, _))| format!("{} and {}", start, end)));
+        document_flag(&mut doc, "to_utc", self.to_utc);
+        doc
+    }
+
+    fn openapi_schema(&self) -> JSONValue {
+        let mut schema = self.metadata.openapi_schema("string");
+
+        schema.insert("format", JSONValue::String("date-time".into()));
+        JSONValue::Object(schema)
+    }
+
+    fn validate(&self, key: &str, value: &JSONValue) -> Result<(), String> {
+        self.validate_in(&ValidationContext::new(key, &EnglishFormatter), value)
+    }
+
+    fn validate_in(&self, context: &ValidationContext, value: &JSONValue) -> Result<(), String> {
+        match value {
+            JSONValue::String(s) => {
+                let date = Timestamp::parse(s).map_err(|_| self.messages.error("date", value, || context.error("date", Vec::new())))?;
+
+                if let Some(before) = &self.before {
+                    if date >= *date_bound(context.name(), before)? {
+                        return Err(self.messages.error("before", value, || context.error("before", vec![("before", before.0.clone())])));
                     }
                 }
 
-                if let Some(rule) = &self.some {
-                    for item in arr {
-                        if rule.validate(key, item).is_ok() {
-                            return Ok(());
-                        }
+                if let Some(after) = &self.after {
+                    if date <= *date_bound(context.name(), after)? {
+                        return Err(self.messages.error("after", value, || context.error("after", vec![("after", after.0.clone())])));
                     }
-                    return Err(format!("No items in the {} match the rule", key));
                 }
 
-                if let Some((index, rule)) = &self.at {
-                    if let Some(item) = arr.get(*index) {
-                        rule.validate(key, item)?;
-                    } else {
-                        return Err(format!("In {}, index {} not found", key, index));
+                if let Some((start, end)) = &self.between {
+                    if date < *date_bound(context.name(), start)? || date > *date_bound(context.name(), end)? {
+                        return Err(self.messages.error("between", value, || context.error("between", vec![("start", start.0.clone()), ("end", end.0.clone())])));
                     }
                 }
 
                 Ok(())
             },
-            _ => Err(format!("Type of {} mismatch, expected Array", key))
+            _ => Err(self.messages.error("type", value, || context.error("type", vec![("expected", "String".to_string())])))
         }
     }
 
     fn transform(&self, key: &str, value: &JSONValue) -> Result<JSONValue, String> {
-        match value {
-            JSONValue::Array(arr) => {
-                let mut transformed = arr.clone();
+        transform_cloned(self, key, value)
+    }
 
-                if let Some(len) = self.truncate {
-                    transformed.truncate(len);
-                }
+    fn transforms(&self) -> bool {
+        self.to_utc
+    }
 
-                if let Some(transform) = &self.transform {
-                    transformed = transform(transformed);
+    fn transform_in_place(&self, key: &str, value: &mut JSONValue) -> Result<(), String> {
+        match value {
+            JSONValue::String(s) => {
+                // An invalid date is kept, to be reported by `validate`.
+                if let (true, Ok(date)) = (self.to_utc, Timestamp::parse(s)) {
+                    *s = date.to_rfc3339().into();
                 }
 
-                Ok(JSONValue::Array(transformed))
+                Ok(())
             },
-            _ => Err(format!("Type of {} mismatch, expected Array", key))
+            _ => Err(self.messages.error("type", value, || format!("Type of {} mismatch, expected String", key)))
         }
     }
 }
 
-pub struct BooleanType {
-    value: Option<bool>,
-    transform: Option<Box<dyn Fn(bool) -> bool>>
+/// A rule accepting any value, as a placeholder or to require a property whatever its type.
+pub struct AnyType;
+
+impl Default for AnyType {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-impl BooleanType {
-    /// Create a new BooleanType instance.
+impl AnyType {
+    /// Create a new AnyType instance.
     pub fn new() -> Self {
-        Self {
-            value: None,
-            transform: None
-        }
+        Self
     }
 
-    /// Set the expected value to true.
-    pub fn truthy(mut self) -> Self {
-        self.value = Some(true);
-        self
+    /// Convert the AnyType to a Box<dyn Validator>.
+    pub fn boxed(self) -> Box<dyn Validator> {
+        Box::new(self)
     }
+}
 
-    /// Set the expected value to false.
-    pub fn falsy(mut self) -> Self {
-        self.value = Some(false);
+impl Validator for AnyType {
+    fn document(&self) -> RuleDoc {
+        RuleDoc::new("any")
+    }
+
+    fn generate(&self, rng: &mut Rng) -> JSONValue {
+        generate_scalar(rng)
+    }
+
+    fn validate(&self, _: &str, _: &JSONValue) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn transforms(&self) -> bool {
+        false
+    }
+
+    fn transform_in_place(&self, _: &str, _: &mut JSONValue) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// A rule accepting the values its inner rule rejects, such as anything but null.
+/// The inner rule is only used to validate: its transformations are not applied.
+///
+/// # Example
+///
+/// ```
+/// use jsonparser::{AnyType, JSONParser, JSONSchema, NotType, NullType};
+///
+/// let schema = JSONSchema::new([
+///   ("id", NotType::new(NullType::new().boxed()).boxed()),
+///   ("metadata", AnyType::new().boxed())
+/// ]);
+///
+/// let json = JSONParser::from(r#"{ "id": 42, "metadata": [1, "a"] }"#).unwrap();
+/// assert!(schema.validate(&json).is_ok());
+///
+/// let json = JSONParser::from(r#"{ "id": null, "metadata": null }"#).unwrap();
+/// assert_eq!(schema.validate(&json).unwrap_err(), "id matches an excluded rule");
+///
+/// let json = JSONParser::from(r#"{ "id": 42 }"#).unwrap();
+/// assert_eq!(schema.validate(&json).unwrap_err(), "Key 'metadata' not found");
+/// ```
+pub struct NotType<'a> {
+    rule: Box<dyn Validator + 'a>,
+    messages: Messages
+}
+
+impl<'a> NotType<'a> {
+    /// Create a new NotType instance, accepting the values the given rule rejects.
+    pub fn new(rule: Box<dyn Validator + 'a>) -> Self {
+        Self { rule, messages: Messages::default() }
+    }
+
+    /// Replace the error message of the rule.
+    pub fn message(mut self, message: &str) -> Self {
+        self.messages.set(fixed_message(message));
         self
     }
 
-    /// Set a custom transformation function for the boolean.
-    pub fn transform<F: 'static + Fn(bool) -> bool>(mut self, transform: F) -> Self {
-        self.transform = Some(Box::new(transform));
+    /// Replace the error message of the rule, building it from the failing value.
+    pub fn message_with<F: 'static + Send + Sync + Fn(&JSONValue) -> String>(mut self, message: F) -> Self {
+        self.messages.set(Box::new(message));
         self
     }
 
-    /// Convert the BooleanType to a Box<dyn Validator>.
-    pub fn boxed(self) -> Box<dyn Validator> {
+    /// Convert the NotType to a Box<dyn Validator>.
+    pub fn boxed(self) -> Box<dyn Validator + 'a> {
         Box::new(self)
     }
 }
 
-impl Validator for BooleanType {
-    fn validate(&self, key: &str, value: &JSONValue) -> Result<(), String> {
-        match value {
-            JSONValue::Boolean(b) => {
-                if let Some(expected) = self.value {
-                    if b != &expected {
-                        return Err(format!("For {}, expected {}", key, expected));
-                    }
-                }
+impl<'a> Validator for NotType<'a> {
+    fn document(&self) -> RuleDoc {
+        let mut doc = RuleDoc::new("not");
 
-                Ok(())
-            },
-            _ => Err(format!("Type of {} mismatch, expected Boolean", key))
-        }
+        doc.constraints.push(format!("rule: {}", self.rule.document().summary()));
+        doc
     }
 
-    fn transform(&self, key: &str,value: &JSONValue) -> Result<JSONValue, String> {
-        match value {
-            JSONValue::Boolean(b) => {
-                let mut transformed = *b;
+    fn openapi_schema(&self) -> JSONValue {
+        JSONValue::Object(vec![("not".to_string(), self.rule.openapi_schema())].into_iter().collect())
+    }
 
-                if let Some(transform) = &self.transform {
-                    transformed = transform(transformed);
-                }
+    fn generate(&self, rng: &mut Rng) -> JSONValue {
+        let mut value = generate_scalar(rng);
 
-                Ok(JSONValue::Boolean(transformed))
-            },
-            _ => Err(format!("Type of {} mismatch, expected Boolean", key))
+        for _ in 1..16 {
+            if self.rule.validate("", &value).is_err() {
+                break;
+            }
+            value = generate_scalar(rng);
         }
+        value
     }
-}
 
-pub struct ObjectType<'a> {
-    rules: OrderedMap<Box<dyn Validator + 'a>>
-}
+    fn validate(&self, key: &str, value: &JSONValue) -> Result<(), String> {
+        self.validate_in(&ValidationContext::new(key, &EnglishFormatter), value)
+    }
 
-impl<'a> ObjectType<'a> {
-    /// Create a new ObjectType instance.
-    pub fn new() -> Self {
-        Self {
-            rules: OrderedMap::new()
+    fn validate_in(&self, context: &ValidationContext, value: &JSONValue) -> Result<(), String> {
+        match self.rule.validate_in(context, value) {
+            Ok(()) => Err(self.messages.error("not", value, || context.error("not", Vec::new()))),
+            Err(_) => Ok(())
         }
     }
 
-    /// Add a rule for a property in the object.
-    pub fn property(mut self, key: &str, rule: Box<dyn Validator>) -> Self {
-        self.rules.insert(key, rule);
-        self
+    fn transforms(&self) -> bool {
+        false
     }
 
-    /// Convert the ObjectType to a Box<dyn Validator>.
-    pub fn boxed(self) -> Box<dyn Validator + 'a> {
-        Box::new(self)
+    fn transform_in_place(&self, _: &str, _: &mut JSONValue) -> Result<(), String> {
+        Ok(())
     }
 }
 
-impl<'a> Validator for ObjectType<'a> {
-    fn validate(&self, key: &str, value: &JSONValue) -> Result<(), String> {
-        match value {
-            JSONValue::Object(obj) => {
-                for (subkey, rule) in self.rules.iter() {
-                    match obj.get(subkey as &str) {
-                        Some(value) => rule.validate(subkey, value)?,
-                        None => return Err(format!("In {}, key '{}' not found", key, subkey))
-                    }
-                }
-                Ok(())
-            },
-            _ => Err(format!("Type of {} mismatch, expected Object", key))
-        }
+/// Read the argument of a rule option expecting a length or an index.
+fn usize_option(name: &str, value: &JSONValue) -> Result<usize, String> {
+    match value {
+        JSONValue::Number(n) if *n >= 0.0 && n.fract() == 0.0 => Ok(*n as usize),
+        _ => Err(format!("option '{}' expects a non-negative integer", name))
     }
 }
 
-pub struct NullType;
+fn f64_option(name: &str, value: &JSONValue) -> Result<f64, String> {
+    value.as_f64().ok_or_else(|| format!("option '{}' expects a number", name))
+}
 
-impl NullType {
-    /// Create a new NullType instance.
-    pub fn new() -> Self {
-        Self
-    }
+fn str_option<'v>(name: &str, value: &'v JSONValue) -> Result<&'v str, String> {
+    value.as_str().ok_or_else(|| format!("option '{}' expects a string", name))
+}
 
-    /// Convert the NullType to a Box<dyn Validator>.
-    pub fn boxed(self) -> Box<dyn Validator> {
-        Box::new(self)
-    }
+/// Read the argument of an option expecting a date, checking that it is valid.
+fn date_option<'v>(name: &str, value: &'v JSONValue) -> Result<&'v str, String> {
+    let date = str_option(name, value)?;
+
+    Timestamp::parse(date).map_err(|_| format!("option '{}' expects an ISO 8601 date, found '{}'", name, date))?;
+    Ok(date)
 }
 
-impl Validator for NullType {
-    fn validate(&self, key: &str, value: &JSONValue) -> Result<(), String> {
-        match value {
-            JSONValue::Null => Ok(()),
-            _ => Err(format!("Type of {} mismatch, expected Null", key)),
-        }
+/// Read the argument of an option without one in the builder, applied when true.
+fn flag_option(name: &str, value: &JSONValue) -> Result<bool, String> {
+    value.as_bool().ok_or_else(|| format!("option '{}' expects a boolean", name))
+}
+
+/// Build the validator described by a rule definition, as read by `JSONSchema::from_json`.
+fn rule_from_json(definition: &JSONValue) -> Result<Box<dyn Validator>, String> {
+    let options = definition.as_object().ok_or("expected a rule object")?;
+    let kind = options.get("type").and_then(|kind| kind.as_str()).ok_or("expected a rule with a string 'type'")?;
+    let options = options.iter().filter(|(name, _)| *name != "type");
+    let unknown = |name: &str| format!("unknown option '{}' for a{} {}", name, if kind == "array" || kind == "object" || kind == "any" { "n" } else { "" }, kind);
+
+    match kind {
+        "string" => {
+            let mut rule = StringType::new();
+
+            for (name, value) in options {
+                rule = match name {
+                    "min_length" => rule.min_length(usize_option(name, value)?),
+                    "max_length" => rule.max_length(usize_option(name, value)?),
+                    "length" => rule.length(usize_option(name, value)?),
+                    "starts_with" => rule.starts_with(str_option(name, value)?),
+                    "ends_with" => rule.ends_with(str_option(name, value)?),
+                    "includes" => rule.includes(str_option(name, value)?),
+                    "starts_with_ignore_case" => rule.starts_with_ignore_case(str_option(name, value)?),
+                    "ends_with_ignore_case" => rule.ends_with_ignore_case(str_option(name, value)?),
+                    "includes_ignore_case" => rule.includes_ignore_case(str_option(name, value)?),
+                    "pattern" => {
+                        let pattern = str_option(name, value)?;
+
+                        Regex::new(pattern).map_err(|e| format!("invalid pattern '{}': {}", pattern, e))?;
+                        rule.pattern(pattern)
+                    },
+                    "len_chars" if flag_option(name, value)? => rule.len_chars(),
+                    "len_bytes" if flag_option(name, value)? => rule.len_bytes(),
+                    "non_empty" if flag_option(name, value)? => rule.non_empty(),
+                    "trim" if flag_option(name, value)? => rule.trim(),
+                    "trim_start" if flag_option(name, value)? => rule.trim_start(),
+                    "trim_end" if flag_option(name, value)? => rule.trim_end(),
+                    "to_lowercase" if flag_option(name, value)? => rule.to_lowercase(),
+                    "to_uppercase" if flag_option(name, value)? => rule.to_uppercase(),
+                    "sensitive" if flag_option(name, value)? => rule.sensitive(),
+                    "warn" if flag_option(name, value)? => rule.warn(),
+                    "describe" => rule.describe(str_option(name, value)?),
+                    "example" => rule.example(value.clone()),
+                    "deprecated" if flag_option(name, value)? => rule.deprecated(),
+                    "len_chars" | "len_bytes" | "non_empty" => rule,
+                    "trim" | "trim_start" | "trim_end" | "to_lowercase" | "to_uppercase" | "sensitive" | "warn" | "deprecated" => rule,
+                    "message" => rule.message(str_option(name, value)?),
+                    _ => return Err(unknown(name))
+                };
+            }
+            Ok(rule.boxed())
+        },
+        "number" => {
+            let mut rule = NumberType::new();
+
+            for (name, value) in options {
+                rule = match name {
+                    "gt" => rule.gt(f64_option(name, value)?),
+                    "gte" => rule.gte(f64_option(name, value)?),
+                    "lt" => rule.lt(f64_option(name, value)?),
+                    "lte" => rule.lte(f64_option(name, value)?),
+                    "eq" => rule.eq(f64_option(name, value)?),
+                    "multiple_of" => rule.multiple_of(f64_option(name, value)?),
+                    "clamp" => match value.as_array() {
+                        Some([min, max]) => rule.clamp(f64_option(name, min)?, f64_option(name, max)?),
+                        _ => return Err("option 'clamp' expects a minimum and a maximum".to_string())
+                    },
+                    "integer" if flag_option(name, value)? => rule.integer(),
+                    "finite" if flag_option(name, value)? => rule.finite(),
+                    "positive" if flag_option(name, value)? => rule.positive(),
+                    "negative" if flag_option(name, value)? => rule.negative(),
+                    "floor" if flag_option(name, value)? => rule.floor(),
+                    "ceil" if flag_option(name, value)? => rule.ceil(),
+                    "round" if flag_option(name, value)? => rule.round(),
+                    "sensitive" if flag_option(name, value)? => rule.sensitive(),
+                    "warn" if flag_option(name, value)? => rule.warn(),
+                    "describe" => rule.describe(str_option(name, value)?),
+                    "example" => rule.example(value.clone()),
+                    "deprecated" if flag_option(name, value)? => rule.deprecated(),
+                    "integer" | "finite" | "positive" | "negative" | "floor" | "ceil" | "round" | "sensitive" | "warn" | "deprecated" => rule,
+                    "message" => rule.message(str_option(name, value)?),
+                    _ => return Err(unknown(name))
+                };
+            }
+            Ok(rule.boxed())
+        },
+        "boolean" => {
+            let mut rule = BooleanType::new();
+
+            for (name, value) in options {
+                rule = match name {
+                    "truthy" if flag_option(name, value)? => rule.truthy(),
+                    "falsy" if flag_option(name, value)? => rule.falsy(),
+                    "coerce" if flag_option(name, value)? => rule.coerce(),
+                    "not" if flag_option(name, value)? => rule.not(),
+                    "or_default" => rule.or_default(flag_option(name, value)?),
+                    "sensitive" if flag_option(name, value)? => rule.sensitive(),
+                    "warn" if flag_option(name, value)? => rule.warn(),
+                    "describe" => rule.describe(str_option(name, value)?),
+                    "example" => rule.example(value.clone()),
+                    "deprecated" if flag_option(name, value)? => rule.deprecated(),
+                    "truthy" | "falsy" | "coerce" | "not" | "sensitive" | "warn" | "deprecated" => rule,
+                    "message" => rule.message(str_option(name, value)?),
+                    _ => return Err(unknown(name))
+                };
+            }
+            Ok(rule.boxed())
+        },
+        "array" => {
+            let mut rule = ArrayType::new();
+
+            for (name, value) in options {
+                rule = match name {
+                    "min_length" => rule.min_length(usize_option(name, value)?),
+                    "max_length" => rule.max_length(usize_option(name, value)?),
+                    "length" => rule.length(usize_option(name, value)?),
+                    "truncate" => rule.truncate(usize_option(name, value)?),
+                    "unique_by" => rule.unique_by(str_option(name, value)?),
+                    "contains" => rule.contains(rule_from_json(value).map_err(|e| format!("in 'contains', {}", e))?),
+                    "min" => rule.min(usize_option(name, value)?),
+                    "max" => rule.max(usize_option(name, value)?),
+                    "every" => rule.every(rule_from_json(value).map_err(|e| format!("in 'every', {}", e))?),
+                    "some" => rule.some(rule_from_json(value).map_err(|e| format!("in 'some', {}", e))?),
+                    "at" => match value.as_array() {
+                        Some([index, item]) => rule.at(usize_option(name, index)?, rule_from_json(item).map_err(|e| format!("in 'at', {}", e))?),
+                        _ => return Err("option 'at' expects an index and a rule".to_string())
+                    },
+                    "empty" if flag_option(name, value)? => rule.empty(),
+                    "unique" if flag_option(name, value)? => rule.unique(),
+                    "compact" if flag_option(name, value)? => rule.compact(),
+                    "dedupe" if flag_option(name, value)? => rule.dedupe(),
+                    "sorted" if flag_option(name, value)? => rule.sorted(),
+                    "reverse" if flag_option(name, value)? => rule.reverse(),
+                    "sensitive" if flag_option(name, value)? => rule.sensitive(),
+                    "warn" if flag_option(name, value)? => rule.warn(),
+                    "describe" => rule.describe(str_option(name, value)?),
+                    "example" => rule.example(value.clone()),
+                    "deprecated" if flag_option(name, value)? => rule.deprecated(),
+                    "empty" | "unique" | "compact" | "dedupe" | "sorted" | "reverse" | "sensitive" | "warn" | "deprecated" => rule,
+                    "message" => rule.message(str_option(name, value)?),
+                    _ => return Err(unknown(name))
+                };
+            }
+            Ok(rule.boxed())
+        },
+        "object" => {
+            let mut rule = ObjectType::new();
+
+            for (name, value) in options {
+                rule = match name {
+                    "properties" => {
+                        let properties = value.as_object().ok_or("option 'properties' expects an object of rules")?;
+
+                        for (key, definition) in properties.iter() {
+                            rule = rule.property(key, rule_from_json(definition).map_err(|e| format!("in property '{}', {}", key, e))?);
+                        }
+                        rule
+                    },
+                    "min_properties" => rule.min_properties(usize_option(name, value)?),
+                    "max_properties" => rule.max_properties(usize_option(name, value)?),
+                    "keys" => rule.keys(rule_from_json(value).map_err(|e| format!("in 'keys', {}", e))?),
+                    "rename" => {
+                        let renames = value.as_object().ok_or("option 'rename' expects an object of keys")?;
+
+                        for (from, to) in renames.iter() {
+                            rule = rule.rename(from, to.as_str().ok_or("option 'rename' expects an object of keys")?);
+                        }
+                        rule
+                    },
+                    "sensitive" if flag_option(name, value)? => rule.sensitive(),
+                    "warn" if flag_option(name, value)? => rule.warn(),
+                    "describe" => rule.describe(str_option(name, value)?),
+                    "example" => rule.example(value.clone()),
+                    "deprecated" if flag_option(name, value)? => rule.deprecated(),
+                    "sensitive" | "warn" | "deprecated" => rule,
+                    "message" => rule.message(str_option(name, value)?),
+                    _ => return Err(unknown(name))
+                };
+            }
+            Ok(rule.boxed())
+        },
+        "null" => match options.map(|(name, _)| name).next() {
+            Some(name) => Err(unknown(name)),
+            None => Ok(NullType::new().boxed())
+        },
+        "date" => {
+            let mut rule = DateType::new();
+
+            for (name, value) in options {
+                rule = match name {
+                    "before" => rule.before(date_option(name, value)?),
+                    "after" => rule.after(date_option(name, value)?),
+                    "between" => match value.as_array() {
+                        Some([start, end]) => rule.between(date_option(name, start)?, date_option(name, end)?),
+                        _ => return Err("option 'between' expects a start and an end".to_string())
+                    },
+                    "to_utc" if flag_option(name, value)? => rule.to_utc(),
+                    "sensitive" if flag_option(name, value)? => rule.sensitive(),
+                    "warn" if flag_option(name, value)? => rule.warn(),
+                    "describe" => rule.describe(str_option(name, value)?),
+                    "example" => rule.example(value.clone()),
+                    "deprecated" if flag_option(name, value)? => rule.deprecated(),
+                    "to_utc" | "sensitive" | "warn" | "deprecated" => rule,
+                    "message" => rule.message(str_option(name, value)?),
+                    _ => return Err(unknown(name))
+                };
+            }
+            Ok(rule.boxed())
+        },
+        "any" => match options.map(|(name, _)| name).next() {
+            Some(name) => Err(unknown(name)),
+            None => Ok(AnyType::new().boxed())
+        },
+        "not" => {
+            let mut rule = None;
+            let mut message = None;
+
+            for (name, value) in options {
+                match name {
+                    "rule" => rule = Some(rule_from_json(value).map_err(|e| format!("in 'rule', {}", e))?),
+                    "message" => message = Some(str_option(name, value)?),
+                    _ => return Err(unknown(name))
+                }
+            }
+            let rule = NotType::new(rule.ok_or("option 'rule' is required for a not")?);
+
+            Ok(match message {
+                Some(message) => rule.message(message),
+                None => rule
+            }.boxed())
+        },
+        _ => Err(format!("unknown rule type '{}'", kind))
     }
 }
@@ -0,0 +1,144 @@
+use crate::utils::compat::*;
+use crate::JSONValue;
+
+/// Controls how a walk proceeds after visiting a node.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Walk {
+    /// Visit the children of the node.
+    Continue,
+    /// Do not visit the children of the node.
+    Skip,
+    /// Stop the whole walk.
+    Stop
+}
+
+/// Append a reference token to a JSON Pointer path, escaping `~` and `/`.
+pub(crate) fn push_token(path: &mut String, token: &str) {
+    path.push('/');
+    for c in token.chars() {
+        match c {
+            '~' => path.push_str("~0"),
+            '/' => path.push_str("~1"),
+            c => path.push(c)
+        }
+    }
+}
+
+impl JSONValue {
+    /// Traverse the value depth-first, calling the visitor with the JSON Pointer
+    /// path of each node and the node itself. The root has the empty path.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, Walk};
+    ///
+    /// let json = JSONParser::from(r#"{ "name": "John Doe", "cars": [{ "model": "Ford" }] }"#).unwrap();
+    /// let mut paths = Vec::new();
+    ///
+    /// json.walk(|path, _| {
+    ///     paths.push(path.to_string());
+    ///     Walk::Continue
+    /// });
+    ///
+    /// assert_eq!(paths, vec!["", "/name", "/cars", "/cars/0", "/cars/0/model"]);
+    /// ```
+    pub fn walk<'a, F: FnMut(&str, &'a JSONValue) -> Walk>(&'a self, mut visitor: F) {
+        self.walk_at(&mut String::new(), &mut visitor);
+    }
+
+    /// Traverse the value depth-first like `walk`, giving mutable access to each node.
+    /// Children are visited after the visitor has run on their parent.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::{JSONParser, JSONValue, Walk};
+    ///
+    /// let mut json = JSONParser::from(r#"{ "name": "John Doe", "password": "secret" }"#).unwrap();
+    ///
+    /// json.walk_mut(|path, value| {
+    ///     if path == "/password" {
+    ///         *value = JSONValue::String("***".into());
+    ///     }
+    ///     Walk::Continue
+    /// });
+    ///
+    /// assert_eq!(json["password"].as_str(), Some("***"));
+    /// ```
+    pub fn walk_mut<F: FnMut(&str, &mut JSONValue) -> Walk>(&mut self, mut visitor: F) {
+        self.walk_mut_at(&mut String::new(), &mut visitor);
+    }
+
+    fn walk_at<'a, F: FnMut(&str, &'a JSONValue) -> Walk>(&'a self, path: &mut String, visitor: &mut F) -> bool {
+        match visitor(path, self) {
+            Walk::Stop => return false,
+            Walk::Skip => return true,
+            Walk::Continue => {}
+        }
+        let len = path.len();
+
+        match self {
+            JSONValue::Object(obj) => {
+                for (key, value) in obj.iter() {
+                    push_token(path, key);
+                    let keep_going = value.walk_at(path, visitor);
+
+                    path.truncate(len);
+                    if !keep_going {
+                        return false;
+                    }
+                }
+            },
+            JSONValue::Array(array) => {
+                for (index, value) in array.iter().enumerate() {
+                    push_token(path, &index.to_string());
+                    let keep_going = value.walk_at(path, visitor);
+
+                    path.truncate(len);
+                    if !keep_going {
+                        return false;
+                    }
+                }
+            },
+            _ => {}
+        }
+        true
+    }
+
+    fn walk_mut_at<F: FnMut(&str, &mut JSONValue) -> Walk>(&mut self, path: &mut String, visitor: &mut F) -> bool {
+        match visitor(path, self) {
+            Walk::Stop => return false,
+            Walk::Skip => return true,
+            Walk::Continue => {}
+        }
+        let len = path.len();
+
+        match self {
+            JSONValue::Object(obj) => {
+                for (key, value) in obj.iter_mut() {
+                    push_token(path, key);
+                    let keep_going = value.walk_mut_at(path, visitor);
+
+                    path.truncate(len);
+                    if !keep_going {
+                        return false;
+                    }
+                }
+            },
+            JSONValue::Array(array) => {
+                for (index, value) in array.iter_mut().enumerate() {
+                    push_token(path, &index.to_string());
+                    let keep_going = value.walk_mut_at(path, visitor);
+
+                    path.truncate(len);
+                    if !keep_going {
+                        return false;
+                    }
+                }
+            },
+            _ => {}
+        }
+        true
+    }
+}
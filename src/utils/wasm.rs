@@ -0,0 +1,49 @@
+//! JavaScript bindings, so that documents can be parsed and validated in the browser
+//! with the same schema definitions as on the server.
+
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+use crate::utils::{JSONSchema, JSONValue};
+use crate::JSONParser;
+
+/// Convert a value to the matching JavaScript value, keeping the order of object keys.
+fn to_js(value: &JSONValue) -> JsValue {
+    match value {
+        JSONValue::Object(obj) => {
+            let object = Object::new();
+
+            for (key, value) in obj.iter() {
+                // Setting a property of a plain object cannot fail.
+                let _ = Reflect::set(&object, &JsValue::from_str(key), &to_js(value));
+            }
+            object.into()
+        },
+        JSONValue::Array(array) => array.iter().map(to_js).collect::<Array>().into(),
+        JSONValue::String(s) => JsValue::from_str(s),
+        JSONValue::Number(n) => JsValue::from_f64(*n),
+        JSONValue::Boolean(b) => JsValue::from_bool(*b),
+        JSONValue::Null => JsValue::NULL,
+        JSONValue::Raw(raw) => to_js(&raw.to_value())
+    }
+}
+
+fn parse_value(input: &str) -> Result<JSONValue, JsValue> {
+    JSONParser::from(input).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Parse a JSON document into a JavaScript value, throwing the parse error as a string.
+#[wasm_bindgen]
+pub fn parse(input: &str) -> Result<JsValue, JsValue> {
+    parse_value(input).map(|value| to_js(&value))
+}
+
+/// Validate a JSON document against a schema definition, as read by `JSONSchema::from_json`,
+/// and return the transformed document. Invalid definitions and documents throw their error as a string.
+#[wasm_bindgen]
+pub fn validate(schema_json: &str, doc_json: &str) -> Result<JsValue, JsValue> {
+    let schema = JSONSchema::from_json(&parse_value(schema_json)?).map_err(|e| JsValue::from_str(&e))?;
+    let value = schema.validate(&parse_value(doc_json)?).map_err(|e| JsValue::from_str(&e))?;
+
+    Ok(to_js(&value))
+}
@@ -0,0 +1,147 @@
+use crate::utils::compat::*;
+use crate::utils::parser::escape;
+use crate::utils::{JSONValue, Serialize};
+
+/// Longest flow collection, such as `[1, 2, 3]`, written on a single line.
+const FLOW_WIDTH: usize = 60;
+
+/// Words that YAML 1.1 or 1.2 would read as something other than a string.
+const RESERVED: &[&str] = &["true", "false", "yes", "no", "on", "off", "y", "n", "null", "~"];
+
+impl JSONValue {
+    /// Serialize the value as a YAML document. Collections holding only scalars are
+    /// written in flow style (`[1, 2]`, `{ a: 1 }`) when they fit on a short line, and
+    /// in block style otherwise. Strings are left unquoted when that is unambiguous,
+    /// and double-quoted with JSON escapes when not.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jsonparser::JSONParser;
+    ///
+    /// let json = JSONParser::from(r#"{
+    ///   "name": "api",
+    ///   "port": 8080,
+    ///   "debug": "false",
+    ///   "hosts": ["a.example.com", "b.example.com"],
+    ///   "limits": { "cpu": 2, "memory": "512Mi" },
+    ///   "routes": [{ "path": "/", "methods": ["GET"] }, { "path": "/admin", "auth": true, "note": "key: value" }],
+    ///   "tags": []
+    /// }"#).unwrap();
+    ///
+    /// assert_eq!(json.to_yaml_string(), concat!(
+    ///     "name: api\n",
+    ///     "port: 8080\n",
+    ///     "debug: \"false\"\n",
+    ///     "hosts: [a.example.com, b.example.com]\n",
+    ///     "limits: { cpu: 2, memory: \"512Mi\" }\n",
+    ///     "routes:\n",
+    ///     "  - path: /\n",
+    ///     "    methods: [GET]\n",
+    ///     "  - { path: /admin, auth: true, note: \"key: value\" }\n",
+    ///     "tags: []\n"
+    /// ));
+    /// ```
+    pub fn to_yaml_string(&self) -> String {
+        let mut output = String::new();
+
+        for line in self.yaml_lines() {
+            output.push_str(&line);
+            output.push('\n');
+        }
+        output
+    }
+
+    /// The lines of the value in block style, or its single line when it fits in flow style.
+    fn yaml_lines(&self) -> Vec<String> {
+        if let Some(flow) = self.yaml_flow() {
+            return vec![flow];
+        }
+        let mut lines = Vec::new();
+
+        match self {
+            JSONValue::Object(obj) => {
+                for (key, value) in obj.iter() {
+                    match value.yaml_flow() {
+                        Some(flow) => lines.push(format!("{}: {}", yaml_string(key), flow)),
+                        None => {
+                            lines.push(format!("{}:", yaml_string(key)));
+                            lines.extend(value.yaml_lines().into_iter().map(|line| format!("  {}", line)));
+                        }
+                    }
+                }
+            },
+            JSONValue::Array(array) => {
+                for value in array.iter() {
+                    for (i, line) in value.yaml_lines().into_iter().enumerate() {
+                        lines.push(format!("{}{}", if i == 0 { "- " } else { "  " }, line));
+                    }
+                }
+            },
+            _ => unreachable!("Scalars are always written in flow style")
+        }
+        lines
+    }
+
+    /// The value on a single line, if it is a scalar, an empty collection, or
+    /// a short collection of scalars.
+    fn yaml_flow(&self) -> Option<String> {
+        let flow = match self {
+            JSONValue::Object(obj) if obj.is_empty() => "{}".to_string(),
+            JSONValue::Array(array) if array.is_empty() => "[]".to_string(),
+            JSONValue::Object(obj) => {
+                let entries = obj.iter()
+                    .map(|(key, value)| value.yaml_scalar().map(|value| format!("{}: {}", yaml_string(key), value)))
+                    .collect::<Option<Vec<_>>>()?;
+
+                format!("{{ {} }}", entries.join(", "))
+            },
+            JSONValue::Array(array) => {
+                let items = array.iter().map(|value| value.yaml_scalar()).collect::<Option<Vec<_>>>()?;
+
+                format!("[{}]", items.join(", "))
+            },
+            value => return value.yaml_scalar()
+        };
+
+        if flow.len() > FLOW_WIDTH {
+            return None;
+        }
+        Some(flow)
+    }
+
+    fn yaml_scalar(&self) -> Option<String> {
+        match self {
+            JSONValue::String(s) => Some(yaml_string(s)),
+            JSONValue::Object(_) | JSONValue::Array(_) => None,
+            value => Some(value.serialize())
+        }
+    }
+}
+
+/// Write a string plain when YAML reads it back as the same string, in flow and
+/// block context alike, and double-quoted otherwise.
+fn yaml_string(value: &str) -> String {
+    let plain = value.chars().next().map_or(false, |c| c.is_alphanumeric() || matches!(c, '_' | '.' | '/'))
+        && value.chars().all(|c| c.is_alphanumeric() || matches!(c, ' ' | '_' | '.' | '/' | '-' | '+' | '@' | '(' | ')'))
+        && !value.ends_with(' ')
+        && !RESERVED.contains(&value.to_ascii_lowercase().as_str())
+        && !looks_numeric(value);
+
+    if plain {
+        return value.to_string();
+    }
+    escape(value)
+}
+
+/// Whether YAML could read the string as a number, including forms JSON lacks
+/// such as `.5`, `0x1F`, `1_000` or `.inf`.
+fn looks_numeric(value: &str) -> bool {
+    let digits = value.trim_start_matches(['+', '-']);
+    let lower = digits.to_ascii_lowercase();
+
+    digits.starts_with(|c: char| c.is_ascii_digit())
+        || (digits.starts_with('.') && digits[1..].starts_with(|c: char| c.is_ascii_digit()))
+        || lower == ".inf"
+        || lower == ".nan"
+}